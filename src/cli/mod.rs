@@ -0,0 +1,1081 @@
+//! Non-interactive subcommands (`binders list`, `songs assign`, `to-print`, ...)
+//! so the library can be scripted or run from cron. Every command goes through
+//! the same [`Library`] trait as the TUI and can print `--json` instead of text
+//! for piping into other tools.
+
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+use anyhow::{anyhow, bail, Context, Result};
+use chrono::Local;
+use serde::Serialize;
+use serde_json::json;
+
+use crate::db::{
+    attach_file, export_library, import_library, AttachmentStore, BackupReason, BackupStore,
+    Library, LibrarySnapshot, RestoreMode, ATTACHMENT_DIR_NAME, TIMESTAMP_FORMAT,
+};
+use crate::models::format_size;
+use crate::models::{Binder, Concert, LinkKind, PrintStage, Song};
+use crate::printing::{send_print_jobs, Printer};
+use crate::reports::{
+    all_binder_contents, binder_contents, checklist_title, contents_html, contents_text,
+    plan_print_job, render_checklist, to_print_report_for, write_print_job, ChecklistFormat,
+    PrintJobError, ToPrintReport, ToPrintTarget,
+};
+use crate::transfer::{
+    apply_import, export_csv_files, plan_import_file, ColumnMapping, ExportKind, ExportTables,
+};
+
+#[cfg(test)]
+mod tests;
+
+/// First words that select a subcommand instead of launching the TUI.
+pub const COMMANDS: &[&str] = &[
+    "binders",
+    "songs",
+    "concerts",
+    "to-print",
+    "print-log",
+    "export",
+    "library",
+    "help",
+];
+
+/// Help text for the subcommands, shared by `--help` and `help`.
+pub const USAGE: &str = "\
+Commands:
+  binders list                          List binders
+  binders add <number> <label>          Create a binder
+  binders index [--binder <number>] [--html] [--output <file>]
+                                        Print each binder's table of contents (one
+                                        binder or all) as plain text or HTML
+  songs list [--binder <number>]        List all songs, or the songs in one binder
+  songs add <title> [--composer <name>] [--link <url>] [--binder <number>]
+                                        Create a song, optionally assigning it
+  songs assign <song> --binder <number> Put a song into a binder
+  songs unassign <song> --binder <number>
+                                        Take a song out of a binder
+  songs attach <song> <file>            Copy a local file (e.g. a PDF score) into the
+                                        attachments folder and attach it to a song
+  songs import <file.csv> [--dry-run] [--title <column>] [--composer <column>]
+               [--link <column>] [--binders <column>]
+                                        Import songs from a spreadsheet export
+  concerts list                         List concerts with their setlists
+  to-print [--concert <concert>]        Show what each binder is missing, compared
+                                        with the director's binder or a setlist
+  to-print [--concert <concert>] --format <markdown|html|songs-csv|binders-csv>
+           [--output <file>]            Write it as a printable checklist: copies per
+                                        song and a tick list per binder
+  to-print [--concert <concert>] --print-job <file.pdf>
+                                        Combine the attached or linked PDF scores into
+                                        one file with the copies each song needs
+  to-print [--concert <concert>] --send Send each song's PDF score to the printer
+                                        (see --printer) for the copies it needs
+  print-log                             List the scores sent to the printer
+  export <songs|binders|memberships> [--output <file>]
+                                        Write a table as CSV (stdout by default)
+  export all --dir <directory>          Write all three tables as dated CSV files
+  library export [--output <file>]      Write a full JSON backup (stdout by default);
+                                        with --output, attached files are copied into
+                                        an attachments/ folder next to it
+  library import <file.json> --replace|--merge
+                                        Restore a JSON backup, replacing the library
+                                        or adding what is missing
+
+  <song> is a song id or its title (case-insensitive); add --composer <name>
+  when several songs share a title. <concert> is a concert id or its name.
+  Import columns are header names or 1-based positions; by default the title,
+  composer, link/url and binders headers are used. Every command accepts
+  --json, except to-print with --format, --print-job or --send.";
+
+/// Whether `arg` names a subcommand rather than a TUI option.
+pub fn is_command(arg: &str) -> bool {
+    COMMANDS.contains(&arg)
+}
+
+/// Everything a subcommand works with besides its arguments: the library, where
+/// its output goes, and the optional stores and printer around the library.
+pub struct RunContext<'a> {
+    library: &'a mut dyn Library,
+    out: &'a mut dyn Write,
+    backups: Option<&'a BackupStore>,
+    attachments: Option<&'a AttachmentStore>,
+    printer: Option<&'a Printer>,
+}
+
+impl<'a> RunContext<'a> {
+    /// Work on `library` and write results to `out`, with no backups,
+    /// attachments or printer.
+    pub fn new(library: &'a mut dyn Library, out: &'a mut dyn Write) -> Self {
+        Self {
+            library,
+            out,
+            backups: None,
+            attachments: None,
+            printer: None,
+        }
+    }
+
+    /// Take a backup into `backups` before commands that overwrite data.
+    pub fn with_backups(mut self, backups: &'a BackupStore) -> Self {
+        self.backups = Some(backups);
+        self
+    }
+
+    /// Keep attached files in `attachments`. Without it `songs attach` is
+    /// refused and library exports and imports carry only the attachment
+    /// records.
+    pub fn with_attachments(mut self, attachments: &'a AttachmentStore) -> Self {
+        self.attachments = Some(attachments);
+        self
+    }
+
+    /// Send scores to `printer`. Without it `to-print --send` is refused.
+    pub fn with_printer(mut self, printer: &'a Printer) -> Self {
+        self.printer = Some(printer);
+        self
+    }
+}
+
+/// Run one subcommand, writing its result to the context's output.
+pub fn run(context: RunContext<'_>, args: &[String]) -> Result<()> {
+    let RunContext {
+        library,
+        out,
+        backups,
+        attachments,
+        printer,
+    } = context;
+    let Some((command, rest)) = args.split_first() else {
+        bail!("missing command (try --help)");
+    };
+    let sub = rest.first().map(String::as_str);
+    let rest = rest.get(1..).unwrap_or_default();
+
+    match (command.as_str(), sub) {
+        ("help", _) => writeln!(out, "{USAGE}")?,
+        ("binders", Some("list")) => {
+            let args = Args::parse(rest, &[])?;
+            args.expect_positionals(0, "binders list")?;
+            binders_list(library, &args, out)?;
+        }
+        ("binders", Some("add")) => {
+            let args = Args::parse(rest, &[])?;
+            args.expect_positionals(2, "binders add <number> <label>")?;
+            binders_add(library, &args, out)?;
+        }
+        ("binders", Some("index")) => {
+            let args = Args::parse_with_flags(rest, &["--binder", "--output"], &["--html"])?;
+            args.expect_positionals(0, "binders index")?;
+            binders_index(library, &args, out)?;
+        }
+        ("songs", Some("list")) => {
+            let args = Args::parse(rest, &["--binder"])?;
+            args.expect_positionals(0, "songs list")?;
+            songs_list(library, &args, out)?;
+        }
+        ("songs", Some("add")) => {
+            let args = Args::parse(rest, &["--composer", "--link", "--binder"])?;
+            args.expect_positionals(1, "songs add <title>")?;
+            songs_add(library, &args, out)?;
+        }
+        ("songs", Some("assign")) => {
+            let args = Args::parse(rest, &["--binder", "--composer"])?;
+            args.expect_positionals(1, "songs assign <song> --binder <number>")?;
+            songs_assign(library, &args, out)?;
+        }
+        ("songs", Some("unassign")) => {
+            let args = Args::parse(rest, &["--binder", "--composer"])?;
+            args.expect_positionals(1, "songs unassign <song> --binder <number>")?;
+            songs_unassign(library, &args, out)?;
+        }
+        ("songs", Some("attach")) => {
+            let args = Args::parse(rest, &["--composer"])?;
+            args.expect_positionals(2, "songs attach <song> <file>")?;
+            songs_attach(library, attachments, &args, out)?;
+        }
+        ("songs", Some("import")) => {
+            let args = Args::parse_with_flags(
+                rest,
+                &["--title", "--composer", "--link", "--binders"],
+                &["--dry-run"],
+            )?;
+            args.expect_positionals(1, "songs import <file.csv>")?;
+            songs_import(library, &args, out)?;
+        }
+        ("concerts", Some("list")) => {
+            let args = Args::parse(rest, &[])?;
+            args.expect_positionals(0, "concerts list")?;
+            concerts_list(library, &args, out)?;
+        }
+        ("to-print", _) => {
+            let args = Args::parse_with_flags(
+                &args[1..],
+                &["--concert", "--format", "--output", "--print-job"],
+                &["--send"],
+            )?;
+            args.expect_positionals(0, "to-print")?;
+            to_print(library, attachments, printer, &args, out)?;
+        }
+        ("print-log", _) => {
+            let args = Args::parse(&args[1..], &[])?;
+            args.expect_positionals(0, "print-log")?;
+            print_log(library, &args, out)?;
+        }
+        ("export", Some("all")) => {
+            let args = Args::parse(rest, &["--dir"])?;
+            args.expect_positionals(0, "export all --dir <directory>")?;
+            export_all(library, &args, out)?;
+        }
+        ("export", Some(kind)) => {
+            let Some(kind) = ExportKind::from_name(kind) else {
+                bail!("unknown export '{kind}' (expected songs, binders, memberships or all)");
+            };
+            let args = Args::parse(rest, &["--output"])?;
+            args.expect_positionals(0, "export <songs|binders|memberships>")?;
+            export_table(library, kind, &args, out)?;
+        }
+        ("export", None) => bail!("export needs a table (try --help)"),
+        ("library", Some("export")) => {
+            let args = Args::parse(rest, &["--output"])?;
+            args.expect_positionals(0, "library export")?;
+            library_export(library, attachments, &args, out)?;
+        }
+        ("library", Some("import")) => {
+            let args = Args::parse_with_flags(rest, &[], &["--replace", "--merge"])?;
+            args.expect_positionals(1, "library import <file.json> --replace|--merge")?;
+            library_import(library, backups, attachments, &args, out)?;
+        }
+        ("binders" | "songs" | "concerts" | "library", Some(other)) => {
+            bail!("unknown {command} command '{other}' (try --help)")
+        }
+        ("binders" | "songs" | "concerts" | "library", None) => {
+            bail!("{command} needs a command (try --help)")
+        }
+        (other, _) => bail!("unknown command '{other}' (try --help)"),
+    }
+
+    Ok(())
+}
+
+/// Positional arguments plus the options and switches a command accepts.
+/// `--json` is recognized everywhere.
+#[derive(Debug, Default)]
+struct Args {
+    positionals: Vec<String>,
+    options: Vec<(&'static str, String)>,
+    flags: Vec<&'static str>,
+    json: bool,
+}
+
+impl Args {
+    fn parse(args: &[String], allowed: &[&'static str]) -> Result<Self> {
+        Self::parse_with_flags(args, allowed, &[])
+    }
+
+    /// Like [`Args::parse`], also accepting value-less switches such as
+    /// `--dry-run`.
+    fn parse_with_flags(
+        args: &[String],
+        allowed: &[&'static str],
+        flags: &[&'static str],
+    ) -> Result<Self> {
+        let mut parsed = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            if arg == "--json" {
+                parsed.json = true;
+                continue;
+            }
+            if let Some(&flag) = flags.iter().find(|&&flag| flag == arg) {
+                parsed.flags.push(flag);
+                continue;
+            }
+            if !arg.starts_with("--") {
+                parsed.positionals.push(arg.clone());
+                continue;
+            }
+            let (name, inline) = match arg.split_once('=') {
+                Some((name, value)) => (name, Some(value.to_string())),
+                None => (arg.as_str(), None),
+            };
+            let Some(&name) = allowed.iter().find(|&&allowed| allowed == name) else {
+                bail!("unrecognized option '{name}' (try --help)");
+            };
+            let value = match inline {
+                Some(value) => value,
+                None => iter
+                    .next()
+                    .cloned()
+                    .with_context(|| format!("{name} requires a value"))?,
+            };
+            parsed.options.push((name, value));
+        }
+        Ok(parsed)
+    }
+
+    fn expect_positionals(&self, count: usize, usage: &str) -> Result<()> {
+        if self.positionals.len() != count {
+            bail!("usage: {usage}");
+        }
+        Ok(())
+    }
+
+    /// Last value given for `name`, so repeated options behave like most CLIs.
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options
+            .iter()
+            .rev()
+            .find(|(option, _)| *option == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    fn has_flag(&self, name: &str) -> bool {
+        self.flags.contains(&name)
+    }
+
+    fn binder_number(&self) -> Result<Option<i64>> {
+        self.option("--binder").map(parse_binder_number).transpose()
+    }
+
+    fn required_binder_number(&self) -> Result<i64> {
+        self.binder_number()?
+            .context("--binder <number> is required")
+    }
+}
+
+fn parse_binder_number(value: &str) -> Result<i64> {
+    value
+        .trim()
+        .parse::<i64>()
+        .with_context(|| format!("Binder number must be an integer, got '{value}'."))
+}
+
+fn print_json<T: Serialize + ?Sized>(out: &mut dyn Write, value: &T) -> Result<()> {
+    serde_json::to_writer_pretty(&mut *out, value)?;
+    writeln!(out)?;
+    Ok(())
+}
+
+fn find_binder(library: &dyn Library, number: i64) -> Result<Binder> {
+    library
+        .fetch_binders()?
+        .into_iter()
+        .find(|binder| binder.number == number)
+        .ok_or_else(|| anyhow!("Binder {number:02} not found"))
+}
+
+/// Resolve a song by id, or by case-insensitive title narrowed by composer.
+fn find_song(library: &dyn Library, reference: &str, composer: Option<&str>) -> Result<Song> {
+    let songs = library.fetch_all_songs()?;
+    if let Ok(id) = reference.parse::<i64>() {
+        if let Some(song) = songs.iter().find(|song| song.id == id) {
+            return Ok(song.clone());
+        }
+    }
+
+    let title = reference.trim().to_lowercase();
+    let composer = composer.map(|composer| composer.trim().to_lowercase());
+    let matches: Vec<Song> = songs
+        .into_iter()
+        .filter(|song| song.title.trim().to_lowercase() == title)
+        .filter(|song| {
+            composer
+                .as_ref()
+                .is_none_or(|composer| song.composer.trim().to_lowercase() == *composer)
+        })
+        .collect();
+
+    match matches.len() {
+        0 => bail!("no song matches '{reference}'"),
+        1 => Ok(matches.into_iter().next().expect("one match")),
+        _ => {
+            let choices: Vec<String> = matches
+                .iter()
+                .map(|song| format!("#{} {}", song.id, song.display_title()))
+                .collect();
+            bail!(
+                "'{reference}' matches several songs ({}); pass the id or --composer",
+                choices.join(", ")
+            )
+        }
+    }
+}
+
+/// Resolve a concert by id, or by case-insensitive name.
+fn find_concert(library: &dyn Library, reference: &str) -> Result<Concert> {
+    let concerts = library.fetch_concerts()?;
+    if let Ok(id) = reference.parse::<i64>() {
+        if let Some(concert) = concerts.iter().find(|concert| concert.id == id) {
+            return Ok(concert.clone());
+        }
+    }
+
+    let name = reference.trim().to_lowercase();
+    let matches: Vec<Concert> = concerts
+        .into_iter()
+        .filter(|concert| concert.name.trim().to_lowercase() == name)
+        .collect();
+    match matches.len() {
+        0 => bail!("no concert matches '{reference}'"),
+        1 => Ok(matches.into_iter().next().expect("one match")),
+        _ => {
+            let choices: Vec<String> = matches
+                .iter()
+                .map(|concert| format!("#{} {}", concert.id, concert.display_title()))
+                .collect();
+            bail!(
+                "'{reference}' matches several concerts ({}); pass the id",
+                choices.join(", ")
+            )
+        }
+    }
+}
+
+fn binders_list(library: &dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let binders = library.fetch_binders()?;
+    if args.json {
+        return print_json(out, &binders);
+    }
+    for binder in &binders {
+        writeln!(out, "Binder {:02}  {}", binder.number, binder.label)?;
+    }
+    Ok(())
+}
+
+fn binders_add(library: &mut dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let number = parse_binder_number(&args.positionals[0])?;
+    let label = args.positionals[1].trim();
+    if label.is_empty() {
+        bail!("Binder label is required.");
+    }
+    let binder = library.create_binder(number, label)?;
+    if args.json {
+        return print_json(out, &binder);
+    }
+    writeln!(out, "Added Binder {:02} ({}).", binder.number, binder.label)?;
+    Ok(())
+}
+
+fn binders_index(library: &dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let contents = match args.binder_number()? {
+        Some(number) => vec![binder_contents(library, &find_binder(library, number)?)?],
+        None => all_binder_contents(library)?,
+    };
+    if args.json {
+        return print_json(out, &contents);
+    }
+    let document = if args.has_flag("--html") {
+        contents_html(&contents)
+    } else {
+        contents_text(&contents)
+    };
+    let Some(path) = args.option("--output") else {
+        out.write_all(document.as_bytes())?;
+        return Ok(());
+    };
+    std::fs::write(path, document).with_context(|| format!("Could not write {path}"))?;
+    writeln!(
+        out,
+        "Wrote the index of {} binder{} to {path}.",
+        contents.len(),
+        if contents.len() == 1 { "" } else { "s" }
+    )?;
+    Ok(())
+}
+
+fn songs_list(library: &dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let songs = match args.binder_number()? {
+        Some(number) => {
+            let binder = find_binder(library, number)?;
+            library.fetch_songs_for_binder(binder.id)?
+        }
+        None => library.fetch_all_songs()?,
+    };
+    if args.json {
+        return print_json(out, &songs);
+    }
+    for song in &songs {
+        writeln!(out, "{:>4}  {}", song.id, song.display_title())?;
+    }
+    Ok(())
+}
+
+fn songs_add(library: &mut dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let title = args.positionals[0].trim();
+    if title.is_empty() {
+        bail!("Song title is required.");
+    }
+    let composer = args.option("--composer").unwrap_or_default().trim();
+    let link = args.option("--link").unwrap_or_default().trim();
+
+    // Resolve the binder first so a typo does not leave an orphaned song.
+    let binder = args
+        .binder_number()?
+        .map(|number| find_binder(library, number))
+        .transpose()?;
+
+    let mut created = None;
+    library.transaction(&mut |library| {
+        let song = library.create_song(title, composer)?;
+        let link = if link.is_empty() {
+            None
+        } else {
+            Some(library.add_song_link(song.id, LinkKind::guess(link), "", "", link)?)
+        };
+        if let Some(binder) = &binder {
+            library.add_song_to_binder(binder.id, song.id)?;
+        }
+        created = Some((song, link));
+        Ok(())
+    })?;
+    let (song, link) = created.expect("transaction created the song");
+
+    if args.json {
+        return print_json(
+            out,
+            &json!({ "song": song, "link": link, "binder": binder }),
+        );
+    }
+    match &binder {
+        Some(binder) => writeln!(
+            out,
+            "Added song #{} {} to Binder {:02}.",
+            song.id,
+            song.display_title(),
+            binder.number
+        )?,
+        None => writeln!(out, "Added song #{} {}.", song.id, song.display_title())?,
+    }
+    Ok(())
+}
+
+fn songs_assign(library: &mut dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let binder = find_binder(library, args.required_binder_number()?)?;
+    let song = find_song(library, &args.positionals[0], args.option("--composer"))?;
+    library.add_song_to_binder(binder.id, song.id)?;
+
+    if args.json {
+        return print_json(out, &json!({ "song": song, "binder": binder }));
+    }
+    writeln!(
+        out,
+        "Assigned {} to Binder {:02}.",
+        song.display_title(),
+        binder.number
+    )?;
+    Ok(())
+}
+
+fn songs_unassign(library: &mut dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let binder = find_binder(library, args.required_binder_number()?)?;
+    let song = find_song(library, &args.positionals[0], args.option("--composer"))?;
+    library.remove_song_from_binder(binder.id, song.id)?;
+
+    if args.json {
+        return print_json(out, &json!({ "song": song, "binder": binder }));
+    }
+    writeln!(
+        out,
+        "Removed {} from Binder {:02}.",
+        song.display_title(),
+        binder.number
+    )?;
+    Ok(())
+}
+
+fn songs_attach(
+    library: &mut dyn Library,
+    attachments: Option<&AttachmentStore>,
+    args: &Args,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let Some(store) = attachments else {
+        bail!("attachments are not available here");
+    };
+    let song = find_song(library, &args.positionals[0], args.option("--composer"))?;
+    let path = Path::new(&args.positionals[1]);
+    if !path.is_file() {
+        bail!("{} is not a file", path.display());
+    }
+    let stamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let attachment = attach_file(library, store, song.id, path, &stamp)?;
+
+    if args.json {
+        return print_json(out, &json!({ "song": song, "attachment": attachment }));
+    }
+    writeln!(
+        out,
+        "Attached {} ({}) to {}.",
+        attachment.file_name,
+        format_size(attachment.size_bytes),
+        song.display_title()
+    )?;
+    Ok(())
+}
+
+/// The `attachments/` folder next to a JSON export.
+fn exported_attachments(json_path: &str) -> AttachmentStore {
+    let parent = Path::new(json_path)
+        .parent()
+        .unwrap_or_else(|| Path::new("."));
+    AttachmentStore::new(parent.join(ATTACHMENT_DIR_NAME))
+}
+
+fn songs_import(library: &mut dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let mapping = ColumnMapping {
+        title: args.option("--title").map(str::to_string),
+        composer: args.option("--composer").map(str::to_string),
+        link: args.option("--link").map(str::to_string),
+        binders: args.option("--binders").map(str::to_string),
+    };
+    let path = Path::new(&args.positionals[0]);
+    let plan = plan_import_file(library, path, &mapping)?;
+    let dry_run = args.has_flag("--dry-run");
+    let summary = if dry_run {
+        plan.summary()
+    } else {
+        apply_import(library, &plan)?
+    };
+
+    if args.json {
+        return print_json(
+            out,
+            &json!({
+                "dry_run": dry_run,
+                "summary": summary,
+                "skipped": plan.skipped,
+                "warnings": plan.warnings,
+            }),
+        );
+    }
+    for issue in &plan.skipped {
+        writeln!(out, "line {}: {} Row skipped.", issue.line, issue.message)?;
+    }
+    for issue in &plan.warnings {
+        writeln!(out, "line {}: {}", issue.line, issue.message)?;
+    }
+    if dry_run {
+        writeln!(out, "Dry run: {}. Nothing was saved.", summary.describe())?;
+    } else {
+        writeln!(out, "Imported: {}.", summary.describe())?;
+    }
+    Ok(())
+}
+
+fn export_table(
+    library: &dyn Library,
+    kind: ExportKind,
+    args: &Args,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let tables = ExportTables::collect(library)?;
+    let Some(path) = args.option("--output") else {
+        if args.json {
+            tables.write_json(kind, &mut *out)?;
+            writeln!(out)?;
+        } else {
+            tables.write_csv(kind, out)?;
+        }
+        return Ok(());
+    };
+
+    let file = File::create(path).with_context(|| format!("Could not create {path}"))?;
+    let mut file = BufWriter::new(file);
+    if args.json {
+        tables.write_json(kind, &mut file)?;
+        writeln!(file)?;
+    } else {
+        tables.write_csv(kind, &mut file)?;
+    }
+    file.flush()
+        .with_context(|| format!("Could not write {path}"))?;
+    writeln!(
+        out,
+        "Wrote {} {} rows to {path}.",
+        tables.len(kind),
+        kind.name()
+    )?;
+    Ok(())
+}
+
+fn export_all(library: &dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let dir = args
+        .option("--dir")
+        .context("--dir <directory> is required")?;
+    let stamp = chrono::Local::now().format("%Y-%m-%d").to_string();
+    let files = export_csv_files(library, Path::new(dir), &stamp)?;
+
+    if args.json {
+        let files: Vec<_> = files
+            .iter()
+            .map(|file| json!({ "kind": file.kind.name(), "path": file.path, "rows": file.rows }))
+            .collect();
+        return print_json(out, &files);
+    }
+    for file in &files {
+        writeln!(
+            out,
+            "Wrote {} {} rows to {}.",
+            file.rows,
+            file.kind.name(),
+            file.path.display()
+        )?;
+    }
+    Ok(())
+}
+
+fn library_export(
+    library: &dyn Library,
+    attachments: Option<&AttachmentStore>,
+    args: &Args,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let snapshot = export_library(library)?;
+    let json = snapshot.to_json()?;
+    let Some(path) = args.option("--output") else {
+        out.write_all(json.as_bytes())?;
+        return Ok(());
+    };
+
+    std::fs::write(path, json).with_context(|| format!("Could not write {path}"))?;
+    if let Some(store) = attachments {
+        store
+            .copy_into(&exported_attachments(path), &snapshot.attachments)
+            .context("Could not copy the attached files")?;
+    }
+    if args.json {
+        return print_json(
+            out,
+            &json!({
+                "path": path,
+                "binders": snapshot.binders.len(),
+                "songs": snapshot.songs.len(),
+                "memberships": snapshot.memberships.len(),
+                "concerts": snapshot.concerts.len(),
+                "performances": snapshot.performances.len(),
+                "choristers": snapshot.choristers.len(),
+                "checkouts": snapshot.checkouts.len(),
+                "tags": snapshot.tags.len(),
+                "song_links": snapshot.song_links.len(),
+                "attachments": snapshot.attachments.len(),
+            }),
+        );
+    }
+    writeln!(
+        out,
+        "Wrote {} binders, {} songs and {} binder assignments to {path}.",
+        snapshot.binders.len(),
+        snapshot.songs.len(),
+        snapshot.memberships.len()
+    )?;
+    Ok(())
+}
+
+fn library_import(
+    library: &mut dyn Library,
+    backups: Option<&BackupStore>,
+    attachments: Option<&AttachmentStore>,
+    args: &Args,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let mode = match (args.has_flag("--replace"), args.has_flag("--merge")) {
+        (true, false) => RestoreMode::Replace,
+        (false, true) => RestoreMode::Merge,
+        _ => bail!("library import needs exactly one of --replace or --merge"),
+    };
+    let path = &args.positionals[0];
+    let json = std::fs::read_to_string(path).with_context(|| format!("Could not read {path}"))?;
+    let snapshot = LibrarySnapshot::from_json(&json)?;
+    if let (RestoreMode::Replace, Some(store)) = (mode, backups) {
+        store
+            .create(library, BackupReason::Import)
+            .context("Could not back up the library first; nothing was changed")?;
+    }
+    let summary = import_library(library, &snapshot, mode)?;
+    if let Some(store) = attachments {
+        exported_attachments(path)
+            .copy_into(store, &snapshot.attachments)
+            .context("Could not copy the attached files")?;
+    }
+
+    if args.json {
+        return print_json(out, &summary);
+    }
+    let verb = match mode {
+        RestoreMode::Replace => "Restored",
+        RestoreMode::Merge => "Merged",
+    };
+    writeln!(
+        out,
+        "{verb} {path}: added {} binders, {} songs and {} binder assignments.",
+        summary.binders_added, summary.songs_added, summary.memberships_added
+    )?;
+    Ok(())
+}
+
+fn concerts_list(library: &dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let concerts = library.fetch_concerts()?;
+    if args.json {
+        let concerts = concerts
+            .into_iter()
+            .map(|concert| {
+                let setlist = library.fetch_setlist(concert.id)?;
+                Ok(json!({ "concert": concert, "setlist": setlist }))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        return print_json(out, &concerts);
+    }
+    for concert in &concerts {
+        writeln!(out, "{:>4}  {}", concert.id, concert.display_title())?;
+        for (index, song) in library.fetch_setlist(concert.id)?.iter().enumerate() {
+            writeln!(out, "      {:>2}. {}", index + 1, song.display_title())?;
+        }
+    }
+    Ok(())
+}
+
+fn to_print(
+    library: &mut dyn Library,
+    attachments: Option<&AttachmentStore>,
+    printer: Option<&Printer>,
+    args: &Args,
+    out: &mut dyn Write,
+) -> Result<()> {
+    let concert = args
+        .option("--concert")
+        .map(|reference| find_concert(library, reference))
+        .transpose()?;
+    let target = concert.as_ref().map_or(ToPrintTarget::Director, |concert| {
+        ToPrintTarget::Concert(concert.id)
+    });
+    let report = to_print_report_for(library, target)?
+        .context("No director binder found. Create Binder 00 to compare against.")?;
+    let writes_elsewhere = args.has_flag("--send")
+        || args.option("--print-job").is_some()
+        || args.option("--format").is_some();
+    if args.json && writes_elsewhere {
+        bail!("--json cannot be combined with --format, --print-job or --send");
+    }
+    if args.has_flag("--send") {
+        if args.option("--format").is_some() || args.option("--print-job").is_some() {
+            bail!("--send cannot be combined with --format or --print-job");
+        }
+        let printer = printer.context("printing is not available here")?;
+        return to_print_send(library, attachments, printer, &report, out);
+    }
+    let library: &dyn Library = library;
+    if let Some(path) = args.option("--print-job") {
+        if args.option("--format").is_some() {
+            bail!("--print-job and --format cannot be combined");
+        }
+        return to_print_job(library, attachments, &report, concert.as_ref(), path, out);
+    }
+    if let Some(name) = args.option("--format") {
+        let Some(format) = ChecklistFormat::from_name(name) else {
+            bail!("unknown format '{name}' (expected markdown, html, songs-csv or binders-csv)");
+        };
+        let document = render_checklist(&report, &checklist_title(concert.as_ref()), format)?;
+        let Some(path) = args.option("--output") else {
+            out.write_all(document.as_bytes())?;
+            return Ok(());
+        };
+        std::fs::write(path, document).with_context(|| format!("Could not write {path}"))?;
+        writeln!(out, "Wrote the To Print checklist to {path}.")?;
+        return Ok(());
+    }
+    if args.option("--output").is_some() {
+        bail!("--output needs --format");
+    }
+    if args.json {
+        return print_json(out, &report);
+    }
+
+    if report.binders.is_empty() {
+        match &concert {
+            Some(concert) => writeln!(out, "Every binder holds the {} setlist.", concert.name)?,
+            None => writeln!(out, "Every binder matches the director's binder.")?,
+        }
+        return Ok(());
+    }
+    for shortfall in &report.binders {
+        writeln!(
+            out,
+            "Binder {:02} • {}",
+            shortfall.binder.number, shortfall.binder.label
+        )?;
+        for missing in &shortfall.missing {
+            match missing.stage {
+                PrintStage::Needed => writeln!(out, "  {}", missing.song.display_title())?,
+                stage => writeln!(out, "  {} ({})", missing.song.display_title(), stage.tag())?,
+            }
+        }
+    }
+    writeln!(out)?;
+    writeln!(out, "Copies to print:")?;
+    for demand in &report.songs {
+        let unit = if demand.needed == 1 { "copy" } else { "copies" };
+        writeln!(
+            out,
+            "  {}  ({} {unit})",
+            demand.song.display_title(),
+            demand.needed
+        )?;
+    }
+    Ok(())
+}
+
+/// Write the print job for `report` to `path` and list the songs left out
+/// for lack of a PDF score.
+fn to_print_job(
+    library: &dyn Library,
+    attachments: Option<&AttachmentStore>,
+    report: &ToPrintReport,
+    concert: Option<&Concert>,
+    path: &str,
+    out: &mut dyn Write,
+) -> Result<()> {
+    if report.songs.is_empty() {
+        writeln!(out, "{}", nothing_to_print(report))?;
+        return Ok(());
+    }
+    let job = plan_print_job(library, attachments, &report.songs)?;
+    let summary = write_print_job(&job, &checklist_title(concert), Path::new(path))?;
+    let songs = job.entries.len();
+    writeln!(
+        out,
+        "Wrote {} {} of {songs} {} ({} pages plus the cover) to {path}.",
+        summary.copies,
+        if summary.copies == 1 {
+            "copy"
+        } else {
+            "copies"
+        },
+        if songs == 1 { "song" } else { "songs" },
+        summary.pages
+    )?;
+    for demand in &job.missing {
+        let unit = if demand.needed == 1 { "copy" } else { "copies" };
+        writeln!(
+            out,
+            "No PDF score for {} ({} {unit}).",
+            demand.song.display_title(),
+            demand.needed
+        )?;
+    }
+    Ok(())
+}
+
+/// Why a report has no copies to print.
+fn nothing_to_print(report: &ToPrintReport) -> &'static str {
+    if report.binders.is_empty() {
+        "Every binder is complete; there is nothing to print."
+    } else {
+        "Every missing song is already printed; there is nothing to print."
+    }
+}
+
+/// Send each score of `report` to `printer` and report what happened to every
+/// job. Binders' copies of each accepted song are marked printed on the print
+/// queue. Fails after reporting when the printer refused any of them.
+fn to_print_send(
+    library: &mut dyn Library,
+    attachments: Option<&AttachmentStore>,
+    printer: &Printer,
+    report: &ToPrintReport,
+    out: &mut dyn Write,
+) -> Result<()> {
+    if report.songs.is_empty() {
+        writeln!(out, "{}", nothing_to_print(report))?;
+        return Ok(());
+    }
+    let job = plan_print_job(library, attachments, &report.songs)?;
+    if job.entries.is_empty() {
+        bail!(PrintJobError::NoScores);
+    }
+    let submitted_at = Local::now().format(TIMESTAMP_FORMAT).to_string();
+    let sent = send_print_jobs(library, printer, &job, &submitted_at);
+    let logged = sent.entries;
+    let destination = printer.describe();
+    for entry in &logged {
+        let unit = if entry.copies == 1 { "copy" } else { "copies" };
+        if entry.accepted() {
+            writeln!(
+                out,
+                "Sent {} ({} {unit}) to {destination} as {}.",
+                entry.title, entry.copies, entry.request_id
+            )?;
+        } else {
+            writeln!(out, "Could not send {}: {}", entry.title, entry.error)?;
+        }
+    }
+    for demand in &job.missing {
+        let unit = if demand.needed == 1 { "copy" } else { "copies" };
+        writeln!(
+            out,
+            "No PDF score for {} ({} {unit}).",
+            demand.song.display_title(),
+            demand.needed
+        )?;
+    }
+    // What the printer accepted no longer needs printing.
+    for song_id in logged
+        .iter()
+        .filter(|entry| entry.accepted())
+        .filter_map(|entry| entry.song_id)
+    {
+        for shortfall in &report.binders {
+            if shortfall
+                .missing
+                .iter()
+                .any(|missing| missing.song.id == song_id && missing.stage == PrintStage::Needed)
+            {
+                library.set_print_stage(
+                    shortfall.binder.id,
+                    song_id,
+                    PrintStage::Printed,
+                    &submitted_at,
+                )?;
+            }
+        }
+    }
+    if let Some(err) = sent.log_error {
+        return Err(anyhow!(err).context("Could not record the print jobs; nothing more was sent"));
+    }
+    let refused = logged.iter().filter(|entry| !entry.accepted()).count();
+    if refused > 0 {
+        bail!("{refused} of {} print jobs were refused", logged.len());
+    }
+    Ok(())
+}
+
+fn print_log(library: &dyn Library, args: &Args, out: &mut dyn Write) -> Result<()> {
+    let log = library.fetch_print_log()?;
+    if args.json {
+        return print_json(out, &log);
+    }
+    if log.is_empty() {
+        writeln!(out, "Nothing has been sent to a printer yet.")?;
+        return Ok(());
+    }
+    for entry in &log {
+        let printer = if entry.printer.is_empty() {
+            "default printer"
+        } else {
+            entry.printer.as_str()
+        };
+        let outcome = if entry.accepted() {
+            entry.request_id.clone()
+        } else {
+            format!("refused: {}", entry.error)
+        };
+        writeln!(
+            out,
+            "{}  {} × {}  ({}) on {printer}  {outcome}",
+            entry.submitted_at, entry.copies, entry.title, entry.file_name
+        )?;
+    }
+    Ok(())
+}
@@ -0,0 +1,644 @@
+//! Subcommand tests run against a [`MemoryLibrary`], capturing stdout.
+
+use serde_json::Value;
+
+use crate::db::{AttachmentStore, Library, MemoryLibrary};
+use crate::models::LinkKind;
+use crate::reports::tests::write_blank_pdf;
+
+use super::{run, RunContext};
+
+/// Run `line` (split on spaces) and return what it printed.
+fn run_line(library: &mut MemoryLibrary, line: &str) -> anyhow::Result<String> {
+    let args: Vec<String> = line.split(' ').map(str::to_string).collect();
+    let mut out = Vec::new();
+    run(RunContext::new(library, &mut out), &args)?;
+    Ok(String::from_utf8(out).expect("utf-8 output"))
+}
+
+fn run_args(library: &mut MemoryLibrary, args: &[&str]) -> anyhow::Result<String> {
+    let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+    let mut out = Vec::new();
+    run(RunContext::new(library, &mut out), &args)?;
+    Ok(String::from_utf8(out).expect("utf-8 output"))
+}
+
+#[test]
+fn binders_add_then_list() {
+    let mut library = MemoryLibrary::new();
+    let added = run_args(&mut library, &["binders", "add", "3", "Tenors"]).unwrap();
+    assert_eq!(added, "Added Binder 03 (Tenors).\n");
+    run_args(&mut library, &["binders", "add", "0", "Director"]).unwrap();
+
+    let listed = run_line(&mut library, "binders list").unwrap();
+    assert_eq!(listed, "Binder 00  Director\nBinder 03  Tenors\n");
+}
+
+#[test]
+fn binders_add_reports_duplicate_number() {
+    let mut library = MemoryLibrary::new();
+    run_line(&mut library, "binders add 1 Sopranos").unwrap();
+    let err = run_line(&mut library, "binders add 1 Altos").unwrap_err();
+    assert_eq!(err.to_string(), "Binder number 1 already exists.");
+}
+
+#[test]
+fn binders_list_json() {
+    let mut library = MemoryLibrary::new();
+    library.create_binder(2, "Altos").unwrap();
+    let json: Value =
+        serde_json::from_str(&run_line(&mut library, "binders list --json").unwrap()).unwrap();
+    assert_eq!(json[0]["number"], 2);
+    assert_eq!(json[0]["label"], "Altos");
+}
+
+#[test]
+fn songs_add_with_binder_links_song() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(3, "Tenors").unwrap();
+
+    let printed = run_args(
+        &mut library,
+        &[
+            "songs",
+            "add",
+            "Ave Verum",
+            "--composer",
+            "Mozart",
+            "--binder=3",
+        ],
+    )
+    .unwrap();
+
+    assert!(printed.starts_with("Added song #1 Ave Verum - Mozart to Binder 03."));
+    let songs = library.fetch_songs_for_binder(binder.id).unwrap();
+    assert_eq!(songs.len(), 1);
+    assert_eq!(songs[0].composer, "Mozart");
+}
+
+#[test]
+fn songs_add_with_unknown_binder_creates_nothing() {
+    let mut library = MemoryLibrary::new();
+    let err = run_line(&mut library, "songs add Gloria --binder 9").unwrap_err();
+    assert_eq!(err.to_string(), "Binder 09 not found");
+    assert!(library.fetch_all_songs().unwrap().is_empty());
+}
+
+#[test]
+fn assign_and_unassign_by_title_or_id() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(3, "Tenors").unwrap();
+    let song = library.create_song("Cantique", "Fauré").unwrap();
+
+    let printed = run_line(&mut library, "songs assign cantique --binder 3").unwrap();
+    assert_eq!(printed, "Assigned Cantique - Fauré to Binder 03.\n");
+    assert_eq!(library.fetch_songs_for_binder(binder.id).unwrap().len(), 1);
+
+    let id = song.id.to_string();
+    run_args(&mut library, &["songs", "unassign", &id, "--binder", "3"]).unwrap();
+    assert!(library
+        .fetch_songs_for_binder(binder.id)
+        .unwrap()
+        .is_empty());
+
+    let err = run_args(&mut library, &["songs", "unassign", &id, "--binder", "3"]).unwrap_err();
+    assert_eq!(err.to_string(), "Song not linked to this binder");
+}
+
+#[test]
+fn ambiguous_title_needs_composer() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(1, "Sopranos").unwrap();
+    library.create_song("Ave Maria", "Schubert").unwrap();
+    let bach = library.create_song("Ave Maria", "Bach/Gounod").unwrap();
+
+    let err = run_args(
+        &mut library,
+        &["songs", "assign", "Ave Maria", "--binder", "1"],
+    )
+    .unwrap_err();
+    assert!(err.to_string().contains("matches several songs"));
+
+    run_args(
+        &mut library,
+        &[
+            "songs",
+            "assign",
+            "Ave Maria",
+            "--binder",
+            "1",
+            "--composer",
+            "bach/gounod",
+        ],
+    )
+    .unwrap();
+    let linked = library.fetch_songs_for_binder(binder.id).unwrap();
+    assert_eq!(linked[0].id, bach.id);
+}
+
+#[test]
+fn to_print_lists_missing_songs_and_copies() {
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    let sopranos = library.create_binder(1, "Sopranos").unwrap();
+    library.create_binder(2, "Altos").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    let cantique = library.create_song("Cantique", "Fauré").unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    library
+        .add_song_to_binder(director.id, cantique.id)
+        .unwrap();
+    library.add_song_to_binder(sopranos.id, ave.id).unwrap();
+
+    let printed = run_line(&mut library, "to-print").unwrap();
+    assert_eq!(
+        printed,
+        "Binder 01 • Sopranos\n  Cantique - Fauré\n\
+         Binder 02 • Altos\n  Ave Verum - Mozart\n  Cantique - Fauré\n\
+         \nCopies to print:\n  Cantique - Fauré  (2 copies)\n  Ave Verum - Mozart  (1 copy)\n"
+    );
+
+    let json: Value =
+        serde_json::from_str(&run_line(&mut library, "to-print --json").unwrap()).unwrap();
+    assert_eq!(json["binders"].as_array().unwrap().len(), 2);
+    assert_eq!(json["songs"][0]["song"]["title"], "Cantique");
+    assert_eq!(json["songs"][0]["needed"], 2);
+}
+
+#[test]
+fn to_print_without_director_fails() {
+    let mut library = MemoryLibrary::new();
+    let err = run_line(&mut library, "to-print").unwrap_err();
+    assert!(err.to_string().starts_with("No director binder found."));
+}
+
+#[test]
+fn to_print_against_a_concert_setlist() {
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    let sopranos = library.create_binder(1, "Sopranos").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    let cantique = library.create_song("Cantique", "Fauré").unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    library.add_song_to_binder(sopranos.id, ave.id).unwrap();
+    let spring = library
+        .create_concert("Spring Concert", "2025-04-12", "St. Mary's")
+        .unwrap();
+    library.add_song_to_setlist(spring.id, cantique.id).unwrap();
+    library.add_song_to_setlist(spring.id, ave.id).unwrap();
+
+    let listed = run_line(&mut library, "concerts list").unwrap();
+    assert_eq!(
+        listed,
+        concat!(
+            "   1  Spring Concert • 2025-04-12 • St. Mary's\n",
+            "       1. Cantique - Fauré\n",
+            "       2. Ave Verum - Mozart\n",
+        )
+    );
+
+    // The director's binder is compared too, since it is not the reference.
+    let printed = run_args(&mut library, &["to-print", "--concert", "spring concert"]).unwrap();
+    assert_eq!(
+        printed,
+        "Binder 00 • Director\n  Cantique - Fauré\n\
+         Binder 01 • Sopranos\n  Cantique - Fauré\n\
+         \nCopies to print:\n  Cantique - Fauré  (2 copies)\n"
+    );
+
+    let err = run_line(&mut library, "to-print --concert Gala").unwrap_err();
+    assert_eq!(err.to_string(), "no concert matches 'Gala'");
+}
+
+#[test]
+fn to_print_exports_checklists() {
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    library.create_binder(1, "Sopranos").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    let spring = library
+        .create_concert("Spring Concert", "2025-04-12", "")
+        .unwrap();
+    library.add_song_to_setlist(spring.id, ave.id).unwrap();
+
+    let markdown = run_line(&mut library, "to-print --format markdown").unwrap();
+    assert!(markdown.starts_with("# To Print – compared with the director's binder\n"));
+    assert!(markdown.contains("| Ave Verum | Mozart | 1 |"));
+    assert!(markdown.contains("### Binder 01 – Sopranos – 1 song\n\n- [ ] Ave Verum – Mozart\n"));
+
+    let html = run_args(
+        &mut library,
+        &[
+            "to-print",
+            "--concert",
+            "spring concert",
+            "--format",
+            "html",
+        ],
+    )
+    .unwrap();
+    assert!(html.contains("<title>To Print – Spring Concert setlist</title>"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("binders.csv");
+    let path = path.to_str().unwrap();
+    let printed = run_args(
+        &mut library,
+        &["to-print", "--format", "binders-csv", "--output", path],
+    )
+    .unwrap();
+    assert_eq!(
+        printed,
+        format!("Wrote the To Print checklist to {path}.\n")
+    );
+    assert_eq!(
+        std::fs::read_to_string(path).unwrap(),
+        format!(
+            "binder_number,binder_label,voice_part,song_id,title,composer,added\n\
+             1,Sopranos,,{},Ave Verum,Mozart,\n",
+            ave.id
+        )
+    );
+
+    let err = run_line(&mut library, "to-print --format pdf").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown format 'pdf' (expected markdown, html, songs-csv or binders-csv)"
+    );
+    let err = run_args(&mut library, &["to-print", "--output", path]).unwrap_err();
+    assert_eq!(err.to_string(), "--output needs --format");
+}
+
+#[test]
+fn to_print_builds_a_print_job() {
+    let dir = tempfile::tempdir().unwrap();
+    let score = dir.path().join("ave.pdf");
+    write_blank_pdf(&score, 2);
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    library.create_binder(1, "Sopranos").unwrap();
+    library.create_binder(2, "Altos").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    library
+        .add_song_link(ave.id, LinkKind::Score, "", "", score.to_str().unwrap())
+        .unwrap();
+    let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    library.add_song_to_binder(director.id, gloria.id).unwrap();
+
+    let output = dir.path().join("job.pdf");
+    let output = output.to_str().unwrap();
+    let printed = run_args(&mut library, &["to-print", "--print-job", output]).unwrap();
+    assert_eq!(
+        printed,
+        format!(
+            "Wrote 2 copies of 1 song (4 pages plus the cover) to {output}.\n\
+             No PDF score for Gloria - Vivaldi (2 copies).\n"
+        )
+    );
+    assert_eq!(lopdf::Document::load(output).unwrap().get_pages().len(), 5);
+
+    let err = run_args(
+        &mut library,
+        &["to-print", "--print-job", output, "--format", "html"],
+    )
+    .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--print-job and --format cannot be combined"
+    );
+    let err = run_args(&mut library, &["to-print", "--print-job", output, "--json"]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "--json cannot be combined with --format, --print-job or --send"
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn to_print_sends_scores_to_the_printer_and_logs_them() {
+    use crate::models::PrintStage;
+    use crate::printing::tests::{lp_calls, stub_lp};
+    use crate::printing::Printer;
+
+    let dir = tempfile::tempdir().unwrap();
+    let lp = stub_lp(dir.path());
+    let score = dir.path().join("ave.pdf");
+    write_blank_pdf(&score, 1);
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    library.create_binder(1, "Sopranos").unwrap();
+    library.create_binder(2, "Altos").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    library
+        .add_song_link(ave.id, LinkKind::Score, "", "", score.to_str().unwrap())
+        .unwrap();
+    let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    library.add_song_to_binder(director.id, gloria.id).unwrap();
+
+    let run_with = |library: &mut MemoryLibrary, printer: &str, args: &[&str]| {
+        let printer = Printer::new(Some(printer.to_string())).with_command(&lp);
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut out = Vec::new();
+        let result = run(
+            RunContext::new(library, &mut out).with_printer(&printer),
+            &args,
+        );
+        (result, String::from_utf8(out).unwrap())
+    };
+    assert_eq!(
+        run_args(&mut library, &["print-log"]).unwrap(),
+        "Nothing has been sent to a printer yet.\n"
+    );
+    // A refused job leaves the copies to print.
+    let (result, printed) = run_with(&mut library, "Missing", &["to-print", "--send"]);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "1 of 1 print jobs were refused"
+    );
+    assert!(printed.starts_with(
+        "Could not send Ave Verum - Mozart: lp: The printer or class does not exist.\n"
+    ));
+    assert!(library.fetch_print_queue().unwrap().is_empty());
+
+    let (result, printed) = run_with(&mut library, "Office", &["to-print", "--send"]);
+    result.unwrap();
+    assert_eq!(
+        printed,
+        "Sent Ave Verum - Mozart (2 copies) to Office as Office-2.\n\
+         No PDF score for Gloria - Vivaldi (2 copies).\n"
+    );
+    assert_eq!(
+        lp_calls(dir.path())[1],
+        format!(
+            "-d Office -n 2 -t Ave Verum - Mozart -- {}",
+            score.display()
+        )
+    );
+
+    // Accepted copies are marked printed, so they are not sent again.
+    let queue = library.fetch_print_queue().unwrap();
+    assert_eq!(queue.len(), 2);
+    assert!(queue
+        .iter()
+        .all(|item| item.song_id == ave.id && item.stage == PrintStage::Printed));
+    let listed = run_args(&mut library, &["to-print"]).unwrap();
+    assert!(listed.contains("  Ave Verum - Mozart (printed)\n"));
+    assert!(listed.ends_with("Copies to print:\n  Gloria - Vivaldi  (2 copies)\n"));
+    let (result, _) = run_with(&mut library, "Office", &["to-print", "--send"]);
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "None of the songs to print has a PDF score attached or linked."
+    );
+
+    let log = run_args(&mut library, &["print-log"]).unwrap();
+    let lines: Vec<&str> = log
+        .lines()
+        .map(|line| line.split_once("  ").unwrap().1)
+        .collect();
+    assert_eq!(
+        lines,
+        vec![
+            "2 × Ave Verum - Mozart  (ave.pdf) on Missing  refused: \
+             lp: The printer or class does not exist.",
+            "2 × Ave Verum - Mozart  (ave.pdf) on Office  Office-2",
+        ]
+    );
+
+    let err = run_args(&mut library, &["to-print", "--send"]).unwrap_err();
+    assert_eq!(err.to_string(), "printing is not available here");
+    let (result, _) = run_with(
+        &mut library,
+        "Office",
+        &["to-print", "--send", "--format", "html"],
+    );
+    assert_eq!(
+        result.unwrap_err().to_string(),
+        "--send cannot be combined with --format or --print-job"
+    );
+}
+
+#[test]
+fn unknown_commands_and_options_are_rejected() {
+    let mut library = MemoryLibrary::new();
+    let err = run_line(&mut library, "binders remove 1").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unknown binders command 'remove' (try --help)"
+    );
+    let err = run_line(&mut library, "binders list --verbose").unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "unrecognized option '--verbose' (try --help)"
+    );
+}
+
+#[test]
+fn songs_import_dry_run_then_commit() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(1, "Sopranos").unwrap();
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(
+        &mut file,
+        b"Piece,Composer,Binders\nGloria,Vivaldi,1\n,Bach,1\n",
+    )
+    .unwrap();
+    let path = file.path().to_str().unwrap();
+
+    let printed = run_args(
+        &mut library,
+        &["songs", "import", path, "--title", "Piece", "--dry-run"],
+    )
+    .unwrap();
+    assert_eq!(
+        printed,
+        "line 3: Title is empty. Row skipped.\n\
+         Dry run: 1 new song, 0 already in the library, 1 binder assignment, 1 row skipped. \
+         Nothing was saved.\n"
+    );
+    assert!(library.fetch_all_songs().unwrap().is_empty());
+
+    let json: Value = serde_json::from_str(
+        &run_args(
+            &mut library,
+            &["songs", "import", path, "--title=1", "--json"],
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(json["dry_run"], false);
+    assert_eq!(json["summary"]["new_songs"], 1);
+    assert_eq!(json["skipped"][0]["line"], 3);
+    assert_eq!(library.fetch_songs_for_binder(binder.id).unwrap().len(), 1);
+}
+
+#[test]
+fn export_writes_csv_to_stdout_or_files() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(1, "Sopranos").unwrap();
+    let song = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.add_song_to_binder(binder.id, song.id).unwrap();
+
+    let printed = run_line(&mut library, "export memberships").unwrap();
+    assert_eq!(
+        printed,
+        "binder_number,binder_label,song_id,title,composer\n1,Sopranos,1,Gloria,Vivaldi\n"
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let dir_arg = dir.path().to_str().unwrap();
+    let json: Value = serde_json::from_str(
+        &run_args(&mut library, &["export", "all", "--dir", dir_arg, "--json"]).unwrap(),
+    )
+    .unwrap();
+    let kinds: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["songs", "binders", "memberships"]);
+    assert_eq!(std::fs::read_dir(dir.path()).unwrap().count(), 3);
+
+    let err = run_line(&mut library, "export concerts").unwrap_err();
+    assert!(err.to_string().starts_with("unknown export 'concerts'"));
+}
+
+#[test]
+fn library_export_then_import_replace_or_merge() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(1, "Sopranos").unwrap();
+    let song = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.add_song_to_binder(binder.id, song.id).unwrap();
+
+    let file = tempfile::NamedTempFile::new().unwrap();
+    let path = file.path().to_str().unwrap();
+    let printed = run_args(&mut library, &["library", "export", "--output", path]).unwrap();
+    assert_eq!(
+        printed,
+        format!("Wrote 1 binders, 1 songs and 1 binder assignments to {path}.\n")
+    );
+
+    let mut restored = MemoryLibrary::new();
+    restored.create_song("Cantique", "Fauré").unwrap();
+    let printed = run_args(&mut restored, &["library", "import", path, "--replace"]).unwrap();
+    assert_eq!(
+        printed,
+        format!("Restored {path}: added 1 binders, 1 songs and 1 binder assignments.\n")
+    );
+    assert_eq!(
+        run_line(&mut restored, "library export").unwrap(),
+        run_line(&mut library, "library export").unwrap()
+    );
+
+    let json: Value = serde_json::from_str(
+        &run_args(
+            &mut restored,
+            &["library", "import", path, "--merge", "--json"],
+        )
+        .unwrap(),
+    )
+    .unwrap();
+    assert_eq!(json["songs_added"], 0);
+
+    let err = run_args(&mut restored, &["library", "import", path]).unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "library import needs exactly one of --replace or --merge"
+    );
+}
+
+#[test]
+fn attached_files_travel_with_library_exports() {
+    let dir = tempfile::tempdir().unwrap();
+    let score = dir.path().join("gloria.pdf");
+    std::fs::write(&score, b"%PDF-1.4 gloria").unwrap();
+    let store = AttachmentStore::new(dir.path().join("data").join("attachments"));
+    let mut library = MemoryLibrary::new();
+    library.create_song("Gloria", "Vivaldi").unwrap();
+
+    let run_with = |library: &mut MemoryLibrary, store: &AttachmentStore, args: &[&str]| {
+        let args: Vec<String> = args.iter().map(|arg| arg.to_string()).collect();
+        let mut out = Vec::new();
+        run(
+            RunContext::new(library, &mut out).with_attachments(store),
+            &args,
+        )
+        .map(|()| String::from_utf8(out).unwrap())
+    };
+    let score_path = score.to_str().unwrap();
+    let printed = run_with(
+        &mut library,
+        &store,
+        &["songs", "attach", "gloria", score_path],
+    )
+    .unwrap();
+    assert_eq!(printed, "Attached gloria.pdf (15 B) to Gloria - Vivaldi.\n");
+    let err = run_args(&mut library, &["songs", "attach", "gloria", score_path]).unwrap_err();
+    assert_eq!(err.to_string(), "attachments are not available here");
+
+    let export_dir = dir.path().join("export");
+    std::fs::create_dir(&export_dir).unwrap();
+    let json_path = export_dir.join("library.json");
+    let json_path = json_path.to_str().unwrap();
+    run_with(
+        &mut library,
+        &store,
+        &["library", "export", "--output", json_path],
+    )
+    .unwrap();
+    let attachment = library.fetch_attachments().unwrap().remove(0);
+    let exported = AttachmentStore::new(export_dir.join("attachments"));
+    assert!(exported.verified_path(&attachment).is_ok());
+
+    let elsewhere = AttachmentStore::new(dir.path().join("other").join("attachments"));
+    let mut restored = MemoryLibrary::new();
+    run_with(
+        &mut restored,
+        &elsewhere,
+        &["library", "import", json_path, "--replace"],
+    )
+    .unwrap();
+    assert_eq!(
+        restored.fetch_attachments().unwrap(),
+        vec![attachment.clone()]
+    );
+    assert!(elsewhere.verified_path(&attachment).is_ok());
+}
+
+#[test]
+fn binders_index_prints_text_html_or_files() {
+    let mut library = MemoryLibrary::new();
+    let sopranos = library.create_binder(1, "Sopranos").unwrap();
+    library.create_binder(2, "Altos").unwrap();
+    let song = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.add_song_to_binder(sopranos.id, song.id).unwrap();
+
+    let text = run_line(&mut library, "binders index --binder 1").unwrap();
+    assert_eq!(
+        text,
+        "Binder 01 – Sopranos\n====================\n\nNo.  Title   Composer\n  1  Gloria  Vivaldi\n"
+    );
+    let html = run_line(&mut library, "binders index --html").unwrap();
+    assert!(html.contains("<h1>Binder 01 – Sopranos</h1>"));
+    assert!(html.contains("<h1>Binder 02 – Altos</h1>"));
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("index.html");
+    let path = path.to_str().unwrap();
+    let printed = run_args(
+        &mut library,
+        &["binders", "index", "--html", "--output", path],
+    )
+    .unwrap();
+    assert_eq!(
+        printed,
+        format!("Wrote the index of 2 binders to {path}.\n")
+    );
+    assert_eq!(std::fs::read_to_string(path).unwrap(), html);
+
+    let err = run_line(&mut library, "binders index --binder 9").unwrap_err();
+    assert_eq!(err.to_string(), "Binder 09 not found");
+}
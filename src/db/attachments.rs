@@ -0,0 +1,260 @@
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+use rusqlite::{params, Connection, Row};
+use sha2::{Digest, Sha256};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+use super::library::Library;
+use crate::models::{stored_file_name, Attachment};
+
+/// Folder, next to the library file, that holds attached files. Backups and
+/// JSON exports keep their copies in a folder of the same name.
+pub const ATTACHMENT_DIR_NAME: &str = "attachments";
+
+fn attachment_from_row(row: &Row<'_>) -> rusqlite::Result<Attachment> {
+    Ok(Attachment {
+        id: row.get(0)?,
+        song_id: row.get(1)?,
+        file_name: row.get(2)?,
+        sha256: row.get(3)?,
+        size_bytes: row.get(4)?,
+        added_at: row.get(5)?,
+    })
+}
+
+/// Every attachment, ordered by song then the order they were added.
+pub fn fetch_attachments(conn: &Connection) -> DbResult<Vec<Attachment>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, file_name, sha256, size_bytes, added_at
+             FROM attachments
+             ORDER BY song_id, id",
+        )
+        .db_context("failed to prepare attachment query")?;
+
+    let attachments = stmt
+        .query_map([], attachment_from_row)
+        .db_context("failed to load attachments")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect attachments")?;
+
+    Ok(attachments)
+}
+
+/// Record a file already copied into the store. Use [`attach_file`] for the
+/// usual case.
+pub fn add_attachment(
+    conn: &Connection,
+    song_id: i64,
+    file_name: &str,
+    sha256: &str,
+    size_bytes: i64,
+    added_at: &str,
+) -> DbResult<Attachment> {
+    conn.execute(
+        "INSERT INTO attachments (song_id, file_name, sha256, size_bytes, added_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![song_id, file_name, sha256, size_bytes, added_at],
+    )
+    .db_context("failed to add attachment")?;
+
+    Ok(Attachment {
+        id: conn.last_insert_rowid(),
+        song_id,
+        file_name: file_name.to_string(),
+        sha256: sha256.to_string(),
+        size_bytes,
+        added_at: added_at.to_string(),
+    })
+}
+
+/// Insert an attachment under a known id, used when restoring a snapshot.
+pub fn restore_attachment(conn: &Connection, attachment: &Attachment) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO attachments (id, song_id, file_name, sha256, size_bytes, added_at)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            attachment.id,
+            attachment.song_id,
+            attachment.file_name,
+            attachment.sha256,
+            attachment.size_bytes,
+            attachment.added_at
+        ],
+    )
+    .db_context("failed to restore attachment")?;
+    Ok(())
+}
+
+/// Remove an attachment from its song. The stored file stays, since backups
+/// taken earlier may still refer to it.
+pub fn delete_attachment(conn: &Connection, id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute("DELETE FROM attachments WHERE id = ?1", params![id])
+        .db_context("failed to delete attachment")?;
+
+    if deleted == 0 {
+        Err(DbError::NotFound(Entity::Attachment))
+    } else {
+        Ok(())
+    }
+}
+
+/// Each song's attachments in the order they were added, keyed by song id.
+pub fn song_attachments(library: &dyn Library) -> DbResult<HashMap<i64, Vec<Attachment>>> {
+    let mut attachments: HashMap<i64, Vec<Attachment>> = HashMap::new();
+    for attachment in library.fetch_attachments()? {
+        attachments
+            .entry(attachment.song_id)
+            .or_default()
+            .push(attachment);
+    }
+    Ok(attachments)
+}
+
+/// Lowercase hex SHA-256 of `bytes`.
+pub fn sha256_hex(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect()
+}
+
+/// A folder of attached files named by checksum (see
+/// [`Attachment::stored_name`]). Files are only ever added: deleting an
+/// attachment leaves its file for older backups to use.
+#[derive(Debug, Clone)]
+pub struct AttachmentStore {
+    dir: PathBuf,
+}
+
+impl AttachmentStore {
+    pub fn new(dir: PathBuf) -> Self {
+        Self { dir }
+    }
+
+    /// Store attachments in `attachments/` next to the library file at
+    /// `db_path`.
+    pub fn beside(db_path: &Path) -> Self {
+        let parent = db_path.parent().unwrap_or_else(|| Path::new("."));
+        Self::new(parent.join(ATTACHMENT_DIR_NAME))
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Where the stored copy of `attachment` lives.
+    pub fn path_of(&self, attachment: &Attachment) -> PathBuf {
+        self.dir.join(attachment.stored_name())
+    }
+
+    /// The stored copy of `attachment`, after checking it is there and still
+    /// matches its checksum.
+    pub fn verified_path(&self, attachment: &Attachment) -> DbResult<PathBuf> {
+        let path = self.path_of(attachment);
+        let bytes = match fs::read(&path) {
+            Ok(bytes) => bytes,
+            Err(err) if err.kind() == ErrorKind::NotFound => {
+                return Err(DbError::AttachmentMissing(attachment.file_name.clone()))
+            }
+            Err(err) => return Err(err).db_context("failed to read attached file"),
+        };
+        if sha256_hex(&bytes) != attachment.sha256 {
+            return Err(DbError::AttachmentCorrupt(attachment.file_name.clone()));
+        }
+        Ok(path)
+    }
+
+    /// Copy `bytes` in under their checksum unless an identical file is
+    /// already there. Returns the checksum.
+    fn put(&self, bytes: &[u8], file_name: &str) -> DbResult<String> {
+        let sha256 = sha256_hex(bytes);
+        let path = self.dir.join(stored_file_name(&sha256, file_name));
+        if !path.exists() {
+            fs::create_dir_all(&self.dir).db_context("failed to create attachments folder")?;
+            // Write under a temporary name first so a half-written file never
+            // carries a checksum name.
+            let partial = path.with_extension("partial");
+            fs::write(&partial, bytes).db_context("failed to copy attached file")?;
+            fs::rename(&partial, &path).db_context("failed to store attached file")?;
+        }
+        Ok(sha256)
+    }
+
+    /// Copy the files of `attachments` that `target` lacks into it, skipping
+    /// any that are missing or damaged here. Returns how many were copied.
+    pub fn copy_into(
+        &self,
+        target: &AttachmentStore,
+        attachments: &[Attachment],
+    ) -> DbResult<usize> {
+        let mut copied = 0;
+        for attachment in attachments {
+            if target.path_of(attachment).exists() {
+                continue;
+            }
+            let Ok(path) = self.verified_path(attachment) else {
+                continue;
+            };
+            let bytes = fs::read(path).db_context("failed to read attached file")?;
+            target.put(&bytes, &attachment.file_name)?;
+            copied += 1;
+        }
+        Ok(copied)
+    }
+
+    /// Delete stored files that none of `keep` refers to.
+    pub(crate) fn retain(&self, keep: &[Attachment]) -> DbResult<()> {
+        let wanted: Vec<String> = keep.iter().map(Attachment::stored_name).collect();
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == ErrorKind::NotFound => return Ok(()),
+            Err(err) => return Err(err).db_context("failed to read attachments folder"),
+        };
+        for entry in entries.filter_map(Result::ok) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            if !wanted.contains(&name) {
+                fs::remove_file(entry.path()).db_context("failed to remove attached file")?;
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Copy the file at `source` into `store` and attach it to a song at
+/// `added_at` ([`TIMESTAMP_FORMAT`](super::TIMESTAMP_FORMAT)). If the
+/// song already has an identical file, that attachment is returned instead.
+pub fn attach_file(
+    library: &mut dyn Library,
+    store: &AttachmentStore,
+    song_id: i64,
+    source: &Path,
+    added_at: &str,
+) -> DbResult<Attachment> {
+    if !library
+        .fetch_all_songs()?
+        .iter()
+        .any(|song| song.id == song_id)
+    {
+        return Err(DbError::NotFound(Entity::Song));
+    }
+    let bytes = fs::read(source).db_context("failed to read the file to attach")?;
+    let file_name = source
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let sha256 = store.put(&bytes, &file_name)?;
+
+    if let Some(existing) = library
+        .fetch_attachments()?
+        .into_iter()
+        .find(|attachment| attachment.song_id == song_id && attachment.sha256 == sha256)
+    {
+        return Ok(existing);
+    }
+    library.add_attachment(song_id, &file_name, &sha256, bytes.len() as i64, added_at)
+}
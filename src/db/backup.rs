@@ -0,0 +1,309 @@
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use chrono::{Local, NaiveDateTime, TimeDelta};
+use rusqlite::{Connection, OpenFlags};
+
+use super::attachments::{AttachmentStore, ATTACHMENT_DIR_NAME};
+use super::error::{DbContext, DbError, DbResult};
+use super::library::Library;
+use crate::models::Attachment;
+
+/// Folder, next to the library file, that holds the rotating backups.
+pub const BACKUP_DIR_NAME: &str = "backups";
+/// Environment variable overriding how many backups are kept (0 turns them off).
+pub const BACKUP_ROTATIONS_ENV: &str = "CHOIR_BINDER_BACKUPS";
+/// Backups kept when nothing else is configured.
+pub const DEFAULT_BACKUP_ROTATIONS: usize = 10;
+
+/// File names look like `binders-20240501-193000123-startup.sqlite`; the
+/// millisecond timestamp sorts chronologically as plain text.
+const FILE_PREFIX: &str = "binders-";
+const FILE_EXTENSION: &str = "sqlite";
+const STAMP_FORMAT: &str = "%Y%m%d-%H%M%S%3f";
+const STAMP_LEN: usize = 18;
+
+/// Why a backup was taken. Stored in the file name and shown on the restore
+/// screen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BackupReason {
+    Startup,
+    DeleteBinder,
+    DeleteSong,
+    DeleteConcert,
+    DeleteChorister,
+    Restore,
+    Import,
+}
+
+impl BackupReason {
+    const ALL: [BackupReason; 7] = [
+        BackupReason::Startup,
+        BackupReason::DeleteBinder,
+        BackupReason::DeleteSong,
+        BackupReason::DeleteConcert,
+        BackupReason::DeleteChorister,
+        BackupReason::Restore,
+        BackupReason::Import,
+    ];
+
+    /// Short tag used in file names.
+    pub fn tag(self) -> &'static str {
+        match self {
+            BackupReason::Startup => "startup",
+            BackupReason::DeleteBinder => "delete-binder",
+            BackupReason::DeleteSong => "delete-song",
+            BackupReason::DeleteConcert => "delete-concert",
+            BackupReason::DeleteChorister => "delete-chorister",
+            BackupReason::Restore => "restore",
+            BackupReason::Import => "import",
+        }
+    }
+
+    /// Human-readable description.
+    pub fn describe(self) -> &'static str {
+        match self {
+            BackupReason::Startup => "on startup",
+            BackupReason::DeleteBinder => "before deleting a binder",
+            BackupReason::DeleteSong => "before deleting a song",
+            BackupReason::DeleteConcert => "before deleting a concert",
+            BackupReason::DeleteChorister => "before deleting a chorister",
+            BackupReason::Restore => "before restoring a backup",
+            BackupReason::Import => "before replacing the library",
+        }
+    }
+
+    fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|reason| reason.tag() == tag)
+    }
+}
+
+/// One backup file with the counts shown on the restore screen.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupInfo {
+    pub path: PathBuf,
+    pub taken_at: NaiveDateTime,
+    /// `None` for files whose tag this build does not know.
+    pub reason: Option<BackupReason>,
+    pub binders: usize,
+    pub songs: usize,
+}
+
+impl BackupInfo {
+    /// Description of why the backup exists, e.g. "before deleting a binder".
+    pub fn describe_reason(&self) -> &'static str {
+        self.reason.map_or("", BackupReason::describe)
+    }
+}
+
+/// A folder of rotating SQLite backups. Every new backup beyond `keep` removes
+/// the oldest one. With an attachment store the attached files are copied into
+/// an `attachments/` folder inside it, shared by every backup.
+#[derive(Debug, Clone)]
+pub struct BackupStore {
+    dir: PathBuf,
+    keep: usize,
+    attachments: Option<AttachmentStore>,
+}
+
+impl BackupStore {
+    pub fn new(dir: PathBuf, keep: usize) -> Self {
+        Self {
+            dir,
+            keep,
+            attachments: None,
+        }
+    }
+
+    /// Back up the files in `attachments` alongside the database.
+    pub fn with_attachments(mut self, attachments: AttachmentStore) -> Self {
+        self.attachments = Some(attachments);
+        self
+    }
+
+    /// Store backups in `backups/` next to the library file at `db_path`.
+    pub fn beside(db_path: &Path, keep: usize) -> Self {
+        let parent = db_path.parent().unwrap_or_else(|| Path::new("."));
+        Self::new(parent.join(BACKUP_DIR_NAME), keep)
+    }
+
+    pub fn dir(&self) -> &Path {
+        &self.dir
+    }
+
+    /// Whether backups are switched on (`keep` is above zero).
+    pub fn enabled(&self) -> bool {
+        self.keep > 0
+    }
+
+    /// Copy `library` into a new backup file, then drop backups beyond the
+    /// rotation limit. Returns `None` when backups are switched off.
+    pub fn create(
+        &self,
+        library: &dyn Library,
+        reason: BackupReason,
+    ) -> DbResult<Option<BackupInfo>> {
+        if !self.enabled() {
+            return Ok(None);
+        }
+        fs::create_dir_all(&self.dir).db_context("failed to create backup directory")?;
+
+        // Two backups in the same millisecond get consecutive timestamps so
+        // names stay unique and ordered.
+        let mut taken_at = Local::now().naive_local();
+        let mut path = self.path_for(taken_at, reason);
+        while path.exists() {
+            taken_at += TimeDelta::milliseconds(1);
+            path = self.path_for(taken_at, reason);
+        }
+
+        library.backup_to(&path)?;
+        if let Some(attachments) = &self.attachments {
+            attachments.copy_into(&self.attachment_copies(), &library.fetch_attachments()?)?;
+        }
+        self.prune()?;
+        read_info(&path).map(Some)
+    }
+
+    /// Every readable backup, newest first.
+    pub fn list(&self) -> DbResult<Vec<BackupInfo>> {
+        let mut backups: Vec<BackupInfo> = self
+            .backup_files()?
+            .iter()
+            .filter_map(|path| read_info(path).ok())
+            .collect();
+        backups.sort_by_key(|backup| std::cmp::Reverse(backup.taken_at));
+        Ok(backups)
+    }
+
+    /// Replace `library` with `backup`, taking a fresh backup of the current
+    /// data first so the restore itself can be undone. Attached files the
+    /// restored library needs are copied back from the backup folder.
+    pub fn restore(&self, library: &mut dyn Library, backup: &BackupInfo) -> DbResult<()> {
+        self.create(library, BackupReason::Restore)?;
+        library.restore_from(&backup.path)?;
+        if let Some(attachments) = &self.attachments {
+            self.attachment_copies()
+                .copy_into(attachments, &library.fetch_attachments()?)?;
+        }
+        Ok(())
+    }
+
+    fn attachment_copies(&self) -> AttachmentStore {
+        AttachmentStore::new(self.dir.join(ATTACHMENT_DIR_NAME))
+    }
+
+    fn path_for(&self, taken_at: NaiveDateTime, reason: BackupReason) -> PathBuf {
+        self.dir.join(format!(
+            "{FILE_PREFIX}{}-{}.{FILE_EXTENSION}",
+            taken_at.format(STAMP_FORMAT),
+            reason.tag()
+        ))
+    }
+
+    /// Backup files in the folder, oldest first by name.
+    fn backup_files(&self) -> DbResult<Vec<PathBuf>> {
+        let entries = match fs::read_dir(&self.dir) {
+            Ok(entries) => entries,
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(err) => return Err(err).db_context("failed to read backup directory"),
+        };
+        let mut files: Vec<PathBuf> = entries
+            .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+            .filter(|path| parse_file_name(path).is_some())
+            .collect();
+        files.sort();
+        Ok(files)
+    }
+
+    fn prune(&self) -> DbResult<()> {
+        let files = self.backup_files()?;
+        let excess = files.len().saturating_sub(self.keep);
+        for path in &files[..excess] {
+            fs::remove_file(path).db_context("failed to remove old backup")?;
+        }
+        if excess > 0 && self.attachments.is_some() {
+            // Keep only the attached files some remaining backup still lists.
+            // If a backup cannot be read, keep every file rather than guess.
+            let mut keep = Vec::new();
+            for path in &files[excess..] {
+                match read_attachments(path) {
+                    Ok(attachments) => keep.extend(attachments),
+                    Err(_) => return Ok(()),
+                }
+            }
+            self.attachment_copies().retain(&keep)?;
+        }
+        Ok(())
+    }
+}
+
+/// How many backups to keep: the explicit value (the `--backups` flag) wins
+/// over `CHOIR_BINDER_BACKUPS`, which wins over the default.
+pub fn resolve_backup_rotations(explicit: Option<usize>) -> DbResult<usize> {
+    if let Some(keep) = explicit {
+        return Ok(keep);
+    }
+    match env::var(BACKUP_ROTATIONS_ENV) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.trim().parse().map_err(|_| DbError::InvalidSetting {
+                name: BACKUP_ROTATIONS_ENV,
+                value,
+            })
+        }
+        _ => Ok(DEFAULT_BACKUP_ROTATIONS),
+    }
+}
+
+/// Split a backup file name into its timestamp and reason tag.
+fn parse_file_name(path: &Path) -> Option<(NaiveDateTime, &str)> {
+    if path.extension()? != FILE_EXTENSION {
+        return None;
+    }
+    let stem = path.file_stem()?.to_str()?.strip_prefix(FILE_PREFIX)?;
+    let stamp = stem.get(..STAMP_LEN)?;
+    let tag = stem.get(STAMP_LEN..)?.strip_prefix('-')?;
+    let taken_at = NaiveDateTime::parse_from_str(stamp, STAMP_FORMAT).ok()?;
+    Some((taken_at, tag))
+}
+
+/// Open a backup read-only and list its attachment records. Backups taken
+/// before attachments existed have none.
+fn read_attachments(path: &Path) -> DbResult<Vec<Attachment>> {
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .db_context("failed to open backup")?;
+    let has_table: bool = conn
+        .query_row(
+            "SELECT EXISTS (SELECT 1 FROM sqlite_master WHERE type = 'table' AND name = 'attachments')",
+            [],
+            |row| row.get(0),
+        )
+        .db_context("failed to read backup")?;
+    if !has_table {
+        return Ok(Vec::new());
+    }
+    super::attachments::fetch_attachments(&conn)
+}
+
+/// Open a backup read-only and count its binders and songs.
+fn read_info(path: &Path) -> DbResult<BackupInfo> {
+    let (taken_at, tag) =
+        parse_file_name(path).ok_or_else(|| DbError::NotABackup(path.display().to_string()))?;
+    let conn = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+        .db_context("failed to open backup")?;
+    let count = |table: &str| -> DbResult<usize> {
+        conn.query_row(&format!("SELECT COUNT(*) FROM {table}"), [], |row| {
+            row.get::<_, i64>(0)
+        })
+        .map(|count| count as usize)
+        .db_context("failed to read backup")
+    };
+    Ok(BackupInfo {
+        path: path.to_path_buf(),
+        taken_at,
+        reason: BackupReason::from_tag(tag),
+        binders: count("binders")?,
+        songs: count("songs")?,
+    })
+}
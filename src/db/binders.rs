@@ -1,14 +1,14 @@
-use anyhow::{anyhow, Context, Result};
-use rusqlite::{params, Connection, Error as SqlError, ErrorCode};
+use rusqlite::{params, Connection, Error as SqlError};
 
+use super::error::{is_unique_violation, DbContext, DbError, DbResult, Entity};
 use crate::models::Binder;
 
 /// Retrieve every binder sorted numerically. The query doubles as the single
 /// source of truth for how we order binders in the UI.
-pub fn fetch_binders(conn: &Connection) -> Result<Vec<Binder>> {
+pub fn fetch_binders(conn: &Connection) -> DbResult<Vec<Binder>> {
     let mut stmt = conn
-        .prepare("SELECT id, number, label FROM binders ORDER BY number")
-        .context("failed to prepare binder query")?;
+        .prepare("SELECT id, number, label, holder_id, voice_part FROM binders ORDER BY number")
+        .db_context("failed to prepare binder query")?;
 
     let binders = stmt
         .query_map([], |row| {
@@ -16,47 +16,100 @@ pub fn fetch_binders(conn: &Connection) -> Result<Vec<Binder>> {
                 id: row.get(0)?,
                 number: row.get(1)?,
                 label: row.get(2)?,
+                holder_id: row.get(3)?,
+                voice_part: row.get(4)?,
             })
         })
-        .context("failed to load binders")?
+        .db_context("failed to load binders")?
         .collect::<Result<Vec<_>, _>>()
-        .context("failed to collect binders")?;
+        .db_context("failed to collect binders")?;
 
     Ok(binders)
 }
 
 /// Insert a new binder row, returning the hydrated struct so the caller can
 /// push it straight into the in-memory list.
-pub fn create_binder(conn: &Connection, number: i64, label: &str) -> Result<Binder> {
+pub fn create_binder(conn: &Connection, number: i64, label: &str) -> DbResult<Binder> {
     conn.execute(
         "INSERT INTO binders (number, label) VALUES (?1, ?2)",
         params![number, label],
     )
-    .map_err(|err| map_unique_constraint(err, number))
-    .context("failed to insert binder")?;
+    .map_err(|err| map_unique_constraint(err, number, "failed to insert binder"))?;
 
     let id = conn.last_insert_rowid();
     Ok(Binder {
         id,
         number,
         label: label.to_string(),
+        holder_id: None,
+        voice_part: String::new(),
     })
 }
 
-/// Update the number and label for an existing binder. We surface a custom
-/// error when nothing was updated so the UI can show a friendly message instead
-/// of silently continuing.
-pub fn update_binder(conn: &Connection, id: i64, number: i64, label: &str) -> Result<()> {
+/// Insert a binder under a known id, used when restoring a snapshot so ids
+/// survive the round trip.
+pub fn restore_binder(conn: &Connection, binder: &Binder) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO binders (id, number, label, holder_id, voice_part)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            binder.id,
+            binder.number,
+            binder.label,
+            binder.holder_id,
+            binder.voice_part
+        ],
+    )
+    .map_err(|err| map_unique_constraint(err, binder.number, "failed to restore binder"))?;
+    Ok(())
+}
+
+/// Update the number and label for an existing binder. We surface
+/// [`DbError::NotFound`] when nothing was updated so the UI can show a friendly
+/// message instead of silently continuing.
+pub fn update_binder(conn: &Connection, id: i64, number: i64, label: &str) -> DbResult<()> {
     let updated = conn
         .execute(
             "UPDATE binders SET number = ?1, label = ?2 WHERE id = ?3",
             params![number, label, id],
         )
-        .map_err(|err| map_unique_constraint(err, number))
-        .context("failed to update binder")?;
+        .map_err(|err| map_unique_constraint(err, number, "failed to update binder"))?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Binder))
+    } else {
+        Ok(())
+    }
+}
+
+/// Hand a binder to a chorister, or take it back with `None`. Fails with
+/// [`DbError::ForeignKey`] if the chorister does not exist.
+pub fn assign_binder(conn: &Connection, id: i64, holder_id: Option<i64>) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE binders SET holder_id = ?1 WHERE id = ?2",
+            params![holder_id, id],
+        )
+        .db_context("failed to assign binder")?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Binder))
+    } else {
+        Ok(())
+    }
+}
+
+/// Set the voice part a binder is for; an empty part means every song.
+pub fn set_binder_part(conn: &Connection, id: i64, voice_part: &str) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE binders SET voice_part = ?1 WHERE id = ?2",
+            params![voice_part, id],
+        )
+        .db_context("failed to set binder voice part")?;
 
     if updated == 0 {
-        Err(anyhow!("Binder not found"))
+        Err(DbError::NotFound(Entity::Binder))
     } else {
         Ok(())
     }
@@ -64,28 +117,24 @@ pub fn update_binder(conn: &Connection, id: i64, number: i64, label: &str) -> Re
 
 /// Remove a binder row. The database schema cascades to `binder_songs`, so we
 /// do not have to delete the join table rows manually.
-pub fn delete_binder(conn: &Connection, id: i64) -> Result<()> {
+pub fn delete_binder(conn: &Connection, id: i64) -> DbResult<()> {
     let deleted = conn
         .execute("DELETE FROM binders WHERE id = ?1", params![id])
-        .context("failed to delete binder")?;
+        .db_context("failed to delete binder")?;
 
     if deleted == 0 {
-        Err(anyhow!("Binder not found"))
+        Err(DbError::NotFound(Entity::Binder))
     } else {
         Ok(())
     }
 }
 
-/// Coerce SQLite constraint errors into human-readable messages. Right now the
-/// only constraint we guard is the uniqueness of binder numbers, but keeping
-/// this helper isolated prepares us for future constraints.
-fn map_unique_constraint(err: SqlError, number: i64) -> anyhow::Error {
-    if matches!(
-        err.sqlite_error_code(),
-        Some(ErrorCode::ConstraintViolation)
-    ) {
-        anyhow!("Binder number {number} already exists.")
+/// Turn a UNIQUE violation on `binders.number` into
+/// [`DbError::DuplicateBinderNumber`]; other failures keep their context.
+fn map_unique_constraint(err: SqlError, number: i64, context: &'static str) -> DbError {
+    if is_unique_violation(&err) {
+        DbError::DuplicateBinderNumber(number)
     } else {
-        err.into()
+        DbError::from_sqlite(context, err)
     }
 }
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+use std::env;
+
+use rusqlite::{params, Connection, Row};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+use super::library::Library;
+use crate::models::BinderCheckout;
+
+/// Environment variable overriding how many days a binder may stay out.
+pub const OVERDUE_DAYS_ENV: &str = "CHOIR_BINDER_OVERDUE_DAYS";
+/// Days a binder may stay out before the overdue report lists it.
+pub const DEFAULT_OVERDUE_DAYS: u32 = 28;
+
+fn checkout_from_row(row: &Row<'_>) -> rusqlite::Result<BinderCheckout> {
+    Ok(BinderCheckout {
+        id: row.get(0)?,
+        binder_id: row.get(1)?,
+        chorister_id: row.get(2)?,
+        checked_out_at: row.get(3)?,
+        checked_in_at: row.get(4)?,
+    })
+}
+
+/// The whole checkout log, oldest first.
+pub fn fetch_checkouts(conn: &Connection) -> DbResult<Vec<BinderCheckout>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, binder_id, chorister_id, checked_out_at, checked_in_at
+             FROM binder_checkouts
+             ORDER BY checked_out_at, id",
+        )
+        .db_context("failed to prepare checkout query")?;
+
+    let checkouts = stmt
+        .query_map([], checkout_from_row)
+        .db_context("failed to load checkouts")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect checkouts")?;
+
+    Ok(checkouts)
+}
+
+/// Add an entry to the log as given. Use [`check_out_binder`] for the usual
+/// case; this is the building block it and merge-restores share.
+pub fn record_checkout(
+    conn: &Connection,
+    binder_id: i64,
+    chorister_id: Option<i64>,
+    checked_out_at: &str,
+    checked_in_at: Option<&str>,
+) -> DbResult<BinderCheckout> {
+    conn.execute(
+        "INSERT INTO binder_checkouts (binder_id, chorister_id, checked_out_at, checked_in_at)
+         VALUES (?1, ?2, ?3, ?4)",
+        params![binder_id, chorister_id, checked_out_at, checked_in_at],
+    )
+    .db_context("failed to record checkout")?;
+
+    Ok(BinderCheckout {
+        id: conn.last_insert_rowid(),
+        binder_id,
+        chorister_id,
+        checked_out_at: checked_out_at.to_string(),
+        checked_in_at: checked_in_at.map(str::to_string),
+    })
+}
+
+/// Insert a checkout under a known id, used when restoring a snapshot.
+pub fn restore_checkout(conn: &Connection, checkout: &BinderCheckout) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO binder_checkouts
+             (id, binder_id, chorister_id, checked_out_at, checked_in_at)
+         VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![
+            checkout.id,
+            checkout.binder_id,
+            checkout.chorister_id,
+            checkout.checked_out_at,
+            checkout.checked_in_at
+        ],
+    )
+    .db_context("failed to restore checkout")?;
+    Ok(())
+}
+
+/// Stamp an open checkout with the time the binder came back.
+pub fn close_checkout(conn: &Connection, id: i64, checked_in_at: &str) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE binder_checkouts SET checked_in_at = ?1
+             WHERE id = ?2 AND checked_in_at IS NULL",
+            params![checked_in_at, id],
+        )
+        .db_context("failed to close checkout")?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Checkout))
+    } else {
+        Ok(())
+    }
+}
+
+/// The open checkout of every binder that is currently out, keyed by binder
+/// id. Binders missing from the map are on the shelf.
+pub fn open_checkouts(library: &dyn Library) -> DbResult<HashMap<i64, BinderCheckout>> {
+    Ok(library
+        .fetch_checkouts()?
+        .into_iter()
+        .filter(BinderCheckout::is_open)
+        .map(|checkout| (checkout.binder_id, checkout))
+        .collect())
+}
+
+/// Check a binder out to its current holder at `at`
+/// ([`TIMESTAMP_FORMAT`](super::TIMESTAMP_FORMAT)). Fails with
+/// [`DbError::AlreadyCheckedOut`] if it is already out.
+pub fn check_out_binder(
+    library: &mut dyn Library,
+    binder_id: i64,
+    at: &str,
+) -> DbResult<BinderCheckout> {
+    let binder = library
+        .fetch_binders()?
+        .into_iter()
+        .find(|binder| binder.id == binder_id)
+        .ok_or(DbError::NotFound(Entity::Binder))?;
+    if open_checkouts(library)?.contains_key(&binder_id) {
+        return Err(DbError::AlreadyCheckedOut { binder_id });
+    }
+    library.record_checkout(binder_id, binder.holder_id, at, None)
+}
+
+/// Put a binder back on the shelf at `at`, closing its open checkout and
+/// returning it. Fails with [`DbError::NotCheckedOut`] if it is not out.
+pub fn check_in_binder(
+    library: &mut dyn Library,
+    binder_id: i64,
+    at: &str,
+) -> DbResult<BinderCheckout> {
+    let mut checkout = open_checkouts(library)?
+        .remove(&binder_id)
+        .ok_or(DbError::NotCheckedOut { binder_id })?;
+    library.close_checkout(checkout.id, at)?;
+    checkout.checked_in_at = Some(at.to_string());
+    Ok(checkout)
+}
+
+/// How many days a binder may stay out: the explicit value (the
+/// `--overdue-days` flag) wins over `CHOIR_BINDER_OVERDUE_DAYS`, which wins
+/// over the default.
+pub fn resolve_overdue_days(explicit: Option<u32>) -> DbResult<u32> {
+    if let Some(days) = explicit {
+        return Ok(days);
+    }
+    match env::var(OVERDUE_DAYS_ENV) {
+        Ok(value) if !value.trim().is_empty() => {
+            value.trim().parse().map_err(|_| DbError::InvalidSetting {
+                name: OVERDUE_DAYS_ENV,
+                value,
+            })
+        }
+        _ => Ok(DEFAULT_OVERDUE_DAYS),
+    }
+}
@@ -0,0 +1,103 @@
+use rusqlite::{params, Connection, Row};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+use crate::models::Chorister;
+
+fn chorister_from_row(row: &Row<'_>) -> rusqlite::Result<Chorister> {
+    Ok(Chorister {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        voice_part: row.get(2)?,
+        contact: row.get(3)?,
+    })
+}
+
+/// Every chorister, ordered case-insensitively by name.
+pub fn fetch_choristers(conn: &Connection) -> DbResult<Vec<Chorister>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, voice_part, contact
+             FROM choristers
+             ORDER BY name COLLATE NOCASE, id",
+        )
+        .db_context("failed to prepare chorister query")?;
+
+    let choristers = stmt
+        .query_map([], chorister_from_row)
+        .db_context("failed to load choristers")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect choristers")?;
+
+    Ok(choristers)
+}
+
+/// Insert a chorister and hand back the stored row.
+pub fn create_chorister(
+    conn: &Connection,
+    name: &str,
+    voice_part: &str,
+    contact: &str,
+) -> DbResult<Chorister> {
+    conn.execute(
+        "INSERT INTO choristers (name, voice_part, contact) VALUES (?1, ?2, ?3)",
+        params![name, voice_part, contact],
+    )
+    .db_context("failed to insert chorister")?;
+
+    Ok(Chorister {
+        id: conn.last_insert_rowid(),
+        name: name.to_string(),
+        voice_part: voice_part.to_string(),
+        contact: contact.to_string(),
+    })
+}
+
+/// Insert a chorister under a known id, used when restoring a snapshot.
+pub fn restore_chorister(conn: &Connection, chorister: &Chorister) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO choristers (id, name, voice_part, contact) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            chorister.id,
+            chorister.name,
+            chorister.voice_part,
+            chorister.contact
+        ],
+    )
+    .db_context("failed to restore chorister")?;
+    Ok(())
+}
+
+/// Replace a chorister's name, voice part and contact.
+pub fn update_chorister(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+    voice_part: &str,
+    contact: &str,
+) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE choristers SET name = ?1, voice_part = ?2, contact = ?3 WHERE id = ?4",
+            params![name, voice_part, contact, id],
+        )
+        .db_context("failed to update chorister")?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Chorister))
+    } else {
+        Ok(())
+    }
+}
+
+/// Delete a chorister. Binders they held become unassigned.
+pub fn delete_chorister(conn: &Connection, id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute("DELETE FROM choristers WHERE id = ?1", params![id])
+        .db_context("failed to delete chorister")?;
+
+    if deleted == 0 {
+        Err(DbError::NotFound(Entity::Chorister))
+    } else {
+        Ok(())
+    }
+}
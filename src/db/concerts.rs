@@ -0,0 +1,192 @@
+use rusqlite::{params, Connection, Row};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+use super::songs::song_from_row;
+use crate::models::{Concert, Song};
+
+fn concert_from_row(row: &Row<'_>) -> rusqlite::Result<Concert> {
+    Ok(Concert {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        date: row.get(2)?,
+        venue: row.get(3)?,
+    })
+}
+
+/// Every concert, soonest date first. Concerts without a date yet come last,
+/// and ties fall back to the name.
+pub fn fetch_concerts(conn: &Connection) -> DbResult<Vec<Concert>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, name, date, venue
+             FROM concerts
+             ORDER BY date = '', date, name COLLATE NOCASE, id",
+        )
+        .db_context("failed to prepare concert query")?;
+
+    let concerts = stmt
+        .query_map([], concert_from_row)
+        .db_context("failed to load concerts")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect concerts")?;
+
+    Ok(concerts)
+}
+
+/// Insert a concert and hand back the stored row.
+pub fn create_concert(conn: &Connection, name: &str, date: &str, venue: &str) -> DbResult<Concert> {
+    conn.execute(
+        "INSERT INTO concerts (name, date, venue) VALUES (?1, ?2, ?3)",
+        params![name, date, venue],
+    )
+    .db_context("failed to insert concert")?;
+
+    Ok(Concert {
+        id: conn.last_insert_rowid(),
+        name: name.to_string(),
+        date: date.to_string(),
+        venue: venue.to_string(),
+    })
+}
+
+/// Insert a concert under a known id, used when restoring a snapshot so ids
+/// survive the round trip.
+pub fn restore_concert(conn: &Connection, concert: &Concert) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO concerts (id, name, date, venue) VALUES (?1, ?2, ?3, ?4)",
+        params![concert.id, concert.name, concert.date, concert.venue],
+    )
+    .db_context("failed to restore concert")?;
+    Ok(())
+}
+
+/// Replace a concert's name, date and venue.
+pub fn update_concert(
+    conn: &Connection,
+    id: i64,
+    name: &str,
+    date: &str,
+    venue: &str,
+) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE concerts SET name = ?1, date = ?2, venue = ?3 WHERE id = ?4",
+            params![name, date, venue, id],
+        )
+        .db_context("failed to update concert")?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Concert))
+    } else {
+        Ok(())
+    }
+}
+
+/// Delete a concert. Its setlist rows cascade with it; the songs stay.
+pub fn delete_concert(conn: &Connection, id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute("DELETE FROM concerts WHERE id = ?1", params![id])
+        .db_context("failed to delete concert")?;
+
+    if deleted == 0 {
+        Err(DbError::NotFound(Entity::Concert))
+    } else {
+        Ok(())
+    }
+}
+
+/// Songs on a concert's setlist in running order.
+pub fn fetch_setlist(conn: &Connection, concert_id: i64) -> DbResult<Vec<Song>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT s.id, s.title, s.composer, s.parts,
+                    s.arranger, s.voicing, s.accompaniment, s.language, s.publisher,
+                    s.catalog_number, s.duration_seconds, s.page_count
+             FROM songs s
+             INNER JOIN concert_songs cs ON cs.song_id = s.id
+             WHERE cs.concert_id = ?1
+             ORDER BY cs.position, s.title COLLATE NOCASE, s.composer COLLATE NOCASE",
+        )
+        .db_context("failed to prepare setlist query")?;
+
+    let songs = stmt
+        .query_map([concert_id], song_from_row)
+        .db_context("failed to iterate setlist")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect setlist")?;
+
+    Ok(songs)
+}
+
+/// Append a song to the end of a setlist. Adding a song that is already on it
+/// is a no-op, mirroring binder links.
+pub fn add_song_to_setlist(conn: &Connection, concert_id: i64, song_id: i64) -> DbResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO concert_songs (concert_id, song_id, position)
+         VALUES (
+             ?1, ?2,
+             (SELECT COALESCE(MAX(position), 0) + 1 FROM concert_songs WHERE concert_id = ?1)
+         )",
+        params![concert_id, song_id],
+    )
+    .db_context("failed to add song to setlist")?;
+    Ok(())
+}
+
+/// Take a song off a setlist, surfacing [`DbError::NotInSetlist`] if it was
+/// not there.
+pub fn remove_song_from_setlist(conn: &Connection, concert_id: i64, song_id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute(
+            "DELETE FROM concert_songs WHERE concert_id = ?1 AND song_id = ?2",
+            params![concert_id, song_id],
+        )
+        .db_context("failed to remove song from setlist")?;
+
+    if deleted == 0 {
+        Err(DbError::NotInSetlist {
+            concert_id,
+            song_id,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+/// Put a setlist in the given order, following the same rules as
+/// [`set_binder_order`](super::songs::set_binder_order).
+pub fn set_setlist_order(conn: &Connection, concert_id: i64, song_ids: &[i64]) -> DbResult<()> {
+    let current: Vec<i64> = fetch_setlist(conn, concert_id)?
+        .into_iter()
+        .map(|song| song.id)
+        .collect();
+    if let Some(&song_id) = song_ids.iter().find(|id| !current.contains(id)) {
+        return Err(DbError::NotInSetlist {
+            concert_id,
+            song_id,
+        });
+    }
+    let order = song_ids
+        .iter()
+        .chain(current.iter().filter(|id| !song_ids.contains(id)));
+
+    conn.execute_batch("SAVEPOINT set_setlist_order")
+        .db_context("failed to start reordering")?;
+    let result = order.enumerate().try_for_each(|(index, song_id)| {
+        conn.execute(
+            "UPDATE concert_songs SET position = ?1 WHERE concert_id = ?2 AND song_id = ?3",
+            params![index as i64 + 1, concert_id, song_id],
+        )
+        .map(|_| ())
+        .db_context("failed to reorder setlist")
+    });
+    match result {
+        Ok(()) => conn
+            .execute_batch("RELEASE set_setlist_order")
+            .db_context("failed to finish reordering"),
+        Err(err) => {
+            let _ = conn.execute_batch("ROLLBACK TO set_setlist_order; RELEASE set_setlist_order");
+            Err(err)
+        }
+    }
+}
@@ -1,68 +1,133 @@
+use std::env;
 use std::fs;
-use std::path::PathBuf;
+use std::io;
+use std::path::{Path, PathBuf};
 
-use anyhow::{anyhow, Context, Result};
 use directories::BaseDirs;
-use rusqlite::Connection;
+use rusqlite::{Connection, MAIN_DB};
+
+use super::error::{DbContext, DbError, DbResult};
+use super::migrations::{latest_version, run_migrations, schema_version};
 
 /// Folder name used beneath the user's home directory for application data.
 const DATA_DIR_NAME: &str = ".choir-binder-manager";
 /// SQLite file name stored inside the application data directory.
 const DB_FILE_NAME: &str = "binders.sqlite";
+/// Environment variable that points the app at a different library file.
+pub const DB_PATH_ENV: &str = "CHOIR_BINDER_DB";
 
-/// Ensure the database file exists, run lazy migrations, and return a live
-/// connection. The function also toggles `PRAGMA foreign_keys = ON` so the
-/// referential integrity checks in our schema behave the same during tests and
-/// production runs.
-pub fn ensure_schema() -> Result<Connection> {
-    let db_path = db_path()?;
+/// Open the library named by `CHOIR_BINDER_DB`, falling back to the default
+/// file in the user's home directory.
+pub fn ensure_schema() -> DbResult<Connection> {
+    ensure_schema_at(&db_path()?)
+}
 
-    if let Some(parent) = db_path.parent() {
-        fs::create_dir_all(parent).context("failed to create data directory")?;
+/// Ensure the database file at `path` exists, bring its schema up to date, and
+/// return a live connection. Missing parent directories are created so a fresh
+/// library can be started anywhere the user has write access.
+pub fn ensure_schema_at(path: &Path) -> DbResult<Connection> {
+    if let Some(parent) = path.parent() {
+        if !parent.as_os_str().is_empty() {
+            fs::create_dir_all(parent).db_context("failed to create data directory")?;
+        }
     }
 
-    let conn = Connection::open(&db_path).context("failed to open SQLite database")?;
+    let existed = path.metadata().map(|meta| meta.len() > 0).unwrap_or(false);
+
+    let conn = Connection::open(path).db_context("failed to open SQLite database")?;
+    let backup_source = if existed { Some(path) } else { None };
+    prepare_connection(conn, backup_source)
+}
+
+/// Build a throwaway library that lives only as long as the returned
+/// connection. Tests and external tools use this to get a fully migrated schema
+/// without touching the filesystem.
+pub fn ensure_schema_in_memory() -> DbResult<Connection> {
+    let conn = Connection::open_in_memory().db_context("failed to open in-memory database")?;
+    prepare_connection(conn, None)
+}
+
+/// Load the library file at `path` into a private in-memory connection and
+/// bring its schema up to date, leaving the file itself untouched. Backups are
+/// read this way so restoring an older backup never migrates it in place.
+pub(crate) fn load_into_memory(path: &Path) -> DbResult<Connection> {
+    if !path.is_file() {
+        return Err(DbError::Io {
+            context: "failed to open backup",
+            source: io::Error::new(io::ErrorKind::NotFound, path.display().to_string()),
+        });
+    }
+    let mut conn = Connection::open_in_memory().db_context("failed to open in-memory database")?;
+    conn.restore(MAIN_DB, path, None::<fn(rusqlite::backup::Progress)>)
+        .db_context("failed to read backup")?;
+    prepare_connection(conn, None)
+}
+
+/// Resolve which library file to open. An explicit path (the `--db` flag) wins
+/// over the environment variable, which in turn wins over the default location.
+pub fn resolve_db_path(explicit: Option<PathBuf>) -> DbResult<PathBuf> {
+    match explicit {
+        Some(path) => Ok(path),
+        None => db_path(),
+    }
+}
+
+/// Shared setup for every connection: refuse schemas from the future, back up
+/// on-disk libraries, and apply pending migrations. The function also toggles
+/// `PRAGMA foreign_keys = ON` so the referential integrity checks in our schema
+/// behave the same during tests and production runs.
+fn prepare_connection(mut conn: Connection, backup_source: Option<&Path>) -> DbResult<Connection> {
     conn.execute("PRAGMA foreign_keys = ON", [])
-        .context("failed to enable foreign keys")?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS binders (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            number INTEGER NOT NULL UNIQUE,
-            label TEXT NOT NULL
-        )",
-        [],
-    )
-    .context("failed to create binders table")?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS songs (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            title TEXT NOT NULL,
-            composer TEXT,
-            link TEXT
-        )",
-        [],
-    )
-    .context("failed to create songs table")?;
-
-    conn.execute(
-        "CREATE TABLE IF NOT EXISTS binder_songs (
-            binder_id INTEGER NOT NULL,
-            song_id INTEGER NOT NULL,
-            PRIMARY KEY (binder_id, song_id),
-            FOREIGN KEY(binder_id) REFERENCES binders(id) ON DELETE CASCADE,
-            FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
-        )",
-        [],
-    )
-    .context("failed to create binder_songs table")?;
+        .db_context("failed to enable foreign keys")?;
+
+    let current = schema_version(&conn)?;
+    let latest = latest_version();
+    if current > latest {
+        return Err(DbError::SchemaTooNew {
+            found: current,
+            supported: latest,
+        });
+    }
+
+    if current < latest {
+        if let Some(path) = backup_source {
+            backup_before_migration(path, current)?;
+        }
+    }
+
+    run_migrations(&mut conn)?;
 
     Ok(conn)
 }
 
-/// Resolve the absolute path to the SQLite database inside the user's home.
-fn db_path() -> Result<PathBuf> {
-    let base_dirs = BaseDirs::new().ok_or_else(|| anyhow!("could not locate home directory"))?;
+/// Copy the database file aside before touching its schema. Nothing has been
+/// written through the fresh connection yet, so a plain file copy is a
+/// consistent snapshot. An earlier copy of the same version is never
+/// overwritten; later ones are numbered `.v<N>.2.bak`, `.v<N>.3.bak` and so on.
+fn backup_before_migration(db_path: &Path, version: i64) -> DbResult<PathBuf> {
+    let file_name = db_path
+        .file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| DB_FILE_NAME.to_string());
+    let backup_path = (1..)
+        .map(|copy| match copy {
+            1 => db_path.with_file_name(format!("{file_name}.v{version}.bak")),
+            _ => db_path.with_file_name(format!("{file_name}.v{version}.{copy}.bak")),
+        })
+        .find(|path| !path.exists())
+        .expect("some backup name is free");
+    fs::copy(db_path, &backup_path).db_context("failed to back up database before migrating")?;
+    Ok(backup_path)
+}
+
+/// Resolve the database path from `CHOIR_BINDER_DB`, or the default file inside
+/// the user's home when the variable is unset or empty.
+fn db_path() -> DbResult<PathBuf> {
+    if let Some(value) = env::var_os(DB_PATH_ENV) {
+        if !value.is_empty() {
+            return Ok(PathBuf::from(value));
+        }
+    }
+    let base_dirs = BaseDirs::new().ok_or(DbError::NoHomeDirectory)?;
     Ok(base_dirs.home_dir().join(DATA_DIR_NAME).join(DB_FILE_NAME))
 }
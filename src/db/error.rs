@@ -0,0 +1,168 @@
+use std::fmt;
+use std::io;
+
+use rusqlite::ErrorCode;
+use thiserror::Error;
+
+/// Extended SQLite result code reported when a foreign key check fails.
+const SQLITE_CONSTRAINT_FOREIGNKEY: i32 = 787;
+/// Extended SQLite result code reported when a UNIQUE index rejects a row.
+const SQLITE_CONSTRAINT_UNIQUE: i32 = 2067;
+
+/// Result alias used by every function in the persistence layer.
+pub type DbResult<T> = Result<T, DbError>;
+
+/// Record kinds that lookups can fail to find. Kept separate from the error so
+/// callers can match on "which thing went missing" without parsing text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Entity {
+    Binder,
+    Song,
+    Concert,
+    Performance,
+    Chorister,
+    Checkout,
+    Tag,
+    Link,
+    Attachment,
+}
+
+impl fmt::Display for Entity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Entity::Binder => write!(f, "Binder"),
+            Entity::Song => write!(f, "Song"),
+            Entity::Concert => write!(f, "Concert"),
+            Entity::Performance => write!(f, "Performance"),
+            Entity::Chorister => write!(f, "Chorister"),
+            Entity::Checkout => write!(f, "Checkout"),
+            Entity::Tag => write!(f, "Tag"),
+            Entity::Link => write!(f, "Link"),
+            Entity::Attachment => write!(f, "Attachment"),
+        }
+    }
+}
+
+/// Every failure the `db` module can report. The UI matches on these variants
+/// to decide how to recover (refocus a field, reload a list, …) while the
+/// `Display` text stays suitable for the status bar.
+#[derive(Debug, Error)]
+pub enum DbError {
+    /// The row being updated or deleted no longer exists.
+    #[error("{0} not found")]
+    NotFound(Entity),
+    /// A binder-song link was expected but is missing.
+    #[error("Song not linked to this binder")]
+    NotLinked { binder_id: i64, song_id: i64 },
+    /// A song was expected on a concert's setlist but is missing.
+    #[error("Song not on this setlist")]
+    NotInSetlist { concert_id: i64, song_id: i64 },
+    /// The binder already has an open checkout.
+    #[error("Binder is already checked out.")]
+    AlreadyCheckedOut { binder_id: i64 },
+    /// The binder has no open checkout to close.
+    #[error("Binder is already on the shelf.")]
+    NotCheckedOut { binder_id: i64 },
+    /// Binder numbers are unique; another binder already uses this one.
+    #[error("Binder number {0} already exists.")]
+    DuplicateBinderNumber(i64),
+    /// Tag names are unique ignoring case; another tag already uses this one.
+    #[error("Tag '{0}' already exists.")]
+    DuplicateTag(String),
+    /// An attached file is gone from the attachments folder.
+    #[error("Attached file '{0}' is missing from the attachments folder.")]
+    AttachmentMissing(String),
+    /// An attached file no longer matches the checksum recorded for it.
+    #[error("Attached file '{0}' does not match its checksum.")]
+    AttachmentCorrupt(String),
+    /// A file in the backups folder is not named like one of our backups.
+    #[error("'{0}' is not a backup file.")]
+    NotABackup(String),
+    /// A write referenced a record (binder, song, concert, chorister, tag…)
+    /// that does not exist.
+    #[error("Referenced record does not exist.")]
+    ForeignKey,
+    /// The database was written by a newer build with a schema we cannot read.
+    #[error("The database was written by a newer version of the app (schema {found}, this build supports {supported}).")]
+    SchemaTooNew { found: i64, supported: i64 },
+    /// A schema migration failed; the database stays at the previous version.
+    #[error("failed to apply migration {version} ({name}): {source}")]
+    Migration {
+        version: i64,
+        name: &'static str,
+        #[source]
+        source: rusqlite::Error,
+    },
+    /// A JSON snapshot could not be parsed or is internally inconsistent.
+    #[error("Invalid library snapshot: {0}")]
+    InvalidSnapshot(String),
+    /// The snapshot was written by a newer build using a format we cannot read.
+    #[error("The snapshot uses format version {found}; this build reads up to {supported}.")]
+    SnapshotTooNew { found: u32, supported: u32 },
+    /// A setting from the environment could not be understood.
+    #[error("{name} must be a whole number, got '{value}'.")]
+    InvalidSetting { name: &'static str, value: String },
+    /// The default library location depends on a home directory.
+    #[error("could not locate home directory")]
+    NoHomeDirectory,
+    /// Filesystem failure around the database file (directories, backups).
+    #[error("{context}: {source}")]
+    Io {
+        context: &'static str,
+        #[source]
+        source: io::Error,
+    },
+    /// Any other SQLite failure, tagged with what we were trying to do.
+    #[error("{context}: {source}")]
+    Sqlite {
+        context: &'static str,
+        #[source]
+        source: rusqlite::Error,
+    },
+}
+
+impl DbError {
+    /// Classify a raw SQLite error. Foreign key violations get their own
+    /// variant; everything else keeps the context string for diagnostics.
+    pub(crate) fn from_sqlite(context: &'static str, source: rusqlite::Error) -> Self {
+        if extended_code(&source) == Some(SQLITE_CONSTRAINT_FOREIGNKEY) {
+            DbError::ForeignKey
+        } else {
+            DbError::Sqlite { context, source }
+        }
+    }
+}
+
+/// Whether the error is a UNIQUE constraint violation.
+pub(crate) fn is_unique_violation(err: &rusqlite::Error) -> bool {
+    extended_code(err) == Some(SQLITE_CONSTRAINT_UNIQUE)
+}
+
+fn extended_code(err: &rusqlite::Error) -> Option<i32> {
+    match err {
+        rusqlite::Error::SqliteFailure(inner, _)
+            if inner.code == ErrorCode::ConstraintViolation =>
+        {
+            Some(inner.extended_code)
+        }
+        _ => None,
+    }
+}
+
+/// `anyhow::Context`-style helper so call sites keep their descriptive
+/// messages while producing a typed [`DbError`].
+pub(crate) trait DbContext<T> {
+    fn db_context(self, context: &'static str) -> DbResult<T>;
+}
+
+impl<T> DbContext<T> for rusqlite::Result<T> {
+    fn db_context(self, context: &'static str) -> DbResult<T> {
+        self.map_err(|err| DbError::from_sqlite(context, err))
+    }
+}
+
+impl<T> DbContext<T> for io::Result<T> {
+    fn db_context(self, context: &'static str) -> DbResult<T> {
+        self.map_err(|source| DbError::Io { context, source })
+    }
+}
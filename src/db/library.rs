@@ -0,0 +1,646 @@
+use std::collections::{HashMap, HashSet};
+use std::ffi::c_int;
+use std::path::Path;
+use std::time::Duration;
+
+use rusqlite::backup::Backup;
+use rusqlite::{Connection, MAIN_DB};
+
+use super::attachments::{
+    add_attachment, delete_attachment, fetch_attachments, restore_attachment,
+};
+use super::binders::{
+    assign_binder, create_binder, delete_binder, fetch_binders, restore_binder, set_binder_part,
+    update_binder,
+};
+use super::checkouts::{close_checkout, fetch_checkouts, record_checkout, restore_checkout};
+use super::choristers::{
+    create_chorister, delete_chorister, fetch_choristers, restore_chorister, update_chorister,
+};
+use super::concerts::{
+    add_song_to_setlist, create_concert, delete_concert, fetch_concerts, fetch_setlist,
+    remove_song_from_setlist, restore_concert, set_setlist_order, update_concert,
+};
+use super::connection::load_into_memory;
+use super::error::{DbContext, DbResult};
+use super::links::{add_song_link, delete_song_link, fetch_song_links, restore_song_link};
+use super::performances::{
+    delete_performance, fetch_performance_stats, fetch_performances, fetch_song_performances,
+    record_performance, restore_performance,
+};
+use super::print_log::{
+    add_print_log_entry, clear_print_log, fetch_print_log, restore_print_log_entry,
+};
+use super::print_queue::{fetch_print_queue, set_print_stage};
+use super::songs::{
+    add_song_to_binder, create_song, delete_song, fetch_all_songs, fetch_available_songs,
+    fetch_composers, fetch_director_song_ids, fetch_memberships, fetch_songs_for_binder,
+    remove_song_from_binder, restore_song, set_binder_order, set_song_details, set_song_parts,
+    update_song,
+};
+use super::tags::{
+    create_tag, delete_tag, fetch_tag_links, fetch_tags, restore_tag, tag_song, untag_song,
+};
+use crate::models::{
+    Attachment, Binder, BinderCheckout, Chorister, Concert, LinkKind, Performance,
+    PerformanceStats, PrintLogEntry, PrintQueueItem, PrintStage, Song, SongDetails, SongLink, Tag,
+};
+
+/// Storage-agnostic view of a choir library: binders, songs and the links
+/// between them. The UI only talks to this trait, so it can run against the
+/// SQLite file in production or a [`MemoryLibrary`](super::MemoryLibrary) in
+/// tests. Reads take `&self`; anything that changes data takes `&mut self`.
+pub trait Library {
+    /// Every binder, ordered by number.
+    fn fetch_binders(&self) -> DbResult<Vec<Binder>>;
+    /// Insert a binder; fails with `DuplicateBinderNumber` if the number is taken.
+    fn create_binder(&mut self, number: i64, label: &str) -> DbResult<Binder>;
+    /// Change a binder's number and label.
+    fn update_binder(&mut self, id: i64, number: i64, label: &str) -> DbResult<()>;
+    /// Remove a binder together with its song links.
+    fn delete_binder(&mut self, id: i64) -> DbResult<()>;
+    /// Assign a binder to a chorister, or unassign it with `None`; fails with
+    /// `ForeignKey` if the chorister does not exist.
+    fn assign_binder(&mut self, id: i64, holder_id: Option<i64>) -> DbResult<()>;
+    /// Set the voice part a binder is for; empty means it needs every song.
+    fn set_binder_part(&mut self, id: i64, voice_part: &str) -> DbResult<()>;
+
+    /// Every chorister, ordered by name.
+    fn fetch_choristers(&self) -> DbResult<Vec<Chorister>>;
+    /// Insert a chorister.
+    fn create_chorister(
+        &mut self,
+        name: &str,
+        voice_part: &str,
+        contact: &str,
+    ) -> DbResult<Chorister>;
+    /// Replace a chorister's name, voice part and contact.
+    fn update_chorister(
+        &mut self,
+        id: i64,
+        name: &str,
+        voice_part: &str,
+        contact: &str,
+    ) -> DbResult<()>;
+    /// Delete a chorister; binders they held become unassigned.
+    fn delete_chorister(&mut self, id: i64) -> DbResult<()>;
+
+    /// Every song, ordered case-insensitively by title then composer.
+    fn fetch_all_songs(&self) -> DbResult<Vec<Song>>;
+    /// Distinct, non-empty composers for auto-complete.
+    fn fetch_composers(&self) -> DbResult<Vec<String>>;
+    /// Songs linked to one binder, in the binder's running order.
+    fn fetch_songs_for_binder(&self, binder_id: i64) -> DbResult<Vec<Song>>;
+    /// Songs not yet linked to the binder.
+    fn fetch_available_songs(&self, binder_id: i64) -> DbResult<Vec<Song>>;
+    /// Ids of the songs in the director's binder (number 0).
+    fn fetch_director_song_ids(&self) -> DbResult<HashSet<i64>>;
+    /// Insert a song.
+    fn create_song(&mut self, title: &str, composer: &str) -> DbResult<Song>;
+    /// Replace a song's editable fields.
+    fn update_song(&mut self, id: i64, title: &str, composer: &str) -> DbResult<()>;
+    /// Set the comma-separated voice parts that need a song; empty means all.
+    fn set_song_parts(&mut self, id: i64, parts: &str) -> DbResult<()>;
+    /// Replace a song's arrangement and edition details.
+    fn set_song_details(&mut self, id: i64, details: &SongDetails) -> DbResult<()>;
+    /// Delete a song and drop it from every binder.
+    fn delete_song(&mut self, id: i64) -> DbResult<()>;
+
+    /// Link a song to a binder as its last song; repeating an existing link is
+    /// a no-op.
+    fn add_song_to_binder(&mut self, binder_id: i64, song_id: i64) -> DbResult<()>;
+    /// Unlink a song from a binder; fails with `NotLinked` if it was not there.
+    fn remove_song_from_binder(&mut self, binder_id: i64, song_id: i64) -> DbResult<()>;
+    /// Reorder a binder: `song_ids` come first, in that order, and any songs
+    /// not listed follow in their current order. Fails with `NotLinked` if a
+    /// listed song is not in the binder.
+    fn set_binder_order(&mut self, binder_id: i64, song_ids: &[i64]) -> DbResult<()>;
+    /// Every binder-song link as `(binder_id, song_id)`, ordered by binder then
+    /// song id.
+    fn fetch_memberships(&self) -> DbResult<Vec<(i64, i64)>>;
+
+    /// Every concert, soonest first; undated concerts come last.
+    fn fetch_concerts(&self) -> DbResult<Vec<Concert>>;
+    /// Insert a concert. `date` is `YYYY-MM-DD` or empty.
+    fn create_concert(&mut self, name: &str, date: &str, venue: &str) -> DbResult<Concert>;
+    /// Replace a concert's name, date and venue.
+    fn update_concert(&mut self, id: i64, name: &str, date: &str, venue: &str) -> DbResult<()>;
+    /// Delete a concert and its setlist; the songs themselves stay.
+    fn delete_concert(&mut self, id: i64) -> DbResult<()>;
+    /// Songs on a concert's setlist, in running order.
+    fn fetch_setlist(&self, concert_id: i64) -> DbResult<Vec<Song>>;
+    /// Append a song to a setlist; repeating it is a no-op.
+    fn add_song_to_setlist(&mut self, concert_id: i64, song_id: i64) -> DbResult<()>;
+    /// Take a song off a setlist; fails with `NotInSetlist` if it was not there.
+    fn remove_song_from_setlist(&mut self, concert_id: i64, song_id: i64) -> DbResult<()>;
+    /// Reorder a setlist the way [`Library::set_binder_order`] reorders a
+    /// binder, failing with `NotInSetlist` for songs not on it.
+    fn set_setlist_order(&mut self, concert_id: i64, song_ids: &[i64]) -> DbResult<()>;
+
+    /// Every recorded performance, oldest first.
+    fn fetch_performances(&self) -> DbResult<Vec<Performance>>;
+    /// One song's performances, most recent first.
+    fn fetch_song_performances(&self, song_id: i64) -> DbResult<Vec<Performance>>;
+    /// Last performance date and count per song; songs never performed are
+    /// absent.
+    fn fetch_performance_stats(&self) -> DbResult<HashMap<i64, PerformanceStats>>;
+    /// Record a performance of a song. `date` is `YYYY-MM-DD`.
+    fn record_performance(
+        &mut self,
+        song_id: i64,
+        date: &str,
+        event: &str,
+    ) -> DbResult<Performance>;
+    /// Remove one performance from the history.
+    fn delete_performance(&mut self, id: i64) -> DbResult<()>;
+
+    /// The binder checkout log, oldest first.
+    fn fetch_checkouts(&self) -> DbResult<Vec<BinderCheckout>>;
+    /// Append a checkout to the log, open when `checked_in_at` is `None`.
+    fn record_checkout(
+        &mut self,
+        binder_id: i64,
+        chorister_id: Option<i64>,
+        checked_out_at: &str,
+        checked_in_at: Option<&str>,
+    ) -> DbResult<BinderCheckout>;
+    /// Mark an open checkout as returned at `checked_in_at`.
+    fn close_checkout(&mut self, id: i64, checked_in_at: &str) -> DbResult<()>;
+
+    /// Every tag, ordered case-insensitively by name.
+    fn fetch_tags(&self) -> DbResult<Vec<Tag>>;
+    /// Every song-tag link as `(song_id, tag_id)`, ordered by song then tag.
+    fn fetch_tag_links(&self) -> DbResult<Vec<(i64, i64)>>;
+    /// Insert a tag; fails with `DuplicateTag` if the name is taken, ignoring
+    /// case.
+    fn create_tag(&mut self, name: &str) -> DbResult<Tag>;
+    /// Delete a tag and take it off every song.
+    fn delete_tag(&mut self, id: i64) -> DbResult<()>;
+    /// Put a tag on a song; repeating an existing link is a no-op.
+    fn tag_song(&mut self, song_id: i64, tag_id: i64) -> DbResult<()>;
+    /// Take a tag off a song; a no-op if the song does not have it.
+    fn untag_song(&mut self, song_id: i64, tag_id: i64) -> DbResult<()>;
+
+    /// Every typed song link, ordered by song then the order they were added.
+    fn fetch_song_links(&self) -> DbResult<Vec<SongLink>>;
+    /// Add a typed link to a song.
+    fn add_song_link(
+        &mut self,
+        song_id: i64,
+        kind: LinkKind,
+        part: &str,
+        label: &str,
+        url: &str,
+    ) -> DbResult<SongLink>;
+    /// Remove one typed link.
+    fn delete_song_link(&mut self, id: i64) -> DbResult<()>;
+
+    /// Every attachment, ordered by song then the order they were added.
+    fn fetch_attachments(&self) -> DbResult<Vec<Attachment>>;
+    /// Record a file already copied into the attachment store.
+    fn add_attachment(
+        &mut self,
+        song_id: i64,
+        file_name: &str,
+        sha256: &str,
+        size_bytes: i64,
+        added_at: &str,
+    ) -> DbResult<Attachment>;
+    /// Remove one attachment; the stored file stays.
+    fn delete_attachment(&mut self, id: i64) -> DbResult<()>;
+
+    /// Every print job sent, oldest first.
+    fn fetch_print_log(&self) -> DbResult<Vec<PrintLogEntry>>;
+    /// Record a print job under a new id, which is returned with the row.
+    fn add_print_log_entry(&mut self, entry: &PrintLogEntry) -> DbResult<PrintLogEntry>;
+    /// Forget every print job sent. Only snapshot restores need this.
+    fn clear_print_log(&mut self) -> DbResult<()>;
+
+    /// Songs printed for a binder but not yet linked to it, by binder then
+    /// song. Linking a song to a binder takes it off the queue.
+    fn fetch_print_queue(&self) -> DbResult<Vec<PrintQueueItem>>;
+    /// Move a binder's copy of a song to `stage`; `Needed` takes it off the
+    /// queue. Fails with `ForeignKey` if the binder or song does not exist.
+    fn set_print_stage(
+        &mut self,
+        binder_id: i64,
+        song_id: i64,
+        stage: PrintStage,
+        updated_at: &str,
+    ) -> DbResult<()>;
+
+    /// Insert a binder keeping its id. Only snapshot restores need this.
+    fn restore_binder(&mut self, binder: &Binder) -> DbResult<()>;
+    /// Insert a song keeping its id. Only snapshot restores need this.
+    fn restore_song(&mut self, song: &Song) -> DbResult<()>;
+    /// Insert a concert keeping its id. Only snapshot restores need this.
+    fn restore_concert(&mut self, concert: &Concert) -> DbResult<()>;
+    /// Insert a performance keeping its id. Only snapshot restores need this.
+    fn restore_performance(&mut self, performance: &Performance) -> DbResult<()>;
+    /// Insert a chorister keeping its id. Only snapshot restores need this.
+    fn restore_chorister(&mut self, chorister: &Chorister) -> DbResult<()>;
+    /// Insert a checkout keeping its id. Only snapshot restores need this.
+    fn restore_checkout(&mut self, checkout: &BinderCheckout) -> DbResult<()>;
+    /// Insert a tag keeping its id. Only snapshot restores need this.
+    fn restore_tag(&mut self, tag: &Tag) -> DbResult<()>;
+    /// Insert a song link keeping its id. Only snapshot restores need this.
+    fn restore_song_link(&mut self, link: &SongLink) -> DbResult<()>;
+    /// Insert an attachment keeping its id. Only snapshot restores need this.
+    fn restore_attachment(&mut self, attachment: &Attachment) -> DbResult<()>;
+    /// Insert a print job keeping its id. Only snapshot restores need this.
+    fn restore_print_log_entry(&mut self, entry: &PrintLogEntry) -> DbResult<()>;
+
+    /// Write a consistent copy of the whole library to a new SQLite file at
+    /// `path`, without interrupting other users of the library.
+    fn backup_to(&self, path: &Path) -> DbResult<()>;
+    /// Replace the whole library with the SQLite file at `path`, migrating a
+    /// copy of it first if it was written by an older build.
+    fn restore_from(&mut self, path: &Path) -> DbResult<()>;
+
+    /// Run `work` atomically: if it returns an error, every change it made is
+    /// rolled back and the error is passed on.
+    fn transaction(
+        &mut self,
+        work: &mut dyn FnMut(&mut dyn Library) -> DbResult<()>,
+    ) -> DbResult<()>;
+}
+
+/// [`Library`] backed by a SQLite connection, delegating to the free functions
+/// in this module so both entry points share one set of queries.
+pub struct SqliteLibrary {
+    conn: Connection,
+}
+
+impl SqliteLibrary {
+    /// Wrap an already migrated connection (see `ensure_schema_at`).
+    pub fn new(conn: Connection) -> Self {
+        Self { conn }
+    }
+
+    /// Borrow the underlying connection for queries the trait does not cover.
+    pub fn connection(&self) -> &Connection {
+        &self.conn
+    }
+
+    /// Give the connection back, e.g. to close it explicitly.
+    pub fn into_inner(self) -> Connection {
+        self.conn
+    }
+}
+
+impl Library for SqliteLibrary {
+    fn fetch_binders(&self) -> DbResult<Vec<Binder>> {
+        fetch_binders(&self.conn)
+    }
+
+    fn create_binder(&mut self, number: i64, label: &str) -> DbResult<Binder> {
+        create_binder(&self.conn, number, label)
+    }
+
+    fn update_binder(&mut self, id: i64, number: i64, label: &str) -> DbResult<()> {
+        update_binder(&self.conn, id, number, label)
+    }
+
+    fn delete_binder(&mut self, id: i64) -> DbResult<()> {
+        delete_binder(&self.conn, id)
+    }
+
+    fn assign_binder(&mut self, id: i64, holder_id: Option<i64>) -> DbResult<()> {
+        assign_binder(&self.conn, id, holder_id)
+    }
+
+    fn set_binder_part(&mut self, id: i64, voice_part: &str) -> DbResult<()> {
+        set_binder_part(&self.conn, id, voice_part)
+    }
+
+    fn fetch_choristers(&self) -> DbResult<Vec<Chorister>> {
+        fetch_choristers(&self.conn)
+    }
+
+    fn create_chorister(
+        &mut self,
+        name: &str,
+        voice_part: &str,
+        contact: &str,
+    ) -> DbResult<Chorister> {
+        create_chorister(&self.conn, name, voice_part, contact)
+    }
+
+    fn update_chorister(
+        &mut self,
+        id: i64,
+        name: &str,
+        voice_part: &str,
+        contact: &str,
+    ) -> DbResult<()> {
+        update_chorister(&self.conn, id, name, voice_part, contact)
+    }
+
+    fn delete_chorister(&mut self, id: i64) -> DbResult<()> {
+        delete_chorister(&self.conn, id)
+    }
+
+    fn fetch_all_songs(&self) -> DbResult<Vec<Song>> {
+        fetch_all_songs(&self.conn)
+    }
+
+    fn fetch_composers(&self) -> DbResult<Vec<String>> {
+        fetch_composers(&self.conn)
+    }
+
+    fn fetch_songs_for_binder(&self, binder_id: i64) -> DbResult<Vec<Song>> {
+        fetch_songs_for_binder(&self.conn, binder_id)
+    }
+
+    fn fetch_available_songs(&self, binder_id: i64) -> DbResult<Vec<Song>> {
+        fetch_available_songs(&self.conn, binder_id)
+    }
+
+    fn fetch_director_song_ids(&self) -> DbResult<HashSet<i64>> {
+        fetch_director_song_ids(&self.conn)
+    }
+
+    fn create_song(&mut self, title: &str, composer: &str) -> DbResult<Song> {
+        create_song(&self.conn, title, composer)
+    }
+
+    fn update_song(&mut self, id: i64, title: &str, composer: &str) -> DbResult<()> {
+        update_song(&self.conn, id, title, composer)
+    }
+
+    fn set_song_parts(&mut self, id: i64, parts: &str) -> DbResult<()> {
+        set_song_parts(&self.conn, id, parts)
+    }
+
+    fn set_song_details(&mut self, id: i64, details: &SongDetails) -> DbResult<()> {
+        set_song_details(&self.conn, id, details)
+    }
+
+    fn delete_song(&mut self, id: i64) -> DbResult<()> {
+        delete_song(&self.conn, id)
+    }
+
+    fn add_song_to_binder(&mut self, binder_id: i64, song_id: i64) -> DbResult<()> {
+        add_song_to_binder(&self.conn, binder_id, song_id)
+    }
+
+    fn remove_song_from_binder(&mut self, binder_id: i64, song_id: i64) -> DbResult<()> {
+        remove_song_from_binder(&self.conn, binder_id, song_id)
+    }
+
+    fn set_binder_order(&mut self, binder_id: i64, song_ids: &[i64]) -> DbResult<()> {
+        set_binder_order(&self.conn, binder_id, song_ids)
+    }
+
+    fn fetch_memberships(&self) -> DbResult<Vec<(i64, i64)>> {
+        fetch_memberships(&self.conn)
+    }
+
+    fn fetch_concerts(&self) -> DbResult<Vec<Concert>> {
+        fetch_concerts(&self.conn)
+    }
+
+    fn create_concert(&mut self, name: &str, date: &str, venue: &str) -> DbResult<Concert> {
+        create_concert(&self.conn, name, date, venue)
+    }
+
+    fn update_concert(&mut self, id: i64, name: &str, date: &str, venue: &str) -> DbResult<()> {
+        update_concert(&self.conn, id, name, date, venue)
+    }
+
+    fn delete_concert(&mut self, id: i64) -> DbResult<()> {
+        delete_concert(&self.conn, id)
+    }
+
+    fn fetch_setlist(&self, concert_id: i64) -> DbResult<Vec<Song>> {
+        fetch_setlist(&self.conn, concert_id)
+    }
+
+    fn add_song_to_setlist(&mut self, concert_id: i64, song_id: i64) -> DbResult<()> {
+        add_song_to_setlist(&self.conn, concert_id, song_id)
+    }
+
+    fn remove_song_from_setlist(&mut self, concert_id: i64, song_id: i64) -> DbResult<()> {
+        remove_song_from_setlist(&self.conn, concert_id, song_id)
+    }
+
+    fn set_setlist_order(&mut self, concert_id: i64, song_ids: &[i64]) -> DbResult<()> {
+        set_setlist_order(&self.conn, concert_id, song_ids)
+    }
+
+    fn fetch_performances(&self) -> DbResult<Vec<Performance>> {
+        fetch_performances(&self.conn)
+    }
+
+    fn fetch_song_performances(&self, song_id: i64) -> DbResult<Vec<Performance>> {
+        fetch_song_performances(&self.conn, song_id)
+    }
+
+    fn fetch_performance_stats(&self) -> DbResult<HashMap<i64, PerformanceStats>> {
+        fetch_performance_stats(&self.conn)
+    }
+
+    fn record_performance(
+        &mut self,
+        song_id: i64,
+        date: &str,
+        event: &str,
+    ) -> DbResult<Performance> {
+        record_performance(&self.conn, song_id, date, event)
+    }
+
+    fn delete_performance(&mut self, id: i64) -> DbResult<()> {
+        delete_performance(&self.conn, id)
+    }
+
+    fn fetch_checkouts(&self) -> DbResult<Vec<BinderCheckout>> {
+        fetch_checkouts(&self.conn)
+    }
+
+    fn record_checkout(
+        &mut self,
+        binder_id: i64,
+        chorister_id: Option<i64>,
+        checked_out_at: &str,
+        checked_in_at: Option<&str>,
+    ) -> DbResult<BinderCheckout> {
+        record_checkout(
+            &self.conn,
+            binder_id,
+            chorister_id,
+            checked_out_at,
+            checked_in_at,
+        )
+    }
+
+    fn close_checkout(&mut self, id: i64, checked_in_at: &str) -> DbResult<()> {
+        close_checkout(&self.conn, id, checked_in_at)
+    }
+
+    fn fetch_tags(&self) -> DbResult<Vec<Tag>> {
+        fetch_tags(&self.conn)
+    }
+
+    fn fetch_tag_links(&self) -> DbResult<Vec<(i64, i64)>> {
+        fetch_tag_links(&self.conn)
+    }
+
+    fn create_tag(&mut self, name: &str) -> DbResult<Tag> {
+        create_tag(&self.conn, name)
+    }
+
+    fn delete_tag(&mut self, id: i64) -> DbResult<()> {
+        delete_tag(&self.conn, id)
+    }
+
+    fn tag_song(&mut self, song_id: i64, tag_id: i64) -> DbResult<()> {
+        tag_song(&self.conn, song_id, tag_id)
+    }
+
+    fn untag_song(&mut self, song_id: i64, tag_id: i64) -> DbResult<()> {
+        untag_song(&self.conn, song_id, tag_id)
+    }
+
+    fn fetch_song_links(&self) -> DbResult<Vec<SongLink>> {
+        fetch_song_links(&self.conn)
+    }
+
+    fn add_song_link(
+        &mut self,
+        song_id: i64,
+        kind: LinkKind,
+        part: &str,
+        label: &str,
+        url: &str,
+    ) -> DbResult<SongLink> {
+        add_song_link(&self.conn, song_id, kind, part, label, url)
+    }
+
+    fn delete_song_link(&mut self, id: i64) -> DbResult<()> {
+        delete_song_link(&self.conn, id)
+    }
+
+    fn fetch_attachments(&self) -> DbResult<Vec<Attachment>> {
+        fetch_attachments(&self.conn)
+    }
+
+    fn add_attachment(
+        &mut self,
+        song_id: i64,
+        file_name: &str,
+        sha256: &str,
+        size_bytes: i64,
+        added_at: &str,
+    ) -> DbResult<Attachment> {
+        add_attachment(&self.conn, song_id, file_name, sha256, size_bytes, added_at)
+    }
+
+    fn delete_attachment(&mut self, id: i64) -> DbResult<()> {
+        delete_attachment(&self.conn, id)
+    }
+
+    fn fetch_print_log(&self) -> DbResult<Vec<PrintLogEntry>> {
+        fetch_print_log(&self.conn)
+    }
+
+    fn add_print_log_entry(&mut self, entry: &PrintLogEntry) -> DbResult<PrintLogEntry> {
+        add_print_log_entry(&self.conn, entry)
+    }
+
+    fn clear_print_log(&mut self) -> DbResult<()> {
+        clear_print_log(&self.conn)
+    }
+
+    fn fetch_print_queue(&self) -> DbResult<Vec<PrintQueueItem>> {
+        fetch_print_queue(&self.conn)
+    }
+
+    fn set_print_stage(
+        &mut self,
+        binder_id: i64,
+        song_id: i64,
+        stage: PrintStage,
+        updated_at: &str,
+    ) -> DbResult<()> {
+        set_print_stage(&self.conn, binder_id, song_id, stage, updated_at)
+    }
+
+    fn restore_binder(&mut self, binder: &Binder) -> DbResult<()> {
+        restore_binder(&self.conn, binder)
+    }
+
+    fn restore_song(&mut self, song: &Song) -> DbResult<()> {
+        restore_song(&self.conn, song)
+    }
+
+    fn restore_concert(&mut self, concert: &Concert) -> DbResult<()> {
+        restore_concert(&self.conn, concert)
+    }
+
+    fn restore_performance(&mut self, performance: &Performance) -> DbResult<()> {
+        restore_performance(&self.conn, performance)
+    }
+
+    fn restore_chorister(&mut self, chorister: &Chorister) -> DbResult<()> {
+        restore_chorister(&self.conn, chorister)
+    }
+
+    fn restore_checkout(&mut self, checkout: &BinderCheckout) -> DbResult<()> {
+        restore_checkout(&self.conn, checkout)
+    }
+
+    fn restore_tag(&mut self, tag: &Tag) -> DbResult<()> {
+        restore_tag(&self.conn, tag)
+    }
+
+    fn restore_song_link(&mut self, link: &SongLink) -> DbResult<()> {
+        restore_song_link(&self.conn, link)
+    }
+
+    fn restore_attachment(&mut self, attachment: &Attachment) -> DbResult<()> {
+        restore_attachment(&self.conn, attachment)
+    }
+
+    fn restore_print_log_entry(&mut self, entry: &PrintLogEntry) -> DbResult<()> {
+        restore_print_log_entry(&self.conn, entry)
+    }
+
+    fn backup_to(&self, path: &Path) -> DbResult<()> {
+        self.conn
+            .backup(MAIN_DB, path, None)
+            .db_context("failed to write backup")
+    }
+
+    fn restore_from(&mut self, path: &Path) -> DbResult<()> {
+        let source = load_into_memory(path)?;
+        // Copy every page in one step so the library is never left half
+        // restored; the pause only matters while another process holds a lock.
+        Backup::new(&source, &mut self.conn)
+            .and_then(|backup| {
+                backup.run_to_completion(c_int::MAX, Duration::from_millis(50), None)
+            })
+            .db_context("failed to restore backup")
+    }
+
+    fn transaction(
+        &mut self,
+        work: &mut dyn FnMut(&mut dyn Library) -> DbResult<()>,
+    ) -> DbResult<()> {
+        // A manual BEGIN keeps `self` usable as a `Library` inside `work`,
+        // which a borrowed `rusqlite::Transaction` would not allow.
+        self.conn
+            .execute_batch("BEGIN IMMEDIATE")
+            .db_context("failed to begin transaction")?;
+        match work(self) {
+            Ok(()) => self
+                .conn
+                .execute_batch("COMMIT")
+                .db_context("failed to commit transaction"),
+            Err(err) => {
+                // The original error matters more than a failed rollback.
+                let _ = self.conn.execute_batch("ROLLBACK");
+                Err(err)
+            }
+        }
+    }
+}
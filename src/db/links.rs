@@ -0,0 +1,112 @@
+use std::collections::HashMap;
+
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Error as SqlError, Row};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+use super::library::Library;
+use crate::models::{LinkKind, SongLink};
+
+fn link_from_row(row: &Row<'_>) -> rusqlite::Result<SongLink> {
+    let kind: String = row.get(2)?;
+    let kind = LinkKind::from_tag(&kind).ok_or_else(|| {
+        SqlError::FromSqlConversionFailure(
+            2,
+            Type::Text,
+            format!("unknown link kind '{kind}'").into(),
+        )
+    })?;
+    Ok(SongLink {
+        id: row.get(0)?,
+        song_id: row.get(1)?,
+        kind,
+        part: row.get(3)?,
+        label: row.get(4)?,
+        url: row.get(5)?,
+    })
+}
+
+/// Every typed song link, ordered by song then id (the order they were added).
+pub fn fetch_song_links(conn: &Connection) -> DbResult<Vec<SongLink>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, kind, part, label, url
+             FROM song_links
+             ORDER BY song_id, id",
+        )
+        .db_context("failed to prepare song link query")?;
+
+    let links = stmt
+        .query_map([], link_from_row)
+        .db_context("failed to load song links")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect song links")?;
+
+    Ok(links)
+}
+
+/// Add a typed link to a song and hand back the stored row.
+pub fn add_song_link(
+    conn: &Connection,
+    song_id: i64,
+    kind: LinkKind,
+    part: &str,
+    label: &str,
+    url: &str,
+) -> DbResult<SongLink> {
+    conn.execute(
+        "INSERT INTO song_links (song_id, kind, part, label, url) VALUES (?1, ?2, ?3, ?4, ?5)",
+        params![song_id, kind.tag(), part, label, url],
+    )
+    .db_context("failed to add song link")?;
+
+    Ok(SongLink {
+        id: conn.last_insert_rowid(),
+        song_id,
+        kind,
+        part: part.to_string(),
+        label: label.to_string(),
+        url: url.to_string(),
+    })
+}
+
+/// Insert a link under a known id, used when restoring a snapshot.
+pub fn restore_song_link(conn: &Connection, link: &SongLink) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO song_links (id, song_id, kind, part, label, url)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+        params![
+            link.id,
+            link.song_id,
+            link.kind.tag(),
+            link.part,
+            link.label,
+            link.url
+        ],
+    )
+    .db_context("failed to restore song link")?;
+    Ok(())
+}
+
+/// Remove one typed link from its song.
+pub fn delete_song_link(conn: &Connection, id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute("DELETE FROM song_links WHERE id = ?1", params![id])
+        .db_context("failed to delete song link")?;
+
+    if deleted == 0 {
+        Err(DbError::NotFound(Entity::Link))
+    } else {
+        Ok(())
+    }
+}
+
+/// Each song's typed links in the order they were added, keyed by song id.
+/// Songs without typed links are missing from the map.
+pub fn song_links(library: &dyn Library) -> DbResult<HashMap<i64, Vec<SongLink>>> {
+    let mut links: HashMap<i64, Vec<SongLink>> = HashMap::new();
+    for link in library.fetch_song_links()? {
+        links.entry(link.song_id).or_default().push(link);
+    }
+    Ok(links)
+}
@@ -0,0 +1,1031 @@
+use std::cmp::Ordering;
+use std::collections::btree_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
+use std::path::Path;
+
+use rusqlite::MAIN_DB;
+
+use super::connection::{ensure_schema_in_memory, load_into_memory};
+use super::error::{DbContext, DbError, DbResult, Entity};
+use super::library::{Library, SqliteLibrary};
+use super::snapshot::{export_library, import_library, RestoreMode};
+use crate::models::{
+    Attachment, Binder, BinderCheckout, Chorister, Concert, LinkKind, Performance,
+    PerformanceStats, PrintLogEntry, PrintQueueItem, PrintStage, Song, SongDetails, SongLink, Tag,
+};
+
+/// Pure in-memory [`Library`] used by tests and tooling that should not touch
+/// the filesystem. It mirrors the SQLite schema's behavior: unique binder
+/// numbers, cascading deletes, idempotent links, and the same sort orders.
+/// Links map `(binder_id, song_id)` to the song's position in the binder, and
+/// setlists map `(concert_id, song_id)` the same way. Performances are a plain
+/// log, like the SQLite table.
+#[derive(Debug, Clone, Default)]
+pub struct MemoryLibrary {
+    binders: Vec<Binder>,
+    songs: Vec<Song>,
+    links: BTreeMap<(i64, i64), i64>,
+    concerts: Vec<Concert>,
+    setlists: BTreeMap<(i64, i64), i64>,
+    performances: Vec<Performance>,
+    choristers: Vec<Chorister>,
+    checkouts: Vec<BinderCheckout>,
+    tags: Vec<Tag>,
+    tag_links: BTreeSet<(i64, i64)>,
+    song_links: Vec<SongLink>,
+    attachments: Vec<Attachment>,
+    print_log: Vec<PrintLogEntry>,
+    print_queue: BTreeMap<(i64, i64), PrintQueueItem>,
+    next_binder_id: i64,
+    next_song_id: i64,
+    next_concert_id: i64,
+    next_performance_id: i64,
+    next_chorister_id: i64,
+    next_checkout_id: i64,
+    next_tag_id: i64,
+    next_song_link_id: i64,
+    next_attachment_id: i64,
+    next_print_log_id: i64,
+}
+
+impl MemoryLibrary {
+    /// Start with an empty library.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn allocate_binder_id(&mut self) -> i64 {
+        self.next_binder_id += 1;
+        self.next_binder_id
+    }
+
+    fn allocate_song_id(&mut self) -> i64 {
+        self.next_song_id += 1;
+        self.next_song_id
+    }
+
+    fn allocate_concert_id(&mut self) -> i64 {
+        self.next_concert_id += 1;
+        self.next_concert_id
+    }
+
+    fn allocate_performance_id(&mut self) -> i64 {
+        self.next_performance_id += 1;
+        self.next_performance_id
+    }
+
+    fn allocate_chorister_id(&mut self) -> i64 {
+        self.next_chorister_id += 1;
+        self.next_chorister_id
+    }
+
+    fn chorister_exists(&self, id: i64) -> bool {
+        self.choristers.iter().any(|chorister| chorister.id == id)
+    }
+
+    fn concert_exists(&self, id: i64) -> bool {
+        self.concerts.iter().any(|concert| concert.id == id)
+    }
+
+    fn binder_exists(&self, id: i64) -> bool {
+        self.binders.iter().any(|binder| binder.id == id)
+    }
+
+    fn song_exists(&self, id: i64) -> bool {
+        self.songs.iter().any(|song| song.id == id)
+    }
+
+    fn ensure_number_free(&self, number: i64, except_id: Option<i64>) -> DbResult<()> {
+        let taken = self
+            .binders
+            .iter()
+            .any(|binder| binder.number == number && Some(binder.id) != except_id);
+        if taken {
+            Err(DbError::DuplicateBinderNumber(number))
+        } else {
+            Ok(())
+        }
+    }
+
+    /// Mirror the `UNIQUE COLLATE NOCASE` constraint on tag names.
+    fn ensure_tag_name_free(&self, name: &str) -> DbResult<()> {
+        if self
+            .tags
+            .iter()
+            .any(|tag| tag.name.eq_ignore_ascii_case(name))
+        {
+            Err(DbError::DuplicateTag(name.to_string()))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn sorted_songs<'a>(&self, songs: impl Iterator<Item = &'a Song>) -> Vec<Song> {
+        let mut songs: Vec<Song> = songs.cloned().collect();
+        songs.sort_by(compare_songs);
+        songs
+    }
+}
+
+/// Title, then composer, ignoring ASCII case only: the order SQLite's
+/// `COLLATE NOCASE` produces for the queries in `songs.rs`.
+fn compare_songs(a: &Song, b: &Song) -> Ordering {
+    a.title
+        .to_ascii_lowercase()
+        .cmp(&b.title.to_ascii_lowercase())
+        .then_with(|| {
+            a.composer
+                .to_ascii_lowercase()
+                .cmp(&b.composer.to_ascii_lowercase())
+        })
+}
+
+impl Library for MemoryLibrary {
+    fn fetch_binders(&self) -> DbResult<Vec<Binder>> {
+        let mut binders = self.binders.clone();
+        binders.sort_by_key(|binder| binder.number);
+        Ok(binders)
+    }
+
+    fn create_binder(&mut self, number: i64, label: &str) -> DbResult<Binder> {
+        self.ensure_number_free(number, None)?;
+        let binder = Binder {
+            id: self.allocate_binder_id(),
+            number,
+            label: label.to_string(),
+            holder_id: None,
+            voice_part: String::new(),
+        };
+        self.binders.push(binder.clone());
+        Ok(binder)
+    }
+
+    fn update_binder(&mut self, id: i64, number: i64, label: &str) -> DbResult<()> {
+        if !self.binder_exists(id) {
+            return Err(DbError::NotFound(Entity::Binder));
+        }
+        self.ensure_number_free(number, Some(id))?;
+        if let Some(binder) = self.binders.iter_mut().find(|binder| binder.id == id) {
+            binder.number = number;
+            binder.label = label.to_string();
+        }
+        Ok(())
+    }
+
+    fn delete_binder(&mut self, id: i64) -> DbResult<()> {
+        let before = self.binders.len();
+        self.binders.retain(|binder| binder.id != id);
+        if self.binders.len() == before {
+            return Err(DbError::NotFound(Entity::Binder));
+        }
+        self.links.retain(|&(binder_id, _), _| binder_id != id);
+        self.checkouts.retain(|checkout| checkout.binder_id != id);
+        self.print_queue
+            .retain(|&(binder_id, _), _| binder_id != id);
+        Ok(())
+    }
+
+    fn assign_binder(&mut self, id: i64, holder_id: Option<i64>) -> DbResult<()> {
+        if holder_id.is_some_and(|holder| !self.chorister_exists(holder)) {
+            return Err(DbError::ForeignKey);
+        }
+        let binder = self
+            .binders
+            .iter_mut()
+            .find(|binder| binder.id == id)
+            .ok_or(DbError::NotFound(Entity::Binder))?;
+        binder.holder_id = holder_id;
+        Ok(())
+    }
+
+    fn set_binder_part(&mut self, id: i64, voice_part: &str) -> DbResult<()> {
+        let binder = self
+            .binders
+            .iter_mut()
+            .find(|binder| binder.id == id)
+            .ok_or(DbError::NotFound(Entity::Binder))?;
+        binder.voice_part = voice_part.to_string();
+        Ok(())
+    }
+
+    fn fetch_choristers(&self) -> DbResult<Vec<Chorister>> {
+        let mut choristers = self.choristers.clone();
+        choristers.sort_by_key(|chorister| (chorister.name.to_ascii_lowercase(), chorister.id));
+        Ok(choristers)
+    }
+
+    fn create_chorister(
+        &mut self,
+        name: &str,
+        voice_part: &str,
+        contact: &str,
+    ) -> DbResult<Chorister> {
+        let chorister = Chorister {
+            id: self.allocate_chorister_id(),
+            name: name.to_string(),
+            voice_part: voice_part.to_string(),
+            contact: contact.to_string(),
+        };
+        self.choristers.push(chorister.clone());
+        Ok(chorister)
+    }
+
+    fn update_chorister(
+        &mut self,
+        id: i64,
+        name: &str,
+        voice_part: &str,
+        contact: &str,
+    ) -> DbResult<()> {
+        let chorister = self
+            .choristers
+            .iter_mut()
+            .find(|chorister| chorister.id == id)
+            .ok_or(DbError::NotFound(Entity::Chorister))?;
+        chorister.name = name.to_string();
+        chorister.voice_part = voice_part.to_string();
+        chorister.contact = contact.to_string();
+        Ok(())
+    }
+
+    fn delete_chorister(&mut self, id: i64) -> DbResult<()> {
+        let before = self.choristers.len();
+        self.choristers.retain(|chorister| chorister.id != id);
+        if self.choristers.len() == before {
+            return Err(DbError::NotFound(Entity::Chorister));
+        }
+        for binder in &mut self.binders {
+            if binder.holder_id == Some(id) {
+                binder.holder_id = None;
+            }
+        }
+        for checkout in &mut self.checkouts {
+            if checkout.chorister_id == Some(id) {
+                checkout.chorister_id = None;
+            }
+        }
+        Ok(())
+    }
+
+    fn fetch_all_songs(&self) -> DbResult<Vec<Song>> {
+        Ok(self.sorted_songs(self.songs.iter()))
+    }
+
+    fn fetch_composers(&self) -> DbResult<Vec<String>> {
+        let mut composers: Vec<String> = self
+            .songs
+            .iter()
+            .map(|song| song.composer.clone())
+            .filter(|composer| !composer.is_empty())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect();
+        composers.sort_by(|a, b| {
+            a.to_ascii_lowercase()
+                .cmp(&b.to_ascii_lowercase())
+                .then(a.cmp(b))
+        });
+        Ok(composers)
+    }
+
+    fn fetch_songs_for_binder(&self, binder_id: i64) -> DbResult<Vec<Song>> {
+        let mut songs = self.sorted_songs(
+            self.songs
+                .iter()
+                .filter(|song| self.links.contains_key(&(binder_id, song.id))),
+        );
+        // Stable, so equal positions keep the title order like SQLite's
+        // secondary sort keys.
+        songs.sort_by_key(|song| self.links[&(binder_id, song.id)]);
+        Ok(songs)
+    }
+
+    fn fetch_available_songs(&self, binder_id: i64) -> DbResult<Vec<Song>> {
+        Ok(self.sorted_songs(
+            self.songs
+                .iter()
+                .filter(|song| !self.links.contains_key(&(binder_id, song.id))),
+        ))
+    }
+
+    fn fetch_director_song_ids(&self) -> DbResult<HashSet<i64>> {
+        let director_ids: HashSet<i64> = self
+            .binders
+            .iter()
+            .filter(|binder| binder.number == 0)
+            .map(|binder| binder.id)
+            .collect();
+        Ok(self
+            .links
+            .keys()
+            .filter(|(binder_id, _)| director_ids.contains(binder_id))
+            .map(|&(_, song_id)| song_id)
+            .collect())
+    }
+
+    fn create_song(&mut self, title: &str, composer: &str) -> DbResult<Song> {
+        let song = Song {
+            id: self.allocate_song_id(),
+            title: title.to_string(),
+            composer: composer.to_string(),
+            parts: String::new(),
+            details: SongDetails::default(),
+        };
+        self.songs.push(song.clone());
+        Ok(song)
+    }
+
+    fn update_song(&mut self, id: i64, title: &str, composer: &str) -> DbResult<()> {
+        let song = self
+            .songs
+            .iter_mut()
+            .find(|song| song.id == id)
+            .ok_or(DbError::NotFound(Entity::Song))?;
+        song.title = title.to_string();
+        song.composer = composer.to_string();
+        Ok(())
+    }
+
+    fn set_song_parts(&mut self, id: i64, parts: &str) -> DbResult<()> {
+        let song = self
+            .songs
+            .iter_mut()
+            .find(|song| song.id == id)
+            .ok_or(DbError::NotFound(Entity::Song))?;
+        song.parts = parts.to_string();
+        Ok(())
+    }
+
+    fn set_song_details(&mut self, id: i64, details: &SongDetails) -> DbResult<()> {
+        let song = self
+            .songs
+            .iter_mut()
+            .find(|song| song.id == id)
+            .ok_or(DbError::NotFound(Entity::Song))?;
+        song.details = details.clone();
+        Ok(())
+    }
+
+    fn delete_song(&mut self, id: i64) -> DbResult<()> {
+        let before = self.songs.len();
+        self.songs.retain(|song| song.id != id);
+        if self.songs.len() == before {
+            return Err(DbError::NotFound(Entity::Song));
+        }
+        self.links.retain(|&(_, song_id), _| song_id != id);
+        self.setlists.retain(|&(_, song_id), _| song_id != id);
+        self.performances
+            .retain(|performance| performance.song_id != id);
+        self.tag_links.retain(|&(song_id, _)| song_id != id);
+        self.song_links.retain(|link| link.song_id != id);
+        self.attachments
+            .retain(|attachment| attachment.song_id != id);
+        for entry in &mut self.print_log {
+            if entry.song_id == Some(id) {
+                entry.song_id = None;
+            }
+        }
+        self.print_queue.retain(|&(_, song_id), _| song_id != id);
+        Ok(())
+    }
+
+    fn add_song_to_binder(&mut self, binder_id: i64, song_id: i64) -> DbResult<()> {
+        if !self.binder_exists(binder_id) || !self.song_exists(song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        let next = self
+            .links
+            .iter()
+            .filter(|((id, _), _)| *id == binder_id)
+            .map(|(_, &position)| position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        if let Entry::Vacant(link) = self.links.entry((binder_id, song_id)) {
+            link.insert(next);
+            self.print_queue.remove(&(binder_id, song_id));
+        }
+        Ok(())
+    }
+
+    fn remove_song_from_binder(&mut self, binder_id: i64, song_id: i64) -> DbResult<()> {
+        if self.links.remove(&(binder_id, song_id)).is_some() {
+            Ok(())
+        } else {
+            Err(DbError::NotLinked { binder_id, song_id })
+        }
+    }
+
+    fn set_binder_order(&mut self, binder_id: i64, song_ids: &[i64]) -> DbResult<()> {
+        let current: Vec<i64> = self
+            .fetch_songs_for_binder(binder_id)?
+            .into_iter()
+            .map(|song| song.id)
+            .collect();
+        if let Some(&song_id) = song_ids.iter().find(|id| !current.contains(id)) {
+            return Err(DbError::NotLinked { binder_id, song_id });
+        }
+        let order = song_ids
+            .iter()
+            .chain(current.iter().filter(|id| !song_ids.contains(id)));
+        for (index, &song_id) in order.enumerate() {
+            self.links.insert((binder_id, song_id), index as i64 + 1);
+        }
+        Ok(())
+    }
+
+    fn fetch_memberships(&self) -> DbResult<Vec<(i64, i64)>> {
+        Ok(self.links.keys().copied().collect())
+    }
+
+    fn fetch_concerts(&self) -> DbResult<Vec<Concert>> {
+        let mut concerts = self.concerts.clone();
+        concerts.sort_by(|a, b| {
+            (
+                a.date.is_empty(),
+                &a.date,
+                a.name.to_ascii_lowercase(),
+                a.id,
+            )
+                .cmp(&(
+                    b.date.is_empty(),
+                    &b.date,
+                    b.name.to_ascii_lowercase(),
+                    b.id,
+                ))
+        });
+        Ok(concerts)
+    }
+
+    fn create_concert(&mut self, name: &str, date: &str, venue: &str) -> DbResult<Concert> {
+        let concert = Concert {
+            id: self.allocate_concert_id(),
+            name: name.to_string(),
+            date: date.to_string(),
+            venue: venue.to_string(),
+        };
+        self.concerts.push(concert.clone());
+        Ok(concert)
+    }
+
+    fn update_concert(&mut self, id: i64, name: &str, date: &str, venue: &str) -> DbResult<()> {
+        let concert = self
+            .concerts
+            .iter_mut()
+            .find(|concert| concert.id == id)
+            .ok_or(DbError::NotFound(Entity::Concert))?;
+        concert.name = name.to_string();
+        concert.date = date.to_string();
+        concert.venue = venue.to_string();
+        Ok(())
+    }
+
+    fn delete_concert(&mut self, id: i64) -> DbResult<()> {
+        let before = self.concerts.len();
+        self.concerts.retain(|concert| concert.id != id);
+        if self.concerts.len() == before {
+            return Err(DbError::NotFound(Entity::Concert));
+        }
+        self.setlists.retain(|&(concert_id, _), _| concert_id != id);
+        Ok(())
+    }
+
+    fn fetch_setlist(&self, concert_id: i64) -> DbResult<Vec<Song>> {
+        let mut songs = self.sorted_songs(
+            self.songs
+                .iter()
+                .filter(|song| self.setlists.contains_key(&(concert_id, song.id))),
+        );
+        songs.sort_by_key(|song| self.setlists[&(concert_id, song.id)]);
+        Ok(songs)
+    }
+
+    fn add_song_to_setlist(&mut self, concert_id: i64, song_id: i64) -> DbResult<()> {
+        if !self.concert_exists(concert_id) || !self.song_exists(song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        let next = self
+            .setlists
+            .iter()
+            .filter(|((id, _), _)| *id == concert_id)
+            .map(|(_, &position)| position)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        self.setlists.entry((concert_id, song_id)).or_insert(next);
+        Ok(())
+    }
+
+    fn remove_song_from_setlist(&mut self, concert_id: i64, song_id: i64) -> DbResult<()> {
+        if self.setlists.remove(&(concert_id, song_id)).is_some() {
+            Ok(())
+        } else {
+            Err(DbError::NotInSetlist {
+                concert_id,
+                song_id,
+            })
+        }
+    }
+
+    fn set_setlist_order(&mut self, concert_id: i64, song_ids: &[i64]) -> DbResult<()> {
+        let current: Vec<i64> = self
+            .fetch_setlist(concert_id)?
+            .into_iter()
+            .map(|song| song.id)
+            .collect();
+        if let Some(&song_id) = song_ids.iter().find(|id| !current.contains(id)) {
+            return Err(DbError::NotInSetlist {
+                concert_id,
+                song_id,
+            });
+        }
+        let order = song_ids
+            .iter()
+            .chain(current.iter().filter(|id| !song_ids.contains(id)));
+        for (index, &song_id) in order.enumerate() {
+            self.setlists
+                .insert((concert_id, song_id), index as i64 + 1);
+        }
+        Ok(())
+    }
+
+    fn fetch_performances(&self) -> DbResult<Vec<Performance>> {
+        let mut performances = self.performances.clone();
+        performances.sort_by(|a, b| (&a.date, a.id).cmp(&(&b.date, b.id)));
+        Ok(performances)
+    }
+
+    fn fetch_song_performances(&self, song_id: i64) -> DbResult<Vec<Performance>> {
+        let mut performances: Vec<Performance> = self
+            .fetch_performances()?
+            .into_iter()
+            .filter(|performance| performance.song_id == song_id)
+            .collect();
+        performances.reverse();
+        Ok(performances)
+    }
+
+    fn fetch_performance_stats(&self) -> DbResult<HashMap<i64, PerformanceStats>> {
+        let mut stats: HashMap<i64, PerformanceStats> = HashMap::new();
+        for performance in &self.performances {
+            let entry = stats.entry(performance.song_id).or_default();
+            entry.count += 1;
+            if performance.date > entry.last_date {
+                entry.last_date = performance.date.clone();
+            }
+        }
+        Ok(stats)
+    }
+
+    fn record_performance(
+        &mut self,
+        song_id: i64,
+        date: &str,
+        event: &str,
+    ) -> DbResult<Performance> {
+        if !self.song_exists(song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        let performance = Performance {
+            id: self.allocate_performance_id(),
+            song_id,
+            date: date.to_string(),
+            event: event.to_string(),
+        };
+        self.performances.push(performance.clone());
+        Ok(performance)
+    }
+
+    fn delete_performance(&mut self, id: i64) -> DbResult<()> {
+        let before = self.performances.len();
+        self.performances.retain(|performance| performance.id != id);
+        if self.performances.len() == before {
+            Err(DbError::NotFound(Entity::Performance))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn fetch_checkouts(&self) -> DbResult<Vec<BinderCheckout>> {
+        let mut checkouts = self.checkouts.clone();
+        checkouts.sort_by(|a, b| (&a.checked_out_at, a.id).cmp(&(&b.checked_out_at, b.id)));
+        Ok(checkouts)
+    }
+
+    fn record_checkout(
+        &mut self,
+        binder_id: i64,
+        chorister_id: Option<i64>,
+        checked_out_at: &str,
+        checked_in_at: Option<&str>,
+    ) -> DbResult<BinderCheckout> {
+        if !self.binder_exists(binder_id)
+            || chorister_id.is_some_and(|chorister| !self.chorister_exists(chorister))
+        {
+            return Err(DbError::ForeignKey);
+        }
+        self.next_checkout_id += 1;
+        let checkout = BinderCheckout {
+            id: self.next_checkout_id,
+            binder_id,
+            chorister_id,
+            checked_out_at: checked_out_at.to_string(),
+            checked_in_at: checked_in_at.map(str::to_string),
+        };
+        self.checkouts.push(checkout.clone());
+        Ok(checkout)
+    }
+
+    fn close_checkout(&mut self, id: i64, checked_in_at: &str) -> DbResult<()> {
+        let checkout = self
+            .checkouts
+            .iter_mut()
+            .find(|checkout| checkout.id == id && checkout.is_open())
+            .ok_or(DbError::NotFound(Entity::Checkout))?;
+        checkout.checked_in_at = Some(checked_in_at.to_string());
+        Ok(())
+    }
+
+    fn fetch_tags(&self) -> DbResult<Vec<Tag>> {
+        let mut tags = self.tags.clone();
+        tags.sort_by(|a, b| {
+            a.name
+                .to_ascii_lowercase()
+                .cmp(&b.name.to_ascii_lowercase())
+                .then(a.id.cmp(&b.id))
+        });
+        Ok(tags)
+    }
+
+    fn fetch_tag_links(&self) -> DbResult<Vec<(i64, i64)>> {
+        Ok(self.tag_links.iter().copied().collect())
+    }
+
+    fn create_tag(&mut self, name: &str) -> DbResult<Tag> {
+        self.ensure_tag_name_free(name)?;
+        self.next_tag_id += 1;
+        let tag = Tag {
+            id: self.next_tag_id,
+            name: name.to_string(),
+        };
+        self.tags.push(tag.clone());
+        Ok(tag)
+    }
+
+    fn delete_tag(&mut self, id: i64) -> DbResult<()> {
+        let before = self.tags.len();
+        self.tags.retain(|tag| tag.id != id);
+        if self.tags.len() == before {
+            return Err(DbError::NotFound(Entity::Tag));
+        }
+        self.tag_links.retain(|&(_, tag_id)| tag_id != id);
+        Ok(())
+    }
+
+    fn tag_song(&mut self, song_id: i64, tag_id: i64) -> DbResult<()> {
+        if !self.song_exists(song_id) || !self.tags.iter().any(|tag| tag.id == tag_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.tag_links.insert((song_id, tag_id));
+        Ok(())
+    }
+
+    fn untag_song(&mut self, song_id: i64, tag_id: i64) -> DbResult<()> {
+        self.tag_links.remove(&(song_id, tag_id));
+        Ok(())
+    }
+
+    fn fetch_song_links(&self) -> DbResult<Vec<SongLink>> {
+        let mut links = self.song_links.clone();
+        links.sort_by_key(|link| (link.song_id, link.id));
+        Ok(links)
+    }
+
+    fn add_song_link(
+        &mut self,
+        song_id: i64,
+        kind: LinkKind,
+        part: &str,
+        label: &str,
+        url: &str,
+    ) -> DbResult<SongLink> {
+        if !self.song_exists(song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.next_song_link_id += 1;
+        let link = SongLink {
+            id: self.next_song_link_id,
+            song_id,
+            kind,
+            part: part.to_string(),
+            label: label.to_string(),
+            url: url.to_string(),
+        };
+        self.song_links.push(link.clone());
+        Ok(link)
+    }
+
+    fn delete_song_link(&mut self, id: i64) -> DbResult<()> {
+        let before = self.song_links.len();
+        self.song_links.retain(|link| link.id != id);
+        if self.song_links.len() == before {
+            Err(DbError::NotFound(Entity::Link))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn fetch_attachments(&self) -> DbResult<Vec<Attachment>> {
+        let mut attachments = self.attachments.clone();
+        attachments.sort_by_key(|attachment| (attachment.song_id, attachment.id));
+        Ok(attachments)
+    }
+
+    fn add_attachment(
+        &mut self,
+        song_id: i64,
+        file_name: &str,
+        sha256: &str,
+        size_bytes: i64,
+        added_at: &str,
+    ) -> DbResult<Attachment> {
+        if !self.song_exists(song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.next_attachment_id += 1;
+        let attachment = Attachment {
+            id: self.next_attachment_id,
+            song_id,
+            file_name: file_name.to_string(),
+            sha256: sha256.to_string(),
+            size_bytes,
+            added_at: added_at.to_string(),
+        };
+        self.attachments.push(attachment.clone());
+        Ok(attachment)
+    }
+
+    fn delete_attachment(&mut self, id: i64) -> DbResult<()> {
+        let before = self.attachments.len();
+        self.attachments.retain(|attachment| attachment.id != id);
+        if self.attachments.len() == before {
+            Err(DbError::NotFound(Entity::Attachment))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn fetch_print_log(&self) -> DbResult<Vec<PrintLogEntry>> {
+        Ok(self.print_log.clone())
+    }
+
+    fn add_print_log_entry(&mut self, entry: &PrintLogEntry) -> DbResult<PrintLogEntry> {
+        if entry
+            .song_id
+            .is_some_and(|song_id| !self.song_exists(song_id))
+        {
+            return Err(DbError::ForeignKey);
+        }
+        self.next_print_log_id += 1;
+        let entry = PrintLogEntry {
+            id: self.next_print_log_id,
+            ..entry.clone()
+        };
+        self.print_log.push(entry.clone());
+        Ok(entry)
+    }
+
+    fn clear_print_log(&mut self) -> DbResult<()> {
+        self.print_log.clear();
+        Ok(())
+    }
+
+    fn fetch_print_queue(&self) -> DbResult<Vec<PrintQueueItem>> {
+        Ok(self.print_queue.values().cloned().collect())
+    }
+
+    fn set_print_stage(
+        &mut self,
+        binder_id: i64,
+        song_id: i64,
+        stage: PrintStage,
+        updated_at: &str,
+    ) -> DbResult<()> {
+        if stage == PrintStage::Needed {
+            self.print_queue.remove(&(binder_id, song_id));
+            return Ok(());
+        }
+        if !self.binder_exists(binder_id) || !self.song_exists(song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.print_queue.insert(
+            (binder_id, song_id),
+            PrintQueueItem {
+                binder_id,
+                song_id,
+                stage,
+                updated_at: updated_at.to_string(),
+            },
+        );
+        Ok(())
+    }
+
+    fn restore_binder(&mut self, binder: &Binder) -> DbResult<()> {
+        if self.binder_exists(binder.id) {
+            return Err(DbError::InvalidSnapshot(format!(
+                "binder id {} is already taken",
+                binder.id
+            )));
+        }
+        self.ensure_number_free(binder.number, None)?;
+        if binder
+            .holder_id
+            .is_some_and(|holder| !self.chorister_exists(holder))
+        {
+            return Err(DbError::ForeignKey);
+        }
+        self.binders.push(binder.clone());
+        self.next_binder_id = self.next_binder_id.max(binder.id);
+        Ok(())
+    }
+
+    fn restore_song(&mut self, song: &Song) -> DbResult<()> {
+        if self.song_exists(song.id) {
+            return Err(DbError::InvalidSnapshot(format!(
+                "song id {} is already taken",
+                song.id
+            )));
+        }
+        self.songs.push(song.clone());
+        self.next_song_id = self.next_song_id.max(song.id);
+        Ok(())
+    }
+
+    fn restore_concert(&mut self, concert: &Concert) -> DbResult<()> {
+        if self.concert_exists(concert.id) {
+            return Err(DbError::InvalidSnapshot(format!(
+                "concert id {} is already taken",
+                concert.id
+            )));
+        }
+        self.concerts.push(concert.clone());
+        self.next_concert_id = self.next_concert_id.max(concert.id);
+        Ok(())
+    }
+
+    fn restore_performance(&mut self, performance: &Performance) -> DbResult<()> {
+        if self
+            .performances
+            .iter()
+            .any(|existing| existing.id == performance.id)
+        {
+            return Err(DbError::InvalidSnapshot(format!(
+                "performance id {} is already taken",
+                performance.id
+            )));
+        }
+        if !self.song_exists(performance.song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.performances.push(performance.clone());
+        self.next_performance_id = self.next_performance_id.max(performance.id);
+        Ok(())
+    }
+
+    fn restore_chorister(&mut self, chorister: &Chorister) -> DbResult<()> {
+        if self.chorister_exists(chorister.id) {
+            return Err(DbError::InvalidSnapshot(format!(
+                "chorister id {} is already taken",
+                chorister.id
+            )));
+        }
+        self.choristers.push(chorister.clone());
+        self.next_chorister_id = self.next_chorister_id.max(chorister.id);
+        Ok(())
+    }
+
+    fn restore_checkout(&mut self, checkout: &BinderCheckout) -> DbResult<()> {
+        if self
+            .checkouts
+            .iter()
+            .any(|existing| existing.id == checkout.id)
+        {
+            return Err(DbError::InvalidSnapshot(format!(
+                "checkout id {} is already taken",
+                checkout.id
+            )));
+        }
+        if !self.binder_exists(checkout.binder_id)
+            || checkout
+                .chorister_id
+                .is_some_and(|chorister| !self.chorister_exists(chorister))
+        {
+            return Err(DbError::ForeignKey);
+        }
+        self.checkouts.push(checkout.clone());
+        self.next_checkout_id = self.next_checkout_id.max(checkout.id);
+        Ok(())
+    }
+
+    fn restore_tag(&mut self, tag: &Tag) -> DbResult<()> {
+        if self.tags.iter().any(|existing| existing.id == tag.id) {
+            return Err(DbError::InvalidSnapshot(format!(
+                "tag id {} is already taken",
+                tag.id
+            )));
+        }
+        self.ensure_tag_name_free(&tag.name)?;
+        self.tags.push(tag.clone());
+        self.next_tag_id = self.next_tag_id.max(tag.id);
+        Ok(())
+    }
+
+    fn restore_song_link(&mut self, link: &SongLink) -> DbResult<()> {
+        if self
+            .song_links
+            .iter()
+            .any(|existing| existing.id == link.id)
+        {
+            return Err(DbError::InvalidSnapshot(format!(
+                "song link id {} is already taken",
+                link.id
+            )));
+        }
+        if !self.song_exists(link.song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.song_links.push(link.clone());
+        self.next_song_link_id = self.next_song_link_id.max(link.id);
+        Ok(())
+    }
+
+    fn restore_attachment(&mut self, attachment: &Attachment) -> DbResult<()> {
+        if self
+            .attachments
+            .iter()
+            .any(|existing| existing.id == attachment.id)
+        {
+            return Err(DbError::InvalidSnapshot(format!(
+                "attachment id {} is already taken",
+                attachment.id
+            )));
+        }
+        if !self.song_exists(attachment.song_id) {
+            return Err(DbError::ForeignKey);
+        }
+        self.attachments.push(attachment.clone());
+        self.next_attachment_id = self.next_attachment_id.max(attachment.id);
+        Ok(())
+    }
+
+    fn restore_print_log_entry(&mut self, entry: &PrintLogEntry) -> DbResult<()> {
+        if self
+            .print_log
+            .iter()
+            .any(|existing| existing.id == entry.id)
+        {
+            return Err(DbError::InvalidSnapshot(format!(
+                "print job id {} is already taken",
+                entry.id
+            )));
+        }
+        if entry
+            .song_id
+            .is_some_and(|song_id| !self.song_exists(song_id))
+        {
+            return Err(DbError::ForeignKey);
+        }
+        self.print_log.push(entry.clone());
+        self.print_log.sort_by_key(|entry| entry.id);
+        self.next_print_log_id = self.next_print_log_id.max(entry.id);
+        Ok(())
+    }
+
+    fn backup_to(&self, path: &Path) -> DbResult<()> {
+        // Go through a real SQLite copy so backups from tests look exactly like
+        // the ones the app writes.
+        let mut copy = SqliteLibrary::new(ensure_schema_in_memory()?);
+        import_library(&mut copy, &export_library(self)?, RestoreMode::Replace)?;
+        copy.connection()
+            .backup(MAIN_DB, path, None)
+            .db_context("failed to write backup")
+    }
+
+    fn restore_from(&mut self, path: &Path) -> DbResult<()> {
+        let source = SqliteLibrary::new(load_into_memory(path)?);
+        let snapshot = export_library(&source)?;
+        import_library(self, &snapshot, RestoreMode::Replace).map(|_| ())
+    }
+
+    fn transaction(
+        &mut self,
+        work: &mut dyn FnMut(&mut dyn Library) -> DbResult<()>,
+    ) -> DbResult<()> {
+        let snapshot = self.clone();
+        let result = work(self);
+        if result.is_err() {
+            *self = snapshot;
+        }
+        result
+    }
+}
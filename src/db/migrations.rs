@@ -0,0 +1,262 @@
+use rusqlite::Connection;
+
+use super::error::{DbContext, DbError, DbResult};
+
+/// A single forward-only schema change. Versions are stored in SQLite's
+/// `PRAGMA user_version`, so every migration must carry a strictly increasing
+/// number and never be edited once it has shipped.
+pub(crate) struct Migration {
+    /// Schema version the database reaches once this step has been applied.
+    pub(crate) version: i64,
+    /// Short description used in error messages.
+    pub(crate) name: &'static str,
+    /// Batch of SQL statements executed inside a single transaction.
+    pub(crate) sql: &'static str,
+}
+
+/// Ordered list of every migration the application knows about. The first
+/// step keeps `IF NOT EXISTS` so databases created before versioning existed
+/// (which report `user_version = 0`) adopt the versioned history cleanly.
+pub(crate) const MIGRATIONS: &[Migration] = &[
+    Migration {
+        version: 1,
+        name: "initial schema",
+        sql: "CREATE TABLE IF NOT EXISTS binders (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  number INTEGER NOT NULL UNIQUE,
+                  label TEXT NOT NULL
+              );
+              CREATE TABLE IF NOT EXISTS songs (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  title TEXT NOT NULL,
+                  composer TEXT,
+                  link TEXT
+              );
+              CREATE TABLE IF NOT EXISTS binder_songs (
+                  binder_id INTEGER NOT NULL,
+                  song_id INTEGER NOT NULL,
+                  PRIMARY KEY (binder_id, song_id),
+                  FOREIGN KEY(binder_id) REFERENCES binders(id) ON DELETE CASCADE,
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
+              );",
+    },
+    Migration {
+        version: 2,
+        name: "song order inside binders",
+        // Existing binders keep the alphabetical order they were shown in.
+        sql: "ALTER TABLE binder_songs ADD COLUMN position INTEGER NOT NULL DEFAULT 0;
+              UPDATE binder_songs SET position = ranked.position
+              FROM (
+                  SELECT bs.binder_id, bs.song_id,
+                         ROW_NUMBER() OVER (
+                             PARTITION BY bs.binder_id
+                             ORDER BY s.title COLLATE NOCASE, s.composer COLLATE NOCASE
+                         ) AS position
+                  FROM binder_songs bs
+                  INNER JOIN songs s ON s.id = bs.song_id
+              ) AS ranked
+              WHERE binder_songs.binder_id = ranked.binder_id
+                AND binder_songs.song_id = ranked.song_id;",
+    },
+    Migration {
+        version: 3,
+        name: "concerts and setlists",
+        sql: "CREATE TABLE concerts (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  name TEXT NOT NULL,
+                  date TEXT NOT NULL DEFAULT '',
+                  venue TEXT NOT NULL DEFAULT ''
+              );
+              CREATE TABLE concert_songs (
+                  concert_id INTEGER NOT NULL,
+                  song_id INTEGER NOT NULL,
+                  position INTEGER NOT NULL,
+                  PRIMARY KEY (concert_id, song_id),
+                  FOREIGN KEY(concert_id) REFERENCES concerts(id) ON DELETE CASCADE,
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
+              );",
+    },
+    Migration {
+        version: 4,
+        name: "performance history",
+        sql: "CREATE TABLE performances (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  song_id INTEGER NOT NULL,
+                  date TEXT NOT NULL,
+                  event TEXT NOT NULL DEFAULT '',
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
+              );
+              CREATE INDEX performances_by_song ON performances (song_id, date);",
+    },
+    Migration {
+        version: 5,
+        name: "choristers and binder holders",
+        sql: "CREATE TABLE choristers (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  name TEXT NOT NULL,
+                  voice_part TEXT NOT NULL DEFAULT '',
+                  contact TEXT NOT NULL DEFAULT ''
+              );
+              ALTER TABLE binders ADD COLUMN holder_id INTEGER
+                  REFERENCES choristers(id) ON DELETE SET NULL;",
+    },
+    Migration {
+        version: 6,
+        name: "binder checkout log",
+        sql: "CREATE TABLE binder_checkouts (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  binder_id INTEGER NOT NULL REFERENCES binders(id) ON DELETE CASCADE,
+                  chorister_id INTEGER REFERENCES choristers(id) ON DELETE SET NULL,
+                  checked_out_at TEXT NOT NULL,
+                  checked_in_at TEXT
+              );
+              CREATE INDEX binder_checkouts_by_binder
+                  ON binder_checkouts (binder_id, checked_out_at);",
+    },
+    Migration {
+        version: 7,
+        name: "voice parts",
+        sql: "ALTER TABLE binders ADD COLUMN voice_part TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN parts TEXT NOT NULL DEFAULT '';",
+    },
+    Migration {
+        version: 8,
+        name: "song details",
+        sql: "ALTER TABLE songs ADD COLUMN arranger TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN voicing TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN accompaniment TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN language TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN publisher TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN catalog_number TEXT NOT NULL DEFAULT '';
+              ALTER TABLE songs ADD COLUMN duration_seconds INTEGER;
+              ALTER TABLE songs ADD COLUMN page_count INTEGER;",
+    },
+    Migration {
+        version: 9,
+        name: "song tags",
+        sql: "CREATE TABLE tags (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  name TEXT NOT NULL UNIQUE COLLATE NOCASE
+              );
+              CREATE TABLE song_tags (
+                  song_id INTEGER NOT NULL,
+                  tag_id INTEGER NOT NULL,
+                  PRIMARY KEY (song_id, tag_id),
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE,
+                  FOREIGN KEY(tag_id) REFERENCES tags(id) ON DELETE CASCADE
+              );",
+    },
+    Migration {
+        version: 10,
+        name: "song links",
+        sql: "CREATE TABLE song_links (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  song_id INTEGER NOT NULL,
+                  kind TEXT NOT NULL
+                      CHECK (kind IN ('score', 'recording', 'rehearsal', 'other')),
+                  part TEXT NOT NULL DEFAULT '',
+                  label TEXT NOT NULL DEFAULT '',
+                  url TEXT NOT NULL,
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
+              );",
+    },
+    Migration {
+        version: 11,
+        name: "attachments",
+        sql: "CREATE TABLE attachments (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  song_id INTEGER NOT NULL,
+                  file_name TEXT NOT NULL,
+                  sha256 TEXT NOT NULL,
+                  size_bytes INTEGER NOT NULL,
+                  added_at TEXT NOT NULL,
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
+              );",
+    },
+    Migration {
+        version: 12,
+        name: "print log",
+        sql: "CREATE TABLE print_log (
+                  id INTEGER PRIMARY KEY AUTOINCREMENT,
+                  song_id INTEGER,
+                  title TEXT NOT NULL,
+                  copies INTEGER NOT NULL,
+                  printer TEXT NOT NULL DEFAULT '',
+                  file_name TEXT NOT NULL,
+                  submitted_at TEXT NOT NULL,
+                  request_id TEXT NOT NULL DEFAULT '',
+                  error TEXT NOT NULL DEFAULT '',
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE SET NULL
+              );",
+    },
+    Migration {
+        version: 13,
+        name: "print queue",
+        // A queued song is done with once it is linked to its binder, however
+        // the link was made.
+        sql: "CREATE TABLE print_queue (
+                  binder_id INTEGER NOT NULL,
+                  song_id INTEGER NOT NULL,
+                  stage TEXT NOT NULL CHECK (stage IN ('printed', 'inserted')),
+                  updated_at TEXT NOT NULL,
+                  PRIMARY KEY (binder_id, song_id),
+                  FOREIGN KEY(binder_id) REFERENCES binders(id) ON DELETE CASCADE,
+                  FOREIGN KEY(song_id) REFERENCES songs(id) ON DELETE CASCADE
+              );
+              CREATE TRIGGER print_queue_done AFTER INSERT ON binder_songs
+              BEGIN
+                  DELETE FROM print_queue
+                  WHERE binder_id = NEW.binder_id AND song_id = NEW.song_id;
+              END;",
+    },
+    Migration {
+        version: 14,
+        name: "song links only",
+        // Songs used to carry one untyped link of their own; it becomes a
+        // typed link so `song_links` is the only place links live. The kind
+        // follows `LinkKind::guess`.
+        sql: "INSERT INTO song_links (song_id, kind, url)
+              SELECT id,
+                     CASE WHEN lower(trim(link)) LIKE '%.pdf' THEN 'score' ELSE 'other' END,
+                     trim(link)
+              FROM songs
+              WHERE trim(link) <> ''
+              ORDER BY id;
+              ALTER TABLE songs DROP COLUMN link;",
+    },
+];
+
+/// Highest schema version this build of the application can read and write.
+pub(crate) fn latest_version() -> i64 {
+    MIGRATIONS.last().map(|step| step.version).unwrap_or(0)
+}
+
+/// Read the schema version recorded in the database header.
+pub(crate) fn schema_version(conn: &Connection) -> DbResult<i64> {
+    conn.pragma_query_value(None, "user_version", |row| row.get(0))
+        .db_context("failed to read schema version")
+}
+
+/// Apply every migration newer than the database's current version. Each step
+/// runs in its own transaction together with the `user_version` bump, so a
+/// failure leaves the database at the last fully applied version.
+pub(crate) fn run_migrations(conn: &mut Connection) -> DbResult<()> {
+    let current = schema_version(conn)?;
+
+    for step in MIGRATIONS.iter().filter(|step| step.version > current) {
+        apply_migration(conn, step).map_err(|source| DbError::Migration {
+            version: step.version,
+            name: step.name,
+            source,
+        })?;
+    }
+
+    Ok(())
+}
+
+fn apply_migration(conn: &mut Connection, step: &Migration) -> rusqlite::Result<()> {
+    let tx = conn.transaction()?;
+    tx.execute_batch(step.sql)?;
+    tx.pragma_update(None, "user_version", step.version)?;
+    tx.commit()
+}
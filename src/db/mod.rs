@@ -1,12 +1,82 @@
 //! Persistence module split across logical submodules.
 
+mod attachments;
+mod backup;
 mod binders;
+mod checkouts;
+mod choristers;
+mod concerts;
 mod connection;
+mod error;
+mod library;
+mod links;
+mod memory;
+mod migrations;
+mod order;
+mod performances;
+mod print_log;
+mod print_queue;
+mod snapshot;
 mod songs;
+mod tags;
+#[cfg(test)]
+pub(crate) mod tests;
 
-pub use binders::{create_binder, delete_binder, fetch_binders, update_binder};
-pub use connection::ensure_schema;
+pub use attachments::{
+    add_attachment, attach_file, delete_attachment, fetch_attachments, restore_attachment,
+    sha256_hex, song_attachments, AttachmentStore, ATTACHMENT_DIR_NAME,
+};
+pub use backup::{
+    resolve_backup_rotations, BackupInfo, BackupReason, BackupStore, BACKUP_DIR_NAME,
+    BACKUP_ROTATIONS_ENV, DEFAULT_BACKUP_ROTATIONS,
+};
+pub use binders::{
+    assign_binder, create_binder, delete_binder, fetch_binders, restore_binder, set_binder_part,
+    update_binder,
+};
+pub use checkouts::{
+    check_in_binder, check_out_binder, close_checkout, fetch_checkouts, open_checkouts,
+    record_checkout, resolve_overdue_days, restore_checkout, DEFAULT_OVERDUE_DAYS,
+    OVERDUE_DAYS_ENV,
+};
+pub use choristers::{
+    create_chorister, delete_chorister, fetch_choristers, restore_chorister, update_chorister,
+};
+pub use concerts::{
+    add_song_to_setlist, create_concert, delete_concert, fetch_concerts, fetch_setlist,
+    remove_song_from_setlist, restore_concert, set_setlist_order, update_concert,
+};
+pub use connection::{
+    ensure_schema, ensure_schema_at, ensure_schema_in_memory, resolve_db_path, DB_PATH_ENV,
+};
+pub use error::{DbError, DbResult, Entity};
+pub use library::{Library, SqliteLibrary};
+pub use links::{add_song_link, delete_song_link, fetch_song_links, restore_song_link, song_links};
+pub use memory::MemoryLibrary;
+pub use order::{copy_director_order, move_song_in_binder, move_song_in_setlist};
+pub use performances::{
+    delete_performance, fetch_performance_stats, fetch_performances, fetch_song_performances,
+    record_performance, record_setlist_performances, restore_performance,
+};
+pub use print_log::{
+    add_print_log_entry, clear_print_log, fetch_print_log, restore_print_log_entry,
+};
+pub use print_queue::{fetch_print_queue, set_print_stage};
+pub use snapshot::{
+    export_library, import_library, LibrarySnapshot, Membership, RestoreMode, RestoreSummary,
+    SetlistEntry, SongTag, SNAPSHOT_FORMAT, SNAPSHOT_VERSION,
+};
 pub use songs::{
     add_song_to_binder, create_song, delete_song, fetch_all_songs, fetch_available_songs,
-    fetch_composers, fetch_songs_for_binder, remove_song_from_binder, update_song,
+    fetch_composers, fetch_director_song_ids, fetch_memberships, fetch_songs_for_binder,
+    remove_song_from_binder, restore_song, set_binder_order, set_song_details, set_song_parts,
+    update_song,
+};
+pub use tags::{
+    create_tag, delete_tag, fetch_tag_links, fetch_tags, parse_tags, restore_tag, set_song_tags,
+    song_tags, tag_song, untag_song,
 };
+
+/// `chrono` format of the local timestamps the library stores: checkouts,
+/// attachments, the print log and the print queue. Sorts as text.
+pub const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M";
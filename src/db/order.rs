@@ -0,0 +1,89 @@
+use super::error::{DbError, DbResult};
+use super::library::Library;
+use crate::models::Song;
+
+/// Move a song `offset` places within its binder (negative moves it up),
+/// stopping at either end. Returns whether the song actually moved.
+pub fn move_song_in_binder(
+    library: &mut dyn Library,
+    binder_id: i64,
+    song_id: i64,
+    offset: isize,
+) -> DbResult<bool> {
+    let order = library.fetch_songs_for_binder(binder_id)?;
+    let Some(order) =
+        shifted(&order, song_id, offset).ok_or(DbError::NotLinked { binder_id, song_id })?
+    else {
+        return Ok(false);
+    };
+    library.set_binder_order(binder_id, &order)?;
+    Ok(true)
+}
+
+/// Move a song `offset` places within a concert's setlist, like
+/// [`move_song_in_binder`].
+pub fn move_song_in_setlist(
+    library: &mut dyn Library,
+    concert_id: i64,
+    song_id: i64,
+    offset: isize,
+) -> DbResult<bool> {
+    let order = library.fetch_setlist(concert_id)?;
+    let Some(order) = shifted(&order, song_id, offset).ok_or(DbError::NotInSetlist {
+        concert_id,
+        song_id,
+    })?
+    else {
+        return Ok(false);
+    };
+    library.set_setlist_order(concert_id, &order)?;
+    Ok(true)
+}
+
+/// The ids of `songs` with `song_id` moved `offset` places, clamped to the
+/// ends. `None` if the song is not listed; `Some(None)` if it cannot move.
+fn shifted(songs: &[Song], song_id: i64, offset: isize) -> Option<Option<Vec<i64>>> {
+    let mut order: Vec<i64> = songs.iter().map(|song| song.id).collect();
+    let from = order.iter().position(|&id| id == song_id)?;
+    let to = from
+        .saturating_add_signed(offset)
+        .min(order.len().saturating_sub(1));
+    if to == from {
+        return Some(None);
+    }
+    let id = order.remove(from);
+    order.insert(to, id);
+    Some(Some(order))
+}
+
+/// Give every other binder the director's running order (Binder 00). Songs the
+/// director does not have stay after the shared ones, in their current order.
+/// Returns how many binders were reordered, or `None` without a director.
+pub fn copy_director_order(library: &mut dyn Library) -> DbResult<Option<usize>> {
+    let binders = library.fetch_binders()?;
+    let Some(director) = binders.iter().find(|binder| binder.number == 0) else {
+        return Ok(None);
+    };
+    let director_order: Vec<i64> = library
+        .fetch_songs_for_binder(director.id)?
+        .into_iter()
+        .map(|song| song.id)
+        .collect();
+
+    let mut reordered = 0;
+    library.transaction(&mut |library| {
+        reordered = 0;
+        for binder in binders.iter().filter(|binder| binder.id != director.id) {
+            let songs = library.fetch_songs_for_binder(binder.id)?;
+            let shared: Vec<i64> = director_order
+                .iter()
+                .copied()
+                .filter(|id| songs.iter().any(|song| song.id == *id))
+                .collect();
+            library.set_binder_order(binder.id, &shared)?;
+            reordered += 1;
+        }
+        Ok(())
+    })?;
+    Ok(Some(reordered))
+}
@@ -0,0 +1,165 @@
+use std::collections::HashMap;
+
+use rusqlite::{params, Connection, Row};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+use super::library::Library;
+use crate::models::{Performance, PerformanceStats};
+
+fn performance_from_row(row: &Row<'_>) -> rusqlite::Result<Performance> {
+    Ok(Performance {
+        id: row.get(0)?,
+        song_id: row.get(1)?,
+        date: row.get(2)?,
+        event: row.get(3)?,
+    })
+}
+
+/// Every recorded performance, oldest first.
+pub fn fetch_performances(conn: &Connection) -> DbResult<Vec<Performance>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, date, event
+             FROM performances
+             ORDER BY date, id",
+        )
+        .db_context("failed to prepare performance query")?;
+
+    let performances = stmt
+        .query_map([], performance_from_row)
+        .db_context("failed to load performances")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect performances")?;
+
+    Ok(performances)
+}
+
+/// One song's performances, most recent first.
+pub fn fetch_song_performances(conn: &Connection, song_id: i64) -> DbResult<Vec<Performance>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, date, event
+             FROM performances
+             WHERE song_id = ?1
+             ORDER BY date DESC, id DESC",
+        )
+        .db_context("failed to prepare song history query")?;
+
+    let performances = stmt
+        .query_map([song_id], performance_from_row)
+        .db_context("failed to load song history")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect song history")?;
+
+    Ok(performances)
+}
+
+/// Last performance date and performance count for every song that has been
+/// performed at least once, keyed by song id.
+pub fn fetch_performance_stats(conn: &Connection) -> DbResult<HashMap<i64, PerformanceStats>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT song_id, MAX(date), COUNT(*)
+             FROM performances
+             GROUP BY song_id",
+        )
+        .db_context("failed to prepare performance summary query")?;
+
+    let stats = stmt
+        .query_map([], |row| {
+            Ok((
+                row.get(0)?,
+                PerformanceStats {
+                    last_date: row.get(1)?,
+                    count: row.get(2)?,
+                },
+            ))
+        })
+        .db_context("failed to summarise performances")?
+        .collect::<Result<HashMap<_, _>, _>>()
+        .db_context("failed to collect performance summary")?;
+
+    Ok(stats)
+}
+
+/// Record that a song was performed on `date` (`YYYY-MM-DD`) at `event`.
+pub fn record_performance(
+    conn: &Connection,
+    song_id: i64,
+    date: &str,
+    event: &str,
+) -> DbResult<Performance> {
+    conn.execute(
+        "INSERT INTO performances (song_id, date, event) VALUES (?1, ?2, ?3)",
+        params![song_id, date, event],
+    )
+    .db_context("failed to record performance")?;
+
+    Ok(Performance {
+        id: conn.last_insert_rowid(),
+        song_id,
+        date: date.to_string(),
+        event: event.to_string(),
+    })
+}
+
+/// Insert a performance under a known id, used when restoring a snapshot.
+pub fn restore_performance(conn: &Connection, performance: &Performance) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO performances (id, song_id, date, event) VALUES (?1, ?2, ?3, ?4)",
+        params![
+            performance.id,
+            performance.song_id,
+            performance.date,
+            performance.event
+        ],
+    )
+    .db_context("failed to restore performance")?;
+    Ok(())
+}
+
+/// Remove one entry from the history, e.g. one recorded by mistake.
+pub fn delete_performance(conn: &Connection, id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute("DELETE FROM performances WHERE id = ?1", params![id])
+        .db_context("failed to delete performance")?;
+
+    if deleted == 0 {
+        Err(DbError::NotFound(Entity::Performance))
+    } else {
+        Ok(())
+    }
+}
+
+/// Record every song on a concert's setlist as performed on the concert's
+/// date, with the concert name as the event. Songs already recorded for that
+/// date and event are skipped, so marking a concert twice changes nothing.
+/// Returns how many performances were added. Callers check the concert has a
+/// date first.
+pub fn record_setlist_performances(library: &mut dyn Library, concert_id: i64) -> DbResult<usize> {
+    let concert = library
+        .fetch_concerts()?
+        .into_iter()
+        .find(|concert| concert.id == concert_id)
+        .ok_or(DbError::NotFound(Entity::Concert))?;
+    let setlist = library.fetch_setlist(concert_id)?;
+
+    let mut added = 0;
+    library.transaction(&mut |library| {
+        added = 0;
+        for song in &setlist {
+            let already = library
+                .fetch_song_performances(song.id)?
+                .iter()
+                .any(|performance| {
+                    performance.date == concert.date && performance.event == concert.name
+                });
+            if !already {
+                library.record_performance(song.id, &concert.date, &concert.name)?;
+                added += 1;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(added)
+}
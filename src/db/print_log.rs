@@ -0,0 +1,92 @@
+use rusqlite::{params, Connection, Row};
+
+use super::error::{DbContext, DbResult};
+use crate::models::PrintLogEntry;
+
+fn entry_from_row(row: &Row<'_>) -> rusqlite::Result<PrintLogEntry> {
+    Ok(PrintLogEntry {
+        id: row.get(0)?,
+        song_id: row.get(1)?,
+        title: row.get(2)?,
+        copies: row.get(3)?,
+        printer: row.get(4)?,
+        file_name: row.get(5)?,
+        submitted_at: row.get(6)?,
+        request_id: row.get(7)?,
+        error: row.get(8)?,
+    })
+}
+
+/// Every print job sent, oldest first.
+pub fn fetch_print_log(conn: &Connection) -> DbResult<Vec<PrintLogEntry>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT id, song_id, title, copies, printer, file_name, submitted_at, request_id, error
+             FROM print_log
+             ORDER BY id",
+        )
+        .db_context("failed to prepare print log query")?;
+
+    let entries = stmt
+        .query_map([], entry_from_row)
+        .db_context("failed to load print log")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect print log")?;
+
+    Ok(entries)
+}
+
+/// Record a print job. The id of `entry` is ignored; the stored row is
+/// returned with its new id.
+pub fn add_print_log_entry(conn: &Connection, entry: &PrintLogEntry) -> DbResult<PrintLogEntry> {
+    conn.execute(
+        "INSERT INTO print_log
+             (song_id, title, copies, printer, file_name, submitted_at, request_id, error)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        params![
+            entry.song_id,
+            entry.title,
+            entry.copies,
+            entry.printer,
+            entry.file_name,
+            entry.submitted_at,
+            entry.request_id,
+            entry.error
+        ],
+    )
+    .db_context("failed to record print job")?;
+
+    Ok(PrintLogEntry {
+        id: conn.last_insert_rowid(),
+        ..entry.clone()
+    })
+}
+
+/// Insert a print job keeping its id. Only snapshot restores need this.
+pub fn restore_print_log_entry(conn: &Connection, entry: &PrintLogEntry) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO print_log
+             (id, song_id, title, copies, printer, file_name, submitted_at, request_id, error)
+         VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+        params![
+            entry.id,
+            entry.song_id,
+            entry.title,
+            entry.copies,
+            entry.printer,
+            entry.file_name,
+            entry.submitted_at,
+            entry.request_id,
+            entry.error
+        ],
+    )
+    .db_context("failed to restore print job")?;
+    Ok(())
+}
+
+/// Forget every print job sent.
+pub fn clear_print_log(conn: &Connection) -> DbResult<()> {
+    conn.execute("DELETE FROM print_log", [])
+        .db_context("failed to clear print log")?;
+    Ok(())
+}
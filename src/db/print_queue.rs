@@ -0,0 +1,70 @@
+use rusqlite::types::Type;
+use rusqlite::{params, Connection, Error as SqlError, Row};
+
+use super::error::{DbContext, DbResult};
+use crate::models::{PrintQueueItem, PrintStage};
+
+fn item_from_row(row: &Row<'_>) -> rusqlite::Result<PrintQueueItem> {
+    let stage: String = row.get(2)?;
+    let stage = PrintStage::from_tag(&stage).ok_or_else(|| {
+        SqlError::FromSqlConversionFailure(
+            2,
+            Type::Text,
+            format!("unknown print stage '{stage}'").into(),
+        )
+    })?;
+    Ok(PrintQueueItem {
+        binder_id: row.get(0)?,
+        song_id: row.get(1)?,
+        stage,
+        updated_at: row.get(3)?,
+    })
+}
+
+/// Every song printed for a binder but not yet linked to it, by binder then
+/// song.
+pub fn fetch_print_queue(conn: &Connection) -> DbResult<Vec<PrintQueueItem>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT binder_id, song_id, stage, updated_at
+             FROM print_queue
+             ORDER BY binder_id, song_id",
+        )
+        .db_context("failed to prepare print queue query")?;
+
+    let items = stmt
+        .query_map([], item_from_row)
+        .db_context("failed to load print queue")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect print queue")?;
+
+    Ok(items)
+}
+
+/// Move a binder's copy of a song to `stage` as of `updated_at`.
+/// [`PrintStage::Needed`] takes it off the queue.
+pub fn set_print_stage(
+    conn: &Connection,
+    binder_id: i64,
+    song_id: i64,
+    stage: PrintStage,
+    updated_at: &str,
+) -> DbResult<()> {
+    if stage == PrintStage::Needed {
+        conn.execute(
+            "DELETE FROM print_queue WHERE binder_id = ?1 AND song_id = ?2",
+            params![binder_id, song_id],
+        )
+        .db_context("failed to take song off the print queue")?;
+        return Ok(());
+    }
+    conn.execute(
+        "INSERT INTO print_queue (binder_id, song_id, stage, updated_at)
+         VALUES (?1, ?2, ?3, ?4)
+         ON CONFLICT (binder_id, song_id)
+         DO UPDATE SET stage = excluded.stage, updated_at = excluded.updated_at",
+        params![binder_id, song_id, stage.tag(), updated_at],
+    )
+    .db_context("failed to update print queue")?;
+    Ok(())
+}
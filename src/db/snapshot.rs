@@ -0,0 +1,887 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use serde::{Deserialize, Serialize};
+
+use super::error::{DbError, DbResult};
+use super::library::Library;
+use crate::models::{
+    Attachment, Binder, BinderCheckout, Chorister, Concert, LinkKind, Performance, PrintLogEntry,
+    PrintQueueItem, PrintStage, Song, SongDetails, SongLink, Tag,
+};
+
+/// Value of the `format` field, so stray JSON files are rejected early.
+pub const SNAPSHOT_FORMAT: &str = "choir-binder-manager/library";
+/// Current snapshot layout. Bump it whenever a field changes meaning; fields
+/// that are merely added should default when missing so older files still load.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+/// Portable copy of a whole library. Records keep their database ids so a
+/// replace-restore reproduces the original exactly, and lists are sorted so two
+/// snapshots of similar libraries diff cleanly.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LibrarySnapshot {
+    pub format: String,
+    pub version: u32,
+    pub binders: Vec<Binder>,
+    pub songs: Vec<Song>,
+    pub memberships: Vec<Membership>,
+    #[serde(default)]
+    pub concerts: Vec<Concert>,
+    #[serde(default)]
+    pub setlists: Vec<SetlistEntry>,
+    #[serde(default)]
+    pub performances: Vec<Performance>,
+    #[serde(default)]
+    pub choristers: Vec<Chorister>,
+    #[serde(default)]
+    pub checkouts: Vec<BinderCheckout>,
+    #[serde(default)]
+    pub tags: Vec<Tag>,
+    #[serde(default)]
+    pub song_tags: Vec<SongTag>,
+    #[serde(default)]
+    pub song_links: Vec<SongLink>,
+    /// Attachment records only; the files themselves travel in an
+    /// `attachments/` folder next to the snapshot.
+    #[serde(default)]
+    pub attachments: Vec<Attachment>,
+    /// Print jobs sent, kept with the title they were sent under so entries
+    /// for deleted songs survive.
+    #[serde(default)]
+    pub print_log: Vec<PrintLogEntry>,
+    /// Songs printed or inserted for a binder but not yet linked to it.
+    #[serde(default)]
+    pub print_queue: Vec<PrintQueueItem>,
+}
+
+/// A song sitting in a binder.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Membership {
+    pub binder_id: i64,
+    pub song_id: i64,
+    /// 1-based place in the binder's running order. Snapshots written before
+    /// songs were ordered lack it and restore alphabetically.
+    #[serde(default)]
+    pub position: i64,
+}
+
+/// The songs of a snapshot written while songs still had a link of their own.
+#[derive(Deserialize)]
+struct LegacySongs {
+    songs: Vec<LegacySong>,
+}
+
+#[derive(Deserialize)]
+struct LegacySong {
+    id: i64,
+    #[serde(default)]
+    link: String,
+}
+
+/// A song on a concert's setlist.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SetlistEntry {
+    pub concert_id: i64,
+    pub song_id: i64,
+    /// 1-based place in the setlist.
+    pub position: i64,
+}
+
+/// A tag on a song.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct SongTag {
+    pub song_id: i64,
+    pub tag_id: i64,
+}
+
+/// How [`import_library`] treats data already in the library.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RestoreMode {
+    /// Delete everything first, then load the snapshot with its original ids.
+    Replace,
+    /// Keep existing data and add whatever the snapshot has that is missing.
+    /// Binders match on number, songs on title and composer (ignoring case),
+    /// concerts on name (ignoring case) and date, performances on song, date
+    /// and event, choristers and tags on name (ignoring case), checkouts on
+    /// binder and checkout time, song links on song, kind, part and URL,
+    /// attachments on song and checksum, print jobs on song, file and
+    /// submission time, and print queue entries on binder and song.
+    /// Binders without a holder or voice part and songs without parts or
+    /// details take the snapshot's, and a binder that is already out keeps its
+    /// own open checkout. A queued song already in its binder, or already
+    /// queued for it, is left as it is.
+    Merge,
+}
+
+/// What a restore changed.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct RestoreSummary {
+    pub binders_added: usize,
+    pub songs_added: usize,
+    pub memberships_added: usize,
+    pub concerts_added: usize,
+    pub performances_added: usize,
+    pub choristers_added: usize,
+    pub checkouts_added: usize,
+    pub tags_added: usize,
+    pub links_added: usize,
+    pub attachments_added: usize,
+    pub print_jobs_added: usize,
+    pub queue_entries_added: usize,
+}
+
+impl LibrarySnapshot {
+    /// Pretty-printed JSON with a trailing newline.
+    pub fn to_json(&self) -> DbResult<String> {
+        let mut json = serde_json::to_string_pretty(self)
+            .map_err(|err| DbError::InvalidSnapshot(err.to_string()))?;
+        json.push('\n');
+        Ok(json)
+    }
+
+    /// Parse and validate a snapshot. The format tag and version are checked
+    /// before the body so a newer file reports a version error rather than a
+    /// confusing field error.
+    pub fn from_json(json: &str) -> DbResult<Self> {
+        #[derive(Deserialize)]
+        struct Header {
+            format: String,
+            version: u32,
+        }
+
+        let header: Header =
+            serde_json::from_str(json).map_err(|err| DbError::InvalidSnapshot(err.to_string()))?;
+        if header.format != SNAPSHOT_FORMAT {
+            return Err(DbError::InvalidSnapshot(format!(
+                "unexpected format '{}'",
+                header.format
+            )));
+        }
+        if header.version > SNAPSHOT_VERSION {
+            return Err(DbError::SnapshotTooNew {
+                found: header.version,
+                supported: SNAPSHOT_VERSION,
+            });
+        }
+
+        let mut snapshot: Self =
+            serde_json::from_str(json).map_err(|err| DbError::InvalidSnapshot(err.to_string()))?;
+        let legacy: LegacySongs =
+            serde_json::from_str(json).map_err(|err| DbError::InvalidSnapshot(err.to_string()))?;
+        snapshot.adopt_legacy_links(legacy.songs);
+        snapshot.validate()?;
+        Ok(snapshot)
+    }
+
+    /// Turn the links songs carried themselves in older snapshots into typed
+    /// links, the way migration 14 does for databases.
+    fn adopt_legacy_links(&mut self, songs: Vec<LegacySong>) {
+        let mut next_id = self
+            .song_links
+            .iter()
+            .map(|link| link.id)
+            .max()
+            .unwrap_or(0)
+            + 1;
+        for song in songs {
+            let url = song.link.trim();
+            if url.is_empty() {
+                continue;
+            }
+            self.song_links.push(SongLink {
+                id: next_id,
+                song_id: song.id,
+                kind: LinkKind::guess(url),
+                part: String::new(),
+                label: String::new(),
+                url: url.to_string(),
+            });
+            next_id += 1;
+        }
+    }
+
+    /// Check ids and tag names are unique, every membership points at a known
+    /// record, and no binder is checked out twice at once.
+    fn validate(&self) -> DbResult<()> {
+        let mut chorister_ids = HashSet::new();
+        for chorister in &self.choristers {
+            if !chorister_ids.insert(chorister.id) {
+                return Err(invalid(format!(
+                    "chorister id {} appears twice",
+                    chorister.id
+                )));
+            }
+        }
+        let mut binder_ids = HashSet::new();
+        let mut numbers = HashSet::new();
+        for binder in &self.binders {
+            if binder
+                .holder_id
+                .is_some_and(|holder| !chorister_ids.contains(&holder))
+            {
+                return Err(invalid(format!(
+                    "binder {} is held by a missing chorister",
+                    binder.number
+                )));
+            }
+            if !binder_ids.insert(binder.id) {
+                return Err(invalid(format!("binder id {} appears twice", binder.id)));
+            }
+            if !numbers.insert(binder.number) {
+                return Err(DbError::DuplicateBinderNumber(binder.number));
+            }
+        }
+        let mut song_ids = HashSet::new();
+        for song in &self.songs {
+            if !song_ids.insert(song.id) {
+                return Err(invalid(format!("song id {} appears twice", song.id)));
+            }
+        }
+        for link in &self.memberships {
+            if !binder_ids.contains(&link.binder_id) || !song_ids.contains(&link.song_id) {
+                return Err(invalid(format!(
+                    "membership ({}, {}) refers to a missing binder or song",
+                    link.binder_id, link.song_id
+                )));
+            }
+        }
+        let mut concert_ids = HashSet::new();
+        for concert in &self.concerts {
+            if !concert_ids.insert(concert.id) {
+                return Err(invalid(format!("concert id {} appears twice", concert.id)));
+            }
+        }
+        for entry in &self.setlists {
+            if !concert_ids.contains(&entry.concert_id) || !song_ids.contains(&entry.song_id) {
+                return Err(invalid(format!(
+                    "setlist entry ({}, {}) refers to a missing concert or song",
+                    entry.concert_id, entry.song_id
+                )));
+            }
+        }
+        let mut performance_ids = HashSet::new();
+        for performance in &self.performances {
+            if !performance_ids.insert(performance.id) {
+                return Err(invalid(format!(
+                    "performance id {} appears twice",
+                    performance.id
+                )));
+            }
+            if !song_ids.contains(&performance.song_id) {
+                return Err(invalid(format!(
+                    "performance {} refers to a missing song",
+                    performance.id
+                )));
+            }
+        }
+        let mut checkout_ids = HashSet::new();
+        let mut out = HashSet::new();
+        for checkout in &self.checkouts {
+            if checkout.is_open() && !out.insert(checkout.binder_id) {
+                return Err(invalid(format!(
+                    "binder id {} is checked out twice",
+                    checkout.binder_id
+                )));
+            }
+            if !checkout_ids.insert(checkout.id) {
+                return Err(invalid(format!(
+                    "checkout id {} appears twice",
+                    checkout.id
+                )));
+            }
+            if !binder_ids.contains(&checkout.binder_id)
+                || checkout
+                    .chorister_id
+                    .is_some_and(|chorister| !chorister_ids.contains(&chorister))
+            {
+                return Err(invalid(format!(
+                    "checkout {} refers to a missing binder or chorister",
+                    checkout.id
+                )));
+            }
+        }
+        let mut tag_ids = HashSet::new();
+        let mut tag_names = HashSet::new();
+        for tag in &self.tags {
+            if !tag_ids.insert(tag.id) {
+                return Err(invalid(format!("tag id {} appears twice", tag.id)));
+            }
+            if !tag_names.insert(tag_key(tag)) {
+                return Err(invalid(format!("tag '{}' appears twice", tag.name)));
+            }
+        }
+        for link in &self.song_tags {
+            if !song_ids.contains(&link.song_id) || !tag_ids.contains(&link.tag_id) {
+                return Err(invalid(format!(
+                    "song tag ({}, {}) refers to a missing song or tag",
+                    link.song_id, link.tag_id
+                )));
+            }
+        }
+        let mut link_ids = HashSet::new();
+        for link in &self.song_links {
+            if !link_ids.insert(link.id) {
+                return Err(invalid(format!("song link id {} appears twice", link.id)));
+            }
+            if !song_ids.contains(&link.song_id) {
+                return Err(invalid(format!(
+                    "song link {} refers to a missing song",
+                    link.id
+                )));
+            }
+        }
+        let mut attachment_ids = HashSet::new();
+        for attachment in &self.attachments {
+            if !attachment_ids.insert(attachment.id) {
+                return Err(invalid(format!(
+                    "attachment id {} appears twice",
+                    attachment.id
+                )));
+            }
+            if !song_ids.contains(&attachment.song_id) {
+                return Err(invalid(format!(
+                    "attachment {} refers to a missing song",
+                    attachment.id
+                )));
+            }
+            let checksum = &attachment.sha256;
+            if checksum.len() != 64
+                || !checksum
+                    .chars()
+                    .all(|ch| ch.is_ascii_digit() || ('a'..='f').contains(&ch))
+            {
+                return Err(invalid(format!(
+                    "attachment {} has an invalid checksum",
+                    attachment.id
+                )));
+            }
+        }
+        let mut print_job_ids = HashSet::new();
+        for entry in &self.print_log {
+            if !print_job_ids.insert(entry.id) {
+                return Err(invalid(format!("print job id {} appears twice", entry.id)));
+            }
+            if entry.song_id.is_some_and(|song| !song_ids.contains(&song)) {
+                return Err(invalid(format!(
+                    "print job {} refers to a missing song",
+                    entry.id
+                )));
+            }
+        }
+        let memberships: HashSet<(i64, i64)> = self
+            .memberships
+            .iter()
+            .map(|link| (link.binder_id, link.song_id))
+            .collect();
+        let mut queued = HashSet::new();
+        for item in &self.print_queue {
+            let pair = (item.binder_id, item.song_id);
+            if !binder_ids.contains(&item.binder_id) || !song_ids.contains(&item.song_id) {
+                return Err(invalid(format!(
+                    "print queue entry ({}, {}) refers to a missing binder or song",
+                    item.binder_id, item.song_id
+                )));
+            }
+            if !queued.insert(pair) {
+                return Err(invalid(format!(
+                    "print queue entry ({}, {}) appears twice",
+                    item.binder_id, item.song_id
+                )));
+            }
+            if item.stage == PrintStage::Needed || memberships.contains(&pair) {
+                return Err(invalid(format!(
+                    "print queue entry ({}, {}) is not waiting to be linked",
+                    item.binder_id, item.song_id
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Songs count as the same when title and composer match, ignoring case.
+fn song_key(song: &Song) -> (String, String) {
+    (song.title.to_lowercase(), song.composer.to_lowercase())
+}
+
+/// Concerts count as the same when name (ignoring case) and date match.
+fn concert_key(concert: &Concert) -> (String, String) {
+    (concert.name.to_lowercase(), concert.date.clone())
+}
+
+/// Choristers count as the same when their names match, ignoring case.
+fn chorister_key(chorister: &Chorister) -> String {
+    chorister.name.to_lowercase()
+}
+
+/// Tags count as the same when their names match, ignoring case.
+fn tag_key(tag: &Tag) -> String {
+    tag.name.to_lowercase()
+}
+
+/// Song links count as the same when song, kind, part (ignoring case) and URL
+/// match.
+fn song_link_key(song_id: i64, link: &SongLink) -> (i64, &'static str, String, String) {
+    (
+        song_id,
+        link.kind.tag(),
+        link.part.to_lowercase(),
+        link.url.clone(),
+    )
+}
+
+/// Performances count as the same when song, date and event (ignoring case)
+/// match.
+fn performance_key(song_id: i64, performance: &Performance) -> (i64, String, String) {
+    (
+        song_id,
+        performance.date.clone(),
+        performance.event.to_lowercase(),
+    )
+}
+
+fn invalid(message: String) -> DbError {
+    DbError::InvalidSnapshot(message)
+}
+
+/// Capture every binder, song, membership, concert, setlist entry,
+/// performance, chorister, checkout, tag, song link, attachment record, print
+/// job and print queue entry. Binders are ordered by
+/// number, other records by id, and links and queue entries by their owner
+/// then song id; song tags go by song then tag id.
+pub fn export_library(library: &dyn Library) -> DbResult<LibrarySnapshot> {
+    let mut songs = library.fetch_all_songs()?;
+    songs.sort_by_key(|song| song.id);
+    let binders = library.fetch_binders()?;
+
+    let mut memberships = BTreeSet::new();
+    for binder in &binders {
+        for (index, song) in library
+            .fetch_songs_for_binder(binder.id)?
+            .iter()
+            .enumerate()
+        {
+            memberships.insert(Membership {
+                binder_id: binder.id,
+                song_id: song.id,
+                position: index as i64 + 1,
+            });
+        }
+    }
+
+    let mut concerts = library.fetch_concerts()?;
+    concerts.sort_by_key(|concert| concert.id);
+    let mut setlists = BTreeSet::new();
+    for concert in &concerts {
+        for (index, song) in library.fetch_setlist(concert.id)?.iter().enumerate() {
+            setlists.insert(SetlistEntry {
+                concert_id: concert.id,
+                song_id: song.id,
+                position: index as i64 + 1,
+            });
+        }
+    }
+
+    let mut performances = library.fetch_performances()?;
+    performances.sort_by_key(|performance| performance.id);
+    let mut choristers = library.fetch_choristers()?;
+    choristers.sort_by_key(|chorister| chorister.id);
+    let mut checkouts = library.fetch_checkouts()?;
+    checkouts.sort_by_key(|checkout| checkout.id);
+    let mut tags = library.fetch_tags()?;
+    tags.sort_by_key(|tag| tag.id);
+    let song_tags = library
+        .fetch_tag_links()?
+        .into_iter()
+        .map(|(song_id, tag_id)| SongTag { song_id, tag_id })
+        .collect();
+    let mut song_links = library.fetch_song_links()?;
+    song_links.sort_by_key(|link| link.id);
+    let mut attachments = library.fetch_attachments()?;
+    attachments.sort_by_key(|attachment| attachment.id);
+    let mut print_log = library.fetch_print_log()?;
+    print_log.sort_by_key(|entry| entry.id);
+    let mut print_queue = library.fetch_print_queue()?;
+    print_queue.sort_by_key(|item| (item.binder_id, item.song_id));
+
+    Ok(LibrarySnapshot {
+        format: SNAPSHOT_FORMAT.to_string(),
+        version: SNAPSHOT_VERSION,
+        binders,
+        songs,
+        memberships: memberships.into_iter().collect(),
+        concerts,
+        setlists: setlists.into_iter().collect(),
+        performances,
+        choristers,
+        checkouts,
+        tags,
+        song_tags,
+        song_links,
+        attachments,
+        print_log,
+        print_queue,
+    })
+}
+
+/// Load a snapshot into `library` in one transaction; on failure nothing
+/// changes. Restoring with [`RestoreMode::Replace`] and exporting again yields
+/// the same snapshot.
+pub fn import_library(
+    library: &mut dyn Library,
+    snapshot: &LibrarySnapshot,
+    mode: RestoreMode,
+) -> DbResult<RestoreSummary> {
+    snapshot.validate()?;
+    let mut summary = RestoreSummary::default();
+    library.transaction(&mut |library| {
+        summary = match mode {
+            RestoreMode::Replace => replace(library, snapshot)?,
+            RestoreMode::Merge => merge(library, snapshot)?,
+        };
+        Ok(())
+    })?;
+    Ok(summary)
+}
+
+fn replace(library: &mut dyn Library, snapshot: &LibrarySnapshot) -> DbResult<RestoreSummary> {
+    for binder in library.fetch_binders()? {
+        library.delete_binder(binder.id)?;
+    }
+    for concert in library.fetch_concerts()? {
+        library.delete_concert(concert.id)?;
+    }
+    for song in library.fetch_all_songs()? {
+        library.delete_song(song.id)?;
+    }
+    for chorister in library.fetch_choristers()? {
+        library.delete_chorister(chorister.id)?;
+    }
+    for tag in library.fetch_tags()? {
+        library.delete_tag(tag.id)?;
+    }
+    library.clear_print_log()?;
+
+    // Choristers go first so binders can point at their holders.
+    for chorister in &snapshot.choristers {
+        library.restore_chorister(chorister)?;
+    }
+    for binder in &snapshot.binders {
+        library.restore_binder(binder)?;
+    }
+    for song in &snapshot.songs {
+        library.restore_song(song)?;
+    }
+    // Links are appended, so adding them in running order rebuilds each
+    // binder's order exactly.
+    let titles: HashMap<i64, (String, String)> = snapshot
+        .songs
+        .iter()
+        .map(|song| (song.id, song_key(song)))
+        .collect();
+    let mut links = snapshot.memberships.clone();
+    links.sort_by(|a, b| {
+        (a.binder_id, a.position, &titles[&a.song_id]).cmp(&(
+            b.binder_id,
+            b.position,
+            &titles[&b.song_id],
+        ))
+    });
+    for link in &links {
+        library.add_song_to_binder(link.binder_id, link.song_id)?;
+    }
+
+    for concert in &snapshot.concerts {
+        library.restore_concert(concert)?;
+    }
+    let mut entries = snapshot.setlists.clone();
+    entries.sort_by_key(|entry| {
+        (
+            entry.concert_id,
+            entry.position,
+            titles[&entry.song_id].clone(),
+        )
+    });
+    for entry in &entries {
+        library.add_song_to_setlist(entry.concert_id, entry.song_id)?;
+    }
+
+    for performance in &snapshot.performances {
+        library.restore_performance(performance)?;
+    }
+    for checkout in &snapshot.checkouts {
+        library.restore_checkout(checkout)?;
+    }
+    for tag in &snapshot.tags {
+        library.restore_tag(tag)?;
+    }
+    for link in &snapshot.song_tags {
+        library.tag_song(link.song_id, link.tag_id)?;
+    }
+    for link in &snapshot.song_links {
+        library.restore_song_link(link)?;
+    }
+    for attachment in &snapshot.attachments {
+        library.restore_attachment(attachment)?;
+    }
+    for entry in &snapshot.print_log {
+        library.restore_print_log_entry(entry)?;
+    }
+    // Deleting the binders above emptied the queue.
+    for item in &snapshot.print_queue {
+        library.set_print_stage(item.binder_id, item.song_id, item.stage, &item.updated_at)?;
+    }
+
+    Ok(RestoreSummary {
+        binders_added: snapshot.binders.len(),
+        songs_added: snapshot.songs.len(),
+        memberships_added: snapshot.memberships.len(),
+        concerts_added: snapshot.concerts.len(),
+        performances_added: snapshot.performances.len(),
+        choristers_added: snapshot.choristers.len(),
+        checkouts_added: snapshot.checkouts.len(),
+        tags_added: snapshot.tags.len(),
+        links_added: snapshot.song_links.len(),
+        attachments_added: snapshot.attachments.len(),
+        print_jobs_added: snapshot.print_log.len(),
+        queue_entries_added: snapshot.print_queue.len(),
+    })
+}
+
+fn merge(library: &mut dyn Library, snapshot: &LibrarySnapshot) -> DbResult<RestoreSummary> {
+    let mut summary = RestoreSummary::default();
+
+    let mut chorister_by_key: HashMap<String, i64> = library
+        .fetch_choristers()?
+        .iter()
+        .map(|chorister| (chorister_key(chorister), chorister.id))
+        .collect();
+    let mut chorister_ids = HashMap::new();
+    for chorister in &snapshot.choristers {
+        let id = match chorister_by_key.get(&chorister_key(chorister)) {
+            Some(&id) => id,
+            None => {
+                let created = library.create_chorister(
+                    &chorister.name,
+                    &chorister.voice_part,
+                    &chorister.contact,
+                )?;
+                chorister_by_key.insert(chorister_key(&created), created.id);
+                summary.choristers_added += 1;
+                created.id
+            }
+        };
+        chorister_ids.insert(chorister.id, id);
+    }
+
+    let mut binder_by_number: HashMap<i64, Binder> = library
+        .fetch_binders()?
+        .into_iter()
+        .map(|binder| (binder.number, binder))
+        .collect();
+    let mut binder_ids = HashMap::new();
+    for binder in &snapshot.binders {
+        let existing = match binder_by_number.get(&binder.number) {
+            Some(existing) => existing.clone(),
+            None => {
+                let created = library.create_binder(binder.number, &binder.label)?;
+                binder_by_number.insert(created.number, created.clone());
+                summary.binders_added += 1;
+                created
+            }
+        };
+        if let (None, Some(holder)) = (existing.holder_id, binder.holder_id) {
+            library.assign_binder(existing.id, Some(chorister_ids[&holder]))?;
+        }
+        if existing.voice_part.is_empty() && !binder.voice_part.is_empty() {
+            library.set_binder_part(existing.id, &binder.voice_part)?;
+        }
+        binder_ids.insert(binder.id, existing.id);
+    }
+
+    let mut song_by_key: HashMap<(String, String), Song> = library
+        .fetch_all_songs()?
+        .into_iter()
+        .map(|song| (song_key(&song), song))
+        .collect();
+    let mut song_ids = HashMap::new();
+    for song in &snapshot.songs {
+        let existing = match song_by_key.get(&song_key(song)) {
+            Some(existing) => existing.clone(),
+            None => {
+                let created = library.create_song(&song.title, &song.composer)?;
+                song_by_key.insert(song_key(&created), created.clone());
+                summary.songs_added += 1;
+                created
+            }
+        };
+        if existing.parts.is_empty() && !song.parts.is_empty() {
+            library.set_song_parts(existing.id, &song.parts)?;
+        }
+        if existing.details == SongDetails::default() && song.details != SongDetails::default() {
+            library.set_song_details(existing.id, &song.details)?;
+        }
+        song_ids.insert(song.id, existing.id);
+    }
+
+    // New links go to the end of their binder, in the snapshot's order.
+    let existing: HashSet<(i64, i64)> = library.fetch_memberships()?.into_iter().collect();
+    let mut links = snapshot.memberships.clone();
+    links.sort_by_key(|link| (link.binder_id, link.position));
+    for link in &links {
+        let pair = (binder_ids[&link.binder_id], song_ids[&link.song_id]);
+        if !existing.contains(&pair) {
+            library.add_song_to_binder(pair.0, pair.1)?;
+            summary.memberships_added += 1;
+        }
+    }
+
+    // Concerts that already exist keep their setlist; new ones get theirs.
+    let existing: HashSet<(String, String)> =
+        library.fetch_concerts()?.iter().map(concert_key).collect();
+    for concert in &snapshot.concerts {
+        if existing.contains(&concert_key(concert)) {
+            continue;
+        }
+        let created = library.create_concert(&concert.name, &concert.date, &concert.venue)?;
+        summary.concerts_added += 1;
+        let mut entries: Vec<&SetlistEntry> = snapshot
+            .setlists
+            .iter()
+            .filter(|entry| entry.concert_id == concert.id)
+            .collect();
+        entries.sort_by_key(|entry| entry.position);
+        for entry in entries {
+            library.add_song_to_setlist(created.id, song_ids[&entry.song_id])?;
+        }
+    }
+
+    let mut existing: HashSet<(i64, String, String)> = library
+        .fetch_performances()?
+        .iter()
+        .map(|performance| performance_key(performance.song_id, performance))
+        .collect();
+    for performance in &snapshot.performances {
+        let song_id = song_ids[&performance.song_id];
+        if existing.insert(performance_key(song_id, performance)) {
+            library.record_performance(song_id, &performance.date, &performance.event)?;
+            summary.performances_added += 1;
+        }
+    }
+
+    let existing_checkouts = library.fetch_checkouts()?;
+    let mut out: HashSet<i64> = existing_checkouts
+        .iter()
+        .filter(|checkout| checkout.is_open())
+        .map(|checkout| checkout.binder_id)
+        .collect();
+    let mut existing: HashSet<(i64, String)> = existing_checkouts
+        .into_iter()
+        .map(|checkout| (checkout.binder_id, checkout.checked_out_at))
+        .collect();
+    for checkout in &snapshot.checkouts {
+        let binder_id = binder_ids[&checkout.binder_id];
+        if (checkout.is_open() && out.contains(&binder_id))
+            || !existing.insert((binder_id, checkout.checked_out_at.clone()))
+        {
+            continue;
+        }
+        if checkout.is_open() {
+            out.insert(binder_id);
+        }
+        library.record_checkout(
+            binder_id,
+            checkout.chorister_id.map(|id| chorister_ids[&id]),
+            &checkout.checked_out_at,
+            checkout.checked_in_at.as_deref(),
+        )?;
+        summary.checkouts_added += 1;
+    }
+
+    let mut tag_by_key: HashMap<String, i64> = library
+        .fetch_tags()?
+        .iter()
+        .map(|tag| (tag_key(tag), tag.id))
+        .collect();
+    let mut tag_ids = HashMap::new();
+    for tag in &snapshot.tags {
+        let id = match tag_by_key.get(&tag_key(tag)) {
+            Some(&id) => id,
+            None => {
+                let created = library.create_tag(&tag.name)?;
+                tag_by_key.insert(tag_key(&created), created.id);
+                summary.tags_added += 1;
+                created.id
+            }
+        };
+        tag_ids.insert(tag.id, id);
+    }
+    for link in &snapshot.song_tags {
+        library.tag_song(song_ids[&link.song_id], tag_ids[&link.tag_id])?;
+    }
+
+    let mut existing: HashSet<(i64, &'static str, String, String)> = library
+        .fetch_song_links()?
+        .iter()
+        .map(|link| song_link_key(link.song_id, link))
+        .collect();
+    for link in &snapshot.song_links {
+        let song_id = song_ids[&link.song_id];
+        if existing.insert(song_link_key(song_id, link)) {
+            library.add_song_link(song_id, link.kind, &link.part, &link.label, &link.url)?;
+            summary.links_added += 1;
+        }
+    }
+
+    let mut existing: HashSet<(i64, String)> = library
+        .fetch_attachments()?
+        .into_iter()
+        .map(|attachment| (attachment.song_id, attachment.sha256))
+        .collect();
+    for attachment in &snapshot.attachments {
+        let song_id = song_ids[&attachment.song_id];
+        if existing.insert((song_id, attachment.sha256.clone())) {
+            library.add_attachment(
+                song_id,
+                &attachment.file_name,
+                &attachment.sha256,
+                attachment.size_bytes,
+                &attachment.added_at,
+            )?;
+            summary.attachments_added += 1;
+        }
+    }
+
+    let mut existing: HashSet<(Option<i64>, String, String)> = library
+        .fetch_print_log()?
+        .into_iter()
+        .map(|entry| (entry.song_id, entry.file_name, entry.submitted_at))
+        .collect();
+    for entry in &snapshot.print_log {
+        let song_id = entry.song_id.map(|id| song_ids[&id]);
+        if existing.insert((song_id, entry.file_name.clone(), entry.submitted_at.clone())) {
+            library.add_print_log_entry(&PrintLogEntry {
+                song_id,
+                ..entry.clone()
+            })?;
+            summary.print_jobs_added += 1;
+        }
+    }
+
+    let mut existing: HashSet<(i64, i64)> = library.fetch_memberships()?.into_iter().collect();
+    existing.extend(
+        library
+            .fetch_print_queue()?
+            .into_iter()
+            .map(|item| (item.binder_id, item.song_id)),
+    );
+    for item in &snapshot.print_queue {
+        let pair = (binder_ids[&item.binder_id], song_ids[&item.song_id]);
+        if existing.insert(pair) {
+            library.set_print_stage(pair.0, pair.1, item.stage, &item.updated_at)?;
+            summary.queue_entries_added += 1;
+        }
+    }
+
+    Ok(summary)
+}
@@ -1,31 +1,50 @@
-use anyhow::{anyhow, Context, Result};
-use rusqlite::{params, Connection};
+use std::collections::HashSet;
 
-use crate::models::Song;
+use rusqlite::{params, Connection, Row};
+
+use super::error::{DbContext, DbError, DbResult, Entity};
+
+use crate::models::{Song, SongDetails};
+
+/// Build a song from a row selecting `id, title, composer, parts`
+/// followed by the [`SongDetails`] columns in declaration order.
+pub(super) fn song_from_row(row: &Row<'_>) -> rusqlite::Result<Song> {
+    Ok(Song {
+        id: row.get(0)?,
+        title: row.get(1)?,
+        composer: row.get(2)?,
+        parts: row.get(3)?,
+        details: SongDetails {
+            arranger: row.get(4)?,
+            voicing: row.get(5)?,
+            accompaniment: row.get(6)?,
+            language: row.get(7)?,
+            publisher: row.get(8)?,
+            catalog_number: row.get(9)?,
+            duration_seconds: row.get(10)?,
+            page_count: row.get(11)?,
+        },
+    })
+}
 
 /// Fetch all songs across binders, ordered case-insensitively so mixed-case
 /// titles group together in the UI.
-pub fn fetch_all_songs(conn: &Connection) -> Result<Vec<Song>> {
+pub fn fetch_all_songs(conn: &Connection) -> DbResult<Vec<Song>> {
     let mut stmt = conn
         .prepare(
-            "SELECT id, title, composer, link
+            "SELECT id, title, composer, parts,
+                    arranger, voicing, accompaniment, language, publisher,
+                    catalog_number, duration_seconds, page_count
              FROM songs
              ORDER BY title COLLATE NOCASE, composer COLLATE NOCASE",
         )
-        .context("failed to prepare all songs query")?;
+        .db_context("failed to prepare all songs query")?;
 
     let songs = stmt
-        .query_map([], |row| {
-            Ok(Song {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                composer: row.get(2)?,
-                link: row.get(3)?,
-            })
-        })
-        .context("failed to iterate songs")?
+        .query_map([], song_from_row)
+        .db_context("failed to iterate songs")?
         .collect::<Result<Vec<_>, _>>()
-        .context("failed to collect songs")?;
+        .db_context("failed to collect songs")?;
 
     Ok(songs)
 }
@@ -33,165 +52,282 @@ pub fn fetch_all_songs(conn: &Connection) -> Result<Vec<Song>> {
 /// Retrieve distinct composers for the auto-complete widget. The ordering sorts
 /// by lowercase first but falls back to the original text to keep accents and
 /// capitalization intact.
-pub fn fetch_composers(conn: &Connection) -> Result<Vec<String>> {
+pub fn fetch_composers(conn: &Connection) -> DbResult<Vec<String>> {
     let mut stmt = conn
         .prepare(
             "SELECT DISTINCT composer FROM songs
              WHERE composer IS NOT NULL AND composer <> ''
              ORDER BY LOWER(composer), composer",
         )
-        .context("failed to prepare composer query")?;
+        .db_context("failed to prepare composer query")?;
 
-    let mut rows = stmt.query([]).context("failed to execute composer query")?;
+    let mut rows = stmt
+        .query([])
+        .db_context("failed to execute composer query")?;
 
     let mut composers = Vec::new();
-    while let Some(row) = rows.next().context("failed to fetch composer row")? {
-        let composer: String = row.get(0).context("failed to read composer value")?;
+    while let Some(row) = rows.next().db_context("failed to fetch composer row")? {
+        let composer: String = row.get(0).db_context("failed to read composer value")?;
         composers.push(composer);
     }
 
     Ok(composers)
 }
 
-/// Get every song linked to a specific binder. Used by the detail view when the
-/// user drills into a binder card.
-pub fn fetch_songs_for_binder(conn: &Connection, binder_id: i64) -> Result<Vec<Song>> {
+/// Get every song linked to a specific binder in its running order. Used by the
+/// detail view when the user drills into a binder card.
+pub fn fetch_songs_for_binder(conn: &Connection, binder_id: i64) -> DbResult<Vec<Song>> {
     let mut stmt = conn
         .prepare(
-            "SELECT s.id, s.title, s.composer, s.link
+            "SELECT s.id, s.title, s.composer, s.parts,
+                    s.arranger, s.voicing, s.accompaniment, s.language, s.publisher,
+                    s.catalog_number, s.duration_seconds, s.page_count
              FROM songs s
              INNER JOIN binder_songs bs ON bs.song_id = s.id
              WHERE bs.binder_id = ?1
-             ORDER BY s.title COLLATE NOCASE, s.composer COLLATE NOCASE",
+             ORDER BY bs.position, s.title COLLATE NOCASE, s.composer COLLATE NOCASE",
         )
-        .context("failed to prepare binder songs query")?;
+        .db_context("failed to prepare binder songs query")?;
 
     let songs = stmt
-        .query_map([binder_id], |row| {
-            Ok(Song {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                composer: row.get(2)?,
-                link: row.get(3)?,
-            })
-        })
-        .context("failed to iterate binder songs")?
+        .query_map([binder_id], song_from_row)
+        .db_context("failed to iterate binder songs")?
         .collect::<Result<Vec<_>, _>>()
-        .context("failed to collect binder songs")?;
+        .db_context("failed to collect binder songs")?;
 
     Ok(songs)
 }
 
 /// Return songs not yet assigned to a given binder, enabling the "Add Song"
 /// workflow to show only eligible options.
-pub fn fetch_available_songs(conn: &Connection, binder_id: i64) -> Result<Vec<Song>> {
+pub fn fetch_available_songs(conn: &Connection, binder_id: i64) -> DbResult<Vec<Song>> {
     let mut stmt = conn
         .prepare(
-            "SELECT s.id, s.title, s.composer, s.link
+            "SELECT s.id, s.title, s.composer, s.parts,
+                    s.arranger, s.voicing, s.accompaniment, s.language, s.publisher,
+                    s.catalog_number, s.duration_seconds, s.page_count
              FROM songs s
              WHERE NOT EXISTS (
                  SELECT 1 FROM binder_songs bs WHERE bs.song_id = s.id AND bs.binder_id = ?1
              )
              ORDER BY s.title COLLATE NOCASE, s.composer COLLATE NOCASE",
         )
-        .context("failed to prepare available songs query")?;
+        .db_context("failed to prepare available songs query")?;
 
     let songs = stmt
-        .query_map([binder_id], |row| {
-            Ok(Song {
-                id: row.get(0)?,
-                title: row.get(1)?,
-                composer: row.get(2)?,
-                link: row.get(3)?,
-            })
-        })
-        .context("failed to iterate available songs")?
+        .query_map([binder_id], song_from_row)
+        .db_context("failed to iterate available songs")?
         .collect::<Result<Vec<_>, _>>()
-        .context("failed to collect available songs")?;
+        .db_context("failed to collect available songs")?;
 
     Ok(songs)
 }
 
 /// Insert a brand new song. We echo the hydrated struct so callers can update
 /// UI state without having to re-query the database.
-pub fn create_song(conn: &Connection, title: &str, composer: &str, link: &str) -> Result<Song> {
+pub fn create_song(conn: &Connection, title: &str, composer: &str) -> DbResult<Song> {
     conn.execute(
-        "INSERT INTO songs (title, composer, link) VALUES (?1, ?2, ?3)",
-        params![title, composer, link],
+        "INSERT INTO songs (title, composer) VALUES (?1, ?2)",
+        params![title, composer],
     )
-    .context("failed to insert song")?;
+    .db_context("failed to insert song")?;
 
     let id = conn.last_insert_rowid();
     Ok(Song {
         id,
         title: title.to_string(),
         composer: composer.to_string(),
-        link: link.to_string(),
+        parts: String::new(),
+        details: SongDetails::default(),
     })
 }
 
-/// Update all editable song fields. Like other update helpers, we surface an
-/// explicit error when zero rows are touched.
-pub fn update_song(
-    conn: &Connection,
-    id: i64,
-    title: &str,
-    composer: &str,
-    link: &str,
-) -> Result<()> {
+/// Insert a song under a known id, used when restoring a snapshot so ids
+/// survive the round trip.
+pub fn restore_song(conn: &Connection, song: &Song) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO songs (id, title, composer, parts) VALUES (?1, ?2, ?3, ?4)",
+        params![song.id, song.title, song.composer, song.parts],
+    )
+    .db_context("failed to restore song")?;
+    set_song_details(conn, song.id, &song.details)
+}
+
+/// Update all editable song fields. Like other update helpers, we surface
+/// [`DbError::NotFound`] when zero rows are touched.
+pub fn update_song(conn: &Connection, id: i64, title: &str, composer: &str) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE songs SET title = ?1, composer = ?2 WHERE id = ?3",
+            params![title, composer, id],
+        )
+        .db_context("failed to update song")?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Song))
+    } else {
+        Ok(())
+    }
+}
+
+/// Set which voice parts need a song (comma-separated); empty means all.
+pub fn set_song_parts(conn: &Connection, id: i64, parts: &str) -> DbResult<()> {
+    let updated = conn
+        .execute(
+            "UPDATE songs SET parts = ?1 WHERE id = ?2",
+            params![parts, id],
+        )
+        .db_context("failed to set song parts")?;
+
+    if updated == 0 {
+        Err(DbError::NotFound(Entity::Song))
+    } else {
+        Ok(())
+    }
+}
+
+/// Replace a song's arrangement and edition details.
+pub fn set_song_details(conn: &Connection, id: i64, details: &SongDetails) -> DbResult<()> {
     let updated = conn
         .execute(
-            "UPDATE songs SET title = ?1, composer = ?2, link = ?3 WHERE id = ?4",
-            params![title, composer, link, id],
+            "UPDATE songs
+             SET arranger = ?1, voicing = ?2, accompaniment = ?3, language = ?4,
+                 publisher = ?5, catalog_number = ?6, duration_seconds = ?7, page_count = ?8
+             WHERE id = ?9",
+            params![
+                details.arranger,
+                details.voicing,
+                details.accompaniment,
+                details.language,
+                details.publisher,
+                details.catalog_number,
+                details.duration_seconds,
+                details.page_count,
+                id
+            ],
         )
-        .context("failed to update song")?;
+        .db_context("failed to set song details")?;
 
     if updated == 0 {
-        Err(anyhow!("Song not found"))
+        Err(DbError::NotFound(Entity::Song))
     } else {
         Ok(())
     }
 }
 
-/// Create a link between a binder and a song. Using `INSERT OR IGNORE` lets us
-/// treat repeated requests idempotently, which simplifies state management in
-/// the UI.
-pub fn add_song_to_binder(conn: &Connection, binder_id: i64, song_id: i64) -> Result<()> {
+/// Create a link between a binder and a song, placing the song last in the
+/// binder. Using `INSERT OR IGNORE` lets us treat repeated requests
+/// idempotently, which simplifies state management in the UI.
+pub fn add_song_to_binder(conn: &Connection, binder_id: i64, song_id: i64) -> DbResult<()> {
     conn.execute(
-        "INSERT OR IGNORE INTO binder_songs (binder_id, song_id) VALUES (?1, ?2)",
+        "INSERT OR IGNORE INTO binder_songs (binder_id, song_id, position)
+         VALUES (
+             ?1, ?2,
+             (SELECT COALESCE(MAX(position), 0) + 1 FROM binder_songs WHERE binder_id = ?1)
+         )",
         params![binder_id, song_id],
     )
-    .context("failed to link song to binder")?;
+    .db_context("failed to link song to binder")?;
     Ok(())
 }
 
-/// Remove a binder-song association and surface a descriptive error if the link
-/// never existed.
-pub fn remove_song_from_binder(conn: &Connection, binder_id: i64, song_id: i64) -> Result<()> {
+/// Remove a binder-song association and surface [`DbError::NotLinked`] if the
+/// link never existed.
+pub fn remove_song_from_binder(conn: &Connection, binder_id: i64, song_id: i64) -> DbResult<()> {
     let deleted = conn
         .execute(
             "DELETE FROM binder_songs WHERE binder_id = ?1 AND song_id = ?2",
             params![binder_id, song_id],
         )
-        .context("failed to unlink song from binder")?;
+        .db_context("failed to unlink song from binder")?;
 
     if deleted == 0 {
-        Err(anyhow!("Song not linked to this binder"))
+        Err(DbError::NotLinked { binder_id, song_id })
     } else {
         Ok(())
     }
 }
 
+/// Put a binder's songs in the given order. Songs missing from `song_ids` keep
+/// their relative order after the listed ones; listing a song that is not in
+/// the binder fails with [`DbError::NotLinked`] and changes nothing.
+pub fn set_binder_order(conn: &Connection, binder_id: i64, song_ids: &[i64]) -> DbResult<()> {
+    let current: Vec<i64> = fetch_songs_for_binder(conn, binder_id)?
+        .into_iter()
+        .map(|song| song.id)
+        .collect();
+    if let Some(&song_id) = song_ids.iter().find(|id| !current.contains(id)) {
+        return Err(DbError::NotLinked { binder_id, song_id });
+    }
+    let order = song_ids
+        .iter()
+        .chain(current.iter().filter(|id| !song_ids.contains(id)));
+
+    // A savepoint nests inside `Library::transaction` as well as standing alone.
+    conn.execute_batch("SAVEPOINT set_binder_order")
+        .db_context("failed to start reordering")?;
+    let result = order.enumerate().try_for_each(|(index, song_id)| {
+        conn.execute(
+            "UPDATE binder_songs SET position = ?1 WHERE binder_id = ?2 AND song_id = ?3",
+            params![index as i64 + 1, binder_id, song_id],
+        )
+        .map(|_| ())
+        .db_context("failed to reorder binder songs")
+    });
+    match result {
+        Ok(()) => conn
+            .execute_batch("RELEASE set_binder_order")
+            .db_context("failed to finish reordering"),
+        Err(err) => {
+            let _ = conn.execute_batch("ROLLBACK TO set_binder_order; RELEASE set_binder_order");
+            Err(err)
+        }
+    }
+}
+
 /// Permanently delete a song. The join table cascades automatically so binders
 /// lose the entry without additional cleanup.
-pub fn delete_song(conn: &Connection, id: i64) -> Result<()> {
+pub fn delete_song(conn: &Connection, id: i64) -> DbResult<()> {
     let deleted = conn
         .execute("DELETE FROM songs WHERE id = ?1", params![id])
-        .context("failed to delete song")?;
+        .db_context("failed to delete song")?;
 
     if deleted == 0 {
-        Err(anyhow!("Song not found"))
+        Err(DbError::NotFound(Entity::Song))
     } else {
         Ok(())
     }
 }
+
+/// Collect the ids of every song in the director's binder (number 0). The song
+/// picker uses this to flag songs that other binders are expected to carry.
+pub fn fetch_director_song_ids(conn: &Connection) -> DbResult<HashSet<i64>> {
+    let mut stmt = conn
+        .prepare(
+            "SELECT bs.song_id
+             FROM binder_songs bs
+             INNER JOIN binders b ON b.id = bs.binder_id
+             WHERE b.number = 0",
+        )
+        .db_context("failed to prepare director song lookup")?;
+    let ids = stmt
+        .query_map([], |row| row.get(0))
+        .db_context("failed to iterate director songs")?
+        .collect::<Result<HashSet<i64>, _>>()
+        .db_context("failed to collect director song ids")?;
+    Ok(ids)
+}
+
+/// Every binder-song link as `(binder_id, song_id)`, ordered by binder then
+/// song id.
+pub fn fetch_memberships(conn: &Connection) -> DbResult<Vec<(i64, i64)>> {
+    let mut stmt = conn
+        .prepare("SELECT binder_id, song_id FROM binder_songs ORDER BY binder_id, song_id")
+        .db_context("failed to prepare membership query")?;
+    let links = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .db_context("failed to iterate memberships")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect memberships")?;
+    Ok(links)
+}
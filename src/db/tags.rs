@@ -0,0 +1,190 @@
+use std::collections::{HashMap, HashSet};
+
+use rusqlite::{params, Connection, Error as SqlError, Row};
+
+use super::error::{is_unique_violation, DbContext, DbError, DbResult, Entity};
+use super::library::Library;
+use crate::models::Tag;
+
+fn tag_from_row(row: &Row<'_>) -> rusqlite::Result<Tag> {
+    Ok(Tag {
+        id: row.get(0)?,
+        name: row.get(1)?,
+    })
+}
+
+/// Every tag, ordered case-insensitively by name.
+pub fn fetch_tags(conn: &Connection) -> DbResult<Vec<Tag>> {
+    let mut stmt = conn
+        .prepare("SELECT id, name FROM tags ORDER BY name COLLATE NOCASE, id")
+        .db_context("failed to prepare tag query")?;
+
+    let tags = stmt
+        .query_map([], tag_from_row)
+        .db_context("failed to load tags")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect tags")?;
+
+    Ok(tags)
+}
+
+/// Every song-tag link as `(song_id, tag_id)`, ordered by song then tag id.
+pub fn fetch_tag_links(conn: &Connection) -> DbResult<Vec<(i64, i64)>> {
+    let mut stmt = conn
+        .prepare("SELECT song_id, tag_id FROM song_tags ORDER BY song_id, tag_id")
+        .db_context("failed to prepare song tag query")?;
+    let links = stmt
+        .query_map([], |row| Ok((row.get(0)?, row.get(1)?)))
+        .db_context("failed to iterate song tags")?
+        .collect::<Result<Vec<_>, _>>()
+        .db_context("failed to collect song tags")?;
+    Ok(links)
+}
+
+/// Insert a tag. Fails with [`DbError::DuplicateTag`] if the name is taken,
+/// ignoring case.
+pub fn create_tag(conn: &Connection, name: &str) -> DbResult<Tag> {
+    conn.execute("INSERT INTO tags (name) VALUES (?1)", params![name])
+        .map_err(|err| map_unique_constraint(err, name, "failed to insert tag"))?;
+
+    Ok(Tag {
+        id: conn.last_insert_rowid(),
+        name: name.to_string(),
+    })
+}
+
+/// Insert a tag under a known id, used when restoring a snapshot.
+pub fn restore_tag(conn: &Connection, tag: &Tag) -> DbResult<()> {
+    conn.execute(
+        "INSERT INTO tags (id, name) VALUES (?1, ?2)",
+        params![tag.id, tag.name],
+    )
+    .map_err(|err| map_unique_constraint(err, &tag.name, "failed to restore tag"))?;
+    Ok(())
+}
+
+/// Delete a tag. Songs carrying it lose it through the cascade.
+pub fn delete_tag(conn: &Connection, id: i64) -> DbResult<()> {
+    let deleted = conn
+        .execute("DELETE FROM tags WHERE id = ?1", params![id])
+        .db_context("failed to delete tag")?;
+
+    if deleted == 0 {
+        Err(DbError::NotFound(Entity::Tag))
+    } else {
+        Ok(())
+    }
+}
+
+/// Put a tag on a song. Tagging a song twice is a no-op.
+pub fn tag_song(conn: &Connection, song_id: i64, tag_id: i64) -> DbResult<()> {
+    conn.execute(
+        "INSERT OR IGNORE INTO song_tags (song_id, tag_id) VALUES (?1, ?2)",
+        params![song_id, tag_id],
+    )
+    .db_context("failed to tag song")?;
+    Ok(())
+}
+
+/// Take a tag off a song; nothing happens if the song did not have it.
+pub fn untag_song(conn: &Connection, song_id: i64, tag_id: i64) -> DbResult<()> {
+    conn.execute(
+        "DELETE FROM song_tags WHERE song_id = ?1 AND tag_id = ?2",
+        params![song_id, tag_id],
+    )
+    .db_context("failed to untag song")?;
+    Ok(())
+}
+
+/// Turn a UNIQUE violation on `tags.name` into [`DbError::DuplicateTag`];
+/// other failures keep their context.
+fn map_unique_constraint(err: SqlError, name: &str, context: &'static str) -> DbError {
+    if is_unique_violation(&err) {
+        DbError::DuplicateTag(name.to_string())
+    } else {
+        DbError::from_sqlite(context, err)
+    }
+}
+
+/// Each tagged song's tag names, sorted ignoring case, keyed by song id.
+pub fn song_tags(library: &dyn Library) -> DbResult<HashMap<i64, Vec<String>>> {
+    let names: HashMap<i64, String> = library
+        .fetch_tags()?
+        .into_iter()
+        .map(|tag| (tag.id, tag.name))
+        .collect();
+    let mut tags: HashMap<i64, Vec<String>> = HashMap::new();
+    for (song_id, tag_id) in library.fetch_tag_links()? {
+        if let Some(name) = names.get(&tag_id) {
+            tags.entry(song_id).or_default().push(name.clone());
+        }
+    }
+    for names in tags.values_mut() {
+        names.sort_by_key(|name| name.to_lowercase());
+    }
+    Ok(tags)
+}
+
+/// Split comma-separated tag input into trimmed names, dropping blanks and
+/// repeats (ignoring case) while keeping the first spelling.
+pub fn parse_tags(text: &str) -> Vec<String> {
+    let mut seen = HashSet::new();
+    text.split(',')
+        .map(str::trim)
+        .filter(|name| !name.is_empty() && seen.insert(name.to_lowercase()))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Give a song exactly the tags in `names`, reusing existing tags whose names
+/// match ignoring case and creating the rest. Tags no song carries any more are
+/// deleted so the tag filter only offers tags in use. Callers that change the
+/// song's other fields too wrap both in [`Library::transaction`].
+pub fn set_song_tags(library: &mut dyn Library, song_id: i64, names: &[String]) -> DbResult<()> {
+    if !library
+        .fetch_all_songs()?
+        .iter()
+        .any(|song| song.id == song_id)
+    {
+        return Err(DbError::NotFound(Entity::Song));
+    }
+
+    let mut by_name: HashMap<String, i64> = library
+        .fetch_tags()?
+        .into_iter()
+        .map(|tag| (tag.name.to_lowercase(), tag.id))
+        .collect();
+    let mut wanted = HashSet::new();
+    for name in names {
+        let key = name.to_lowercase();
+        let id = match by_name.get(&key) {
+            Some(&id) => id,
+            None => {
+                let created = library.create_tag(name)?;
+                by_name.insert(key, created.id);
+                created.id
+            }
+        };
+        library.tag_song(song_id, id)?;
+        wanted.insert(id);
+    }
+
+    let links = library.fetch_tag_links()?;
+    for &(_, tag_id) in links
+        .iter()
+        .filter(|(song, tag)| *song == song_id && !wanted.contains(tag))
+    {
+        library.untag_song(song_id, tag_id)?;
+    }
+    let used: HashSet<i64> = library
+        .fetch_tag_links()?
+        .into_iter()
+        .map(|(_, tag_id)| tag_id)
+        .collect();
+    for tag in library.fetch_tags()? {
+        if !used.contains(&tag.id) {
+            library.delete_tag(tag.id)?;
+        }
+    }
+    Ok(())
+}
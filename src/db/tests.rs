@@ -0,0 +1,1436 @@
+//! Persistence tests: migrations, snapshots and backups, plus the behavior of
+//! each table checked against both backends.
+
+use super::migrations::{latest_version, schema_version};
+use super::{
+    attach_file, check_in_binder, check_out_binder, copy_director_order, ensure_schema_at,
+    ensure_schema_in_memory, export_library, import_library, move_song_in_binder,
+    move_song_in_setlist, open_checkouts, record_setlist_performances, set_song_tags, song_links,
+    song_tags, AttachmentStore, BackupReason, BackupStore, DbError, Entity, Library,
+    LibrarySnapshot, MemoryLibrary, RestoreMode, RestoreSummary, SqliteLibrary, SNAPSHOT_VERSION,
+};
+use std::path::Path;
+
+use crate::models::{LinkKind, PrintLogEntry, PrintStage, SongDetails};
+use crate::reports::to_print_report;
+
+/// A small library with a gap in the song ids, so restores must keep ids
+/// rather than renumber.
+fn sample_library() -> MemoryLibrary {
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    let altos = library.create_binder(2, "Altos").unwrap();
+    let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+    let dropped = library.create_song("Dropped", "").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    library.delete_song(dropped.id).unwrap();
+    library.add_song_to_binder(director.id, gloria.id).unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    library.add_song_to_binder(altos.id, ave.id).unwrap();
+    library
+}
+
+#[test]
+fn replace_round_trips_through_json_into_sqlite() {
+    let snapshot = export_library(&sample_library()).unwrap();
+    let json = snapshot.to_json().unwrap();
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    sqlite.create_binder(7, "Leftover").unwrap();
+    let parsed = LibrarySnapshot::from_json(&json).unwrap();
+    let summary = import_library(&mut sqlite, &parsed, RestoreMode::Replace).unwrap();
+
+    assert_eq!(
+        summary,
+        RestoreSummary {
+            binders_added: 2,
+            songs_added: 2,
+            memberships_added: 3,
+            concerts_added: 0,
+            performances_added: 0,
+            choristers_added: 0,
+            checkouts_added: 0,
+            tags_added: 0,
+            links_added: 0,
+            attachments_added: 0,
+            print_jobs_added: 0,
+            queue_entries_added: 0,
+        }
+    );
+    let restored = export_library(&sqlite).unwrap();
+    assert_eq!(restored, snapshot);
+    assert_eq!(restored.to_json().unwrap(), json);
+    assert_eq!(restored.songs[1].id, 3);
+}
+
+#[test]
+fn merge_adds_only_what_is_missing() {
+    let snapshot = export_library(&sample_library()).unwrap();
+    let mut library = MemoryLibrary::new();
+    let altos = library.create_binder(2, "Alto Section").unwrap();
+    let ave = library.create_song("ave verum", "MOZART").unwrap();
+    library.add_song_to_binder(altos.id, ave.id).unwrap();
+
+    let summary = import_library(&mut library, &snapshot, RestoreMode::Merge).unwrap();
+
+    assert_eq!(
+        summary,
+        RestoreSummary {
+            binders_added: 1,
+            songs_added: 1,
+            memberships_added: 2,
+            concerts_added: 0,
+            performances_added: 0,
+            choristers_added: 0,
+            checkouts_added: 0,
+            tags_added: 0,
+            links_added: 0,
+            attachments_added: 0,
+            print_jobs_added: 0,
+            queue_entries_added: 0,
+        }
+    );
+    let binders = library.fetch_binders().unwrap();
+    assert_eq!(binders.len(), 2);
+    assert_eq!(binders[1].label, "Alto Section");
+    assert_eq!(library.fetch_all_songs().unwrap().len(), 2);
+    assert_eq!(
+        library.fetch_songs_for_binder(binders[0].id).unwrap().len(),
+        2
+    );
+
+    let again = import_library(&mut library, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(again, RestoreSummary::default());
+}
+
+#[test]
+fn newer_or_foreign_snapshots_are_rejected() {
+    let mut value = serde_json::to_value(export_library(&sample_library()).unwrap()).unwrap();
+    value["version"] = (SNAPSHOT_VERSION + 1).into();
+    let err = LibrarySnapshot::from_json(&value.to_string()).unwrap_err();
+    assert!(matches!(err, DbError::SnapshotTooNew { found, .. } if found == SNAPSHOT_VERSION + 1));
+
+    let err = LibrarySnapshot::from_json(r#"{"format": "other", "version": 1}"#).unwrap_err();
+    assert!(matches!(err, DbError::InvalidSnapshot(_)));
+}
+
+#[test]
+fn invalid_snapshot_leaves_library_untouched() {
+    let mut snapshot = export_library(&sample_library()).unwrap();
+    snapshot.memberships[0].song_id = 99;
+    let mut library = sample_library();
+    let before = export_library(&library).unwrap();
+
+    let err = import_library(&mut library, &snapshot, RestoreMode::Replace).unwrap_err();
+
+    assert!(matches!(err, DbError::InvalidSnapshot(_)));
+    assert_eq!(export_library(&library).unwrap(), before);
+}
+
+#[test]
+fn backups_rotate_and_report_counts() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = BackupStore::new(dir.path().join("backups"), 2);
+    let mut library = sample_library();
+
+    store.create(&library, BackupReason::Startup).unwrap();
+    library.create_song("Cantique", "Fauré").unwrap();
+    store.create(&library, BackupReason::DeleteSong).unwrap();
+    library.delete_binder(1).unwrap();
+    store.create(&library, BackupReason::DeleteBinder).unwrap();
+
+    let backups = store.list().unwrap();
+    let summary: Vec<_> = backups
+        .iter()
+        .map(|backup| (backup.reason, backup.binders, backup.songs))
+        .collect();
+    assert_eq!(
+        summary,
+        vec![
+            (Some(BackupReason::DeleteBinder), 1, 3),
+            (Some(BackupReason::DeleteSong), 2, 3),
+        ]
+    );
+    assert!(backups[0].taken_at > backups[1].taken_at);
+    assert_eq!(std::fs::read_dir(store.dir()).unwrap().count(), 2);
+}
+
+#[test]
+fn restoring_a_backup_replaces_the_library_on_both_backends() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = BackupStore::new(dir.path().to_path_buf(), 5);
+    let original = sample_library();
+    let expected = export_library(&original).unwrap();
+    let backup = store
+        .create(&original, BackupReason::Startup)
+        .unwrap()
+        .unwrap();
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    sqlite.create_binder(9, "Spare").unwrap();
+    store.restore(&mut sqlite, &backup).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), expected);
+
+    let mut memory = MemoryLibrary::new();
+    memory.create_song("Gloria", "Vivaldi").unwrap();
+    store.restore(&mut memory, &backup).unwrap();
+    assert_eq!(export_library(&memory).unwrap(), expected);
+
+    // Each restore backed up the data it replaced.
+    let reasons: Vec<_> = store
+        .list()
+        .unwrap()
+        .into_iter()
+        .map(|backup| backup.reason)
+        .collect();
+    assert_eq!(
+        reasons,
+        vec![
+            Some(BackupReason::Restore),
+            Some(BackupReason::Restore),
+            Some(BackupReason::Startup),
+        ]
+    );
+}
+
+#[test]
+fn zero_rotations_disable_backups() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = BackupStore::new(dir.path().join("backups"), 0);
+    let created = store
+        .create(&sample_library(), BackupReason::Startup)
+        .unwrap();
+    assert!(created.is_none());
+    assert!(store.list().unwrap().is_empty());
+}
+
+fn titles(library: &dyn Library, binder_id: i64) -> Vec<String> {
+    library
+        .fetch_songs_for_binder(binder_id)
+        .unwrap()
+        .into_iter()
+        .map(|song| song.title)
+        .collect()
+}
+
+fn both_backends() -> [Box<dyn Library>; 2] {
+    [
+        Box::new(MemoryLibrary::new()),
+        Box::new(SqliteLibrary::new(ensure_schema_in_memory().unwrap())),
+    ]
+}
+
+#[test]
+fn binders_keep_their_running_order() {
+    for mut library in both_backends() {
+        let binder = library.create_binder(1, "").unwrap();
+        let mut ids = Vec::new();
+        for title in ["Zadok", "Ave", "Magnificat"] {
+            let song = library.create_song(title, "").unwrap();
+            library.add_song_to_binder(binder.id, song.id).unwrap();
+            ids.push(song.id);
+        }
+        assert_eq!(titles(&*library, binder.id), ["Zadok", "Ave", "Magnificat"]);
+
+        assert!(move_song_in_binder(&mut *library, binder.id, ids[2], -1).unwrap());
+        assert!(!move_song_in_binder(&mut *library, binder.id, ids[0], -1).unwrap());
+        assert_eq!(titles(&*library, binder.id), ["Zadok", "Magnificat", "Ave"]);
+
+        library.set_binder_order(binder.id, &[ids[1]]).unwrap();
+        assert_eq!(titles(&*library, binder.id), ["Ave", "Zadok", "Magnificat"]);
+
+        let stray = library.create_song("Stray", "").unwrap();
+        assert!(matches!(
+            library.set_binder_order(binder.id, &[stray.id, ids[0]]),
+            Err(DbError::NotLinked { .. })
+        ));
+        assert_eq!(titles(&*library, binder.id), ["Ave", "Zadok", "Magnificat"]);
+    }
+}
+
+#[test]
+fn director_order_is_copied_to_every_binder() {
+    for mut library in both_backends() {
+        assert_eq!(copy_director_order(&mut *library).unwrap(), None);
+
+        let director = library.create_binder(0, "Director").unwrap();
+        let tenors = library.create_binder(3, "Tenors").unwrap();
+        let songs: Vec<i64> = ["Alpha", "Beta", "Gamma", "Solo"]
+            .iter()
+            .map(|title| library.create_song(title, "").unwrap().id)
+            .collect();
+        for &id in [songs[2], songs[0], songs[1]].iter() {
+            library.add_song_to_binder(director.id, id).unwrap();
+        }
+        for &id in [songs[3], songs[0], songs[2]].iter() {
+            library.add_song_to_binder(tenors.id, id).unwrap();
+        }
+
+        assert_eq!(copy_director_order(&mut *library).unwrap(), Some(1));
+        assert_eq!(titles(&*library, tenors.id), ["Gamma", "Alpha", "Solo"]);
+    }
+}
+
+#[test]
+fn snapshots_preserve_running_order() {
+    let mut library = sample_library();
+    let director = library.fetch_binders().unwrap()[0].id;
+    let first = library.fetch_songs_for_binder(director).unwrap()[0].id;
+    move_song_in_binder(&mut library, director, first, 1).unwrap();
+    let snapshot = export_library(&library).unwrap();
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(titles(&sqlite, director), ["Ave Verum", "Gloria"]);
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+}
+
+#[test]
+fn both_backends_ignore_only_ascii_case_when_sorting() {
+    for mut library in both_backends() {
+        // SQLite's NOCASE folds A-Z only, so "É" still sorts before "é".
+        for title in ["élégie", "Étude", "zulu", "Alleluia"] {
+            library.create_song(title, "").unwrap();
+        }
+        for name in ["élodie", "Émile"] {
+            library.create_chorister(name, "", "").unwrap();
+            library.create_tag(name).unwrap();
+        }
+        let titles: Vec<String> = library
+            .fetch_all_songs()
+            .unwrap()
+            .into_iter()
+            .map(|song| song.title)
+            .collect();
+        assert_eq!(titles, vec!["Alleluia", "zulu", "Étude", "élégie"]);
+        let choristers: Vec<String> = library
+            .fetch_choristers()
+            .unwrap()
+            .into_iter()
+            .map(|chorister| chorister.name)
+            .collect();
+        assert_eq!(choristers, vec!["Émile", "élodie"]);
+        let tags: Vec<String> = library
+            .fetch_tags()
+            .unwrap()
+            .into_iter()
+            .map(|tag| tag.name)
+            .collect();
+        assert_eq!(tags, vec!["Émile", "élodie"]);
+    }
+}
+
+#[test]
+fn concerts_sort_by_date_and_keep_setlists() {
+    for mut library in both_backends() {
+        let undated = library.create_concert("Gala", "", "").unwrap();
+        let winter = library
+            .create_concert("Winter", "2025-12-20", "Cathedral")
+            .unwrap();
+        let spring = library.create_concert("Spring", "2025-04-12", "").unwrap();
+        let names: Vec<String> = library
+            .fetch_concerts()
+            .unwrap()
+            .into_iter()
+            .map(|concert| concert.name)
+            .collect();
+        assert_eq!(names, ["Spring", "Winter", "Gala"]);
+
+        let songs: Vec<i64> = ["Alpha", "Beta", "Gamma"]
+            .iter()
+            .map(|title| library.create_song(title, "").unwrap().id)
+            .collect();
+        for &id in [songs[2], songs[0], songs[1], songs[0]].iter() {
+            library.add_song_to_setlist(winter.id, id).unwrap();
+        }
+        let setlist = |library: &dyn Library| -> Vec<String> {
+            library
+                .fetch_setlist(winter.id)
+                .unwrap()
+                .into_iter()
+                .map(|song| song.title)
+                .collect()
+        };
+        assert_eq!(setlist(&*library), ["Gamma", "Alpha", "Beta"]);
+
+        move_song_in_setlist(&mut *library, winter.id, songs[1], -2).unwrap();
+        assert_eq!(setlist(&*library), ["Beta", "Gamma", "Alpha"]);
+        assert!(matches!(
+            library.remove_song_from_setlist(spring.id, songs[0]),
+            Err(DbError::NotInSetlist { .. })
+        ));
+
+        library.delete_song(songs[2]).unwrap();
+        assert_eq!(setlist(&*library), ["Beta", "Alpha"]);
+        library.delete_concert(winter.id).unwrap();
+        assert!(library.fetch_setlist(winter.id).unwrap().is_empty());
+        assert!(matches!(
+            library.update_concert(winter.id, "Winter", "", ""),
+            Err(DbError::NotFound(_))
+        ));
+        library
+            .update_concert(undated.id, "Gala", "2026-01-01", "Hall")
+            .unwrap();
+    }
+}
+
+#[test]
+fn snapshots_carry_concerts_and_setlists() {
+    let mut library = sample_library();
+    let songs = library.fetch_all_songs().unwrap();
+    let spring = library
+        .create_concert("Spring", "2025-04-12", "St. Mary's")
+        .unwrap();
+    library.add_song_to_setlist(spring.id, songs[1].id).unwrap();
+    library.add_song_to_setlist(spring.id, songs[0].id).unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.setlists.len(), 2);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging twice adds the concert only once.
+    let mut merged = MemoryLibrary::new();
+    let first = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    let second = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!((first.concerts_added, second.concerts_added), (1, 0));
+    let concert = &merged.fetch_concerts().unwrap()[0];
+    let titles: Vec<String> = merged
+        .fetch_setlist(concert.id)
+        .unwrap()
+        .into_iter()
+        .map(|song| song.title)
+        .collect();
+    assert_eq!(titles, [songs[1].title.clone(), songs[0].title.clone()]);
+
+    // Snapshots from before concerts existed still load.
+    let json = snapshot.to_json().unwrap();
+    let mut value: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let object = value.as_object_mut().unwrap();
+    object.remove("concerts");
+    object.remove("setlists");
+    let old = LibrarySnapshot::from_json(&value.to_string()).unwrap();
+    assert!(old.concerts.is_empty());
+}
+
+#[test]
+fn performance_history_summarises_each_song() {
+    for mut library in both_backends() {
+        let gloria = library.create_song("Gloria", "").unwrap();
+        let ave = library.create_song("Ave", "").unwrap();
+        library
+            .record_performance(gloria.id, "2023-12-24", "Carols")
+            .unwrap();
+        let spring = library
+            .record_performance(gloria.id, "2025-04-12", "Spring")
+            .unwrap();
+        library
+            .record_performance(gloria.id, "2024-06-01", "Summer")
+            .unwrap();
+        assert!(matches!(
+            library.record_performance(999, "2024-01-01", ""),
+            Err(DbError::ForeignKey)
+        ));
+
+        let history: Vec<String> = library
+            .fetch_song_performances(gloria.id)
+            .unwrap()
+            .into_iter()
+            .map(|performance| performance.event)
+            .collect();
+        assert_eq!(history, ["Spring", "Summer", "Carols"]);
+        let stats = library.fetch_performance_stats().unwrap();
+        assert_eq!(stats[&gloria.id].last_date, "2025-04-12");
+        assert_eq!(stats[&gloria.id].count, 3);
+        assert!(!stats.contains_key(&ave.id));
+
+        library.delete_performance(spring.id).unwrap();
+        assert!(matches!(
+            library.delete_performance(spring.id),
+            Err(DbError::NotFound(_))
+        ));
+        let stats = library.fetch_performance_stats().unwrap();
+        assert_eq!(stats[&gloria.id].last_date, "2024-06-01");
+
+        library.delete_song(gloria.id).unwrap();
+        assert!(library.fetch_performances().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn marking_a_concert_records_its_setlist_once() {
+    for mut library in both_backends() {
+        let concert = library.create_concert("Spring", "2025-04-12", "").unwrap();
+        for title in ["Gloria", "Ave"] {
+            let song = library.create_song(title, "").unwrap();
+            library.add_song_to_setlist(concert.id, song.id).unwrap();
+        }
+
+        assert_eq!(
+            record_setlist_performances(&mut *library, concert.id).unwrap(),
+            2
+        );
+        assert_eq!(
+            record_setlist_performances(&mut *library, concert.id).unwrap(),
+            0
+        );
+        let performances = library.fetch_performances().unwrap();
+        assert_eq!(performances.len(), 2);
+        assert!(performances
+            .iter()
+            .all(|performance| performance.date == "2025-04-12" && performance.event == "Spring"));
+        assert!(matches!(
+            record_setlist_performances(&mut *library, 999),
+            Err(DbError::NotFound(_))
+        ));
+    }
+}
+
+#[test]
+fn snapshots_carry_performances() {
+    let mut library = sample_library();
+    let songs = library.fetch_all_songs().unwrap();
+    library
+        .record_performance(songs[0].id, "2024-12-24", "Carols")
+        .unwrap();
+    library
+        .record_performance(songs[1].id, "2025-04-12", "Spring")
+        .unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.performances.len(), 2);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    let mut merged = MemoryLibrary::new();
+    let first = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    let second = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(
+        (first.performances_added, second.performances_added),
+        (2, 0)
+    );
+}
+
+#[test]
+fn choristers_hold_binders_and_unassign_on_delete() {
+    for mut library in both_backends() {
+        let binder = library.create_binder(3, "").unwrap();
+        let zoe = library.create_chorister("Zoe", "Alto", "").unwrap();
+        let anna = library
+            .create_chorister("anna", "Soprano", "anna@example.com")
+            .unwrap();
+        let names: Vec<String> = library
+            .fetch_choristers()
+            .unwrap()
+            .into_iter()
+            .map(|chorister| chorister.name)
+            .collect();
+        assert_eq!(names, ["anna", "Zoe"]);
+
+        library.assign_binder(binder.id, Some(zoe.id)).unwrap();
+        assert_eq!(library.fetch_binders().unwrap()[0].holder_id, Some(zoe.id));
+        assert!(matches!(
+            library.assign_binder(binder.id, Some(999)),
+            Err(DbError::ForeignKey)
+        ));
+        assert!(matches!(
+            library.assign_binder(999, Some(anna.id)),
+            Err(DbError::NotFound(_))
+        ));
+
+        library.delete_chorister(zoe.id).unwrap();
+        assert_eq!(library.fetch_binders().unwrap()[0].holder_id, None);
+        assert!(matches!(
+            library.delete_chorister(zoe.id),
+            Err(DbError::NotFound(_))
+        ));
+    }
+}
+
+#[test]
+fn snapshots_carry_choristers_and_holders() {
+    let mut library = sample_library();
+    let binder = library.fetch_binders().unwrap()[0].clone();
+    let anna = library.create_chorister("Anna", "Soprano", "").unwrap();
+    library.assign_binder(binder.id, Some(anna.id)).unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.choristers.len(), 1);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    let mut merged = MemoryLibrary::new();
+    let first = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    let second = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!((first.choristers_added, second.choristers_added), (1, 0));
+    let holder = merged
+        .fetch_binders()
+        .unwrap()
+        .into_iter()
+        .find(|merged_binder| merged_binder.number == binder.number)
+        .and_then(|merged_binder| merged_binder.holder_id);
+    assert_eq!(holder, Some(merged.fetch_choristers().unwrap()[0].id));
+}
+
+#[test]
+fn binders_check_out_to_their_holder_and_back_in() {
+    for mut library in both_backends() {
+        let binder = library.create_binder(4, "").unwrap();
+        let anna = library.create_chorister("Anna", "", "").unwrap();
+        library.assign_binder(binder.id, Some(anna.id)).unwrap();
+
+        let out = check_out_binder(&mut *library, binder.id, "2025-01-05 19:30").unwrap();
+        assert_eq!(out.chorister_id, Some(anna.id));
+        assert!(matches!(
+            check_out_binder(&mut *library, binder.id, "2025-01-06 19:30"),
+            Err(DbError::AlreadyCheckedOut { .. })
+        ));
+        assert!(open_checkouts(&*library).unwrap().contains_key(&binder.id));
+
+        let back = check_in_binder(&mut *library, binder.id, "2025-01-12 19:30").unwrap();
+        assert_eq!(back.checked_in_at.as_deref(), Some("2025-01-12 19:30"));
+        assert!(matches!(
+            check_in_binder(&mut *library, binder.id, "2025-01-13 19:30"),
+            Err(DbError::NotCheckedOut { .. })
+        ));
+        assert!(open_checkouts(&*library).unwrap().is_empty());
+
+        check_out_binder(&mut *library, binder.id, "2025-02-01 10:00").unwrap();
+        library.delete_chorister(anna.id).unwrap();
+        let log = library.fetch_checkouts().unwrap();
+        assert_eq!(log.len(), 2);
+        assert!(log.iter().all(|checkout| checkout.chorister_id.is_none()));
+
+        library.delete_binder(binder.id).unwrap();
+        assert!(library.fetch_checkouts().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn snapshots_carry_the_checkout_log() {
+    let mut library = sample_library();
+    let binders = library.fetch_binders().unwrap();
+    check_out_binder(&mut library, binders[0].id, "2025-01-05 19:30").unwrap();
+    check_in_binder(&mut library, binders[0].id, "2025-01-12 19:30").unwrap();
+    check_out_binder(&mut library, binders[1].id, "2025-03-01 09:00").unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.checkouts.len(), 2);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // A binder already out in the target keeps its own open checkout.
+    let mut merged = MemoryLibrary::new();
+    let altos = merged.create_binder(binders[1].number, "").unwrap();
+    check_out_binder(&mut merged, altos.id, "2025-02-01 09:00").unwrap();
+    let first = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    let second = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!((first.checkouts_added, second.checkouts_added), (1, 0));
+    assert_eq!(
+        open_checkouts(&merged).unwrap()[&altos.id].checked_out_at,
+        "2025-02-01 09:00"
+    );
+
+    let mut doubled = snapshot.clone();
+    let mut again = doubled.checkouts[1].clone();
+    again.id += 1;
+    doubled.checkouts.push(again);
+    assert!(matches!(
+        LibrarySnapshot::from_json(&doubled.to_json().unwrap()),
+        Err(DbError::InvalidSnapshot(_))
+    ));
+}
+
+#[test]
+fn voice_parts_limit_what_to_print_counts() {
+    for mut library in both_backends() {
+        let director = library.create_binder(0, "Director").unwrap();
+        let altos = library.create_binder(1, "Altos").unwrap();
+        let tenors = library.create_binder(2, "Tenors").unwrap();
+        library.set_binder_part(altos.id, "A").unwrap();
+        library.set_binder_part(tenors.id, "T").unwrap();
+        let tutti = library.create_song("Tutti", "").unwrap();
+        let men = library.create_song("Men Only", "").unwrap();
+        library.set_song_parts(men.id, "T, b").unwrap();
+        library.add_song_to_binder(director.id, tutti.id).unwrap();
+        library.add_song_to_binder(director.id, men.id).unwrap();
+
+        let report = to_print_report(&*library).unwrap().unwrap();
+        let missing: Vec<(i64, Vec<String>)> = report
+            .binders
+            .iter()
+            .map(|shortfall| {
+                let titles = shortfall
+                    .missing
+                    .iter()
+                    .map(|missing| missing.song.title.clone());
+                (shortfall.binder.number, titles.collect())
+            })
+            .collect();
+        assert_eq!(
+            missing,
+            vec![
+                (1, vec!["Tutti".to_string()]),
+                (2, vec!["Tutti".to_string(), "Men Only".to_string()]),
+            ]
+        );
+        let men_needed = report.songs.iter().find(|demand| demand.song.id == men.id);
+        assert_eq!(men_needed.map(|demand| demand.needed), Some(1));
+
+        assert!(matches!(
+            library.set_binder_part(999, "S"),
+            Err(DbError::NotFound(Entity::Binder))
+        ));
+        assert!(matches!(
+            library.set_song_parts(999, "S"),
+            Err(DbError::NotFound(Entity::Song))
+        ));
+    }
+}
+
+#[test]
+fn snapshots_carry_voice_parts() {
+    let mut library = sample_library();
+    let altos = library.fetch_binders().unwrap()[1].clone();
+    let ave = library.fetch_all_songs().unwrap()[0].clone();
+    library.set_binder_part(altos.id, "A").unwrap();
+    library.set_song_parts(ave.id, "S, A").unwrap();
+    let snapshot = export_library(&library).unwrap();
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging fills in blank parts but keeps ones already set.
+    let mut merged = MemoryLibrary::new();
+    let own = merged.create_binder(altos.number, "").unwrap();
+    merged.set_binder_part(own.id, "Alto 2").unwrap();
+    import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    let parts: Vec<String> = merged
+        .fetch_binders()
+        .unwrap()
+        .into_iter()
+        .map(|binder| binder.voice_part)
+        .collect();
+    assert_eq!(parts, vec!["", "Alto 2"]);
+    let song = merged
+        .fetch_all_songs()
+        .unwrap()
+        .into_iter()
+        .find(|song| song.title == ave.title)
+        .unwrap();
+    assert_eq!(song.parts, "S, A");
+}
+
+#[test]
+fn song_details_round_trip_through_both_backends() {
+    let details = SongDetails {
+        arranger: "Rutter".to_string(),
+        voicing: "SATB".to_string(),
+        accompaniment: "organ".to_string(),
+        language: "Latin".to_string(),
+        publisher: "OUP".to_string(),
+        catalog_number: "X123".to_string(),
+        duration_seconds: Some(225),
+        page_count: Some(8),
+    };
+    for mut library in both_backends() {
+        let song = library.create_song("Ave Maria", "Schubert").unwrap();
+        library.set_song_details(song.id, &details).unwrap();
+        assert_eq!(library.fetch_all_songs().unwrap()[0].details, details);
+        assert!(matches!(
+            library.set_song_details(999, &details),
+            Err(DbError::NotFound(Entity::Song))
+        ));
+    }
+
+    let mut library = sample_library();
+    let ave = library.fetch_all_songs().unwrap()[0].clone();
+    library.set_song_details(ave.id, &details).unwrap();
+    let snapshot = export_library(&library).unwrap();
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    let mut merged = MemoryLibrary::new();
+    merged.create_song(&ave.title, &ave.composer).unwrap();
+    import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    let song = merged
+        .fetch_all_songs()
+        .unwrap()
+        .into_iter()
+        .find(|song| song.title == ave.title)
+        .unwrap();
+    assert_eq!(song.details, details);
+}
+
+fn names(tags: &[&str]) -> Vec<String> {
+    tags.iter().map(|tag| tag.to_string()).collect()
+}
+
+#[test]
+fn song_tags_are_shared_pruned_and_cascade() {
+    for mut library in both_backends() {
+        let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+        let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+        set_song_tags(library.as_mut(), gloria.id, &names(&["Latin", "Easter"])).unwrap();
+        set_song_tags(library.as_mut(), ave.id, &names(&["latin"])).unwrap();
+
+        // "latin" reuses the existing tag rather than creating another.
+        let tags = library.fetch_tags().unwrap();
+        let tag_names: Vec<&str> = tags.iter().map(|tag| tag.name.as_str()).collect();
+        assert_eq!(tag_names, vec!["Easter", "Latin"]);
+        let by_song = song_tags(library.as_ref()).unwrap();
+        assert_eq!(by_song[&gloria.id], names(&["Easter", "Latin"]));
+        assert_eq!(by_song[&ave.id], names(&["Latin"]));
+        assert!(matches!(
+            library.create_tag("EASTER"),
+            Err(DbError::DuplicateTag(name)) if name == "EASTER"
+        ));
+
+        // Dropping the last use of a tag deletes it.
+        set_song_tags(library.as_mut(), gloria.id, &names(&["Latin"])).unwrap();
+        assert_eq!(library.fetch_tags().unwrap().len(), 1);
+
+        library.delete_song(ave.id).unwrap();
+        assert_eq!(library.fetch_tag_links().unwrap().len(), 1);
+        assert!(matches!(
+            set_song_tags(library.as_mut(), ave.id, &names(&["Latin"])),
+            Err(DbError::NotFound(Entity::Song))
+        ));
+        assert!(matches!(
+            library.tag_song(gloria.id, 999),
+            Err(DbError::ForeignKey)
+        ));
+    }
+}
+
+#[test]
+fn snapshots_carry_tags() {
+    let mut library = sample_library();
+    let songs = library.fetch_all_songs().unwrap();
+    set_song_tags(&mut library, songs[0].id, &names(&["Latin", "Communion"])).unwrap();
+    set_song_tags(&mut library, songs[1].id, &names(&["Christmas"])).unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.tags.len(), 3);
+    assert_eq!(snapshot.song_tags.len(), 3);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging matches tags by name, ignoring case, and adds the rest.
+    let mut merged = MemoryLibrary::new();
+    let own = merged
+        .create_song(&songs[0].title, &songs[0].composer)
+        .unwrap();
+    set_song_tags(&mut merged, own.id, &names(&["latin"])).unwrap();
+    let summary = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.tags_added, 2);
+    let by_song = song_tags(&merged).unwrap();
+    assert_eq!(by_song[&own.id], names(&["Communion", "latin"]));
+}
+
+#[test]
+fn song_links_are_typed_and_cascade() {
+    for mut library in both_backends() {
+        let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+        let score = library
+            .add_song_link(ave.id, LinkKind::Score, "", "", "scores/ave.pdf")
+            .unwrap();
+        let tenor = library
+            .add_song_link(
+                ave.id,
+                LinkKind::Rehearsal,
+                "T",
+                "Tenor track",
+                "https://example.org/t.mp3",
+            )
+            .unwrap();
+        assert_eq!(
+            library.fetch_song_links().unwrap(),
+            vec![score, tenor.clone()]
+        );
+        assert_eq!(tenor.describe(), "Rehearsal track (T) – Tenor track");
+        assert!(matches!(
+            library.add_song_link(999, LinkKind::Other, "", "", "x"),
+            Err(DbError::ForeignKey)
+        ));
+
+        library.delete_song_link(tenor.id).unwrap();
+        assert!(matches!(
+            library.delete_song_link(tenor.id),
+            Err(DbError::NotFound(Entity::Link))
+        ));
+        assert_eq!(song_links(library.as_ref()).unwrap()[&ave.id].len(), 1);
+
+        library.delete_song(ave.id).unwrap();
+        assert!(library.fetch_song_links().unwrap().is_empty());
+    }
+}
+
+#[test]
+fn snapshots_carry_song_links() {
+    let mut library = sample_library();
+    let ave = library.fetch_all_songs().unwrap()[0].clone();
+    library
+        .add_song_link(
+            ave.id,
+            LinkKind::Recording,
+            "",
+            "",
+            "https://example.org/ave",
+        )
+        .unwrap();
+    library
+        .add_song_link(
+            ave.id,
+            LinkKind::Rehearsal,
+            "S",
+            "",
+            "https://example.org/s.mp3",
+        )
+        .unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.song_links.len(), 2);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging skips links the song already has.
+    let mut merged = MemoryLibrary::new();
+    let own = merged.create_song(&ave.title, &ave.composer).unwrap();
+    merged
+        .add_song_link(
+            own.id,
+            LinkKind::Rehearsal,
+            "s",
+            "Mine",
+            "https://example.org/s.mp3",
+        )
+        .unwrap();
+    let summary = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.links_added, 1);
+    assert_eq!(merged.fetch_song_links().unwrap().len(), 2);
+}
+
+#[test]
+fn older_snapshots_turn_song_links_into_typed_links() {
+    let mut value = serde_json::to_value(export_library(&sample_library()).unwrap()).unwrap();
+    let (gloria, ave) = (
+        value["songs"][0]["id"].clone(),
+        value["songs"][1]["id"].clone(),
+    );
+    value["songs"][0]["link"] = "https://example.org/gloria".into();
+    value["songs"][1]["link"] = " https://example.org/ave.PDF ".into();
+    value["song_links"] = serde_json::json!([{
+        "id": 7,
+        "song_id": gloria,
+        "kind": "recording",
+        "part": "",
+        "label": "",
+        "url": "https://example.org/gloria.mp3",
+    }]);
+
+    let snapshot = LibrarySnapshot::from_json(&value.to_string()).unwrap();
+    let links: Vec<_> = snapshot
+        .song_links
+        .iter()
+        .map(|link| (link.id, link.song_id, link.kind, link.url.as_str()))
+        .collect();
+    let (gloria, ave) = (gloria.as_i64().unwrap(), ave.as_i64().unwrap());
+    assert_eq!(
+        links,
+        vec![
+            (
+                7,
+                gloria,
+                LinkKind::Recording,
+                "https://example.org/gloria.mp3"
+            ),
+            (8, gloria, LinkKind::Other, "https://example.org/gloria"),
+            (9, ave, LinkKind::Score, "https://example.org/ave.PDF"),
+        ]
+    );
+}
+
+#[test]
+fn attachments_are_stored_by_checksum_and_cascade() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("Ave Verum.PDF");
+    std::fs::write(&source, b"%PDF-1.4 ave").unwrap();
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+
+    for mut library in both_backends() {
+        let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+        let attached = attach_file(
+            library.as_mut(),
+            &store,
+            ave.id,
+            &source,
+            "2024-05-01 19:30",
+        )
+        .unwrap();
+        assert_eq!(attached.file_name, "Ave Verum.PDF");
+        assert_eq!(attached.size_bytes, 12);
+        let stored = store.verified_path(&attached).unwrap();
+        assert_eq!(
+            stored.file_name().unwrap().to_string_lossy(),
+            format!("{}.pdf", attached.sha256)
+        );
+
+        // The same file twice is one attachment.
+        let again = attach_file(
+            library.as_mut(),
+            &store,
+            ave.id,
+            &source,
+            "2024-05-02 10:00",
+        )
+        .unwrap();
+        assert_eq!(again, attached);
+        assert_eq!(library.fetch_attachments().unwrap(), vec![attached.clone()]);
+        assert!(matches!(
+            attach_file(library.as_mut(), &store, 999, &source, "2024-05-02 10:00"),
+            Err(DbError::NotFound(Entity::Song))
+        ));
+
+        library.delete_attachment(attached.id).unwrap();
+        assert!(matches!(
+            library.delete_attachment(attached.id),
+            Err(DbError::NotFound(Entity::Attachment))
+        ));
+        // Removing the attachment keeps the stored file for older backups.
+        assert!(stored.exists());
+
+        attach_file(
+            library.as_mut(),
+            &store,
+            ave.id,
+            &source,
+            "2024-05-03 09:00",
+        )
+        .unwrap();
+        library.delete_song(ave.id).unwrap();
+        assert!(library.fetch_attachments().unwrap().is_empty());
+    }
+
+    let mut library = MemoryLibrary::new();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    let attached = attach_file(&mut library, &store, ave.id, &source, "2024-05-01 19:30").unwrap();
+    std::fs::write(store.path_of(&attached), b"tampered").unwrap();
+    assert!(matches!(
+        store.verified_path(&attached),
+        Err(DbError::AttachmentCorrupt(_))
+    ));
+    std::fs::remove_file(store.path_of(&attached)).unwrap();
+    assert!(matches!(
+        store.verified_path(&attached),
+        Err(DbError::AttachmentMissing(_))
+    ));
+}
+
+#[test]
+fn snapshots_carry_attachments() {
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("gloria.pdf");
+    std::fs::write(&source, b"%PDF-1.4 gloria").unwrap();
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+    let mut library = sample_library();
+    let gloria = library.fetch_all_songs().unwrap()[1].clone();
+    attach_file(&mut library, &store, gloria.id, &source, "2024-05-01 19:30").unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.attachments.len(), 1);
+
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging skips files the song already has.
+    let summary = import_library(&mut sqlite, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.attachments_added, 0);
+
+    let mut bad = snapshot.clone();
+    bad.attachments[0].sha256 = "not-a-checksum".to_string();
+    assert!(matches!(
+        import_library(&mut MemoryLibrary::new(), &bad, RestoreMode::Replace),
+        Err(DbError::InvalidSnapshot(_))
+    ));
+}
+
+#[test]
+fn snapshots_carry_the_print_log() {
+    let mut library = sample_library();
+    let gloria = library.fetch_all_songs().unwrap()[1].clone();
+    let sent = PrintLogEntry {
+        id: 0,
+        song_id: Some(gloria.id),
+        title: "Gloria – Vivaldi".to_string(),
+        copies: 2,
+        printer: "Office".to_string(),
+        file_name: "gloria.pdf".to_string(),
+        submitted_at: "2024-05-01 19:30".to_string(),
+        request_id: "Office-7".to_string(),
+        error: String::new(),
+    };
+    library.add_print_log_entry(&sent).unwrap();
+    let scratch = library.create_song("Scratch", "").unwrap();
+    library
+        .add_print_log_entry(&PrintLogEntry {
+            song_id: Some(scratch.id),
+            title: "Scratch".to_string(),
+            error: "lp: no such printer".to_string(),
+            ..sent.clone()
+        })
+        .unwrap();
+    library.delete_song(scratch.id).unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.print_log.len(), 2);
+    assert_eq!(snapshot.print_log[1].song_id, None);
+
+    // Replacing drops the log that was there.
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    let stale = sqlite.create_song("Stale", "").unwrap();
+    sqlite
+        .add_print_log_entry(&PrintLogEntry {
+            song_id: Some(stale.id),
+            ..sent.clone()
+        })
+        .unwrap();
+    let summary = import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(summary.print_jobs_added, 2);
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging skips jobs already logged.
+    let summary = import_library(&mut sqlite, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.print_jobs_added, 0);
+    let mut merged = MemoryLibrary::new();
+    let summary = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.print_jobs_added, 2);
+    let log = merged.fetch_print_log().unwrap();
+    assert_eq!(
+        log[0].song_id,
+        Some(merged.fetch_all_songs().unwrap()[1].id)
+    );
+
+    let mut bad = snapshot.clone();
+    bad.print_log[1].song_id = Some(99);
+    assert!(matches!(
+        import_library(&mut MemoryLibrary::new(), &bad, RestoreMode::Replace),
+        Err(DbError::InvalidSnapshot(_))
+    ));
+}
+
+#[test]
+fn snapshots_carry_the_print_queue() {
+    let mut library = sample_library();
+    let altos = library.fetch_binders().unwrap()[1].clone();
+    let gloria = library.fetch_all_songs().unwrap()[1].clone();
+    library
+        .set_print_stage(
+            altos.id,
+            gloria.id,
+            PrintStage::Inserted,
+            "2024-05-01 19:30",
+        )
+        .unwrap();
+    let snapshot = export_library(&library).unwrap();
+    assert_eq!(snapshot.print_queue.len(), 1);
+    let json = snapshot.to_json().unwrap();
+    assert_eq!(LibrarySnapshot::from_json(&json).unwrap(), snapshot);
+
+    // Replacing drops the queue that was there.
+    let mut sqlite = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    let stale_binder = sqlite.create_binder(9, "Stale").unwrap();
+    let stale_song = sqlite.create_song("Stale", "").unwrap();
+    sqlite
+        .set_print_stage(stale_binder.id, stale_song.id, PrintStage::Printed, "")
+        .unwrap();
+    let summary = import_library(&mut sqlite, &snapshot, RestoreMode::Replace).unwrap();
+    assert_eq!(summary.queue_entries_added, 1);
+    assert_eq!(export_library(&sqlite).unwrap(), snapshot);
+
+    // Merging skips songs already queued for, or already in, their binder.
+    let summary = import_library(&mut sqlite, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.queue_entries_added, 0);
+    let mut merged = MemoryLibrary::new();
+    let summary = import_library(&mut merged, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.queue_entries_added, 1);
+    let queue = merged.fetch_print_queue().unwrap();
+    assert_eq!(
+        (queue[0].stage, queue[0].updated_at.as_str()),
+        (PrintStage::Inserted, "2024-05-01 19:30")
+    );
+    let mut linked = MemoryLibrary::new();
+    let own_altos = linked.create_binder(2, "Altos").unwrap();
+    let own_gloria = linked.create_song("Gloria", "Vivaldi").unwrap();
+    linked
+        .add_song_to_binder(own_altos.id, own_gloria.id)
+        .unwrap();
+    let summary = import_library(&mut linked, &snapshot, RestoreMode::Merge).unwrap();
+    assert_eq!(summary.queue_entries_added, 0);
+    assert!(linked.fetch_print_queue().unwrap().is_empty());
+
+    let mut bad = snapshot.clone();
+    bad.print_queue[0].binder_id = 99;
+    assert!(matches!(
+        import_library(&mut MemoryLibrary::new(), &bad, RestoreMode::Replace),
+        Err(DbError::InvalidSnapshot(_))
+    ));
+    let mut bad = snapshot.clone();
+    bad.print_queue[0].song_id = snapshot.memberships[0].song_id;
+    bad.print_queue[0].binder_id = snapshot.memberships[0].binder_id;
+    assert!(matches!(
+        import_library(&mut MemoryLibrary::new(), &bad, RestoreMode::Replace),
+        Err(DbError::InvalidSnapshot(_))
+    ));
+}
+
+#[test]
+fn backups_keep_attached_files_until_no_backup_needs_them() {
+    let dir = tempfile::tempdir().unwrap();
+    let attachments = AttachmentStore::new(dir.path().join("attachments"));
+    let backups =
+        BackupStore::new(dir.path().join("backups"), 2).with_attachments(attachments.clone());
+    let source = dir.path().join("gloria.pdf");
+    std::fs::write(&source, b"%PDF-1.4 gloria").unwrap();
+
+    let mut library = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+    let attached = attach_file(
+        &mut library,
+        &attachments,
+        gloria.id,
+        &source,
+        "2024-05-01 19:30",
+    )
+    .unwrap();
+    let backup = backups
+        .create(&library, BackupReason::Startup)
+        .unwrap()
+        .unwrap();
+    let copies = AttachmentStore::new(backups.dir().join("attachments"));
+    assert!(copies.verified_path(&attached).is_ok());
+
+    // Restoring brings back files that went missing from the library folder.
+    std::fs::remove_file(attachments.path_of(&attached)).unwrap();
+    backups.restore(&mut library, &backup).unwrap();
+    assert!(attachments.verified_path(&attached).is_ok());
+
+    // Once the last backup listing the file rotates out, its copy goes too.
+    library.delete_song(gloria.id).unwrap();
+    backups.create(&library, BackupReason::DeleteSong).unwrap();
+    assert!(copies.path_of(&attached).exists());
+    backups.create(&library, BackupReason::DeleteSong).unwrap();
+    assert!(!copies.path_of(&attached).exists());
+}
+
+#[test]
+fn print_queue_moves_through_stages_until_linked() {
+    for mut library in both_backends() {
+        let sopranos = library.create_binder(1, "Sopranos").unwrap();
+        let altos = library.create_binder(2, "Altos").unwrap();
+        let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+        let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+
+        library
+            .set_print_stage(sopranos.id, ave.id, PrintStage::Printed, "2024-05-01 10:00")
+            .unwrap();
+        library
+            .set_print_stage(altos.id, ave.id, PrintStage::Printed, "2024-05-01 10:00")
+            .unwrap();
+        library
+            .set_print_stage(
+                sopranos.id,
+                ave.id,
+                PrintStage::Inserted,
+                "2024-05-02 19:30",
+            )
+            .unwrap();
+        library
+            .set_print_stage(
+                altos.id,
+                gloria.id,
+                PrintStage::Inserted,
+                "2024-05-02 19:30",
+            )
+            .unwrap();
+        let stages = |library: &dyn Library| -> Vec<(i64, i64, PrintStage)> {
+            library
+                .fetch_print_queue()
+                .unwrap()
+                .into_iter()
+                .map(|item| (item.binder_id, item.song_id, item.stage))
+                .collect()
+        };
+        assert_eq!(
+            stages(library.as_ref()),
+            vec![
+                (sopranos.id, ave.id, PrintStage::Inserted),
+                (altos.id, ave.id, PrintStage::Printed),
+                (altos.id, gloria.id, PrintStage::Inserted),
+            ]
+        );
+        assert_eq!(
+            library.fetch_print_queue().unwrap()[0].updated_at,
+            "2024-05-02 19:30"
+        );
+        assert!(matches!(
+            library.set_print_stage(99, ave.id, PrintStage::Printed, "2024-05-01 10:00"),
+            Err(DbError::ForeignKey)
+        ));
+
+        // Back to needed, or linked to the binder, ends the entry.
+        library
+            .set_print_stage(altos.id, ave.id, PrintStage::Needed, "2024-05-03 09:00")
+            .unwrap();
+        library.add_song_to_binder(sopranos.id, ave.id).unwrap();
+        assert_eq!(
+            stages(library.as_ref()),
+            vec![(altos.id, gloria.id, PrintStage::Inserted)]
+        );
+
+        library.delete_song(gloria.id).unwrap();
+        assert!(library.fetch_print_queue().unwrap().is_empty());
+        library
+            .set_print_stage(altos.id, ave.id, PrintStage::Printed, "2024-05-03 09:00")
+            .unwrap();
+        library.delete_binder(altos.id).unwrap();
+        assert!(library.fetch_print_queue().unwrap().is_empty());
+    }
+}
+
+/// A library file as written before schema versions existed: the original
+/// three tables, `user_version` 0, one binder holding one song.
+fn write_baseline_library(path: &Path, title: &str) {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    conn.execute_batch(
+        "CREATE TABLE binders (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             number INTEGER NOT NULL UNIQUE,
+             label TEXT NOT NULL
+         );
+         CREATE TABLE songs (
+             id INTEGER PRIMARY KEY AUTOINCREMENT,
+             title TEXT NOT NULL,
+             composer TEXT,
+             link TEXT
+         );
+         CREATE TABLE binder_songs (
+             binder_id INTEGER NOT NULL,
+             song_id INTEGER NOT NULL,
+             PRIMARY KEY (binder_id, song_id)
+         );
+         INSERT INTO binders (number, label) VALUES (1, 'Sopranos');
+         INSERT INTO binder_songs (binder_id, song_id) VALUES (1, 1);",
+    )
+    .unwrap();
+    conn.execute(
+        "INSERT INTO songs (title, composer, link) VALUES (?1, 'Mozart', '')",
+        [title],
+    )
+    .unwrap();
+}
+
+fn titles_in(path: &Path) -> Vec<String> {
+    let conn = rusqlite::Connection::open(path).unwrap();
+    let mut stmt = conn.prepare("SELECT title FROM songs ORDER BY id").unwrap();
+    let titles = stmt
+        .query_map([], |row| row.get(0))
+        .unwrap()
+        .collect::<Result<_, _>>()
+        .unwrap();
+    titles
+}
+
+#[test]
+fn baseline_libraries_are_backed_up_then_migrated() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("binders.sqlite");
+    write_baseline_library(&path, "Ave Verum");
+
+    let library = SqliteLibrary::new(ensure_schema_at(&path).unwrap());
+    let sopranos = &library.fetch_binders().unwrap()[0];
+    assert_eq!(sopranos.label, "Sopranos");
+    let songs = library.fetch_songs_for_binder(sopranos.id).unwrap();
+    assert_eq!(songs[0].title, "Ave Verum");
+    let conn = ensure_schema_at(&path).unwrap();
+    assert_eq!(schema_version(&conn).unwrap(), latest_version());
+
+    // The copy is taken before migrating, so it still has version 0.
+    let backup = dir.path().join("binders.sqlite.v0.bak");
+    let old = rusqlite::Connection::open(&backup).unwrap();
+    assert_eq!(schema_version(&old).unwrap(), 0);
+    assert_eq!(titles_in(&backup), vec!["Ave Verum"]);
+
+    // A second baseline library at the same path keeps the first copy.
+    drop((library, conn, old));
+    std::fs::remove_file(&path).unwrap();
+    write_baseline_library(&path, "Gloria");
+    ensure_schema_at(&path).unwrap();
+    assert_eq!(titles_in(&backup), vec!["Ave Verum"]);
+    assert_eq!(
+        titles_in(&dir.path().join("binders.sqlite.v0.2.bak")),
+        vec!["Gloria"]
+    );
+}
+
+#[test]
+fn song_links_move_into_typed_links_when_migrating() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("binders.sqlite");
+    write_baseline_library(&path, "Ave Verum");
+    let conn = rusqlite::Connection::open(&path).unwrap();
+    conn.execute_batch(
+        "UPDATE songs SET link = 'scores/ave.pdf';
+         INSERT INTO songs (title, composer, link) VALUES ('Gloria', 'Vivaldi', ' https://example.org/gloria ');
+         INSERT INTO songs (title, composer, link) VALUES ('Cantique', 'Fauré', NULL);",
+    )
+    .unwrap();
+    drop(conn);
+
+    let library = SqliteLibrary::new(ensure_schema_at(&path).unwrap());
+    let links: Vec<_> = library
+        .fetch_song_links()
+        .unwrap()
+        .into_iter()
+        .map(|link| (link.song_id, link.kind, link.url))
+        .collect();
+    assert_eq!(
+        links,
+        vec![
+            (1, LinkKind::Score, "scores/ave.pdf".to_string()),
+            (2, LinkKind::Other, "https://example.org/gloria".to_string()),
+        ]
+    );
+}
+
+#[test]
+fn libraries_from_a_newer_build_are_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("binders.sqlite");
+    write_baseline_library(&path, "Ave Verum");
+    let newer = latest_version() + 1;
+    rusqlite::Connection::open(&path)
+        .unwrap()
+        .pragma_update(None, "user_version", newer)
+        .unwrap();
+
+    match ensure_schema_at(&path) {
+        Err(DbError::SchemaTooNew { found, supported }) => {
+            assert_eq!((found, supported), (newer, latest_version()));
+        }
+        other => panic!("expected SchemaTooNew, got {:?}", other.map(|_| ())),
+    }
+    // Nothing was copied or changed.
+    assert!(!dir
+        .path()
+        .join(format!("binders.sqlite.v{newer}.bak"))
+        .exists());
+    assert_eq!(titles_in(&path), vec!["Ave Verum"]);
+}
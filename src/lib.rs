@@ -4,17 +4,27 @@
 //! `bin` target as well as potential external tooling can reuse the same pieces.
 //! Keeping the glue logic documented makes it easy to recall why each re-export
 //! exists when revisiting the project.
+pub mod cli;
 pub mod db;
 pub mod models;
+pub mod printing;
+pub mod reports;
+pub mod transfer;
 pub mod ui;
 
 /// Convenience re-exports for the persistence layer. These functions are
 /// typically used by `main.rs` to initialize the embedded SQLite store and
-/// preload data.
-pub use db::{ensure_schema, fetch_binders, fetch_composers};
+/// preload data, along with the typed error every persistence call returns.
+pub use db::{
+    ensure_schema, ensure_schema_at, ensure_schema_in_memory, fetch_binders, fetch_composers,
+    DbError, DbResult, Library, MemoryLibrary, SqliteLibrary,
+};
 
-/// The two primary domain types that other layers manipulate.
-pub use models::{Binder, Song};
+/// Domain types shared by the persistence layer, the reports and the UI.
+pub use models::{
+    Attachment, Binder, LinkKind, PrintLogEntry, PrintQueueItem, PrintStage, Song, SongDetails,
+    SongLink,
+};
 
 /// The interactive application entry point and state container.
 pub use ui::{run_app, App};
@@ -2,7 +2,33 @@
 //! Summarizing the bootstrapping pipeline here keeps the intent obvious when
 //! revisiting the code: we bring up the database, hydrate the initial app
 //! state, and drive the Ratatui event loop until the user exits.
-use choir_binder_manager::{ensure_schema, fetch_binders, fetch_composers, run_app, App};
+use std::env;
+use std::io;
+use std::path::PathBuf;
+
+use anyhow::{bail, Context};
+use choir_binder_manager::cli;
+use choir_binder_manager::db::{
+    resolve_backup_rotations, resolve_db_path, resolve_overdue_days, AttachmentStore, BackupStore,
+    BACKUP_ROTATIONS_ENV, DB_PATH_ENV, DEFAULT_BACKUP_ROTATIONS, DEFAULT_OVERDUE_DAYS,
+    OVERDUE_DAYS_ENV,
+};
+use choir_binder_manager::printing::{resolve_printer, Printer, PRINTER_ENV};
+use choir_binder_manager::{ensure_schema_at, run_app, App, Library, SqliteLibrary};
+
+/// What the command line asked for once global options are stripped.
+struct Invocation {
+    /// `--db` override, if any.
+    db: Option<PathBuf>,
+    /// `--backups` override, if any.
+    backups: Option<usize>,
+    /// `--overdue-days` override, if any.
+    overdue_days: Option<u32>,
+    /// `--printer` override, if any.
+    printer: Option<String>,
+    /// Subcommand words (`binders list --json`); empty launches the TUI.
+    command: Vec<String>,
+}
 
 /// Initialize persistence, load cached data, and launch the Ratatui event loop.
 ///
@@ -10,10 +36,129 @@ use choir_binder_manager::{ensure_schema, fetch_binders, fetch_composers, run_ap
 /// the user removing the writable `~/.choir-binder-manager/` directory) to the terminal instead of
 /// crashing silently.
 fn main() -> anyhow::Result<()> {
-    let conn = ensure_schema()?;
-    let binders = fetch_binders(&conn)?;
-    let composers = fetch_composers(&conn)?;
+    let Some(invocation) = parse_args()? else {
+        print_usage();
+        return Ok(());
+    };
+
+    let db_path = resolve_db_path(invocation.db)?;
+    let mut library = SqliteLibrary::new(ensure_schema_at(&db_path)?);
+    let attachments = AttachmentStore::beside(&db_path);
+    let backups = BackupStore::beside(&db_path, resolve_backup_rotations(invocation.backups)?)
+        .with_attachments(attachments.clone());
+    let printer = Printer::new(resolve_printer(invocation.printer));
+    if !invocation.command.is_empty() {
+        let mut out = io::stdout().lock();
+        let context = cli::RunContext::new(&mut library, &mut out)
+            .with_backups(&backups)
+            .with_attachments(&attachments)
+            .with_printer(&printer);
+        return cli::run(context, &invocation.command);
+    }
 
-    let mut app = App::new(conn, binders, composers);
+    let binders = library.fetch_binders()?;
+    let composers = library.fetch_composers()?;
+
+    let mut app = App::new(Box::new(library), binders, composers);
+    if let Some(dir) = db_path.parent() {
+        app.set_data_dir(dir.to_path_buf());
+    }
+    app.set_overdue_days(resolve_overdue_days(invocation.overdue_days)?);
+    app.enable_backups(backups);
+    app.set_attachments(attachments);
+    app.set_printer(printer);
     run_app(&mut app)
 }
+
+/// Parse the global options. Returns `None` when the user asked for help;
+/// everything from the first subcommand word onwards is handed to [`cli::run`].
+fn parse_args() -> anyhow::Result<Option<Invocation>> {
+    let mut db = None;
+    let mut backups = None;
+    let mut overdue_days = None;
+    let mut printer = None;
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--db" => {
+                let value = args.next().context("--db requires a path")?;
+                db = Some(PathBuf::from(value));
+            }
+            "--backups" => {
+                let value = args.next().context("--backups requires a number")?;
+                backups = Some(parse_backups(&value)?);
+            }
+            "--overdue-days" => {
+                let value = args.next().context("--overdue-days requires a number")?;
+                overdue_days = Some(parse_overdue_days(&value)?);
+            }
+            "--printer" => {
+                printer = Some(args.next().context("--printer requires a printer name")?);
+            }
+            other if cli::is_command(other) => {
+                let command = std::iter::once(arg).chain(args).collect();
+                return Ok(Some(Invocation {
+                    db,
+                    backups,
+                    overdue_days,
+                    printer,
+                    command,
+                }));
+            }
+            other => {
+                if let Some(value) = other.strip_prefix("--db=") {
+                    db = Some(PathBuf::from(value));
+                } else if let Some(value) = other.strip_prefix("--backups=") {
+                    backups = Some(parse_backups(value)?);
+                } else if let Some(value) = other.strip_prefix("--overdue-days=") {
+                    overdue_days = Some(parse_overdue_days(value)?);
+                } else if let Some(value) = other.strip_prefix("--printer=") {
+                    printer = Some(value.to_string());
+                } else {
+                    bail!("unrecognized argument '{other}' (try --help)");
+                }
+            }
+        }
+    }
+    Ok(Some(Invocation {
+        db,
+        backups,
+        overdue_days,
+        printer,
+        command: Vec::new(),
+    }))
+}
+
+fn parse_backups(value: &str) -> anyhow::Result<usize> {
+    value
+        .parse()
+        .with_context(|| format!("--backups must be a whole number, got '{value}'"))
+}
+
+fn parse_overdue_days(value: &str) -> anyhow::Result<u32> {
+    value
+        .parse()
+        .with_context(|| format!("--overdue-days must be a whole number, got '{value}'"))
+}
+
+fn print_usage() {
+    println!(
+        "Usage: choir-binder-manager [--db <path>] [--backups <n>] [--overdue-days <n>]\n\
+         \x20                           [--printer <name>] [<command> [--json]]\n\n\
+         Without a command the interactive interface starts.\n\n\
+         Options:\n  \
+           --db <path>     Open the library stored at <path> instead of the default\n                  \
+                           (also settable through the {DB_PATH_ENV} environment variable)\n  \
+           --backups <n>   Keep the <n> newest automatic backups, 0 turns them off\n                  \
+                           (default {DEFAULT_BACKUP_ROTATIONS}; also settable through {BACKUP_ROTATIONS_ENV})\n  \
+           --overdue-days <n>\n                  \
+                           Days a binder may stay checked out before it is overdue\n                  \
+                           (default {DEFAULT_OVERDUE_DAYS}; also settable through {OVERDUE_DAYS_ENV})\n  \
+           --printer <name>\n                  \
+                           CUPS printer that scores are sent to (default: the system's\n                  \
+                           default printer; also settable through {PRINTER_ENV})\n  \
+           -h, --help      Show this message\n\n{}",
+        cli::USAGE
+    );
+}
@@ -6,7 +6,9 @@
 
 use std::fmt;
 
-#[derive(Debug, Clone)]
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// Represents a physical binder that choristers use. The `number` provides a
 /// stable sorting key, while `label` stores the friendly name shown in the UI.
 pub struct Binder {
@@ -19,6 +21,15 @@ pub struct Binder {
     pub number: i64,
     /// User-facing display label.
     pub label: String,
+    /// Chorister the binder is assigned to, if any. Snapshots written before
+    /// binders had holders leave it out.
+    #[serde(default)]
+    pub holder_id: Option<i64>,
+    /// Voice part the binder is for, such as `S`, `A`, `T`, `B` or a custom
+    /// name like `Alto 2`. Empty means the binder needs every song, which is
+    /// also how snapshots from before voice parts load.
+    #[serde(default)]
+    pub voice_part: String,
 }
 
 impl fmt::Display for Binder {
@@ -29,7 +40,7 @@ impl fmt::Display for Binder {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 /// In-memory representation of a song. The struct mirrors rows in both the
 /// `songs` table and the join table that links songs to binders.
 pub struct Song {
@@ -39,12 +50,39 @@ pub struct Song {
     pub title: String,
     /// Composer field used both for display and filtering.
     pub composer: String,
-    /// Optional URL pointing to an online reference (kept as raw text so we can
-    /// store non-web references as well).
-    pub link: String,
+    /// Comma-separated voice parts that need the song in their binders, e.g.
+    /// `T, B` for a men's-only piece. Empty means every binder needs it.
+    #[serde(default)]
+    pub parts: String,
+    /// Arrangement and edition details. Flattened so snapshots keep one flat
+    /// object per song; older snapshots simply leave the fields out.
+    #[serde(default, flatten)]
+    pub details: SongDetails,
 }
 
 impl Song {
+    /// Whether a binder for `voice_part` should hold this song. Songs without
+    /// parts are needed everywhere, and so are binders without a part (the
+    /// director's, for instance). Parts compare ignoring case.
+    pub fn needed_by(&self, voice_part: &str) -> bool {
+        let voice_part = voice_part.trim();
+        if voice_part.is_empty() || self.parts.trim().is_empty() {
+            return true;
+        }
+        self.parts
+            .split(',')
+            .any(|part| part.trim().eq_ignore_ascii_case(voice_part))
+    }
+
+    /// Whether a lowercase search query appears in the title, composer or any
+    /// of the text details.
+    pub fn matches_query(&self, query: &str) -> bool {
+        [self.title.as_str(), self.composer.as_str()]
+            .into_iter()
+            .chain(self.details.text_fields())
+            .any(|field| field.to_lowercase().contains(query))
+    }
+
     /// Compose a `Title - Composer` string that gracefully omits the hyphen if
     /// the composer is blank. Many views (auto-complete, binder listings) rely
     /// on this ready-to-use formatting.
@@ -56,3 +94,439 @@ impl Song {
         }
     }
 }
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+/// Optional catalogue details that tell two arrangements of the same piece
+/// apart and say what to quote when ordering more copies. Every field may be
+/// blank.
+pub struct SongDetails {
+    /// Who arranged this version, if it is an arrangement.
+    pub arranger: String,
+    /// Scoring such as `SATB`, `SSA` or `TTBB`.
+    pub voicing: String,
+    /// e.g. `a cappella`, `piano` or `organ`.
+    pub accompaniment: String,
+    /// Language the text is sung in.
+    pub language: String,
+    pub publisher: String,
+    /// Publisher's catalogue or octavo number.
+    pub catalog_number: String,
+    /// Approximate performance length in seconds.
+    pub duration_seconds: Option<u32>,
+    /// Pages in one copy of the score.
+    pub page_count: Option<u32>,
+}
+
+impl SongDetails {
+    /// The free-text fields, in display order.
+    fn text_fields(&self) -> [&str; 6] {
+        [
+            &self.arranger,
+            &self.voicing,
+            &self.accompaniment,
+            &self.language,
+            &self.publisher,
+            &self.catalog_number,
+        ]
+    }
+
+    /// One-line summary for song cards, e.g.
+    /// `arr. Rutter • SATB • piano • Latin • OUP X123 • 3:45 • 8 pp.`, leaving
+    /// out blank fields. Empty when nothing is filled in.
+    pub fn summary(&self) -> String {
+        let mut parts: Vec<String> = Vec::new();
+        if !self.arranger.trim().is_empty() {
+            parts.push(format!("arr. {}", self.arranger.trim()));
+        }
+        let edition = [&self.publisher, &self.catalog_number]
+            .into_iter()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        parts.extend(
+            [&self.voicing, &self.accompaniment, &self.language, &edition]
+                .into_iter()
+                .map(|part| part.trim())
+                .filter(|part| !part.is_empty())
+                .map(str::to_string),
+        );
+        if let Some(seconds) = self.duration_seconds {
+            parts.push(format_duration(seconds));
+        }
+        if let Some(pages) = self.page_count {
+            parts.push(format!("{pages} pp."));
+        }
+        parts.join(" • ")
+    }
+}
+
+/// Format seconds as `m:ss`, the way durations are typed into the song form.
+pub fn format_duration(seconds: u32) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+/// Parse a duration typed as `m:ss` or as whole minutes. `None` for anything
+/// else, including seconds of 60 or more and durations too long to store.
+pub fn parse_duration(text: &str) -> Option<u32> {
+    let text = text.trim();
+    match text.split_once(':') {
+        Some((minutes, seconds)) => {
+            let minutes: u32 = minutes.trim().parse().ok()?;
+            let seconds: u32 = seconds.trim().parse().ok()?;
+            if seconds >= 60 {
+                return None;
+            }
+            minutes.checked_mul(60)?.checked_add(seconds)
+        }
+        None => text.parse::<u32>().ok()?.checked_mul(60),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A concert or other event the choir prepares for. Its setlist lives in the
+/// `concert_songs` table and can stand in for the director's binder when
+/// working out what to print.
+pub struct Concert {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// Display name, e.g. "Spring Concert".
+    pub name: String,
+    /// ISO `YYYY-MM-DD` date, or empty while the date is still open. Stored as
+    /// text so it sorts chronologically in SQL as well as in Rust.
+    pub date: String,
+    /// Where the concert takes place; may be empty.
+    pub venue: String,
+}
+
+impl Concert {
+    /// Compose `Name • Date • Venue`, leaving out whichever parts are blank.
+    pub fn display_title(&self) -> String {
+        [&self.name, &self.date, &self.venue]
+            .into_iter()
+            .map(|part| part.trim())
+            .filter(|part| !part.is_empty())
+            .collect::<Vec<_>>()
+            .join(" • ")
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// One time a song was sung in public, so the director can see when a piece
+/// was last on a programme before choosing it again.
+pub struct Performance {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// The song that was performed.
+    pub song_id: i64,
+    /// ISO `YYYY-MM-DD` date of the performance.
+    pub date: String,
+    /// What the performance was for, e.g. a concert name; may be empty.
+    pub event: String,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+/// Per-song summary of the performance history, shown on song cards and used
+/// to find repertoire that has not been sung for a while.
+pub struct PerformanceStats {
+    /// Date of the most recent performance.
+    pub last_date: String,
+    /// How many performances are on record.
+    pub count: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A choir member who can be handed a binder.
+pub struct Chorister {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// Name shown on the binder card of the binder they hold.
+    pub name: String,
+    /// Voice part such as "Soprano" or "Bass 2"; may be empty.
+    pub voice_part: String,
+    /// Phone number or email; may be empty.
+    pub contact: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// One trip of a binder off the shelf. The entry stays open until the binder
+/// is checked back in, so the log doubles as the binder's current status.
+pub struct BinderCheckout {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// The binder that left the shelf.
+    pub binder_id: i64,
+    /// Who took it, usually the binder's holder at the time; `None` when the
+    /// binder had no holder or the chorister was later deleted.
+    pub chorister_id: Option<i64>,
+    /// Local `YYYY-MM-DD HH:MM` time the binder was checked out.
+    pub checked_out_at: String,
+    /// Local `YYYY-MM-DD HH:MM` time it came back, `None` while it is out.
+    pub checked_in_at: Option<String>,
+}
+
+impl BinderCheckout {
+    /// Whether the binder is still out on this entry.
+    pub fn is_open(&self) -> bool {
+        self.checked_in_at.is_none()
+    }
+}
+
+impl Chorister {
+    /// Compose `Name (Voice part)`, leaving out the part when it is blank.
+    pub fn display_name(&self) -> String {
+        if self.voice_part.trim().is_empty() {
+            self.name.clone()
+        } else {
+            format!("{} ({})", self.name, self.voice_part.trim())
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A label grouping repertoire by season, style or occasion (Advent, Gospel,
+/// Weddings…). Songs and tags are linked many-to-many through `song_tags`.
+pub struct Tag {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// Display name; unique ignoring case.
+    pub name: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// What a song link points at.
+pub enum LinkKind {
+    /// Sheet music, usually a PDF.
+    Score,
+    /// A reference recording, e.g. on YouTube.
+    Recording,
+    /// A practice track for one voice part.
+    Rehearsal,
+    /// Anything else: translations, programme notes…
+    Other,
+}
+
+impl LinkKind {
+    /// Every kind, in the order the link form cycles through them.
+    pub const ALL: [LinkKind; 4] = [
+        LinkKind::Score,
+        LinkKind::Recording,
+        LinkKind::Rehearsal,
+        LinkKind::Other,
+    ];
+
+    /// Value stored in the `song_links.kind` column.
+    pub fn tag(self) -> &'static str {
+        match self {
+            LinkKind::Score => "score",
+            LinkKind::Recording => "recording",
+            LinkKind::Rehearsal => "rehearsal",
+            LinkKind::Other => "other",
+        }
+    }
+
+    /// Parse a stored [`LinkKind::tag`].
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.tag() == tag)
+    }
+
+    /// Best guess for a link given without a kind, as the CLI and CSV import
+    /// do: PDFs are scores, anything else is other. Migration 14 applies the
+    /// same rule to the links songs used to carry themselves.
+    pub fn guess(url: &str) -> Self {
+        if url.trim().to_ascii_lowercase().ends_with(".pdf") {
+            LinkKind::Score
+        } else {
+            LinkKind::Other
+        }
+    }
+
+    /// Name shown in the UI.
+    pub fn label(self) -> &'static str {
+        match self {
+            LinkKind::Score => "Score",
+            LinkKind::Recording => "Recording",
+            LinkKind::Rehearsal => "Rehearsal track",
+            LinkKind::Other => "Other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A typed link on a song. A song can have any number of them, e.g. a score,
+/// a recording and one rehearsal track per voice part.
+pub struct SongLink {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// The song the link belongs to.
+    pub song_id: i64,
+    /// What the link points at.
+    pub kind: LinkKind,
+    /// Voice part a rehearsal track is for, e.g. `T`; empty otherwise.
+    pub part: String,
+    /// Optional description, e.g. "King's College 1998".
+    pub label: String,
+    /// URL or file path, opened with the system's default handler.
+    pub url: String,
+}
+
+impl SongLink {
+    /// Compose `Kind (Part) – Label`, leaving out whichever parts are blank.
+    pub fn describe(&self) -> String {
+        let mut text = self.kind.label().to_string();
+        if !self.part.trim().is_empty() {
+            text.push_str(&format!(" ({})", self.part.trim()));
+        }
+        if !self.label.trim().is_empty() {
+            text.push_str(&format!(" – {}", self.label.trim()));
+        }
+        text
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A local file, usually a score PDF, copied into the library's attachments
+/// folder so it travels with the library instead of pointing at one laptop.
+/// The stored copy is named after its checksum, so identical files are kept
+/// once however many songs use them.
+pub struct Attachment {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// The song the file belongs to.
+    pub song_id: i64,
+    /// Name of the file it was attached from, shown in the UI.
+    pub file_name: String,
+    /// Lowercase hex SHA-256 of the contents.
+    pub sha256: String,
+    /// Size of the file in bytes.
+    pub size_bytes: i64,
+    /// Local `YYYY-MM-DD HH:MM` time the file was attached.
+    pub added_at: String,
+}
+
+impl Attachment {
+    /// Name of the stored copy: the checksum plus the original extension, so
+    /// the system still knows which program opens it.
+    pub fn stored_name(&self) -> String {
+        stored_file_name(&self.sha256, &self.file_name)
+    }
+}
+
+/// `<sha256>.<ext>`, taking the lowercased extension from `file_name` when it
+/// is a plain alphanumeric one.
+pub fn stored_file_name(sha256: &str, file_name: &str) -> String {
+    let extension = std::path::Path::new(file_name)
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .filter(|ext| !ext.is_empty() && ext.chars().all(|ch| ch.is_ascii_alphanumeric()))
+        .map(str::to_ascii_lowercase);
+    match extension {
+        Some(ext) => format!("{sha256}.{ext}"),
+        None => sha256.to_string(),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// One score sent to a printer, kept so the librarian can see what was
+/// printed when, and what failed.
+pub struct PrintLogEntry {
+    /// Primary key from the SQLite store.
+    pub id: i64,
+    /// The song printed; `None` once the song has been deleted.
+    pub song_id: Option<i64>,
+    /// The song's title and composer when it was sent, kept for deleted songs.
+    pub title: String,
+    /// Copies asked for.
+    pub copies: i64,
+    /// Printer (CUPS queue) the job went to; empty for the default printer.
+    pub printer: String,
+    /// Name of the score file that was sent.
+    pub file_name: String,
+    /// Local `YYYY-MM-DD HH:MM` time the job was sent.
+    pub submitted_at: String,
+    /// Request id the print system gave the job, e.g. `Office-42`.
+    pub request_id: String,
+    /// Why the job was refused; empty when it was accepted.
+    pub error: String,
+}
+
+impl PrintLogEntry {
+    /// Whether the printer accepted the job.
+    pub fn accepted(&self) -> bool {
+        self.error.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+/// How far a song missing from a binder has got on its way in.
+pub enum PrintStage {
+    /// Still to be printed.
+    Needed,
+    /// A copy has been printed but is not in the binder yet.
+    Printed,
+    /// The copy is in the binder; the link is added when the To Print changes
+    /// are applied.
+    Inserted,
+}
+
+impl PrintStage {
+    /// Every stage, in the order a song moves through them.
+    pub const ALL: [PrintStage; 3] = [
+        PrintStage::Needed,
+        PrintStage::Printed,
+        PrintStage::Inserted,
+    ];
+
+    /// Value stored in the `print_queue.stage` column.
+    pub fn tag(self) -> &'static str {
+        match self {
+            PrintStage::Needed => "needed",
+            PrintStage::Printed => "printed",
+            PrintStage::Inserted => "inserted",
+        }
+    }
+
+    /// Parse a stored [`PrintStage::tag`].
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|stage| stage.tag() == tag)
+    }
+
+    /// The stage after this one, wrapping from inserted back to needed.
+    pub fn next(self) -> Self {
+        match self {
+            PrintStage::Needed => PrintStage::Printed,
+            PrintStage::Printed => PrintStage::Inserted,
+            PrintStage::Inserted => PrintStage::Needed,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+/// A song on its way into a binder. Only songs past [`PrintStage::Needed`]
+/// are queued; the entry goes away once the song is linked to the binder.
+pub struct PrintQueueItem {
+    /// The binder the copy is for.
+    pub binder_id: i64,
+    /// The song printed for it.
+    pub song_id: i64,
+    /// Either [`PrintStage::Printed`] or [`PrintStage::Inserted`].
+    pub stage: PrintStage,
+    /// Local `YYYY-MM-DD HH:MM` time the song reached its stage.
+    pub updated_at: String,
+}
+
+/// Human-readable file size, e.g. `512 B`, `12 KB` or `3.4 MB`.
+pub fn format_size(bytes: i64) -> String {
+    const KB: i64 = 1024;
+    const MB: i64 = 1024 * KB;
+    if bytes < KB {
+        format!("{bytes} B")
+    } else if bytes < MB {
+        format!("{} KB", (bytes + KB / 2) / KB)
+    } else {
+        format!("{:.1} MB", bytes as f64 / MB as f64)
+    }
+}
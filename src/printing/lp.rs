@@ -0,0 +1,178 @@
+use std::env;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use thiserror::Error;
+
+use crate::db::{DbError, Library};
+use crate::models::PrintLogEntry;
+use crate::reports::PrintJob;
+
+/// Environment variable naming the printer (CUPS queue) to send jobs to.
+pub const PRINTER_ENV: &str = "CHOIR_BINDER_PRINTER";
+
+/// Why the print system refused a job.
+#[derive(Debug, Error)]
+pub enum PrintError {
+    /// The `lp` command is not installed.
+    #[error("The '{0}' command was not found. Is CUPS installed?")]
+    NotInstalled(String),
+    /// `lp` could not be started.
+    #[error("Could not run '{command}': {source}")]
+    Launch {
+        command: String,
+        #[source]
+        source: std::io::Error,
+    },
+    /// `lp` ran but refused the job; holds its message, e.g. "lp: The printer
+    /// or class does not exist."
+    #[error("{0}")]
+    Rejected(String),
+}
+
+/// A printer reached through the CUPS `lp` command.
+#[derive(Debug, Clone)]
+pub struct Printer {
+    command: PathBuf,
+    destination: Option<String>,
+}
+
+impl Printer {
+    /// Send jobs to `destination`, or to the system's default printer when it
+    /// is `None`.
+    pub fn new(destination: Option<String>) -> Self {
+        Self {
+            command: PathBuf::from("lp"),
+            destination,
+        }
+    }
+
+    /// Use another `lp`-compatible command, e.g. a stub in tests.
+    pub fn with_command(mut self, command: impl Into<PathBuf>) -> Self {
+        self.command = command.into();
+        self
+    }
+
+    /// The configured printer name; `None` means the default printer.
+    pub fn destination(&self) -> Option<&str> {
+        self.destination.as_deref()
+    }
+
+    /// The printer as named in messages.
+    pub fn describe(&self) -> String {
+        match &self.destination {
+            Some(name) => name.clone(),
+            None => "the default printer".to_string(),
+        }
+    }
+
+    /// Queue `copies` copies of `file` under `title`. Returns the request id
+    /// the print system gave the job, e.g. `Office-42`.
+    pub fn submit(&self, file: &Path, copies: usize, title: &str) -> Result<String, PrintError> {
+        let mut command = Command::new(&self.command);
+        if let Some(destination) = &self.destination {
+            command.arg("-d").arg(destination);
+        }
+        command
+            .arg("-n")
+            .arg(copies.to_string())
+            .arg("-t")
+            .arg(title)
+            .arg("--")
+            .arg(file);
+        let name = self.command.display().to_string();
+        let output = command.output().map_err(|source| {
+            if source.kind() == ErrorKind::NotFound {
+                PrintError::NotInstalled(name.clone())
+            } else {
+                PrintError::Launch {
+                    command: name.clone(),
+                    source,
+                }
+            }
+        })?;
+        if !output.status.success() {
+            let message = String::from_utf8_lossy(&output.stderr).trim().to_string();
+            return Err(PrintError::Rejected(if message.is_empty() {
+                format!("'{name}' failed ({})", output.status)
+            } else {
+                message
+            }));
+        }
+        // lp answers "request id is Office-42 (1 file(s))".
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        Ok(stdout
+            .split("request id is ")
+            .nth(1)
+            .and_then(|rest| rest.split_whitespace().next())
+            .unwrap_or_else(|| stdout.trim())
+            .to_string())
+    }
+}
+
+/// The printer to use: the explicit value (the `--printer` flag) wins over
+/// `CHOIR_BINDER_PRINTER`. `None` leaves the choice to CUPS, which uses the
+/// default printer.
+pub fn resolve_printer(explicit: Option<String>) -> Option<String> {
+    explicit
+        .or_else(|| env::var(PRINTER_ENV).ok())
+        .map(|name| name.trim().to_string())
+        .filter(|name| !name.is_empty())
+}
+
+/// What [`send_print_jobs`] did: every job handed to the printer, and why
+/// logging stopped if the print log could not be written.
+#[derive(Debug)]
+pub struct SentJobs {
+    /// One entry per job sent, in order. Entries that could not be logged
+    /// keep id 0.
+    pub entries: Vec<PrintLogEntry>,
+    /// The print log error that stopped the run; jobs after it were not sent.
+    pub log_error: Option<DbError>,
+}
+
+/// Send each score of `job` to `printer` for the copies its song needs and
+/// log every attempt at `submitted_at`
+/// ([`TIMESTAMP_FORMAT`](crate::db::TIMESTAMP_FORMAT)). A refused job
+/// is logged with its error and the rest are still sent. If the print log
+/// cannot be written, nothing more is sent, but the jobs already handed to
+/// the printer are still returned so the caller can tell what went out.
+pub fn send_print_jobs(
+    library: &mut dyn Library,
+    printer: &Printer,
+    job: &PrintJob,
+    submitted_at: &str,
+) -> SentJobs {
+    let mut sent = SentJobs {
+        entries: Vec::with_capacity(job.entries.len()),
+        log_error: None,
+    };
+    for entry in &job.entries {
+        let title = entry.song.display_title();
+        let (request_id, error) = match printer.submit(&entry.path, entry.copies, &title) {
+            Ok(request_id) => (request_id, String::new()),
+            Err(err) => (String::new(), err.to_string()),
+        };
+        let attempt = PrintLogEntry {
+            id: 0,
+            song_id: Some(entry.song.id),
+            title,
+            copies: entry.copies as i64,
+            printer: printer.destination().unwrap_or_default().to_string(),
+            file_name: entry.file_name.clone(),
+            submitted_at: submitted_at.to_string(),
+            request_id,
+            error,
+        };
+        match library.add_print_log_entry(&attempt) {
+            Ok(logged) => sent.entries.push(logged),
+            Err(err) => {
+                sent.entries.push(attempt);
+                sent.log_error = Some(err);
+                break;
+            }
+        }
+    }
+    sent
+}
@@ -0,0 +1,9 @@
+//! Sending scores to a printer through CUPS. Jobs go to the `lp` command,
+//! which hands them to the local CUPS server or, with `CUPS_SERVER` set, to
+//! another IPP server. Every job is recorded in the library's print log.
+
+mod lp;
+#[cfg(all(test, unix))]
+pub(crate) mod tests;
+
+pub use lp::{resolve_printer, send_print_jobs, PrintError, Printer, SentJobs, PRINTER_ENV};
@@ -0,0 +1,150 @@
+//! Print submission tests against a stub `lp` shell script.
+
+use std::fs;
+use std::os::unix::fs::PermissionsExt;
+use std::path::{Path, PathBuf};
+
+use super::{send_print_jobs, PrintError, Printer};
+use crate::db::{ensure_schema_in_memory, Library, MemoryLibrary, SqliteLibrary};
+use crate::models::LinkKind;
+use crate::reports::tests::write_blank_pdf;
+use crate::reports::{plan_print_job, SongDemand};
+
+/// Write an `lp` stand-in into `dir` that appends its arguments to
+/// `lp-calls.txt` and answers like CUPS. It refuses every job for the printer
+/// `Missing` and any file whose name contains `broken`. Shared with the
+/// command line and TUI tests.
+pub(crate) fn stub_lp(dir: &Path) -> PathBuf {
+    let calls = dir.join("lp-calls.txt");
+    let script = dir.join("lp");
+    fs::write(
+        &script,
+        format!(
+            "#!/bin/sh\n\
+             printf '%s\\n' \"$*\" >> '{calls}'\n\
+             if [ \"$1\" = -d ] && [ \"$2\" = Missing ]; then\n\
+             \x20 echo 'lp: The printer or class does not exist.' >&2; exit 1\n\
+             fi\n\
+             case \"$*\" in *broken*) echo 'lp: Unsupported document-format.' >&2; exit 1;; esac\n\
+             echo \"request id is Office-$(wc -l < '{calls}' | tr -d ' ') (1 file(s))\"\n",
+            calls = calls.display()
+        ),
+    )
+    .unwrap();
+    fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+    script
+}
+
+/// Arguments of every `lp` call the stub in `dir` received, one per call.
+pub(crate) fn lp_calls(dir: &Path) -> Vec<String> {
+    fs::read_to_string(dir.join("lp-calls.txt"))
+        .unwrap_or_default()
+        .lines()
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn submit_passes_printer_copies_and_title_to_lp() {
+    let dir = tempfile::tempdir().unwrap();
+    let lp = stub_lp(dir.path());
+    let score = dir.path().join("ave.pdf");
+    write_blank_pdf(&score, 1);
+
+    let office = Printer::new(Some("Office".to_string())).with_command(&lp);
+    assert_eq!(
+        office.submit(&score, 3, "Ave Verum - Mozart").unwrap(),
+        "Office-1"
+    );
+    let default = Printer::new(None).with_command(&lp);
+    assert_eq!(default.describe(), "the default printer");
+    assert_eq!(default.submit(&score, 1, "Ave").unwrap(), "Office-2");
+    assert_eq!(
+        lp_calls(dir.path()),
+        vec![
+            format!(
+                "-d Office -n 3 -t Ave Verum - Mozart -- {}",
+                score.display()
+            ),
+            format!("-n 1 -t Ave -- {}", score.display()),
+        ]
+    );
+
+    let missing = Printer::new(Some("Missing".to_string())).with_command(&lp);
+    let err = missing.submit(&score, 1, "Ave").unwrap_err();
+    assert_eq!(err.to_string(), "lp: The printer or class does not exist.");
+    let not_installed = Printer::new(None).with_command(dir.path().join("no-such-lp"));
+    assert!(matches!(
+        not_installed.submit(&score, 1, "Ave"),
+        Err(PrintError::NotInstalled(_))
+    ));
+}
+
+#[test]
+fn sent_jobs_are_logged_even_when_refused() {
+    let dir = tempfile::tempdir().unwrap();
+    let lp = stub_lp(dir.path());
+    write_blank_pdf(&dir.path().join("ave.pdf"), 1);
+    write_blank_pdf(&dir.path().join("broken.pdf"), 1);
+    let printer = Printer::new(Some("Office".to_string())).with_command(&lp);
+
+    let backends: [Box<dyn Library>; 2] = [
+        Box::new(MemoryLibrary::new()),
+        Box::new(SqliteLibrary::new(ensure_schema_in_memory().unwrap())),
+    ];
+    for mut library in backends {
+        let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+        let broken = library.create_song("Gloria", "").unwrap();
+        for (song, file) in [(&ave, "ave.pdf"), (&broken, "broken.pdf")] {
+            let path = dir.path().join(file).display().to_string();
+            library
+                .add_song_link(song.id, LinkKind::Score, "", "", &path)
+                .unwrap();
+        }
+        let cantique = library.create_song("Cantique", "Fauré").unwrap();
+        let demands: Vec<SongDemand> = [(&ave, 4), (&broken, 1), (&cantique, 2)]
+            .into_iter()
+            .map(|(song, needed)| SongDemand {
+                song: song.clone(),
+                needed,
+            })
+            .collect();
+        let job = plan_print_job(library.as_ref(), None, &demands).unwrap();
+
+        let sent = send_print_jobs(library.as_mut(), &printer, &job, "2024-05-01 10:00");
+        assert!(sent.log_error.is_none());
+        let logged = sent.entries;
+        assert_eq!(logged.len(), 2);
+        assert!(logged[0].accepted());
+        assert_eq!(
+            (
+                logged[0].title.as_str(),
+                logged[0].copies,
+                logged[0].printer.as_str()
+            ),
+            ("Ave Verum - Mozart", 4, "Office")
+        );
+        assert_eq!(logged[0].file_name, "ave.pdf");
+        assert!(logged[0].request_id.starts_with("Office-"));
+        assert!(!logged[1].accepted());
+        assert_eq!(logged[1].error, "lp: Unsupported document-format.");
+        assert_eq!(library.fetch_print_log().unwrap(), logged);
+
+        // The log outlives the songs it mentions.
+        library.delete_song(ave.id).unwrap();
+        let log = library.fetch_print_log().unwrap();
+        assert_eq!(log[0].song_id, None);
+        assert_eq!(log[0].title, "Ave Verum - Mozart");
+        assert_eq!(log[1].song_id, Some(broken.id));
+
+        // A job that cannot be logged stops the run but is still reported.
+        let calls = lp_calls(dir.path()).len();
+        let sent = send_print_jobs(library.as_mut(), &printer, &job, "2024-05-01 11:00");
+        assert!(sent.log_error.is_some());
+        assert_eq!(sent.entries.len(), 1);
+        assert!(sent.entries[0].accepted());
+        assert_eq!(sent.entries[0].id, 0);
+        assert_eq!(lp_calls(dir.path()).len(), calls + 1);
+        assert_eq!(library.fetch_print_log().unwrap().len(), 2);
+    }
+}
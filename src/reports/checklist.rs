@@ -0,0 +1,319 @@
+use std::fmt::Write as _;
+use std::io::Write;
+
+use serde::Serialize;
+
+use super::contents::{binder_heading, escape_html};
+use super::to_print::{BinderShortfall, MissingSong, ToPrintReport};
+use crate::models::{Binder, Concert, PrintStage};
+
+/// Printable forms of a [`ToPrintReport`] for the volunteer at the copier.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChecklistFormat {
+    /// Copy counts and per-binder tick lists as a Markdown document.
+    Markdown,
+    /// The same document as a standalone HTML page.
+    Html,
+    /// Copy counts only, one row per song.
+    SongsCsv,
+    /// The per-binder tick lists, one row per binder and song.
+    BindersCsv,
+}
+
+impl ChecklistFormat {
+    pub const ALL: [ChecklistFormat; 4] = [
+        ChecklistFormat::Markdown,
+        ChecklistFormat::Html,
+        ChecklistFormat::SongsCsv,
+        ChecklistFormat::BindersCsv,
+    ];
+
+    /// Name used on the command line.
+    pub fn name(self) -> &'static str {
+        match self {
+            ChecklistFormat::Markdown => "markdown",
+            ChecklistFormat::Html => "html",
+            ChecklistFormat::SongsCsv => "songs-csv",
+            ChecklistFormat::BindersCsv => "binders-csv",
+        }
+    }
+
+    /// Inverse of [`ChecklistFormat::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|format| format.name() == name)
+    }
+
+    /// File name for an export taken on `stamp`, e.g.
+    /// `to-print-songs-2024-05-01.csv`.
+    pub fn file_name(self, stamp: &str) -> String {
+        match self {
+            ChecklistFormat::Markdown => format!("to-print-{stamp}.md"),
+            ChecklistFormat::Html => format!("to-print-{stamp}.html"),
+            ChecklistFormat::SongsCsv => format!("to-print-songs-{stamp}.csv"),
+            ChecklistFormat::BindersCsv => format!("to-print-binders-{stamp}.csv"),
+        }
+    }
+}
+
+/// Title of a checklist comparing against the director's binder or against
+/// `concert`'s setlist.
+pub fn checklist_title(concert: Option<&Concert>) -> String {
+    match concert {
+        Some(concert) => format!("To Print – {} setlist", concert.name.trim()),
+        None => "To Print – compared with the director's binder".to_string(),
+    }
+}
+
+/// Render `report` under `title` in `format`.
+pub fn render_checklist(
+    report: &ToPrintReport,
+    title: &str,
+    format: ChecklistFormat,
+) -> Result<String, csv::Error> {
+    match format {
+        ChecklistFormat::Markdown => Ok(checklist_markdown(report, title)),
+        ChecklistFormat::Html => Ok(checklist_html(report, title)),
+        ChecklistFormat::SongsCsv | ChecklistFormat::BindersCsv => {
+            let mut bytes = Vec::new();
+            write_checklist_csv(report, format, &mut bytes)?;
+            Ok(String::from_utf8(bytes).expect("CSV built from strings is UTF-8"))
+        }
+    }
+}
+
+/// Copies of one song to print, as a CSV row.
+#[derive(Serialize)]
+struct SongCopiesRow<'a> {
+    song_id: i64,
+    title: &'a str,
+    composer: &'a str,
+    copies: usize,
+}
+
+/// One tick-box line of a binder's checklist, as a CSV row. `added` is left
+/// blank for the volunteer to fill in.
+#[derive(Serialize)]
+struct BinderChecklistRow<'a> {
+    binder_number: i64,
+    binder_label: &'a str,
+    voice_part: &'a str,
+    song_id: i64,
+    title: &'a str,
+    composer: &'a str,
+    added: &'a str,
+}
+
+/// Write the songs or binders CSV with a header row, even when nothing is
+/// missing. Markdown and HTML are not CSV and write nothing.
+fn write_checklist_csv(
+    report: &ToPrintReport,
+    format: ChecklistFormat,
+    output: impl Write,
+) -> Result<(), csv::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(output);
+    match format {
+        ChecklistFormat::SongsCsv => {
+            writer.write_record(["song_id", "title", "composer", "copies"])?;
+            for demand in &report.songs {
+                writer.serialize(SongCopiesRow {
+                    song_id: demand.song.id,
+                    title: &demand.song.title,
+                    composer: &demand.song.composer,
+                    copies: demand.needed,
+                })?;
+            }
+        }
+        ChecklistFormat::BindersCsv => {
+            writer.write_record([
+                "binder_number",
+                "binder_label",
+                "voice_part",
+                "song_id",
+                "title",
+                "composer",
+                "added",
+            ])?;
+            for shortfall in &report.binders {
+                for MissingSong { song, .. } in &shortfall.missing {
+                    writer.serialize(BinderChecklistRow {
+                        binder_number: shortfall.binder.number,
+                        binder_label: &shortfall.binder.label,
+                        voice_part: &shortfall.binder.voice_part,
+                        song_id: song.id,
+                        title: &song.title,
+                        composer: &song.composer,
+                        added: "",
+                    })?;
+                }
+            }
+        }
+        ChecklistFormat::Markdown | ChecklistFormat::Html => {}
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// "Binder 01 – Sopranos (S)": the binder heading plus its voice part.
+fn shortfall_heading(binder: &Binder) -> String {
+    let heading = binder_heading(binder);
+    if binder.voice_part.trim().is_empty() {
+        heading
+    } else {
+        format!("{heading} ({})", binder.voice_part.trim())
+    }
+}
+
+fn copies_label(copies: usize) -> &'static str {
+    if copies == 1 {
+        "copy"
+    } else {
+        "copies"
+    }
+}
+
+fn total_copies(report: &ToPrintReport) -> usize {
+    report.songs.iter().map(|demand| demand.needed).sum()
+}
+
+/// "1 song" or "3 songs".
+fn song_count(count: usize) -> String {
+    format!("{count} song{}", if count == 1 { "" } else { "s" })
+}
+
+fn missing_count(shortfall: &BinderShortfall) -> String {
+    song_count(shortfall.missing.len())
+}
+
+/// Escape characters that would break a Markdown table cell or list item.
+fn escape_markdown(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        if matches!(ch, '\\' | '|' | '*' | '_' | '[' | ']' | '`' | '<' | '>') {
+            escaped.push('\\');
+        }
+        escaped.push(ch);
+    }
+    escaped
+}
+
+/// Title and composer, noting a copy that is already printed or inserted.
+fn song_line(missing: &MissingSong) -> String {
+    let song = &missing.song;
+    let line = if song.composer.trim().is_empty() {
+        song.title.clone()
+    } else {
+        format!("{} – {}", song.title, song.composer.trim())
+    };
+    match missing.stage {
+        PrintStage::Needed => line,
+        PrintStage::Printed => format!("{line} (printed)"),
+        PrintStage::Inserted => format!("{line} (inserted)"),
+    }
+}
+
+/// Copy counts per song followed by a tick list per binder, as Markdown.
+pub fn checklist_markdown(report: &ToPrintReport, title: &str) -> String {
+    let mut text = format!("# {}\n\n", escape_markdown(title));
+    if report.binders.is_empty() {
+        text.push_str("Every binder is complete; there is nothing to print.\n");
+        return text;
+    }
+
+    text.push_str("## Copies to print\n\n");
+    text.push_str("| Song | Composer | Copies |\n| --- | --- | ---: |\n");
+    for demand in &report.songs {
+        let _ = writeln!(
+            text,
+            "| {} | {} | {} |",
+            escape_markdown(&demand.song.title),
+            escape_markdown(&demand.song.composer),
+            demand.needed
+        );
+    }
+    let total = total_copies(report);
+    let _ = writeln!(
+        text,
+        "\nTotal: {total} {} of {}.",
+        copies_label(total),
+        song_count(report.songs.len())
+    );
+
+    text.push_str("\n## Binder checklists\n");
+    for shortfall in &report.binders {
+        let _ = writeln!(
+            text,
+            "\n### {} – {}\n",
+            escape_markdown(&shortfall_heading(&shortfall.binder)),
+            missing_count(shortfall)
+        );
+        for song in &shortfall.missing {
+            let _ = writeln!(text, "- [ ] {}", escape_markdown(&song_line(song)));
+        }
+    }
+    text
+}
+
+/// Copy counts per song followed by a tick list per binder, as a standalone
+/// HTML page. Each binder's list starts a new printed page.
+pub fn checklist_html(report: &ToPrintReport, title: &str) -> String {
+    let title = escape_html(title);
+    let mut html = format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>{title}</title>\n<style>\n\
+         body {{ font-family: sans-serif; }}\n\
+         table {{ border-collapse: collapse; }}\n\
+         th, td {{ text-align: left; padding: 4px 8px; border-bottom: 1px solid #999; }}\n\
+         td.copies {{ text-align: right; font-weight: bold; }}\n\
+         section {{ break-before: page; }}\n\
+         ul {{ list-style: none; padding-left: 0; }}\n\
+         li {{ margin: 6px 0; }}\n\
+         .box {{ display: inline-block; width: 0.9em; height: 0.9em; \
+         border: 1px solid #000; margin-right: 0.5em; vertical-align: middle; }}\n\
+         </style>\n</head>\n<body>\n<h1>{title}</h1>\n"
+    );
+    if report.binders.is_empty() {
+        html.push_str("<p>Every binder is complete; there is nothing to print.</p>\n");
+        html.push_str("</body>\n</html>\n");
+        return html;
+    }
+
+    html.push_str("<h2>Copies to print</h2>\n<table>\n");
+    html.push_str("<tr><th>Song</th><th>Composer</th><th>Copies</th></tr>\n");
+    for demand in &report.songs {
+        let _ = writeln!(
+            html,
+            "<tr><td>{}</td><td>{}</td><td class=\"copies\">{}</td></tr>",
+            escape_html(&demand.song.title),
+            escape_html(&demand.song.composer),
+            demand.needed
+        );
+    }
+    let total = total_copies(report);
+    let _ = writeln!(
+        html,
+        "</table>\n<p>Total: {total} {} of {}.</p>",
+        copies_label(total),
+        song_count(report.songs.len())
+    );
+
+    for shortfall in &report.binders {
+        let _ = writeln!(
+            html,
+            "<section>\n<h2>{} – {}</h2>\n<ul>",
+            escape_html(&shortfall_heading(&shortfall.binder)),
+            missing_count(shortfall)
+        );
+        for song in &shortfall.missing {
+            let _ = writeln!(
+                html,
+                "<li><span class=\"box\"></span>{}</li>",
+                escape_html(&song_line(song))
+            );
+        }
+        html.push_str("</ul>\n</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
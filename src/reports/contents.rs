@@ -0,0 +1,207 @@
+use std::fmt::Write as _;
+
+use serde::Serialize;
+
+use crate::db::{DbResult, Library};
+use crate::models::Binder;
+
+/// One song on a binder's index sheet.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ContentsEntry {
+    /// 1-based place in the binder's running order.
+    pub position: usize,
+    pub title: String,
+    pub composer: String,
+    /// Page the song starts on, counted from the page counts of the songs
+    /// before it. `None` once a song without a page count comes first, and
+    /// for every song when no song in the binder has a page count.
+    pub page: Option<u32>,
+}
+
+/// The index sheet of one binder: its songs in running order.
+#[derive(Debug, Clone, Serialize)]
+pub struct BinderContents {
+    pub binder: Binder,
+    pub entries: Vec<ContentsEntry>,
+}
+
+impl BinderContents {
+    /// Whether any song's starting page is known, i.e. the sheet needs a page
+    /// column.
+    pub fn has_pages(&self) -> bool {
+        self.entries.iter().any(|entry| entry.page.is_some())
+    }
+
+    /// Heading printed above the list, e.g. "Binder 01 – Sopranos".
+    pub fn heading(&self) -> String {
+        binder_heading(&self.binder)
+    }
+}
+
+/// A binder's number and label as printed on sheets, e.g. "Binder 01 –
+/// Sopranos".
+pub(crate) fn binder_heading(binder: &Binder) -> String {
+    if binder.label.trim().is_empty() {
+        format!("Binder {:02}", binder.number)
+    } else {
+        format!("Binder {:02} – {}", binder.number, binder.label.trim())
+    }
+}
+
+/// The index sheet of `binder`.
+pub fn binder_contents(library: &dyn Library, binder: &Binder) -> DbResult<BinderContents> {
+    let songs = library.fetch_songs_for_binder(binder.id)?;
+    let mut next_page = songs
+        .iter()
+        .any(|song| song.details.page_count.is_some())
+        .then_some(1u32);
+    let entries = songs
+        .into_iter()
+        .enumerate()
+        .map(|(idx, song)| {
+            let page = next_page;
+            next_page = next_page
+                .zip(song.details.page_count)
+                .map(|(page, count)| page.saturating_add(count));
+            ContentsEntry {
+                position: idx + 1,
+                title: song.title,
+                composer: song.composer,
+                page,
+            }
+        })
+        .collect();
+    Ok(BinderContents {
+        binder: binder.clone(),
+        entries,
+    })
+}
+
+/// Index sheets of every binder, by binder number.
+pub fn all_binder_contents(library: &dyn Library) -> DbResult<Vec<BinderContents>> {
+    library
+        .fetch_binders()?
+        .iter()
+        .map(|binder| binder_contents(library, binder))
+        .collect()
+}
+
+/// Plain-text index sheets. Binders are separated by a form feed so each
+/// starts on a new page when printed.
+pub fn contents_text(contents: &[BinderContents]) -> String {
+    let sheets: Vec<String> = contents
+        .iter()
+        .map(|sheet| {
+            let heading = sheet.heading();
+            let mut text = format!("{heading}\n{}\n\n", "=".repeat(heading.chars().count()));
+            if sheet.entries.is_empty() {
+                text.push_str("(no songs)\n");
+                return text;
+            }
+            let width = |header: &str, field: fn(&ContentsEntry) -> &str| {
+                sheet
+                    .entries
+                    .iter()
+                    .map(|entry| field(entry).chars().count())
+                    .chain([header.len()])
+                    .max()
+                    .unwrap_or_default()
+            };
+            let title_width = width("Title", |entry| &entry.title);
+            let composer_width = width("Composer", |entry| &entry.composer);
+            let pages = sheet.has_pages();
+            let line = |no: &str, title: &str, composer: &str, page: &str| {
+                let line = if pages {
+                    format!(
+                        "{no:>3}  {title:<title_width$}  {composer:<composer_width$}  {page:>4}"
+                    )
+                } else {
+                    format!("{no:>3}  {title:<title_width$}  {composer}")
+                };
+                line.trim_end().to_string()
+            };
+            let _ = writeln!(text, "{}", line("No.", "Title", "Composer", "Page"));
+            for entry in &sheet.entries {
+                let page = entry.page.map(|page| page.to_string()).unwrap_or_default();
+                let _ = writeln!(
+                    text,
+                    "{}",
+                    line(
+                        &entry.position.to_string(),
+                        &entry.title,
+                        &entry.composer,
+                        &page
+                    )
+                );
+            }
+            text
+        })
+        .collect();
+    sheets.join("\x0c")
+}
+
+/// A standalone HTML page with one index sheet per printed page.
+pub fn contents_html(contents: &[BinderContents]) -> String {
+    let mut html = String::from(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n\
+         <title>Binder index</title>\n<style>\n\
+         body { font-family: serif; }\n\
+         section { break-after: page; }\n\
+         section:last-child { break-after: auto; }\n\
+         table { border-collapse: collapse; width: 100%; }\n\
+         th, td { text-align: left; padding: 4px 8px; border-bottom: 1px solid #999; }\n\
+         td.no, td.page { text-align: right; width: 3em; }\n\
+         </style>\n</head>\n<body>\n",
+    );
+    for sheet in contents {
+        let _ = writeln!(
+            html,
+            "<section>\n<h1>{}</h1>",
+            escape_html(&sheet.heading())
+        );
+        if sheet.entries.is_empty() {
+            html.push_str("<p>(no songs)</p>\n</section>\n");
+            continue;
+        }
+        let pages = sheet.has_pages();
+        html.push_str("<table>\n<tr><th>No.</th><th>Title</th><th>Composer</th>");
+        html.push_str(if pages {
+            "<th>Page</th></tr>\n"
+        } else {
+            "</tr>\n"
+        });
+        for entry in &sheet.entries {
+            let _ = write!(
+                html,
+                "<tr><td class=\"no\">{}</td><td>{}</td><td>{}</td>",
+                entry.position,
+                escape_html(&entry.title),
+                escape_html(&entry.composer)
+            );
+            if pages {
+                let page = entry.page.map(|page| page.to_string()).unwrap_or_default();
+                let _ = write!(html, "<td class=\"page\">{page}</td>");
+            }
+            html.push_str("</tr>\n");
+        }
+        html.push_str("</table>\n</section>\n");
+    }
+    html.push_str("</body>\n</html>\n");
+    html
+}
+
+/// Escape text for use in HTML element content and attribute values.
+pub(crate) fn escape_html(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for ch in text.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
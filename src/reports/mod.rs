@@ -0,0 +1,27 @@
+//! Read-only reports computed from a [`Library`](crate::db::Library). They live
+//! outside the UI so the TUI, the command line, and exports all agree on the
+//! numbers.
+
+mod checklist;
+mod contents;
+mod overdue;
+mod print_job;
+#[cfg(test)]
+pub(crate) mod tests;
+mod to_print;
+
+pub use checklist::{
+    checklist_html, checklist_markdown, checklist_title, render_checklist, ChecklistFormat,
+};
+pub use contents::{
+    all_binder_contents, binder_contents, contents_html, contents_text, BinderContents,
+    ContentsEntry,
+};
+pub use overdue::{days_out, overdue_report, OverdueBinder};
+pub use print_job::{
+    plan_print_job, write_print_job, PrintJob, PrintJobEntry, PrintJobError, PrintJobSummary,
+};
+pub use to_print::{
+    to_print_report, to_print_report_for, BinderShortfall, MissingSong, SongDemand, ToPrintReport,
+    ToPrintTarget,
+};
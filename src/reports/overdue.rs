@@ -0,0 +1,57 @@
+use chrono::NaiveDateTime;
+use serde::Serialize;
+
+use crate::db::{open_checkouts, DbResult, Library, TIMESTAMP_FORMAT};
+use crate::models::{Binder, BinderCheckout, Chorister};
+
+/// A binder that has been out longer than the allowed period.
+#[derive(Debug, Clone, Serialize)]
+pub struct OverdueBinder {
+    pub binder: Binder,
+    /// Who checked it out, if anyone was recorded.
+    pub chorister: Option<Chorister>,
+    pub checkout: BinderCheckout,
+    /// Whole days since the checkout.
+    pub days_out: i64,
+}
+
+/// Whole days between a checkout timestamp and `now`, or `None` if the
+/// timestamp cannot be read.
+pub fn days_out(checked_out_at: &str, now: NaiveDateTime) -> Option<i64> {
+    let out = NaiveDateTime::parse_from_str(checked_out_at, TIMESTAMP_FORMAT).ok()?;
+    Some((now - out).num_days())
+}
+
+/// Binders checked out more than `days` days before `now`, longest out first.
+pub fn overdue_report(
+    library: &dyn Library,
+    now: NaiveDateTime,
+    days: u32,
+) -> DbResult<Vec<OverdueBinder>> {
+    let mut open = open_checkouts(library)?;
+    let choristers = library.fetch_choristers()?;
+
+    let mut overdue: Vec<OverdueBinder> = library
+        .fetch_binders()?
+        .into_iter()
+        .filter_map(|binder| {
+            let checkout = open.remove(&binder.id)?;
+            let days_out = days_out(&checkout.checked_out_at, now)?;
+            if days_out <= i64::from(days) {
+                return None;
+            }
+            let chorister = checkout
+                .chorister_id
+                .and_then(|id| choristers.iter().find(|chorister| chorister.id == id))
+                .cloned();
+            Some(OverdueBinder {
+                binder,
+                chorister,
+                checkout,
+                days_out,
+            })
+        })
+        .collect();
+    overdue.sort_by(|a, b| (b.days_out, a.binder.number).cmp(&(a.days_out, b.binder.number)));
+    Ok(overdue)
+}
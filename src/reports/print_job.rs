@@ -0,0 +1,424 @@
+use std::io;
+use std::path::{Path, PathBuf};
+
+use lopdf::content::{Content, Operation};
+use lopdf::{dictionary, Dictionary, Document, Object, ObjectId, Stream};
+use thiserror::Error;
+
+use super::to_print::SongDemand;
+use crate::db::{song_attachments, song_links, AttachmentStore, DbResult, Library};
+use crate::models::{Attachment, LinkKind, Song, SongLink};
+
+/// A4 portrait, in points.
+const COVER_SIZE: (i64, i64) = (595, 842);
+/// Summary lines that fit on one cover page below the heading.
+const COVER_LINES_PER_PAGE: usize = 44;
+/// Page attributes a page may inherit from the page tree above it. They are
+/// copied onto each page because the source page trees are dropped.
+const INHERITED_PAGE_KEYS: [&[u8]; 4] = [b"Resources", b"MediaBox", b"CropBox", b"Rotate"];
+
+/// Why a print job could not be written.
+#[derive(Debug, Error)]
+pub enum PrintJobError {
+    /// No song to print has a PDF attached or linked.
+    #[error("None of the songs to print has a PDF score attached or linked.")]
+    NoScores,
+    /// A score could not be read as a PDF.
+    #[error("Could not read '{file}' as a PDF: {source}")]
+    Unreadable {
+        file: String,
+        #[source]
+        source: lopdf::Error,
+    },
+    /// A score PDF has no pages to print.
+    #[error("'{0}' has no pages.")]
+    NoPages(String),
+    /// The combined PDF could not be saved.
+    #[error("Could not write the print job: {0}")]
+    Write(#[source] io::Error),
+}
+
+/// One song of a print job: its score and how many copies to print.
+#[derive(Debug, Clone)]
+pub struct PrintJobEntry {
+    pub song: Song,
+    pub copies: usize,
+    /// The local PDF printed for the song.
+    pub path: PathBuf,
+    /// The score's file name as the user knows it; attachments are stored
+    /// under their checksum.
+    pub file_name: String,
+}
+
+/// The songs of a To Print report split into those with a local PDF score
+/// and those someone still has to find sheet music for.
+#[derive(Debug, Clone, Default)]
+pub struct PrintJob {
+    pub entries: Vec<PrintJobEntry>,
+    pub missing: Vec<SongDemand>,
+}
+
+/// What went into a written print job.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PrintJobSummary {
+    /// Copies of scores, summed over the songs.
+    pub copies: usize,
+    /// Score pages, not counting the cover.
+    pub pages: usize,
+    /// Pages taken by the summary in front.
+    pub cover_pages: usize,
+}
+
+impl PrintJob {
+    pub fn total_copies(&self) -> usize {
+        self.entries.iter().map(|entry| entry.copies).sum()
+    }
+}
+
+/// Find a local PDF score for each song in `songs`. A song's PDF attachments
+/// come first, checked against their checksums (only when `store` is given),
+/// then its score links, as long as they name a PDF file on this computer. Web links are never downloaded.
+pub fn plan_print_job(
+    library: &dyn Library,
+    store: Option<&AttachmentStore>,
+    songs: &[SongDemand],
+) -> DbResult<PrintJob> {
+    let attachments = song_attachments(library)?;
+    let links = song_links(library)?;
+    let mut job = PrintJob::default();
+    for demand in songs.iter().filter(|demand| demand.needed > 0) {
+        let attached = attachments
+            .get(&demand.song.id)
+            .map_or(&[][..], Vec::as_slice);
+        let linked = links.get(&demand.song.id).map_or(&[][..], Vec::as_slice);
+        match score_pdf(attached, linked, store) {
+            Some((path, file_name)) => job.entries.push(PrintJobEntry {
+                song: demand.song.clone(),
+                copies: demand.needed,
+                path,
+                file_name,
+            }),
+            None => job.missing.push(demand.clone()),
+        }
+    }
+    Ok(job)
+}
+
+fn score_pdf(
+    attachments: &[Attachment],
+    links: &[SongLink],
+    store: Option<&AttachmentStore>,
+) -> Option<(PathBuf, String)> {
+    let attached = store.and_then(|store| {
+        attachments
+            .iter()
+            .filter(|attachment| has_pdf_extension(Path::new(&attachment.file_name)))
+            .find_map(|attachment| {
+                let path = store.verified_path(attachment).ok()?;
+                Some((path, attachment.file_name.clone()))
+            })
+    });
+    attached.or_else(|| {
+        let path = links
+            .iter()
+            .filter(|link| link.kind == LinkKind::Score)
+            .find_map(|link| local_pdf_path(&link.url))?;
+        let file_name = path.file_name()?.to_string_lossy().into_owned();
+        Some((path, file_name))
+    })
+}
+
+fn has_pdf_extension(path: &Path) -> bool {
+    path.extension()
+        .is_some_and(|extension| extension.eq_ignore_ascii_case("pdf"))
+}
+
+/// The PDF file a link points to, if it is a plain path or a `file://` URL
+/// naming an existing `.pdf` file.
+fn local_pdf_path(link: &str) -> Option<PathBuf> {
+    let link = link.trim();
+    let path = match link.strip_prefix("file://") {
+        Some(rest) => PathBuf::from(percent_decode(rest)?),
+        None if link.contains("://") || link.is_empty() => return None,
+        None => PathBuf::from(link),
+    };
+    (has_pdf_extension(&path) && path.is_file()).then_some(path)
+}
+
+/// Decode `%20`-style escapes in a `file://` URL path.
+fn percent_decode(text: &str) -> Option<String> {
+    let mut bytes = Vec::with_capacity(text.len());
+    let mut rest = text.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        if byte == b'%' {
+            let hex = std::str::from_utf8(tail.get(..2)?).ok()?;
+            bytes.push(u8::from_str_radix(hex, 16).ok()?);
+            rest = &tail[2..];
+        } else {
+            bytes.push(byte);
+            rest = tail;
+        }
+    }
+    String::from_utf8(bytes).ok()
+}
+
+/// Write `job` to `output` as one PDF: a cover page summarising the job under
+/// `title`, then every score repeated for its number of copies.
+pub fn write_print_job(
+    job: &PrintJob,
+    title: &str,
+    output: &Path,
+) -> Result<PrintJobSummary, PrintJobError> {
+    if job.entries.is_empty() {
+        return Err(PrintJobError::NoScores);
+    }
+    let scores = job
+        .entries
+        .iter()
+        .map(|entry| {
+            let file = entry.path.display().to_string();
+            let document =
+                Document::load(&entry.path).map_err(|source| PrintJobError::Unreadable {
+                    file: file.clone(),
+                    source,
+                })?;
+            if document.get_pages().is_empty() {
+                return Err(PrintJobError::NoPages(file));
+            }
+            Ok(document)
+        })
+        .collect::<Result<Vec<_>, _>>()?;
+    let page_counts: Vec<usize> = scores
+        .iter()
+        .map(|document| document.get_pages().len())
+        .collect();
+
+    let mut output_doc = Document::with_version("1.5");
+    let pages_id = output_doc.new_object_id();
+    let cover = cover_lines(job, &page_counts);
+    let mut kids =
+        add_cover_pages(&mut output_doc, pages_id, title, &cover).map_err(PrintJobError::Write)?;
+    let cover_pages = kids.len();
+
+    for (entry, score) in job.entries.iter().zip(scores) {
+        kids.extend(append_score(&mut output_doc, pages_id, score, entry.copies));
+    }
+
+    let count = kids.len() as i64;
+    output_doc.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids.into_iter().map(Object::Reference).collect::<Vec<_>>(),
+            "Count" => count,
+        }),
+    );
+    let catalog_id = output_doc.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    output_doc.trailer.set("Root", catalog_id);
+    output_doc.save(output).map_err(PrintJobError::Write)?;
+
+    Ok(PrintJobSummary {
+        copies: job.total_copies(),
+        pages: job
+            .entries
+            .iter()
+            .zip(&page_counts)
+            .map(|(entry, pages)| entry.copies * pages)
+            .sum(),
+        cover_pages,
+    })
+}
+
+/// Move the objects of `score` into `output` once and hang `copies` copies of
+/// its pages under `pages_id`. Each copy only adds page dictionaries; they all
+/// point at the same contents and resources. Returns the page ids in reading
+/// order.
+fn append_score(
+    output: &mut Document,
+    pages_id: ObjectId,
+    mut score: Document,
+    copies: usize,
+) -> Vec<ObjectId> {
+    score.renumber_objects_with(output.max_id + 1);
+    let pages: Vec<(ObjectId, Dictionary)> = score
+        .get_pages()
+        .into_values()
+        .filter_map(|id| {
+            let mut page = score.get_dictionary(id).ok()?.clone();
+            inherit_page_attributes(&score, &mut page);
+            page.set("Parent", pages_id);
+            Some((id, page))
+        })
+        .collect();
+
+    output.max_id = output.max_id.max(score.max_id);
+    for (id, object) in score.objects {
+        match object.type_name().unwrap_or_default() {
+            b"Catalog" | b"Pages" | b"Outlines" | b"Outline" => {}
+            _ => {
+                output.objects.insert(id, object);
+            }
+        }
+    }
+
+    let mut page_ids = Vec::with_capacity(pages.len() * copies);
+    for copy in 0..copies {
+        for (id, page) in &pages {
+            // The first copy keeps the original page ids, so anything in the
+            // score that refers to its pages still finds them.
+            let id = if copy == 0 {
+                output.objects.insert(*id, Object::Dictionary(page.clone()));
+                *id
+            } else {
+                output.add_object(page.clone())
+            };
+            page_ids.push(id);
+        }
+    }
+    page_ids
+}
+
+/// Copy the attributes `page` inherits from its ancestors in `document` onto
+/// the page itself.
+fn inherit_page_attributes(document: &Document, page: &mut Dictionary) {
+    for key in INHERITED_PAGE_KEYS {
+        if page.has(key) {
+            continue;
+        }
+        let mut parent = page.get(b"Parent").and_then(Object::as_reference).ok();
+        // Page trees are shallow; the bound only guards against cycles.
+        for _ in 0..32 {
+            let Some(node) = parent.and_then(|id| document.get_dictionary(id).ok()) else {
+                break;
+            };
+            if let Ok(value) = node.get(key) {
+                // References stay valid: only the page tree nodes are dropped.
+                page.set(key.to_vec(), value.clone());
+                break;
+            }
+            parent = node.get(b"Parent").and_then(Object::as_reference).ok();
+        }
+    }
+}
+
+/// The summary listed on the cover: one line per song and copy count, then
+/// the songs left out for lack of a PDF.
+fn cover_lines(job: &PrintJob, page_counts: &[usize]) -> Vec<String> {
+    let songs = job.entries.len();
+    let mut lines = vec![
+        format!(
+            "{} {} of {} {}.",
+            job.total_copies(),
+            if job.total_copies() == 1 {
+                "copy"
+            } else {
+                "copies"
+            },
+            songs,
+            if songs == 1 { "song" } else { "songs" }
+        ),
+        String::new(),
+    ];
+    for (entry, pages) in job.entries.iter().zip(page_counts) {
+        lines.push(format!(
+            "{} × {}  ({} {}, {})",
+            entry.copies,
+            song_label(&entry.song),
+            pages,
+            if *pages == 1 { "page" } else { "pages" },
+            entry.file_name
+        ));
+    }
+    if !job.missing.is_empty() {
+        lines.push(String::new());
+        lines.push("Not included, no PDF score found:".to_string());
+        for demand in &job.missing {
+            lines.push(format!("{} × {}", demand.needed, song_label(&demand.song)));
+        }
+    }
+    lines
+}
+
+fn song_label(song: &Song) -> String {
+    if song.composer.trim().is_empty() {
+        song.title.clone()
+    } else {
+        format!("{} – {}", song.title, song.composer.trim())
+    }
+}
+
+/// Lay `lines` out on as many A4 cover pages as needed, each headed by
+/// `title`. Returns the new page ids.
+fn add_cover_pages(
+    document: &mut Document,
+    pages_id: ObjectId,
+    title: &str,
+    lines: &[String],
+) -> io::Result<Vec<ObjectId>> {
+    let font = |document: &mut Document, base: &str| {
+        document.add_object(dictionary! {
+            "Type" => "Font",
+            "Subtype" => "Type1",
+            "BaseFont" => base,
+            "Encoding" => "WinAnsiEncoding",
+        })
+    };
+    let regular = font(document, "Helvetica");
+    let bold = font(document, "Helvetica-Bold");
+    let resources = document.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => regular, "F2" => bold },
+    });
+
+    lines
+        .chunks(COVER_LINES_PER_PAGE)
+        .map(|chunk| {
+            let mut operations = vec![
+                Operation::new("BT", vec![]),
+                Operation::new("Tf", vec!["F2".into(), 18.into()]),
+                Operation::new("Td", vec![56.into(), (COVER_SIZE.1 - 72).into()]),
+                Operation::new("Tj", vec![Object::string_literal(win_ansi(title))]),
+                Operation::new("Tf", vec!["F1".into(), 11.into()]),
+                Operation::new("TL", vec![16.into()]),
+                Operation::new("T*", vec![]),
+            ];
+            for line in chunk {
+                operations.push(Operation::new("T*", vec![]));
+                operations.push(Operation::new(
+                    "Tj",
+                    vec![Object::string_literal(win_ansi(line))],
+                ));
+            }
+            operations.push(Operation::new("ET", vec![]));
+            let content = Content { operations }.encode().map_err(io::Error::other)?;
+            let contents = document.add_object(Stream::new(dictionary! {}, content));
+            Ok(document.add_object(dictionary! {
+                "Type" => "Page",
+                "Parent" => pages_id,
+                "Contents" => contents,
+                "Resources" => resources,
+                "MediaBox" => vec![0.into(), 0.into(), COVER_SIZE.0.into(), COVER_SIZE.1.into()],
+            }))
+        })
+        .collect()
+}
+
+/// Encode `text` for the standard fonts' WinAnsi encoding, replacing what it
+/// cannot show with `?`.
+fn win_ansi(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|ch| match ch {
+            '–' => 0x96,
+            '—' => 0x97,
+            '•' => 0x95,
+            '‘' => 0x91,
+            '’' => 0x92,
+            '“' => 0x93,
+            '”' => 0x94,
+            ' '..='~' | '\u{a0}'..='\u{ff}' => ch as u8,
+            _ => b'?',
+        })
+        .collect()
+}
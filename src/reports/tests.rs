@@ -0,0 +1,424 @@
+//! Report tests (binder index sheets, To Print checklists and print jobs)
+//! run against both backends.
+
+use std::path::Path;
+
+use lopdf::content::Content;
+use lopdf::{dictionary, Document, Object, Stream};
+
+use super::{
+    all_binder_contents, checklist_title, contents_html, contents_text, plan_print_job,
+    render_checklist, to_print_report, write_print_job, ChecklistFormat, PrintJob, PrintJobEntry,
+    PrintJobError, PrintJobSummary,
+};
+use crate::db::{
+    attach_file, ensure_schema_in_memory, move_song_in_binder, AttachmentStore, Library,
+    MemoryLibrary, SqliteLibrary,
+};
+use crate::models::{LinkKind, PrintStage, SongDetails};
+
+fn both_backends() -> [Box<dyn Library>; 2] {
+    [
+        Box::new(MemoryLibrary::new()),
+        Box::new(SqliteLibrary::new(ensure_schema_in_memory().unwrap())),
+    ]
+}
+
+#[test]
+fn binder_index_follows_the_running_order() {
+    for mut library in both_backends() {
+        let director = library.create_binder(0, "Director").unwrap();
+        let altos = library.create_binder(2, "").unwrap();
+        let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+        let ave = library.create_song("Ave <Verum> & Co", "").unwrap();
+        library.add_song_to_binder(director.id, gloria.id).unwrap();
+        library.add_song_to_binder(director.id, ave.id).unwrap();
+        move_song_in_binder(library.as_mut(), director.id, ave.id, -1).unwrap();
+
+        let contents = all_binder_contents(library.as_ref()).unwrap();
+        assert_eq!(contents.len(), 2);
+        assert_eq!(contents[1].binder.id, altos.id);
+        assert_eq!(
+            contents_text(&contents),
+            "Binder 00 – Director\n\
+             ====================\n\n\
+             No.  Title             Composer\n  \
+             1  Ave <Verum> & Co\n  \
+             2  Gloria            Vivaldi\n\
+             \x0cBinder 02\n\
+             =========\n\n\
+             (no songs)\n"
+        );
+        let html = contents_html(&contents);
+        assert!(html.contains("<h1>Binder 00 – Director</h1>"));
+        assert!(html.contains(
+            "<tr><td class=\"no\">1</td><td>Ave &lt;Verum&gt; &amp; Co</td><td></td></tr>"
+        ));
+        assert!(html.contains("<h1>Binder 02</h1>\n<p>(no songs)</p>"));
+
+        // Page counts give each song its starting page.
+        let details = SongDetails {
+            page_count: Some(3),
+            ..SongDetails::default()
+        };
+        library.set_song_details(ave.id, &details).unwrap();
+        let contents = all_binder_contents(library.as_ref()).unwrap();
+        let pages: Vec<_> = contents[0].entries.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![Some(1), Some(4)]);
+        assert!(contents_text(&contents[..1]).contains(
+            "No.  Title             Composer  Page\n  \
+             1  Ave <Verum> & Co               1\n  \
+             2  Gloria            Vivaldi      4\n"
+        ));
+        assert!(contents_html(&contents).contains("<td class=\"page\">4</td>"));
+
+        // A mistyped page count cannot overflow the running total.
+        let details = SongDetails {
+            page_count: Some(u32::MAX),
+            ..SongDetails::default()
+        };
+        library.set_song_details(ave.id, &details).unwrap();
+        library.set_song_details(gloria.id, &details).unwrap();
+        let contents = all_binder_contents(library.as_ref()).unwrap();
+        let pages: Vec<_> = contents[0].entries.iter().map(|entry| entry.page).collect();
+        assert_eq!(pages, vec![Some(1), Some(u32::MAX)]);
+    }
+}
+
+#[test]
+fn to_print_checklists_count_copies_and_tick_boxes() {
+    for mut library in both_backends() {
+        let director = library.create_binder(0, "Director").unwrap();
+        let altos = library.create_binder(2, "Altos").unwrap();
+        let tenors = library.create_binder(3, "").unwrap();
+        library.set_binder_part(altos.id, "A").unwrap();
+        let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+        let stars = library.create_song("Stars | *Night*", "").unwrap();
+        library.add_song_to_binder(director.id, gloria.id).unwrap();
+        library.add_song_to_binder(director.id, stars.id).unwrap();
+        library.add_song_to_binder(tenors.id, stars.id).unwrap();
+
+        let report = to_print_report(&*library).unwrap().unwrap();
+        let title = checklist_title(None);
+        let render = |format| render_checklist(&report, &title, format).unwrap();
+        assert_eq!(
+            render(ChecklistFormat::Markdown),
+            "# To Print – compared with the director's binder\n\n\
+             ## Copies to print\n\n\
+             | Song | Composer | Copies |\n| --- | --- | ---: |\n\
+             | Gloria | Vivaldi | 2 |\n\
+             | Stars \\| \\*Night\\* |  | 1 |\n\
+             \nTotal: 3 copies of 2 songs.\n\
+             \n## Binder checklists\n\
+             \n### Binder 02 – Altos (A) – 2 songs\n\n\
+             - [ ] Gloria – Vivaldi\n\
+             - [ ] Stars \\| \\*Night\\*\n\
+             \n### Binder 03 – 1 song\n\n\
+             - [ ] Gloria – Vivaldi\n"
+        );
+        let html = render(ChecklistFormat::Html);
+        assert!(
+            html.contains("<tr><td>Gloria</td><td>Vivaldi</td><td class=\"copies\">2</td></tr>")
+        );
+        assert!(html.contains(
+            "<section>\n<h2>Binder 03 – 1 song</h2>\n<ul>\n\
+             <li><span class=\"box\"></span>Gloria – Vivaldi</li>"
+        ));
+        assert_eq!(
+            render(ChecklistFormat::SongsCsv),
+            format!(
+                "song_id,title,composer,copies\n{},Gloria,Vivaldi,2\n{},Stars | *Night*,,1\n",
+                gloria.id, stars.id
+            )
+        );
+        assert_eq!(
+            render(ChecklistFormat::BindersCsv),
+            format!(
+                "binder_number,binder_label,voice_part,song_id,title,composer,added\n\
+                 2,Altos,A,{gloria},Gloria,Vivaldi,\n\
+                 2,Altos,A,{stars},Stars | *Night*,,\n\
+                 3,,,{gloria},Gloria,Vivaldi,\n",
+                gloria = gloria.id,
+                stars = stars.id
+            )
+        );
+
+        // Copies on the print queue leave the totals but stay on the lists.
+        library
+            .set_print_stage(altos.id, gloria.id, PrintStage::Printed, "2024-05-01 19:30")
+            .unwrap();
+        library
+            .set_print_stage(altos.id, stars.id, PrintStage::Inserted, "2024-05-01 19:30")
+            .unwrap();
+        let report = to_print_report(&*library).unwrap().unwrap();
+        let copies: Vec<_> = report
+            .songs
+            .iter()
+            .map(|demand| (demand.song.id, demand.needed))
+            .collect();
+        assert_eq!(copies, vec![(gloria.id, 1)]);
+        assert!(render_checklist(&report, &title, ChecklistFormat::Markdown)
+            .unwrap()
+            .contains(
+                "- [ ] Gloria – Vivaldi (printed)\n\
+                 - [ ] Stars \\| \\*Night\\* (inserted)\n"
+            ));
+
+        // A complete library still gets a checklist saying so.
+        library.add_song_to_binder(altos.id, gloria.id).unwrap();
+        library.add_song_to_binder(altos.id, stars.id).unwrap();
+        library.add_song_to_binder(tenors.id, gloria.id).unwrap();
+        let report = to_print_report(&*library).unwrap().unwrap();
+        assert!(render_checklist(&report, &title, ChecklistFormat::Markdown)
+            .unwrap()
+            .ends_with("Every binder is complete; there is nothing to print.\n"));
+        assert_eq!(
+            render_checklist(&report, &title, ChecklistFormat::SongsCsv).unwrap(),
+            "song_id,title,composer,copies\n"
+        );
+    }
+}
+
+/// Save a PDF of `pages` blank pages that take their size from the page tree.
+/// Shared with the command line and TUI print job tests.
+pub(crate) fn write_blank_pdf(path: &Path, pages: usize) {
+    let mut document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let kids: Vec<Object> = (0..pages)
+        .map(|_| {
+            document
+                .add_object(dictionary! { "Type" => "Page", "Parent" => pages_id })
+                .into()
+        })
+        .collect();
+    document.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => kids,
+            "Count" => pages as i64,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        }),
+    );
+    let catalog_id = document.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    document.trailer.set("Root", catalog_id);
+    document.save(path).unwrap();
+}
+
+#[test]
+fn print_jobs_repeat_local_pdf_scores() {
+    let dir = tempfile::tempdir().unwrap();
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+    let scores = dir.path().join("My Scores");
+    std::fs::create_dir(&scores).unwrap();
+    write_blank_pdf(&scores.join("ave.pdf"), 2);
+    write_blank_pdf(&scores.join("gloria.pdf"), 3);
+    std::fs::write(scores.join("notes.txt"), "not a score").unwrap();
+    let linked = format!(
+        "file://{}",
+        scores
+            .join("gloria.pdf")
+            .display()
+            .to_string()
+            .replace(' ', "%20")
+    );
+
+    for mut library in both_backends() {
+        let director = library.create_binder(0, "Director").unwrap();
+        let altos = library.create_binder(1, "Altos").unwrap();
+        library.create_binder(2, "Tenors").unwrap();
+        let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+        let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+        let cantique = library.create_song("Cantique", "Fauré").unwrap();
+        for song in [&ave, &gloria, &cantique] {
+            library.add_song_to_binder(director.id, song.id).unwrap();
+        }
+        library.add_song_to_binder(altos.id, cantique.id).unwrap();
+        attach_file(
+            library.as_mut(),
+            &store,
+            ave.id,
+            &scores.join("ave.pdf"),
+            "2024-05-01 10:00",
+        )
+        .unwrap();
+        library
+            .add_song_link(gloria.id, LinkKind::Recording, "", "", &linked)
+            .unwrap();
+        library
+            .add_song_link(
+                gloria.id,
+                LinkKind::Score,
+                "",
+                "",
+                "https://example.org/gloria.pdf",
+            )
+            .unwrap();
+        library
+            .add_song_link(
+                cantique.id,
+                LinkKind::Score,
+                "",
+                "",
+                "/no/such/cantique.pdf",
+            )
+            .unwrap();
+        let report = to_print_report(&*library).unwrap().unwrap();
+
+        // Only score links count, and web links never do.
+        let job = plan_print_job(library.as_ref(), Some(&store), &report.songs).unwrap();
+        let planned: Vec<_> = job
+            .entries
+            .iter()
+            .map(|entry| (entry.song.title.as_str(), entry.copies))
+            .collect();
+        assert_eq!(planned, vec![("Ave Verum", 2)]);
+        assert_eq!(
+            job.entries[0].path,
+            store.path_of(&library.fetch_attachments().unwrap()[0])
+        );
+        let missing: Vec<_> = job
+            .missing
+            .iter()
+            .map(|demand| demand.song.title.as_str())
+            .collect();
+        assert_eq!(missing, vec!["Gloria", "Cantique"]);
+
+        library
+            .add_song_link(gloria.id, LinkKind::Score, "", "", &linked)
+            .unwrap();
+        let job = plan_print_job(library.as_ref(), Some(&store), &report.songs).unwrap();
+        assert_eq!(job.entries[1].path, scores.join("gloria.pdf"));
+        assert_eq!(job.missing.len(), 1);
+
+        let output = dir.path().join("print-job.pdf");
+        let summary = write_print_job(&job, &checklist_title(None), &output).unwrap();
+        assert_eq!(
+            summary,
+            PrintJobSummary {
+                copies: 4,
+                pages: 2 * 2 + 2 * 3,
+                cover_pages: 1,
+            }
+        );
+        let printed = Document::load(&output).unwrap();
+        let pages = printed.get_pages();
+        assert_eq!(pages.len(), 11);
+        // Pages keep the size they inherited from their own page tree.
+        let last = printed.get_dictionary(pages[&11]).unwrap();
+        assert!(last.get(b"MediaBox").is_ok());
+        let cover = Content::decode(&printed.get_page_content(pages[&1])).unwrap();
+        let lines: Vec<&[u8]> = cover
+            .operations
+            .iter()
+            .filter(|operation| operation.operator == "Tj")
+            .map(|operation| operation.operands[0].as_str().unwrap())
+            .collect();
+        assert_eq!(
+            lines[0],
+            b"To Print \x96 compared with the director's binder"
+        );
+        assert_eq!(lines[1], b"4 copies of 2 songs.");
+        assert_eq!(
+            lines[3],
+            b"2 \xd7 Ave Verum \x96 Mozart  (2 pages, ave.pdf)"
+        );
+        assert_eq!(
+            lines[6..],
+            [
+                &b"Not included, no PDF score found:"[..],
+                b"1 \xd7 Cantique \x96 Faur\xe9"
+            ]
+        );
+
+        // Without the attachment store only linked scores can be found.
+        let unattached = plan_print_job(library.as_ref(), None, &report.songs).unwrap();
+        assert_eq!(unattached.entries.len(), 1);
+        assert_eq!(unattached.entries[0].song.id, gloria.id);
+        let nothing = plan_print_job(library.as_ref(), None, &report.songs[2..]).unwrap();
+        assert!(matches!(
+            write_print_job(&nothing, "To Print", &output),
+            Err(PrintJobError::NoScores)
+        ));
+    }
+}
+
+#[test]
+fn print_job_copies_share_the_score_objects() {
+    let dir = tempfile::tempdir().unwrap();
+    let score = dir.path().join("gloria.pdf");
+    let mut document = Document::with_version("1.5");
+    let pages_id = document.new_object_id();
+    let font = document.add_object(dictionary! {
+        "Type" => "Font",
+        "Subtype" => "Type1",
+        "BaseFont" => "Helvetica",
+    });
+    let resources = document.add_object(dictionary! {
+        "Font" => dictionary! { "F1" => font },
+    });
+    let contents = document.add_object(Stream::new(dictionary! {}, b"BT ET".to_vec()));
+    let page = document.add_object(dictionary! {
+        "Type" => "Page",
+        "Parent" => pages_id,
+        "Contents" => contents,
+    });
+    document.objects.insert(
+        pages_id,
+        Object::Dictionary(dictionary! {
+            "Type" => "Pages",
+            "Kids" => vec![page.into()],
+            "Count" => 1,
+            "Resources" => resources,
+            "MediaBox" => vec![0.into(), 0.into(), 612.into(), 792.into()],
+        }),
+    );
+    let catalog_id = document.add_object(dictionary! {
+        "Type" => "Catalog",
+        "Pages" => pages_id,
+    });
+    document.trailer.set("Root", catalog_id);
+    document.save(&score).unwrap();
+
+    let mut library = MemoryLibrary::new();
+    let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+    let print = |copies: usize| {
+        let job = PrintJob {
+            entries: vec![PrintJobEntry {
+                song: gloria.clone(),
+                copies,
+                path: score.clone(),
+                file_name: "gloria.pdf".to_string(),
+            }],
+            missing: Vec::new(),
+        };
+        let output = dir.path().join(format!("print-job-{copies}.pdf"));
+        write_print_job(&job, "To Print", &output).unwrap();
+        Document::load(&output).unwrap()
+    };
+    let streams = |document: &Document| {
+        document
+            .objects
+            .values()
+            .filter(|object| object.as_stream().is_ok())
+            .count()
+    };
+
+    let printed = print(3);
+    let pages = printed.get_pages();
+    assert_eq!(pages.len(), 4);
+    let copies: Vec<_> = (2..=4)
+        .map(|number| printed.get_dictionary(pages[&number]).unwrap())
+        .collect();
+    for key in [&b"Contents"[..], b"Resources"] {
+        let shared = copies[0].get(key).unwrap().as_reference().unwrap();
+        assert!(copies
+            .iter()
+            .all(|page| page.get(key).unwrap().as_reference().unwrap() == shared));
+    }
+    // More copies add pages, not streams.
+    assert_eq!(streams(&printed), streams(&print(1)));
+}
@@ -0,0 +1,148 @@
+use std::collections::{HashMap, HashSet};
+
+use serde::Serialize;
+
+use crate::db::{DbError, DbResult, Entity, Library};
+use crate::models::{Binder, PrintStage, Song};
+
+/// What the binders are compared against.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToPrintTarget {
+    /// The director's binder (number 0); every other binder should match it.
+    Director,
+    /// A concert's setlist; every binder, the director's included, should
+    /// hold each song on it.
+    Concert(i64),
+}
+
+/// Songs each binder is missing compared to the director's binder (number 0)
+/// or a concert's setlist, plus the per-song copy totals the volunteer at the
+/// copier needs.
+#[derive(Debug, Clone, Serialize)]
+pub struct ToPrintReport {
+    /// Binders with at least one missing song, in binder order.
+    pub binders: Vec<BinderShortfall>,
+    /// One entry per song with copies still to print, in the order the songs
+    /// first come up while walking the binders. Copies already printed or
+    /// inserted (see the print queue) are not counted.
+    pub songs: Vec<SongDemand>,
+}
+
+/// A binder and the required songs it does not contain yet, leaving out songs
+/// its voice part does not sing.
+#[derive(Debug, Clone, Serialize)]
+pub struct BinderShortfall {
+    pub binder: Binder,
+    pub missing: Vec<MissingSong>,
+}
+
+/// A song missing from a binder, with how far its copy has got.
+#[derive(Debug, Clone, Serialize)]
+pub struct MissingSong {
+    #[serde(flatten)]
+    pub song: Song,
+    pub stage: PrintStage,
+}
+
+/// How many binders still need a copy of a given song printed.
+#[derive(Debug, Clone, Serialize)]
+pub struct SongDemand {
+    pub song: Song,
+    pub needed: usize,
+}
+
+/// Compare every binder against the director's binder. Returns `None` when no
+/// binder numbered 0 exists, since there is nothing to compare against.
+pub fn to_print_report(library: &dyn Library) -> DbResult<Option<ToPrintReport>> {
+    to_print_report_for(library, ToPrintTarget::Director)
+}
+
+/// Compare the binders against `target`. A song only counts as missing from
+/// a binder whose voice part needs it (see [`Song::needed_by`]), and each
+/// missing song carries its stage from the print queue. Returns
+/// `None` for [`ToPrintTarget::Director`] without a director's binder, and
+/// fails with `NotFound(Concert)` for a concert that does not exist.
+pub fn to_print_report_for(
+    library: &dyn Library,
+    target: ToPrintTarget,
+) -> DbResult<Option<ToPrintReport>> {
+    let binders = library.fetch_binders()?;
+    let (required, skip) = match target {
+        ToPrintTarget::Director => {
+            let Some(director) = binders.iter().find(|binder| binder.number == 0) else {
+                return Ok(None);
+            };
+            (
+                library.fetch_songs_for_binder(director.id)?,
+                Some(director.id),
+            )
+        }
+        ToPrintTarget::Concert(concert_id) => {
+            if !library
+                .fetch_concerts()?
+                .iter()
+                .any(|concert| concert.id == concert_id)
+            {
+                return Err(DbError::NotFound(Entity::Concert));
+            }
+            (library.fetch_setlist(concert_id)?, None)
+        }
+    };
+
+    let stages: HashMap<(i64, i64), PrintStage> = library
+        .fetch_print_queue()?
+        .into_iter()
+        .map(|item| ((item.binder_id, item.song_id), item.stage))
+        .collect();
+    let mut report = ToPrintReport {
+        binders: Vec::new(),
+        songs: Vec::new(),
+    };
+
+    for binder in binders.iter().filter(|binder| Some(binder.id) != skip) {
+        let song_ids: HashSet<i64> = library
+            .fetch_songs_for_binder(binder.id)?
+            .iter()
+            .map(|song| song.id)
+            .collect();
+
+        let missing: Vec<MissingSong> = required
+            .iter()
+            .filter(|song| !song_ids.contains(&song.id) && song.needed_by(&binder.voice_part))
+            .map(|song| MissingSong {
+                song: song.clone(),
+                stage: stages
+                    .get(&(binder.id, song.id))
+                    .copied()
+                    .unwrap_or(PrintStage::Needed),
+            })
+            .collect();
+
+        for MissingSong { song, stage } in &missing {
+            if *stage != PrintStage::Needed {
+                continue;
+            }
+            if let Some(entry) = report
+                .songs
+                .iter_mut()
+                .find(|entry| entry.song.id == song.id)
+            {
+                entry.needed += 1;
+            } else {
+                report.songs.push(SongDemand {
+                    song: song.clone(),
+                    needed: 1,
+                });
+            }
+        }
+
+        if !missing.is_empty() {
+            report.binders.push(BinderShortfall {
+                binder: binder.clone(),
+                missing,
+            });
+        }
+    }
+
+    Ok(Some(report))
+}
@@ -0,0 +1,259 @@
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::db::{song_links, DbError, DbResult, Library};
+
+/// Why an export could not be written.
+#[derive(Debug, Error)]
+pub enum ExportError {
+    #[error("Could not write {}: {source}", path.display())]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Could not write CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error(transparent)]
+    Db(#[from] DbError),
+}
+
+/// The tables that can be exported.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExportKind {
+    /// The song catalog, one row per song.
+    Songs,
+    /// The binder list, one row per binder.
+    Binders,
+    /// Long-form binder contents, one row per binder and song.
+    Memberships,
+}
+
+impl ExportKind {
+    pub const ALL: [ExportKind; 3] = [
+        ExportKind::Songs,
+        ExportKind::Binders,
+        ExportKind::Memberships,
+    ];
+
+    /// Name used on the command line and in file names.
+    pub fn name(self) -> &'static str {
+        match self {
+            ExportKind::Songs => "songs",
+            ExportKind::Binders => "binders",
+            ExportKind::Memberships => "memberships",
+        }
+    }
+
+    /// Inverse of [`ExportKind::name`].
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|kind| kind.name() == name)
+    }
+}
+
+/// A song in the catalog export. `link` is the song's first link and
+/// `binders` lists binder numbers separated by semicolons, so the file can be
+/// fed back into the CSV import.
+#[derive(Debug, Clone, Serialize)]
+pub struct SongExportRow {
+    pub id: i64,
+    pub title: String,
+    pub composer: String,
+    pub link: String,
+    pub binders: String,
+}
+
+impl SongExportRow {
+    const HEADERS: &'static [&'static str] = &["id", "title", "composer", "link", "binders"];
+}
+
+/// A binder in the binder list export.
+#[derive(Debug, Clone, Serialize)]
+pub struct BinderExportRow {
+    pub id: i64,
+    pub number: i64,
+    pub label: String,
+    pub songs: usize,
+}
+
+impl BinderExportRow {
+    const HEADERS: &'static [&'static str] = &["id", "number", "label", "songs"];
+}
+
+/// One song in one binder.
+#[derive(Debug, Clone, Serialize)]
+pub struct MembershipExportRow {
+    pub binder_number: i64,
+    pub binder_label: String,
+    pub song_id: i64,
+    pub title: String,
+    pub composer: String,
+}
+
+impl MembershipExportRow {
+    const HEADERS: &'static [&'static str] = &[
+        "binder_number",
+        "binder_label",
+        "song_id",
+        "title",
+        "composer",
+    ];
+}
+
+/// Rows of every export, gathered in one pass over the library.
+#[derive(Debug, Clone, Default)]
+pub struct ExportTables {
+    pub songs: Vec<SongExportRow>,
+    pub binders: Vec<BinderExportRow>,
+    pub memberships: Vec<MembershipExportRow>,
+}
+
+impl ExportTables {
+    /// Read the library. Songs keep the `fetch_all_songs` order; binders and
+    /// memberships follow binder numbers, then the binder's song order.
+    pub fn collect(library: &dyn Library) -> DbResult<Self> {
+        let mut tables = Self::default();
+        let mut numbers_by_song: HashMap<i64, Vec<i64>> = HashMap::new();
+
+        for binder in library.fetch_binders()? {
+            let songs = library.fetch_songs_for_binder(binder.id)?;
+            for song in &songs {
+                numbers_by_song
+                    .entry(song.id)
+                    .or_default()
+                    .push(binder.number);
+                tables.memberships.push(MembershipExportRow {
+                    binder_number: binder.number,
+                    binder_label: binder.label.clone(),
+                    song_id: song.id,
+                    title: song.title.clone(),
+                    composer: song.composer.clone(),
+                });
+            }
+            tables.binders.push(BinderExportRow {
+                id: binder.id,
+                number: binder.number,
+                label: binder.label,
+                songs: songs.len(),
+            });
+        }
+
+        let links = song_links(library)?;
+        for song in library.fetch_all_songs()? {
+            let binders = numbers_by_song
+                .get(&song.id)
+                .map(|numbers| {
+                    numbers
+                        .iter()
+                        .map(i64::to_string)
+                        .collect::<Vec<_>>()
+                        .join("; ")
+                })
+                .unwrap_or_default();
+            tables.songs.push(SongExportRow {
+                id: song.id,
+                title: song.title,
+                composer: song.composer,
+                link: links
+                    .get(&song.id)
+                    .and_then(|links| links.first())
+                    .map(|link| link.url.clone())
+                    .unwrap_or_default(),
+                binders,
+            });
+        }
+
+        Ok(tables)
+    }
+
+    /// Number of rows the given export holds.
+    pub fn len(&self, kind: ExportKind) -> usize {
+        match kind {
+            ExportKind::Songs => self.songs.len(),
+            ExportKind::Binders => self.binders.len(),
+            ExportKind::Memberships => self.memberships.len(),
+        }
+    }
+
+    /// Write one table as CSV with a header row.
+    pub fn write_csv(&self, kind: ExportKind, output: impl Write) -> Result<(), csv::Error> {
+        match kind {
+            ExportKind::Songs => write_rows(SongExportRow::HEADERS, &self.songs, output),
+            ExportKind::Binders => write_rows(BinderExportRow::HEADERS, &self.binders, output),
+            ExportKind::Memberships => {
+                write_rows(MembershipExportRow::HEADERS, &self.memberships, output)
+            }
+        }
+    }
+
+    /// Write one table as pretty-printed JSON.
+    pub fn write_json(&self, kind: ExportKind, output: impl Write) -> serde_json::Result<()> {
+        match kind {
+            ExportKind::Songs => serde_json::to_writer_pretty(output, &self.songs),
+            ExportKind::Binders => serde_json::to_writer_pretty(output, &self.binders),
+            ExportKind::Memberships => serde_json::to_writer_pretty(output, &self.memberships),
+        }
+    }
+}
+
+/// Write `headers` followed by `rows`. Headers are written by hand because
+/// `csv` only derives them from the first row, which leaves empty tables
+/// without a header line.
+fn write_rows<T: Serialize>(
+    headers: &[&str],
+    rows: &[T],
+    output: impl Write,
+) -> Result<(), csv::Error> {
+    let mut writer = csv::WriterBuilder::new()
+        .has_headers(false)
+        .from_writer(output);
+    writer.write_record(headers)?;
+    for row in rows {
+        writer.serialize(row)?;
+    }
+    writer.flush()?;
+    Ok(())
+}
+
+/// A file written by [`export_csv_files`].
+#[derive(Debug, Clone)]
+pub struct ExportedFile {
+    pub kind: ExportKind,
+    pub path: PathBuf,
+    pub rows: usize,
+}
+
+/// Write all three exports into `dir` (created if needed) as
+/// `<kind>-<stamp>.csv`, e.g. `songs-2024-05-01.csv`.
+pub fn export_csv_files(
+    library: &dyn Library,
+    dir: &Path,
+    stamp: &str,
+) -> Result<Vec<ExportedFile>, ExportError> {
+    let tables = ExportTables::collect(library)?;
+    fs::create_dir_all(dir).map_err(|source| ExportError::Io {
+        path: dir.to_path_buf(),
+        source,
+    })?;
+
+    let mut written = Vec::new();
+    for kind in ExportKind::ALL {
+        let path = dir.join(format!("{}-{stamp}.csv", kind.name()));
+        let file = File::create(&path).map_err(|source| ExportError::Io {
+            path: path.clone(),
+            source,
+        })?;
+        tables.write_csv(kind, file)?;
+        written.push(ExportedFile {
+            kind,
+            path,
+            rows: tables.len(kind),
+        });
+    }
+    Ok(written)
+}
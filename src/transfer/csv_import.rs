@@ -0,0 +1,372 @@
+use std::collections::{HashMap, HashSet};
+use std::fs::File;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::db::{DbError, DbResult, Library};
+use crate::models::{Binder, LinkKind};
+
+/// Header names recognized when a column is not mapped explicitly.
+const TITLE_HEADERS: &[&str] = &["title", "song", "name"];
+const COMPOSER_HEADERS: &[&str] = &["composer", "author"];
+const LINK_HEADERS: &[&str] = &["link", "url"];
+const BINDER_HEADERS: &[&str] = &["binders", "binder", "binder numbers"];
+
+/// Why a CSV file could not be turned into an [`ImportPlan`].
+#[derive(Debug, Error)]
+pub enum ImportError {
+    #[error("Could not open {}: {source}", path.display())]
+    Open {
+        path: PathBuf,
+        #[source]
+        source: std::io::Error,
+    },
+    #[error("Invalid CSV: {0}")]
+    Csv(#[from] csv::Error),
+    #[error("No column matches '{0}'.")]
+    UnknownColumn(String),
+    #[error("Could not find a title column; map one explicitly.")]
+    MissingTitleColumn,
+    #[error(transparent)]
+    Db(#[from] DbError),
+}
+
+/// Which CSV column feeds each song field. A column is named by its header
+/// (case-insensitive) or by its 1-based position; `None` falls back to the
+/// usual header names (`title`, `composer`, `link`/`url`, `binders`).
+#[derive(Debug, Clone, Default)]
+pub struct ColumnMapping {
+    pub title: Option<String>,
+    pub composer: Option<String>,
+    pub link: Option<String>,
+    pub binders: Option<String>,
+}
+
+/// Column indexes after the mapping has been matched against the header row.
+#[derive(Debug, Clone, Copy)]
+struct Columns {
+    title: usize,
+    composer: Option<usize>,
+    link: Option<usize>,
+    binders: Option<usize>,
+}
+
+/// Everything an import would do, computed without touching the library so it
+/// can be shown as a dry run first.
+#[derive(Debug, Clone, Default)]
+pub struct ImportPlan {
+    /// One entry per distinct title + composer, in file order.
+    pub songs: Vec<PlannedSong>,
+    /// Rows left out entirely, e.g. because the title is blank.
+    pub skipped: Vec<RowIssue>,
+    /// Problems that did not stop the row, such as unknown binder numbers.
+    pub warnings: Vec<RowIssue>,
+    /// Rows that repeated an earlier title + composer and were merged into it.
+    pub duplicate_rows: usize,
+}
+
+/// A song the import will create or reuse.
+#[derive(Debug, Clone)]
+pub struct PlannedSong {
+    /// CSV line the song first appeared on.
+    pub line: u64,
+    pub title: String,
+    pub composer: String,
+    /// Added as a typed link of the [`LinkKind::guess`]ed kind when the song
+    /// is new.
+    pub link: String,
+    /// Id of the library song with the same title and composer, if any.
+    pub existing_id: Option<i64>,
+    /// Binders the song still has to be added to.
+    pub binders: Vec<Binder>,
+}
+
+/// A note about one CSV line.
+#[derive(Debug, Clone, Serialize)]
+pub struct RowIssue {
+    pub line: u64,
+    pub message: String,
+}
+
+/// Counts describing an import, used for both the dry run and the result.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct ImportSummary {
+    pub new_songs: usize,
+    pub existing_songs: usize,
+    pub duplicate_rows: usize,
+    pub binder_assignments: usize,
+    pub skipped_rows: usize,
+    pub warnings: usize,
+}
+
+impl ImportSummary {
+    /// One-line description for status bars and the command line.
+    pub fn describe(&self) -> String {
+        let mut parts = vec![
+            plural(self.new_songs, "new song", "new songs"),
+            format!("{} already in the library", self.existing_songs),
+            plural(
+                self.binder_assignments,
+                "binder assignment",
+                "binder assignments",
+            ),
+        ];
+        if self.duplicate_rows > 0 {
+            parts.push(plural(
+                self.duplicate_rows,
+                "duplicate row merged",
+                "duplicate rows merged",
+            ));
+        }
+        if self.skipped_rows > 0 {
+            parts.push(plural(self.skipped_rows, "row skipped", "rows skipped"));
+        }
+        if self.warnings > 0 {
+            parts.push(plural(self.warnings, "warning", "warnings"));
+        }
+        parts.join(", ")
+    }
+}
+
+fn plural(count: usize, one: &str, many: &str) -> String {
+    if count == 1 {
+        format!("{count} {one}")
+    } else {
+        format!("{count} {many}")
+    }
+}
+
+impl ImportPlan {
+    /// What applying this plan will do.
+    pub fn summary(&self) -> ImportSummary {
+        let existing_songs = self
+            .songs
+            .iter()
+            .filter(|song| song.existing_id.is_some())
+            .count();
+        ImportSummary {
+            new_songs: self.songs.len() - existing_songs,
+            existing_songs,
+            duplicate_rows: self.duplicate_rows,
+            binder_assignments: self.songs.iter().map(|song| song.binders.len()).sum(),
+            skipped_rows: self.skipped.len(),
+            warnings: self.warnings.len(),
+        }
+    }
+
+    /// Whether applying the plan would change anything.
+    pub fn is_empty(&self) -> bool {
+        self.songs
+            .iter()
+            .all(|song| song.existing_id.is_some() && song.binders.is_empty())
+    }
+}
+
+/// Key used to recognize the same song across the file and the library.
+fn song_key(title: &str, composer: &str) -> (String, String) {
+    (title.trim().to_lowercase(), composer.trim().to_lowercase())
+}
+
+/// Read the CSV file at `path` and plan its import (see [`plan_import`]).
+pub fn plan_import_file(
+    library: &dyn Library,
+    path: &Path,
+    mapping: &ColumnMapping,
+) -> Result<ImportPlan, ImportError> {
+    let file = File::open(path).map_err(|source| ImportError::Open {
+        path: path.to_path_buf(),
+        source,
+    })?;
+    plan_import(library, file, mapping)
+}
+
+/// Match CSV rows against the library. Songs are recognized by title and
+/// composer, ignoring case and surrounding spaces; the binder column may list
+/// several binder numbers separated by commas, semicolons or spaces.
+pub fn plan_import(
+    library: &dyn Library,
+    input: impl Read,
+    mapping: &ColumnMapping,
+) -> Result<ImportPlan, ImportError> {
+    let mut reader = csv::ReaderBuilder::new()
+        .flexible(true)
+        .trim(csv::Trim::All)
+        .from_reader(input);
+    let headers: Vec<String> = reader
+        .headers()?
+        .iter()
+        .map(|header| header.trim_start_matches('\u{feff}').to_string())
+        .collect();
+    let columns = resolve_columns(&headers, mapping)?;
+
+    let binders_by_number: HashMap<i64, Binder> = library
+        .fetch_binders()?
+        .into_iter()
+        .map(|binder| (binder.number, binder))
+        .collect();
+    let existing: HashMap<(String, String), i64> = library
+        .fetch_all_songs()?
+        .into_iter()
+        .map(|song| (song_key(&song.title, &song.composer), song.id))
+        .collect();
+    let mut linked: HashMap<i64, HashSet<i64>> = HashMap::new();
+    if columns.binders.is_some() {
+        for binder in binders_by_number.values() {
+            let ids = library
+                .fetch_songs_for_binder(binder.id)?
+                .into_iter()
+                .map(|song| song.id)
+                .collect();
+            linked.insert(binder.id, ids);
+        }
+    }
+
+    let mut plan = ImportPlan::default();
+    let mut planned_index: HashMap<(String, String), usize> = HashMap::new();
+
+    for record in reader.records() {
+        let record = record?;
+        let line = record.position().map_or(0, |position| position.line());
+        let field = |index: Option<usize>| {
+            index
+                .and_then(|index| record.get(index))
+                .unwrap_or_default()
+                .to_string()
+        };
+
+        if record.iter().all(str::is_empty) {
+            continue;
+        }
+        let title = field(Some(columns.title));
+        if title.is_empty() {
+            plan.skipped.push(RowIssue {
+                line,
+                message: "Title is empty.".to_string(),
+            });
+            continue;
+        }
+        let composer = field(columns.composer);
+        let link = field(columns.link);
+
+        let mut targets = Vec::new();
+        for token in field(columns.binders)
+            .split([',', ';', ' ', '\t'])
+            .filter(|token| !token.is_empty())
+        {
+            match token.parse::<i64>() {
+                Ok(number) => match binders_by_number.get(&number) {
+                    Some(binder) => targets.push(binder.clone()),
+                    None => plan.warnings.push(RowIssue {
+                        line,
+                        message: format!("Binder {number:02} does not exist."),
+                    }),
+                },
+                Err(_) => plan.warnings.push(RowIssue {
+                    line,
+                    message: format!("'{token}' is not a binder number."),
+                }),
+            }
+        }
+
+        let key = song_key(&title, &composer);
+        let index = match planned_index.get(&key) {
+            Some(&index) => {
+                plan.duplicate_rows += 1;
+                index
+            }
+            None => {
+                plan.songs.push(PlannedSong {
+                    line,
+                    title,
+                    composer,
+                    link,
+                    existing_id: existing.get(&key).copied(),
+                    binders: Vec::new(),
+                });
+                planned_index.insert(key, plan.songs.len() - 1);
+                plan.songs.len() - 1
+            }
+        };
+
+        let song = &mut plan.songs[index];
+        for binder in targets {
+            let already_linked = song
+                .existing_id
+                .is_some_and(|id| linked.get(&binder.id).is_some_and(|ids| ids.contains(&id)));
+            let already_planned = song.binders.iter().any(|planned| planned.id == binder.id);
+            if !already_linked && !already_planned {
+                song.binders.push(binder);
+            }
+        }
+    }
+
+    Ok(plan)
+}
+
+/// Apply a plan in a single transaction: either every song and binder
+/// assignment lands, or none does.
+pub fn apply_import(library: &mut dyn Library, plan: &ImportPlan) -> DbResult<ImportSummary> {
+    library.transaction(&mut |library| {
+        for planned in &plan.songs {
+            let song_id = match planned.existing_id {
+                Some(id) => id,
+                None => {
+                    let song = library.create_song(&planned.title, &planned.composer)?;
+                    if !planned.link.is_empty() {
+                        let kind = LinkKind::guess(&planned.link);
+                        library.add_song_link(song.id, kind, "", "", &planned.link)?;
+                    }
+                    song.id
+                }
+            };
+            for binder in &planned.binders {
+                library.add_song_to_binder(binder.id, song_id)?;
+            }
+        }
+        Ok(())
+    })?;
+    Ok(plan.summary())
+}
+
+fn resolve_columns(headers: &[String], mapping: &ColumnMapping) -> Result<Columns, ImportError> {
+    let title = match &mapping.title {
+        Some(name) => find_column(headers, name)?,
+        None => detect_column(headers, TITLE_HEADERS).ok_or(ImportError::MissingTitleColumn)?,
+    };
+    let optional = |explicit: &Option<String>, aliases: &[&str]| match explicit {
+        Some(name) => find_column(headers, name).map(Some),
+        None => Ok(detect_column(headers, aliases)),
+    };
+    Ok(Columns {
+        title,
+        composer: optional(&mapping.composer, COMPOSER_HEADERS)?,
+        link: optional(&mapping.link, LINK_HEADERS)?,
+        binders: optional(&mapping.binders, BINDER_HEADERS)?,
+    })
+}
+
+/// Find a column by header name, or by 1-based position when `name` is a number.
+fn find_column(headers: &[String], name: &str) -> Result<usize, ImportError> {
+    let wanted = name.trim();
+    if let Some(index) = headers
+        .iter()
+        .position(|header| header.eq_ignore_ascii_case(wanted))
+    {
+        return Ok(index);
+    }
+    match wanted.parse::<usize>() {
+        Ok(position) if (1..=headers.len()).contains(&position) => Ok(position - 1),
+        _ => Err(ImportError::UnknownColumn(wanted.to_string())),
+    }
+}
+
+fn detect_column(headers: &[String], aliases: &[&str]) -> Option<usize> {
+    aliases.iter().find_map(|alias| {
+        headers
+            .iter()
+            .position(|header| header.trim().eq_ignore_ascii_case(alias))
+    })
+}
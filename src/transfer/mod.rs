@@ -0,0 +1,16 @@
+//! Moving songs and binders in and out of the library as spreadsheet (CSV)
+//! files.
+
+mod csv_export;
+mod csv_import;
+#[cfg(test)]
+mod tests;
+
+pub use csv_export::{
+    export_csv_files, BinderExportRow, ExportError, ExportKind, ExportTables, ExportedFile,
+    MembershipExportRow, SongExportRow,
+};
+pub use csv_import::{
+    apply_import, plan_import, plan_import_file, ColumnMapping, ImportError, ImportPlan,
+    ImportSummary, PlannedSong, RowIssue,
+};
@@ -0,0 +1,243 @@
+//! Import and export tests run against a [`MemoryLibrary`].
+
+use crate::db::{
+    ensure_schema_in_memory, DbError, DbResult, Library, MemoryLibrary, SqliteLibrary,
+};
+use crate::models::LinkKind;
+
+use super::{
+    apply_import, plan_import, ColumnMapping, ExportKind, ExportTables, ImportError, ImportSummary,
+};
+
+fn titles(library: &dyn Library) -> Vec<String> {
+    library
+        .fetch_all_songs()
+        .unwrap()
+        .into_iter()
+        .map(|song| song.display_title())
+        .collect()
+}
+
+#[test]
+fn import_detects_headers_and_assigns_binders() {
+    let mut library = MemoryLibrary::new();
+    let sopranos = library.create_binder(1, "Sopranos").unwrap();
+    let altos = library.create_binder(2, "Altos").unwrap();
+    let csv = "Title,Composer,URL,Binders\n\
+               Ave Verum,Mozart,https://example.org/ave.pdf,\"1, 2\"\n\
+               Cantique,Fauré,,2\n";
+
+    let plan = plan_import(&library, csv.as_bytes(), &ColumnMapping::default()).unwrap();
+    let summary = apply_import(&mut library, &plan).unwrap();
+
+    assert_eq!(
+        summary,
+        ImportSummary {
+            new_songs: 2,
+            binder_assignments: 3,
+            ..ImportSummary::default()
+        }
+    );
+    assert_eq!(
+        titles(&library),
+        vec!["Ave Verum - Mozart", "Cantique - Fauré"]
+    );
+    assert_eq!(
+        library.fetch_songs_for_binder(sopranos.id).unwrap().len(),
+        1
+    );
+    let alto_songs = library.fetch_songs_for_binder(altos.id).unwrap();
+    assert_eq!(alto_songs.len(), 2);
+    let links = library.fetch_song_links().unwrap();
+    assert_eq!(links.len(), 1);
+    assert_eq!(links[0].song_id, alto_songs[0].id);
+    assert_eq!(links[0].kind, LinkKind::Score);
+    assert_eq!(links[0].url, "https://example.org/ave.pdf");
+}
+
+#[test]
+fn explicit_mapping_by_name_or_position() {
+    let library = MemoryLibrary::new();
+    let csv = "Werk,Komponist\nGloria,Vivaldi\n";
+    let mapping = ColumnMapping {
+        title: Some("werk".to_string()),
+        composer: Some("2".to_string()),
+        ..ColumnMapping::default()
+    };
+
+    let plan = plan_import(&library, csv.as_bytes(), &mapping).unwrap();
+    assert_eq!(plan.songs[0].title, "Gloria");
+    assert_eq!(plan.songs[0].composer, "Vivaldi");
+
+    let err = plan_import(
+        &library,
+        "Werk\nGloria\n".as_bytes(),
+        &ColumnMapping::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, ImportError::MissingTitleColumn));
+    let mapping = ColumnMapping {
+        title: Some("Titel".to_string()),
+        ..ColumnMapping::default()
+    };
+    let err = plan_import(&library, "Werk\nGloria\n".as_bytes(), &mapping).unwrap_err();
+    assert_eq!(err.to_string(), "No column matches 'Titel'.");
+}
+
+#[test]
+fn existing_songs_and_duplicate_rows_are_matched_case_insensitively() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(3, "Tenors").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    library.add_song_to_binder(binder.id, ave.id).unwrap();
+    let csv = "title,composer,binder\n\
+               ave verum , MOZART,3\n\
+               Gloria,Vivaldi,3\n\
+               GLORIA,vivaldi,3\n";
+
+    let plan = plan_import(&library, csv.as_bytes(), &ColumnMapping::default()).unwrap();
+    let summary = plan.summary();
+
+    assert_eq!(summary.new_songs, 1);
+    assert_eq!(summary.existing_songs, 1);
+    assert_eq!(summary.duplicate_rows, 1);
+    // Ave Verum is already in binder 3, Gloria only needs linking once.
+    assert_eq!(summary.binder_assignments, 1);
+
+    apply_import(&mut library, &plan).unwrap();
+    assert_eq!(
+        titles(&library),
+        vec!["Ave Verum - Mozart", "Gloria - Vivaldi"]
+    );
+    assert_eq!(library.fetch_songs_for_binder(binder.id).unwrap().len(), 2);
+}
+
+#[test]
+fn blank_titles_are_skipped_and_unknown_binders_warned() {
+    let mut library = MemoryLibrary::new();
+    library.create_binder(1, "Sopranos").unwrap();
+    let csv = "title,binders\n,1\nGloria,1;9 x\n\n";
+
+    let plan = plan_import(&library, csv.as_bytes(), &ColumnMapping::default()).unwrap();
+
+    assert_eq!(plan.skipped.len(), 1);
+    assert_eq!(plan.skipped[0].line, 2);
+    let warnings: Vec<&str> = plan
+        .warnings
+        .iter()
+        .map(|issue| issue.message.as_str())
+        .collect();
+    assert_eq!(
+        warnings,
+        vec!["Binder 09 does not exist.", "'x' is not a binder number."]
+    );
+    assert_eq!(
+        plan.summary().describe(),
+        "1 new song, 0 already in the library, 1 binder assignment, 1 row skipped, 2 warnings"
+    );
+}
+
+#[test]
+fn dry_run_leaves_library_untouched() {
+    let library = MemoryLibrary::new();
+    let plan = plan_import(
+        &library,
+        "title\nGloria\n".as_bytes(),
+        &ColumnMapping::default(),
+    )
+    .unwrap();
+    assert_eq!(plan.summary().new_songs, 1);
+    assert!(library.fetch_all_songs().unwrap().is_empty());
+}
+
+#[test]
+fn transaction_rolls_back_on_failure() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(1, "Sopranos").unwrap();
+    let result: DbResult<()> = library.transaction(&mut |library| {
+        let song = library.create_song("Gloria", "Vivaldi")?;
+        library.add_song_to_binder(binder.id, song.id)?;
+        library.add_song_to_binder(999, song.id)
+    });
+
+    assert!(matches!(result, Err(DbError::ForeignKey)));
+    assert!(library.fetch_all_songs().unwrap().is_empty());
+    assert!(library
+        .fetch_songs_for_binder(binder.id)
+        .unwrap()
+        .is_empty());
+}
+
+#[test]
+fn sqlite_transaction_rolls_back_on_failure() {
+    let mut library = SqliteLibrary::new(ensure_schema_in_memory().unwrap());
+    let result: DbResult<()> = library.transaction(&mut |library| {
+        library.create_song("Gloria", "Vivaldi")?;
+        library.add_song_to_binder(999, 1)
+    });
+
+    assert!(matches!(result, Err(DbError::ForeignKey)));
+    assert!(library.fetch_all_songs().unwrap().is_empty());
+}
+
+#[test]
+fn export_tables_cover_songs_binders_and_memberships() {
+    let mut library = MemoryLibrary::new();
+    let altos = library.create_binder(2, "Altos").unwrap();
+    library.create_binder(1, "Sopranos").unwrap();
+    let gloria = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.create_song("Ave Verum", "Mozart, W. A.").unwrap();
+    library.add_song_to_binder(altos.id, gloria.id).unwrap();
+    for (kind, url) in [
+        (LinkKind::Recording, "https://example.org/gloria.mp3"),
+        (LinkKind::Score, "gloria.pdf"),
+    ] {
+        library.add_song_link(gloria.id, kind, "", "", url).unwrap();
+    }
+
+    let tables = ExportTables::collect(&library).unwrap();
+    let csv_of = |kind| {
+        let mut out = Vec::new();
+        tables.write_csv(kind, &mut out).unwrap();
+        String::from_utf8(out).unwrap()
+    };
+
+    assert_eq!(
+        csv_of(ExportKind::Songs),
+        "id,title,composer,link,binders\n2,Ave Verum,\"Mozart, W. A.\",,\n1,Gloria,Vivaldi,https://example.org/gloria.mp3,2\n"
+    );
+    assert_eq!(
+        csv_of(ExportKind::Binders),
+        "id,number,label,songs\n2,1,Sopranos,0\n1,2,Altos,1\n"
+    );
+    assert_eq!(
+        csv_of(ExportKind::Memberships),
+        "binder_number,binder_label,song_id,title,composer\n2,Altos,1,Gloria,Vivaldi\n"
+    );
+}
+
+#[test]
+fn exported_songs_import_back_without_duplicates() {
+    let mut library = MemoryLibrary::new();
+    let binder = library.create_binder(4, "Basses").unwrap();
+    let song = library.create_song("Gloria", "Vivaldi").unwrap();
+    library.add_song_to_binder(binder.id, song.id).unwrap();
+
+    let mut out = Vec::new();
+    ExportTables::collect(&library)
+        .unwrap()
+        .write_csv(ExportKind::Songs, &mut out)
+        .unwrap();
+    let plan = plan_import(&library, out.as_slice(), &ColumnMapping::default()).unwrap();
+
+    assert!(plan.is_empty());
+    assert_eq!(plan.summary().existing_songs, 1);
+}
+
+#[test]
+fn empty_export_still_has_headers() {
+    let tables = ExportTables::collect(&MemoryLibrary::new()).unwrap();
+    let mut out = Vec::new();
+    tables.write_csv(ExportKind::Binders, &mut out).unwrap();
+    assert_eq!(String::from_utf8(out).unwrap(), "id,number,label,songs\n");
+}
@@ -1,32 +1,50 @@
 use std::cmp::min;
-use std::collections::HashSet;
-use std::mem;
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::{fs, io, mem};
 
 use anyhow::Result;
+use chrono::{Local, NaiveDateTime};
 use crossterm::event::KeyCode;
-use open::that as open_link;
 use ratatui::layout::{Alignment, Constraint, Direction, Layout, Rect};
 use ratatui::prelude::*;
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Block, Borders, Clear, List, ListItem, ListState, Paragraph, Wrap};
 use ratatui::Frame;
-use rusqlite::Connection;
 
 use crate::db::{
-    add_song_to_binder, create_binder, create_song, delete_binder, delete_song, fetch_all_songs,
-    fetch_binders, fetch_composers, fetch_songs_for_binder, remove_song_from_binder, update_binder,
-    update_song,
+    attach_file, check_in_binder, check_out_binder, copy_director_order, move_song_in_binder,
+    move_song_in_setlist, open_checkouts, record_setlist_performances, set_song_tags,
+    song_attachments, song_links, song_tags, AttachmentStore, BackupInfo, BackupReason,
+    BackupStore, DbError, Entity, Library, DEFAULT_OVERDUE_DAYS, TIMESTAMP_FORMAT,
 };
-use crate::models::{Binder, Song};
+use crate::models::{
+    format_size, Attachment, Binder, BinderCheckout, Chorister, Concert, PerformanceStats,
+    PrintStage, Song, SongLink,
+};
+use crate::printing::{send_print_jobs, Printer};
+use crate::reports::{
+    all_binder_contents, binder_contents, checklist_title, contents_html, contents_text, days_out,
+    plan_print_job, render_checklist, to_print_report_for, write_print_job, ChecklistFormat,
+    PrintJob, PrintJobError, SongDemand, ToPrintTarget,
+};
+use crate::transfer::{apply_import, export_csv_files, plan_import_file};
 
 use super::forms::{
-    BinderField, BinderForm, ConfirmBinderDelete, ConfirmPrintChoice, ConfirmSongDelete,
-    ConfirmSongRemove, ConfirmToPrintExit, SongField, SongForm,
+    AttachForm, BinderField, BinderForm, ChoristerField, ChoristerForm, ConcertField, ConcertForm,
+    ConfirmBinderDelete, ConfirmPrintChoice, ConfirmSongDelete, ConfirmSongRemove,
+    ConfirmToPrintExit, CsvImportField, CsvImportForm, CsvImportPreview, ExportForm, LinkField,
+    LinkForm, PerformanceField, PerformanceForm, SongField, SongForm,
+};
+use super::helpers::{
+    attachment_summary, binder_card_title, build_binder_cover_lines, centered_rect, link_summary,
+    surface_error, tag_chips, tags_of,
 };
-use super::helpers::{build_binder_cover_lines, centered_rect, surface_error};
 use super::screens::{
-    AddSongItem, AddSongState, BinderReport, MissingSong, SongManagerScreen, SongNeeded,
-    SongScreen, ToPrintMode, ToPrintScreen,
+    AddSongItem, AddSongState, BackupScreen, BinderLog, BinderReport, ChoristerScreen,
+    ConcertScreen, HolderPicker, LinkPicker, LinkTarget, OverdueScreen, SetlistPicker,
+    SetlistScreen, SongHistory, SongManagerScreen, SongNeeded, SongScreen, ToPrintMode,
+    ToPrintScreen,
 };
 
 /// Number of binder cards shown in each row of the main grid. Four columns are
@@ -34,8 +52,14 @@ use super::screens::{
 const GRID_COLUMNS: usize = 4;
 /// Footer space reserved for status messages and instructions.
 const FOOTER_HEIGHT: u16 = 3;
+/// Subfolder of the data directory that CSV exports default to.
+const EXPORT_DIR_NAME: &str = "exports";
+/// Rows taken by the inline search bar at the top of song lists.
+const SEARCH_BAR_HEIGHT: u16 = 3;
 /// Height allocation per song card in list-style views.
-const SONG_CARD_HEIGHT: u16 = 5;
+const SONG_CARD_HEIGHT: u16 = 6;
+/// How far `+`/`-` move the overdue period on the overdue report.
+const OVERDUE_STEP_DAYS: u32 = 7;
 /// ASCII textures used to decorate binder covers. We rotate through the list so
 /// large collections feel more playful without needing color support.
 const BINDER_ART: &[&[&str]] = &[
@@ -68,6 +92,11 @@ enum Screen {
     Songs(SongScreen),
     SongManager(SongManagerScreen),
     ToPrint(ToPrintScreen),
+    Backups(BackupScreen),
+    Concerts(ConcertScreen),
+    Setlist(SetlistScreen),
+    Choristers(ChoristerScreen),
+    Overdue(OverdueScreen),
 }
 
 /// Fine-grained modes scoped to the current screen.
@@ -92,6 +121,41 @@ enum Mode {
     },
     ConfirmToPrintExit(ConfirmToPrintExit),
     Searching(SearchState),
+    ImportingCsv(CsvImportForm),
+    ExportingCsv(ExportForm),
+    ConfirmCsvImport(CsvImportPreview),
+    ConfirmBackupRestore(BackupInfo),
+    ConfirmSendToPrinter(PrintJob),
+    ConfirmCopyOrder,
+    AddingConcert(ConcertForm),
+    EditingConcert {
+        id: i64,
+        form: ConcertForm,
+    },
+    ConfirmConcertDelete(Concert),
+    PickingSetlistSongs(SetlistPicker),
+    SongHistory(SongHistory),
+    AddingPerformance {
+        history: SongHistory,
+        form: PerformanceForm,
+    },
+    AddingChorister(ChoristerForm),
+    EditingChorister {
+        id: i64,
+        form: ChoristerForm,
+    },
+    ConfirmChoristerDelete(Chorister),
+    AssigningBinder(HolderPicker),
+    BinderLog(BinderLog),
+    PickingLink(LinkPicker),
+    AddingLink {
+        picker: LinkPicker,
+        form: LinkForm,
+    },
+    AttachingFile {
+        picker: LinkPicker,
+        form: AttachForm,
+    },
 }
 
 /// Which screen the search is targeting.
@@ -127,9 +191,14 @@ impl StatusKind {
     }
 }
 
+/// Hand a URL or file path to the desktop's default viewer.
+fn open_in_viewer(target: &str) -> io::Result<()> {
+    open::that(target)
+}
+
 /// Central application state shared across the TUI.
 pub struct App {
-    conn: Connection,
+    library: Box<dyn Library>,
     binders: Vec<Binder>,
     selected: usize,
     composers: Vec<String>,
@@ -137,22 +206,139 @@ pub struct App {
     mode: Mode,
     status: Option<StatusMessage>,
     saved_search: Option<SearchState>,
+    /// Folder holding the library file; exports default to a subfolder.
+    data_dir: Option<PathBuf>,
+    /// Rotating backups taken before destructive actions, when enabled.
+    backups: Option<BackupStore>,
+    /// Last performance and count per song, shown on song cards.
+    performance_stats: HashMap<i64, PerformanceStats>,
+    /// Tag names per song, shown as chips on song cards.
+    song_tags: HashMap<i64, Vec<String>>,
+    /// Typed links per song, listed on song cards.
+    song_links: HashMap<i64, Vec<SongLink>>,
+    /// Folder of attached files, when the app runs over a library file.
+    attachments: Option<AttachmentStore>,
+    /// Attached files per song, listed on song cards.
+    song_attachments: HashMap<i64, Vec<Attachment>>,
+    /// Where To Print sends scores, when printing is set up.
+    printer: Option<Printer>,
+    /// Choir members, used to name binder holders on the grid.
+    choristers: Vec<Chorister>,
+    /// Open checkout of every binder that is out, keyed by binder id.
+    checkouts: HashMap<i64, BinderCheckout>,
+    /// Days a binder may stay out before it counts as overdue.
+    overdue_days: u32,
+    /// Opens links and generated documents; tests swap in a stub.
+    opener: fn(&str) -> io::Result<()>,
 }
 
 impl App {
-    pub fn new(conn: Connection, binders: Vec<Binder>, composers: Vec<String>) -> Self {
+    /// Build the app over any [`Library`] backend. `binders` and `composers`
+    /// seed the initial grid and auto-complete so the first frame can render
+    /// without another round-trip. Binder holders and checkouts are loaded
+    /// here; if that fails the grid just shows no names or statuses and the
+    /// status bar says why.
+    pub fn new(library: Box<dyn Library>, binders: Vec<Binder>, composers: Vec<String>) -> Self {
+        let loaded = library
+            .fetch_choristers()
+            .and_then(|choristers| Ok((choristers, open_checkouts(library.as_ref())?)));
+        let ((choristers, checkouts), status) = match loaded {
+            Ok(loaded) => (loaded, None),
+            Err(err) => (
+                Default::default(),
+                Some(StatusMessage {
+                    text: format!("Could not load binder holders: {err}"),
+                    kind: StatusKind::Error,
+                }),
+            ),
+        };
         Self {
-            conn,
+            library,
             binders,
             selected: 0,
             composers,
             screen: Screen::Binders,
             mode: Mode::Normal,
-            status: None,
+            status,
             saved_search: None,
+            data_dir: None,
+            backups: None,
+            performance_stats: HashMap::new(),
+            song_tags: HashMap::new(),
+            song_links: HashMap::new(),
+            attachments: None,
+            song_attachments: HashMap::new(),
+            printer: None,
+            choristers,
+            checkouts,
+            overdue_days: DEFAULT_OVERDUE_DAYS,
+            opener: open_in_viewer,
+        }
+    }
+
+    /// Tell the app where the library file lives so exports can default to
+    /// `<dir>/exports`.
+    pub fn set_data_dir(&mut self, dir: PathBuf) {
+        self.data_dir = Some(dir);
+    }
+
+    /// Set how many days a binder may stay out before the overdue report
+    /// lists it.
+    pub fn set_overdue_days(&mut self, days: u32) {
+        self.overdue_days = days;
+    }
+
+    /// Replace how links and documents are opened, so tests never start a
+    /// viewer.
+    #[cfg(test)]
+    pub(crate) fn set_opener(&mut self, opener: fn(&str) -> io::Result<()>) {
+        self.opener = opener;
+    }
+
+    /// Keep attached files in `store`, letting the link picker attach and open
+    /// them.
+    pub fn set_attachments(&mut self, store: AttachmentStore) {
+        self.attachments = Some(store);
+    }
+
+    /// Let To Print send scores straight to `printer`.
+    pub fn set_printer(&mut self, printer: Printer) {
+        self.printer = Some(printer);
+    }
+
+    /// Turn on rotating backups and take the startup backup. An empty library
+    /// is not backed up; a failed backup is reported in the status bar rather
+    /// than keeping the app from starting.
+    pub fn enable_backups(&mut self, store: BackupStore) {
+        let has_data = !self.binders.is_empty()
+            || self
+                .library
+                .fetch_all_songs()
+                .is_ok_and(|songs| !songs.is_empty());
+        let result = if has_data {
+            store.create(self.library.as_ref(), BackupReason::Startup)
+        } else {
+            Ok(None)
+        };
+        self.backups = Some(store);
+        if let Err(err) = result {
+            self.set_status(format!("Startup backup failed: {err}"), StatusKind::Error);
         }
     }
 
+    /// Read-only access to the backing library, e.g. for tests asserting on
+    /// persisted state.
+    pub fn library(&self) -> &dyn Library {
+        self.library.as_ref()
+    }
+
+    /// Change the backing library behind the app's back, e.g. to test how a
+    /// screen copes with data that went away.
+    #[cfg(test)]
+    pub(crate) fn library_mut(&mut self) -> &mut dyn Library {
+        self.library.as_mut()
+    }
+
     pub fn handle_key(&mut self, code: KeyCode) -> Result<bool> {
         let mut exit = false;
         let mut mode = mem::replace(&mut self.mode, Mode::Normal);
@@ -175,6 +361,36 @@ impl App {
                 self.handle_confirm_to_print_exit(code, confirm, &mut exit)?
             }
             Mode::Searching(state) => self.handle_search(code, state)?,
+            Mode::ImportingCsv(form) => self.handle_csv_import(code, form)?,
+            Mode::ExportingCsv(form) => self.handle_csv_export(code, form)?,
+            Mode::ConfirmCsvImport(preview) => self.handle_confirm_csv_import(code, preview)?,
+            Mode::ConfirmBackupRestore(backup) => {
+                self.handle_confirm_backup_restore(code, backup)?
+            }
+            Mode::ConfirmSendToPrinter(job) => self.handle_confirm_send_to_printer(code, job)?,
+            Mode::ConfirmCopyOrder => self.handle_confirm_copy_order(code)?,
+            Mode::AddingConcert(form) => self.handle_concert_form(code, None, form)?,
+            Mode::EditingConcert { id, form } => self.handle_concert_form(code, Some(id), form)?,
+            Mode::ConfirmConcertDelete(concert) => {
+                self.handle_confirm_concert_delete(code, concert)?
+            }
+            Mode::PickingSetlistSongs(picker) => self.handle_pick_setlist_songs(code, picker)?,
+            Mode::SongHistory(history) => self.handle_song_history(code, history)?,
+            Mode::AddingPerformance { history, form } => {
+                self.handle_performance_form(code, history, form)?
+            }
+            Mode::AddingChorister(form) => self.handle_chorister_form(code, None, form)?,
+            Mode::EditingChorister { id, form } => {
+                self.handle_chorister_form(code, Some(id), form)?
+            }
+            Mode::ConfirmChoristerDelete(chorister) => {
+                self.handle_confirm_chorister_delete(code, chorister)?
+            }
+            Mode::AssigningBinder(picker) => self.handle_assign_binder(code, picker)?,
+            Mode::BinderLog(log) => self.handle_binder_log(code, log)?,
+            Mode::PickingLink(picker) => self.handle_link_picker(code, picker)?,
+            Mode::AddingLink { picker, form } => self.handle_link_form(code, picker, form)?,
+            Mode::AttachingFile { picker, form } => self.handle_attach_form(code, picker, form)?,
         };
 
         self.mode = mode;
@@ -207,6 +423,68 @@ impl App {
                         self.clear_status();
                         self.open_to_print_view()?;
                     }
+                    KeyCode::Char('b') | KeyCode::Char('B') => {
+                        self.clear_status();
+                        self.open_backups()?;
+                    }
+                    KeyCode::Char('c') | KeyCode::Char('C') => {
+                        self.clear_status();
+                        self.open_concerts(None)?;
+                    }
+                    KeyCode::Char('m') | KeyCode::Char('M') => {
+                        self.clear_status();
+                        self.open_choristers(None)?;
+                    }
+                    KeyCode::Char('o') | KeyCode::Char('O') => {
+                        if let Some(binder) = self.current_binder().cloned() {
+                            self.check_out(&binder)?;
+                        } else {
+                            self.set_status("No binder selected.", StatusKind::Error);
+                        }
+                    }
+                    KeyCode::Char('i') | KeyCode::Char('I') => {
+                        if let Some(binder) = self.current_binder().cloned() {
+                            self.check_in(&binder)?;
+                        } else {
+                            self.set_status("No binder selected.", StatusKind::Error);
+                        }
+                    }
+                    KeyCode::Char('h') | KeyCode::Char('H') => {
+                        if let Some(binder) = self.current_binder().cloned() {
+                            self.clear_status();
+                            let log = BinderLog::load(self.library.as_ref(), binder)?;
+                            return Ok(Mode::BinderLog(log));
+                        }
+                        self.set_status("No binder selected.", StatusKind::Error);
+                    }
+                    KeyCode::Char('d') | KeyCode::Char('D') => {
+                        self.clear_status();
+                        self.open_overdue()?;
+                    }
+                    KeyCode::Char('t') => {
+                        if let Some(binder) = self.current_binder().cloned() {
+                            self.print_binder_index(Some(&binder))?;
+                        } else {
+                            self.set_status("No binder selected.", StatusKind::Error);
+                        }
+                    }
+                    KeyCode::Char('T') => self.print_binder_index(None)?,
+                    KeyCode::Char('a') | KeyCode::Char('A') => {
+                        if let Some(binder) = self.current_binder().cloned() {
+                            if self.choristers.is_empty() {
+                                self.set_status(
+                                    "No choristers yet. Press m to add them.",
+                                    StatusKind::Error,
+                                );
+                            } else {
+                                self.clear_status();
+                                let picker = HolderPicker::new(binder, self.choristers.clone());
+                                return Ok(Mode::AssigningBinder(picker));
+                            }
+                        } else {
+                            self.set_status("No binder selected.", StatusKind::Error);
+                        }
+                    }
                     KeyCode::Char('+') => {
                         self.clear_status();
                         let mut form = BinderForm::with_number(self.next_binder_number());
@@ -244,6 +522,7 @@ impl App {
                 let mut switch_to_binders = false;
                 let mut open_manager = false;
                 let mut open_to_print = false;
+                let mut move_song: Option<(i64, i64, isize)> = None;
 
                 {
                     let songs = &mut *songs;
@@ -283,28 +562,12 @@ impl App {
                         }
                         KeyCode::Enter => {
                             if let Some(song) = songs.current_song().cloned() {
-                                let link = song.link.trim().to_string();
-                                if link.is_empty() {
-                                    status_to_set = Some((
-                                        "This song does not have a link.".to_string(),
-                                        StatusKind::Error,
-                                    ));
-                                } else if let Err(err) = open_link(&link) {
-                                    status_to_set = Some((
-                                        format!("Failed to open link: {err}"),
-                                        StatusKind::Error,
-                                    ));
-                                } else {
-                                    status_to_set = Some((
-                                        format!("Opened {}.", song.display_title()),
-                                        StatusKind::Info,
-                                    ));
-                                }
+                                return self.open_link_picker(song);
                             }
                         }
                         KeyCode::Char('+') => {
                             if let Some(binder_id) = songs.binder_id() {
-                                let state = AddSongState::load(&self.conn, binder_id)?;
+                                let state = AddSongState::load(self.library.as_ref(), binder_id)?;
                                 if state.len() == 1 {
                                     let form = SongForm::default();
                                     return Ok(Mode::CreatingSong {
@@ -333,7 +596,10 @@ impl App {
                             if let Some(song) = songs.current_song().cloned() {
                                 return Ok(Mode::EditingSong {
                                     song_id: song.id,
-                                    form: SongForm::from_song(&song),
+                                    form: SongForm::from_song(
+                                        &song,
+                                        tags_of(&self.song_tags, song.id),
+                                    ),
                                 });
                             } else {
                                 status_to_set = Some((
@@ -342,10 +608,38 @@ impl App {
                                 ));
                             }
                         }
+                        KeyCode::Char('[') | KeyCode::Char(']') => {
+                            if let Some(song) = songs.current_song() {
+                                let offset = if code == KeyCode::Char('[') { -1 } else { 1 };
+                                move_song = Some((songs.binder.id, song.id, offset));
+                            }
+                        }
+                        KeyCode::Char('o') | KeyCode::Char('O') => {
+                            if songs.binder.number == 0 {
+                                self.clear_status();
+                                return Ok(Mode::ConfirmCopyOrder);
+                            }
+                            status_to_set = Some((
+                                "Open the director's binder (00) to copy its order.".to_string(),
+                                StatusKind::Error,
+                            ));
+                        }
                         _ => {}
                     }
                 }
 
+                if let Some((binder_id, song_id, offset)) = move_song {
+                    match move_song_in_binder(self.library.as_mut(), binder_id, song_id, offset) {
+                        Ok(_) => {
+                            self.refresh_song_screen()?;
+                            if let Screen::Songs(songs) = &mut self.screen {
+                                songs.select_song(song_id);
+                            }
+                        }
+                        Err(err) => status_to_set = Some((err.to_string(), StatusKind::Error)),
+                    }
+                }
+
                 if switch_to_binders {
                     self.screen = Screen::Binders;
                 } else if open_manager {
@@ -367,6 +661,8 @@ impl App {
                 let mut return_to_binders = false;
                 let mut open_to_print = false;
                 let mut toggled_no_link: Option<bool> = None;
+                let mut toggled_unsung: Option<Option<u32>> = None;
+                let mut toggled_tag: Option<Option<String>> = None;
 
                 {
                     let manager = &mut *manager;
@@ -391,23 +687,7 @@ impl App {
                         KeyCode::End => manager.select_last(),
                         KeyCode::Enter => {
                             if let Some(song) = manager.current_song().cloned() {
-                                let link = song.link.trim().to_string();
-                                if link.is_empty() {
-                                    status_to_set = Some((
-                                        "This song does not have a link.".to_string(),
-                                        StatusKind::Error,
-                                    ));
-                                } else if let Err(err) = open_link(&link) {
-                                    status_to_set = Some((
-                                        format!("Failed to open link: {err}"),
-                                        StatusKind::Error,
-                                    ));
-                                } else {
-                                    status_to_set = Some((
-                                        format!("Opened {}.", song.display_title()),
-                                        StatusKind::Info,
-                                    ));
-                                }
+                                return self.open_link_picker(song);
                             }
                         }
                         KeyCode::Char('+') => {
@@ -431,7 +711,10 @@ impl App {
                             if let Some(song) = manager.current_song().cloned() {
                                 return Ok(Mode::EditingSong {
                                     song_id: song.id,
-                                    form: SongForm::from_song(&song),
+                                    form: SongForm::from_song(
+                                        &song,
+                                        tags_of(&self.song_tags, song.id),
+                                    ),
                                 });
                             } else {
                                 status_to_set = Some((
@@ -446,6 +729,29 @@ impl App {
                         KeyCode::Char('l') | KeyCode::Char('L') => {
                             toggled_no_link = Some(manager.toggle_show_no_link());
                         }
+                        KeyCode::Char('n') | KeyCode::Char('N') => {
+                            toggled_unsung = Some(manager.cycle_unsung_filter());
+                        }
+                        KeyCode::Char('t') | KeyCode::Char('T') => {
+                            toggled_tag = Some(manager.cycle_tag_filter());
+                        }
+                        KeyCode::Char('h') | KeyCode::Char('H') => {
+                            if let Some(song) = manager.current_song().cloned() {
+                                let history = SongHistory::load(self.library.as_ref(), song)?;
+                                self.clear_status();
+                                return Ok(Mode::SongHistory(history));
+                            } else {
+                                status_to_set =
+                                    Some(("No song selected.".to_string(), StatusKind::Error));
+                            }
+                        }
+                        KeyCode::Char('i') | KeyCode::Char('I') => {
+                            return Ok(Mode::ImportingCsv(CsvImportForm::default()));
+                        }
+                        KeyCode::Char('x') | KeyCode::Char('X') => {
+                            let dir = self.data_dir.as_ref().map(|dir| dir.join(EXPORT_DIR_NAME));
+                            return Ok(Mode::ExportingCsv(ExportForm::with_dir(dir.as_deref())));
+                        }
                         _ => {}
                     }
                 }
@@ -462,6 +768,24 @@ impl App {
                         "Showing all songs.".to_string()
                     };
                     self.set_status(message, StatusKind::Info);
+                } else if let Some(years) = toggled_unsung {
+                    let message = match years {
+                        Some(years) => {
+                            let plural = if years == 1 { "" } else { "s" };
+                            format!("Showing songs not performed in {years} year{plural}.")
+                        }
+                        None => "Showing songs regardless of when they were performed.".to_string(),
+                    };
+                    self.set_status(message, StatusKind::Info);
+                } else if let Some(tag) = toggled_tag {
+                    let message = match tag {
+                        Some(tag) => format!("Showing songs tagged {tag}."),
+                        None if self.song_tags.is_empty() => {
+                            "No songs are tagged yet. Add tags in the song form.".to_string()
+                        }
+                        None => "Showing songs with any tag or none.".to_string(),
+                    };
+                    self.set_status(message, StatusKind::Info);
                 } else if let Some((text, kind)) = status_to_set {
                     self.set_status(text, kind);
                 }
@@ -481,7 +805,7 @@ impl App {
                             return Ok(Mode::ConfirmToPrintExit(ConfirmToPrintExit::new(false)));
                         }
                         self.clear_status();
-                        self.screen = Screen::Binders;
+                        self.leave_to_print()?;
                     }
                     KeyCode::Tab | KeyCode::BackTab | KeyCode::Char('t') | KeyCode::Char('T') => {
                         report.toggle_mode();
@@ -495,35 +819,282 @@ impl App {
                     KeyCode::Enter => {
                         if matches!(report.mode, ToPrintMode::BySong) {
                             if let Some(song) = report.current_song().cloned() {
-                                let link = song.link.trim().to_string();
-                                if link.is_empty() {
-                                    self.set_status(
-                                        "This song does not have a link.".to_string(),
-                                        StatusKind::Error,
-                                    );
-                                } else if let Err(err) = open_link(&link) {
-                                    self.set_status(
-                                        format!("Failed to open link: {err}"),
-                                        StatusKind::Error,
-                                    );
-                                } else {
+                                return self.open_link_picker(song);
+                            }
+                        }
+                    }
+                    KeyCode::Char('x') | KeyCode::Char('X') if report.director_exists => {
+                        let concert = report.concert.clone();
+                        self.export_to_print_checklist(concert.as_ref())?;
+                    }
+                    KeyCode::Char('b') | KeyCode::Char('B') if report.director_exists => {
+                        let concert = report.concert.clone();
+                        let songs = report.song_demands();
+                        self.build_print_job(concert.as_ref(), &songs)?;
+                    }
+                    KeyCode::Char('s') | KeyCode::Char('S') if report.director_exists => {
+                        let songs = report.song_demands();
+                        return self.prepare_send_to_printer(&songs);
+                    }
+                    KeyCode::Char(' ') => {
+                        // Only move the row on once the new stage is saved.
+                        if let Some((binder_id, song_id, stage)) = report.next_stage_for_current() {
+                            let now = Local::now().format(TIMESTAMP_FORMAT).to_string();
+                            match self
+                                .library
+                                .set_print_stage(binder_id, song_id, stage, &now)
+                            {
+                                Ok(()) => {
+                                    report.advance_current();
+                                    let message = match stage {
+                                        PrintStage::Needed => "Song needs printing again.",
+                                        PrintStage::Printed => "Marked song as printed.",
+                                        PrintStage::Inserted => {
+                                            "Marked song as inserted in the binder."
+                                        }
+                                    };
+                                    self.set_status(message, StatusKind::Info);
+                                }
+                                Err(err) => self.set_status(
+                                    format!("Could not save the print queue: {err}"),
+                                    StatusKind::Error,
+                                ),
+                            }
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(Mode::Normal)
+            }
+            Screen::Backups(ref mut list) => {
+                match code {
+                    KeyCode::Char('q') => {
+                        *exit = true;
+                    }
+                    KeyCode::Esc | KeyCode::Char('b') | KeyCode::Char('B') => {
+                        self.clear_status();
+                        self.screen = Screen::Binders;
+                    }
+                    KeyCode::Up => list.move_selection(-1),
+                    KeyCode::Down => list.move_selection(1),
+                    KeyCode::PageUp => list.move_selection(-5),
+                    KeyCode::PageDown => list.move_selection(5),
+                    KeyCode::Home => list.select_first(),
+                    KeyCode::End => list.select_last(),
+                    KeyCode::Enter => {
+                        if let Some(backup) = list.current_backup().cloned() {
+                            self.clear_status();
+                            return Ok(Mode::ConfirmBackupRestore(backup));
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(Mode::Normal)
+            }
+            Screen::Concerts(ref mut list) => {
+                match code {
+                    KeyCode::Char('q') => {
+                        *exit = true;
+                    }
+                    KeyCode::Esc | KeyCode::Char('c') | KeyCode::Char('C') => {
+                        self.clear_status();
+                        self.screen = Screen::Binders;
+                    }
+                    KeyCode::Up => list.move_selection(-1),
+                    KeyCode::Down => list.move_selection(1),
+                    KeyCode::PageUp => list.move_selection(-5),
+                    KeyCode::PageDown => list.move_selection(5),
+                    KeyCode::Home => list.select_first(),
+                    KeyCode::End => list.select_last(),
+                    KeyCode::Char('+') => {
+                        self.clear_status();
+                        return Ok(Mode::AddingConcert(ConcertForm::default()));
+                    }
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        if let Some(concert) = list.current_concert() {
+                            let mode = Mode::EditingConcert {
+                                id: concert.id,
+                                form: ConcertForm::from_concert(concert),
+                            };
+                            self.clear_status();
+                            return Ok(mode);
+                        }
+                        self.set_status("No concert selected to edit.", StatusKind::Error);
+                    }
+                    KeyCode::Char('-') => {
+                        if let Some(concert) = list.current_concert().cloned() {
+                            self.clear_status();
+                            return Ok(Mode::ConfirmConcertDelete(concert));
+                        }
+                        self.set_status("No concert selected to remove.", StatusKind::Error);
+                    }
+                    KeyCode::Enter => {
+                        if let Some(concert) = list.current_concert().cloned() {
+                            self.clear_status();
+                            self.open_setlist(concert)?;
+                        }
+                    }
+                    KeyCode::Char('p') | KeyCode::Char('P') => {
+                        if let Some(concert) = list.current_concert().cloned() {
+                            self.clear_status();
+                            self.open_to_print(Some(concert))?;
+                        } else {
+                            self.set_status("No concert selected.", StatusKind::Error);
+                        }
+                    }
+                    KeyCode::Char('m') | KeyCode::Char('M') => {
+                        if let Some(concert) = list.current_concert().cloned() {
+                            self.mark_concert_performed(concert)?;
+                        } else {
+                            self.set_status("No concert selected.", StatusKind::Error);
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(Mode::Normal)
+            }
+            Screen::Overdue(ref mut report) => {
+                match code {
+                    KeyCode::Char('q') => {
+                        *exit = true;
+                    }
+                    KeyCode::Esc | KeyCode::Char('d') | KeyCode::Char('D') => {
+                        self.clear_status();
+                        self.screen = Screen::Binders;
+                    }
+                    KeyCode::Up => report.move_selection(-1),
+                    KeyCode::Down => report.move_selection(1),
+                    KeyCode::PageUp => report.move_selection(-5),
+                    KeyCode::PageDown => report.move_selection(5),
+                    KeyCode::Char('+') => {
+                        self.overdue_days = self.overdue_days.saturating_add(OVERDUE_STEP_DAYS);
+                        self.refresh_overdue()?;
+                    }
+                    KeyCode::Char('-') => {
+                        self.overdue_days = self.overdue_days.saturating_sub(OVERDUE_STEP_DAYS);
+                        self.refresh_overdue()?;
+                    }
+                    KeyCode::Char('i') | KeyCode::Char('I') => {
+                        if let Some(entry) = report.current_entry() {
+                            let binder = entry.binder.clone();
+                            self.check_in(&binder)?;
+                            self.refresh_overdue()?;
+                        } else {
+                            self.set_status("No overdue binder selected.", StatusKind::Error);
+                        }
+                    }
+                    _ => {}
+                }
+                Ok(Mode::Normal)
+            }
+            Screen::Choristers(ref mut list) => {
+                match code {
+                    KeyCode::Char('q') => {
+                        *exit = true;
+                    }
+                    KeyCode::Esc | KeyCode::Char('m') | KeyCode::Char('M') => {
+                        self.clear_status();
+                        self.screen = Screen::Binders;
+                    }
+                    KeyCode::Up => list.move_selection(-1),
+                    KeyCode::Down => list.move_selection(1),
+                    KeyCode::PageUp => list.move_selection(-5),
+                    KeyCode::PageDown => list.move_selection(5),
+                    KeyCode::Home => list.select_first(),
+                    KeyCode::End => list.select_last(),
+                    KeyCode::Char('+') => {
+                        self.clear_status();
+                        return Ok(Mode::AddingChorister(ChoristerForm::default()));
+                    }
+                    KeyCode::Char('e') | KeyCode::Char('E') => {
+                        if let Some(chorister) = list.current_chorister() {
+                            let mode = Mode::EditingChorister {
+                                id: chorister.id,
+                                form: ChoristerForm::from_chorister(chorister),
+                            };
+                            self.clear_status();
+                            return Ok(mode);
+                        }
+                        self.set_status("No chorister selected to edit.", StatusKind::Error);
+                    }
+                    KeyCode::Char('-') => {
+                        if let Some(chorister) = list.current_chorister().cloned() {
+                            self.clear_status();
+                            return Ok(Mode::ConfirmChoristerDelete(chorister));
+                        }
+                        self.set_status("No chorister selected to remove.", StatusKind::Error);
+                    }
+                    _ => {}
+                }
+                Ok(Mode::Normal)
+            }
+            Screen::Setlist(ref mut setlist) => {
+                let concert = setlist.concert.clone();
+                match code {
+                    KeyCode::Char('q') => {
+                        *exit = true;
+                    }
+                    KeyCode::Esc => {
+                        self.clear_status();
+                        self.open_concerts(Some(concert.id))?;
+                    }
+                    KeyCode::Up => setlist.move_selection(-1),
+                    KeyCode::Down => setlist.move_selection(1),
+                    KeyCode::PageUp => setlist.move_selection(-5),
+                    KeyCode::PageDown => setlist.move_selection(5),
+                    KeyCode::Home => setlist.select_first(),
+                    KeyCode::End => setlist.select_last(),
+                    KeyCode::Char('+') => {
+                        let picker = SetlistPicker::load(self.library.as_ref(), concert.id)?;
+                        if picker.songs.is_empty() {
+                            self.set_status(
+                                "Every song is already on this setlist.",
+                                StatusKind::Info,
+                            );
+                        } else {
+                            self.clear_status();
+                            return Ok(Mode::PickingSetlistSongs(picker));
+                        }
+                    }
+                    KeyCode::Char('-') => {
+                        if let Some(song) = setlist.current_song().cloned() {
+                            match self.library.remove_song_from_setlist(concert.id, song.id) {
+                                Ok(()) | Err(DbError::NotInSetlist { .. }) => {
+                                    self.refresh_setlist()?;
                                     self.set_status(
-                                        format!("Opened {}.", song.display_title()),
+                                        format!("Removed {} from the setlist.", song.title),
                                         StatusKind::Info,
                                     );
                                 }
+                                Err(err) => self.set_status(err.to_string(), StatusKind::Error),
                             }
+                        } else {
+                            self.set_status("No song selected to remove.", StatusKind::Error);
                         }
                     }
-                    KeyCode::Char(' ') => {
-                        if let Some(checked) = report.toggle_current() {
-                            if checked {
-                                self.set_status("Marked song as added.", StatusKind::Info);
-                            } else {
-                                self.set_status("Song unchecked.", StatusKind::Info);
+                    KeyCode::Char('[') | KeyCode::Char(']') => {
+                        if let Some(song_id) = setlist.current_song().map(|song| song.id) {
+                            let offset = if code == KeyCode::Char('[') { -1 } else { 1 };
+                            match move_song_in_setlist(
+                                self.library.as_mut(),
+                                concert.id,
+                                song_id,
+                                offset,
+                            ) {
+                                Ok(_) => {
+                                    self.refresh_setlist()?;
+                                    if let Screen::Setlist(setlist) = &mut self.screen {
+                                        setlist.select_song(song_id);
+                                    }
+                                }
+                                Err(err) => self.set_status(err.to_string(), StatusKind::Error),
                             }
                         }
                     }
+                    KeyCode::Char('p') | KeyCode::Char('P') => {
+                        self.clear_status();
+                        self.open_to_print(Some(concert))?;
+                    }
                     _ => {}
                 }
                 Ok(Mode::Normal)
@@ -540,18 +1111,19 @@ impl App {
             }
             KeyCode::Tab | KeyCode::BackTab => form.toggle_field(),
             KeyCode::Backspace => form.backspace(),
-            KeyCode::Enter => match self.save_new_binder(&form) {
-                Ok(_) => keep_open = false,
+            KeyCode::Enter => match form.parse_inputs() {
+                Ok((number, label, part)) => match self.save_new_binder(number, &label, &part) {
+                    Ok(()) => keep_open = false,
+                    Err(err) => keep_open = self.handle_binder_save_error(&mut form, err)?,
+                },
                 Err(err) => {
                     let message = surface_error(&err);
                     form.error = Some(message.clone());
                     self.set_status(message, StatusKind::Error);
                 }
             },
-            KeyCode::Char(ch) => {
-                if form.push_char(ch) {
-                    form.error = None;
-                }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
             }
             _ => {}
         }
@@ -572,18 +1144,21 @@ impl App {
             }
             KeyCode::Tab | KeyCode::BackTab => form.toggle_field(),
             KeyCode::Backspace => form.backspace(),
-            KeyCode::Enter => match self.save_existing_binder(id, &form) {
-                Ok(_) => keep_open = false,
+            KeyCode::Enter => match form.parse_inputs() {
+                Ok((number, label, part)) => {
+                    match self.save_existing_binder(id, number, label, part) {
+                        Ok(()) => keep_open = false,
+                        Err(err) => keep_open = self.handle_binder_save_error(&mut form, err)?,
+                    }
+                }
                 Err(err) => {
                     let message = surface_error(&err);
                     form.error = Some(message.clone());
                     self.set_status(message, StatusKind::Error);
                 }
             },
-            KeyCode::Char(ch) => {
-                if form.push_char(ch) {
-                    form.error = None;
-                }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
             }
             _ => {}
         }
@@ -608,9 +1183,14 @@ impl App {
             KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
                 match self.perform_delete(&confirm) {
                     Ok(_) => Ok(Mode::Normal),
+                    Err(DbError::NotFound(Entity::Binder)) => {
+                        self.reload_binders(None)?;
+                        self.screen = Screen::Binders;
+                        self.set_status("That binder was already deleted.", StatusKind::Info);
+                        Ok(Mode::Normal)
+                    }
                     Err(err) => {
-                        let message = surface_error(&err);
-                        self.set_status(message, StatusKind::Error);
+                        self.set_status(err.to_string(), StatusKind::Error);
                         Ok(Mode::ConfirmBinderDelete(confirm))
                     }
                 }
@@ -627,11 +1207,9 @@ impl App {
     ) -> Result<Mode> {
         let mut keep_open = true;
         match code {
-            KeyCode::Esc => {
-                if !form.cancel_autocomplete() {
-                    self.set_status("Edit cancelled.", StatusKind::Info);
-                    keep_open = false;
-                }
+            KeyCode::Esc if !form.cancel_autocomplete() => {
+                self.set_status("Edit cancelled.", StatusKind::Info);
+                keep_open = false;
             }
             KeyCode::Tab => {
                 let consumed = form.has_active_suggestion() && form.accept_suggestion();
@@ -649,16 +1227,31 @@ impl App {
                 form.update_suggestion(&self.composers);
             }
             KeyCode::Enter => match form.parse_inputs() {
-                Ok((title, composer, link)) => {
-                    if let Err(err) = update_song(&self.conn, song_id, &title, &composer, &link) {
-                        let message = surface_error(&err);
-                        form.error = Some(message.clone());
-                        self.set_status(message, StatusKind::Error);
-                    } else {
-                        self.refresh_song_screen()?;
-                        self.refresh_song_manager()?;
-                        self.set_status("Song updated.", StatusKind::Info);
-                        keep_open = false;
+                Ok(input) => {
+                    let saved = self.library.transaction(&mut |library| {
+                        library.update_song(song_id, &input.title, &input.composer)?;
+                        library.set_song_parts(song_id, &input.parts)?;
+                        library.set_song_details(song_id, &input.details)?;
+                        set_song_tags(library, song_id, &input.tags)
+                    });
+                    match saved {
+                        Ok(()) => {
+                            self.refresh_song_screen()?;
+                            self.refresh_song_manager()?;
+                            self.set_status("Song updated.", StatusKind::Info);
+                            keep_open = false;
+                        }
+                        Err(DbError::NotFound(Entity::Song)) => {
+                            self.refresh_song_screen()?;
+                            self.refresh_song_manager()?;
+                            self.set_status("That song no longer exists.", StatusKind::Error);
+                            keep_open = false;
+                        }
+                        Err(err) => {
+                            let message = err.to_string();
+                            form.error = Some(message.clone());
+                            self.set_status(message, StatusKind::Error);
+                        }
                     }
                 }
                 Err(err) => {
@@ -667,11 +1260,9 @@ impl App {
                     self.set_status(message, StatusKind::Error);
                 }
             },
-            KeyCode::Char(ch) => {
-                if form.push_char(ch) {
-                    form.error = None;
-                    form.update_suggestion(&self.composers);
-                }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+                form.update_suggestion(&self.composers);
             }
             _ => {}
         }
@@ -696,15 +1287,25 @@ impl App {
                 Ok(Mode::Normal)
             }
             KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
-                match remove_song_from_binder(&self.conn, confirm.binder_id, confirm.song.id) {
+                match self
+                    .library
+                    .remove_song_from_binder(confirm.binder_id, confirm.song.id)
+                {
                     Ok(_) => {
                         self.refresh_song_screen()?;
                         self.set_status("Song removed from binder.", StatusKind::Info);
                         Ok(Mode::Normal)
                     }
+                    Err(DbError::NotLinked { .. }) => {
+                        self.refresh_song_screen()?;
+                        self.set_status(
+                            "Song was already removed from this binder.",
+                            StatusKind::Info,
+                        );
+                        Ok(Mode::Normal)
+                    }
                     Err(err) => {
-                        let message = surface_error(&err);
-                        self.set_status(message, StatusKind::Error);
+                        self.set_status(err.to_string(), StatusKind::Error);
                         Ok(Mode::ConfirmSongRemove(confirm))
                     }
                 }
@@ -724,16 +1325,24 @@ impl App {
                 Ok(Mode::Normal)
             }
             KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
-                match delete_song(&self.conn, confirm.song.id) {
+                let deleted = self
+                    .backup_before(BackupReason::DeleteSong)
+                    .and_then(|()| self.library.delete_song(confirm.song.id));
+                match deleted {
                     Ok(_) => {
                         self.refresh_song_manager()?;
                         self.refresh_song_screen()?;
                         self.set_status("Song deleted.", StatusKind::Info);
                         Ok(Mode::Normal)
                     }
+                    Err(DbError::NotFound(Entity::Song)) => {
+                        self.refresh_song_manager()?;
+                        self.refresh_song_screen()?;
+                        self.set_status("Song was already deleted.", StatusKind::Info);
+                        Ok(Mode::Normal)
+                    }
                     Err(err) => {
-                        let message = surface_error(&err);
-                        self.set_status(message, StatusKind::Error);
+                        self.set_status(err.to_string(), StatusKind::Error);
                         Ok(Mode::ConfirmSongDelete(confirm))
                     }
                 }
@@ -778,11 +1387,11 @@ impl App {
                 if !selections.is_empty() {
                     let mut added = 0usize;
                     for song in selections {
-                        if let Err(err) = add_song_to_binder(&self.conn, state.binder_id, song.id) {
-                            let message = surface_error(&err);
-                            self.set_status(message, StatusKind::Error);
-                            if added > 0 {
-                                self.refresh_song_screen()?;
+                        if let Err(err) = self.library.add_song_to_binder(state.binder_id, song.id)
+                        {
+                            let stale = matches!(err, DbError::ForeignKey);
+                            self.report_link_error(err)?;
+                            if added > 0 || stale {
                                 return Ok(Mode::Normal);
                             } else {
                                 return Ok(Mode::SelectingSong(state));
@@ -810,11 +1419,15 @@ impl App {
                         }),
                         Some(AddSongItem::Existing(song)) => {
                             if let Err(err) =
-                                add_song_to_binder(&self.conn, state.binder_id, song.id)
+                                self.library.add_song_to_binder(state.binder_id, song.id)
                             {
-                                let message = surface_error(&err);
-                                self.set_status(message, StatusKind::Error);
-                                Ok(Mode::SelectingSong(state))
+                                let stale = matches!(err, DbError::ForeignKey);
+                                self.report_link_error(err)?;
+                                if stale {
+                                    Ok(Mode::Normal)
+                                } else {
+                                    Ok(Mode::SelectingSong(state))
+                                }
                             } else {
                                 self.refresh_song_screen()?;
                                 self.set_status("Song added to binder.", StatusKind::Info);
@@ -868,23 +1481,8 @@ impl App {
                     }
                     KeyCode::Enter => {
                         if let Some(song) = manager.current_song().cloned() {
-                            let link = song.link.trim().to_string();
-                            if link.is_empty() {
-                                self.set_status(
-                                    "This song does not have a link.",
-                                    StatusKind::Error,
-                                );
-                            } else if let Err(err) = open_link(&link) {
-                                self.set_status(
-                                    format!("Failed to open link: {err}"),
-                                    StatusKind::Error,
-                                );
-                            } else {
-                                self.set_status(
-                                    format!("Opened {}.", song.display_title()),
-                                    StatusKind::Info,
-                                );
-                            }
+                            self.saved_search = Some(state);
+                            return self.open_link_picker(song);
                         }
                         return Ok(Mode::Searching(state));
                     }
@@ -893,22 +1491,19 @@ impl App {
                     }
                     KeyCode::Char(ch) => {
                         if ch.is_control() {
-                            match ch {
-                                '\u{5}' => {
-                                    if let Some(song) = manager.current_song().cloned() {
-                                        return Ok(Mode::EditingSong {
-                                            song_id: song.id,
-                                            form: SongForm::from_song(&song),
-                                        });
-                                    } else {
-                                        self.set_status(
-                                            "No song selected to edit.",
-                                            StatusKind::Error,
-                                        );
-                                        return Ok(Mode::Searching(state));
-                                    }
+                            if ch == '\u{5}' {
+                                if let Some(song) = manager.current_song().cloned() {
+                                    return Ok(Mode::EditingSong {
+                                        song_id: song.id,
+                                        form: SongForm::from_song(
+                                            &song,
+                                            tags_of(&self.song_tags, song.id),
+                                        ),
+                                    });
+                                } else {
+                                    self.set_status("No song selected to edit.", StatusKind::Error);
+                                    return Ok(Mode::Searching(state));
                                 }
-                                _ => {}
                             }
                         } else {
                             state.query.push(ch);
@@ -962,23 +1557,8 @@ impl App {
                     }
                     KeyCode::Enter => {
                         if let Some(song) = songs.current_song().cloned() {
-                            let link = song.link.trim().to_string();
-                            if link.is_empty() {
-                                self.set_status(
-                                    "This song does not have a link.",
-                                    StatusKind::Error,
-                                );
-                            } else if let Err(err) = open_link(&link) {
-                                self.set_status(
-                                    format!("Failed to open link: {err}"),
-                                    StatusKind::Error,
-                                );
-                            } else {
-                                self.set_status(
-                                    format!("Opened {}.", song.display_title()),
-                                    StatusKind::Info,
-                                );
-                            }
+                            self.saved_search = Some(state);
+                            return self.open_link_picker(song);
                         }
                         return Ok(Mode::Searching(state));
                     }
@@ -987,22 +1567,19 @@ impl App {
                     }
                     KeyCode::Char(ch) => {
                         if ch.is_control() {
-                            match ch {
-                                '\u{5}' => {
-                                    if let Some(song) = songs.current_song().cloned() {
-                                        return Ok(Mode::EditingSong {
-                                            song_id: song.id,
-                                            form: SongForm::from_song(&song),
-                                        });
-                                    } else {
-                                        self.set_status(
-                                            "No song selected to edit.",
-                                            StatusKind::Error,
-                                        );
-                                        return Ok(Mode::Searching(state));
-                                    }
+                            if ch == '\u{5}' {
+                                if let Some(song) = songs.current_song().cloned() {
+                                    return Ok(Mode::EditingSong {
+                                        song_id: song.id,
+                                        form: SongForm::from_song(
+                                            &song,
+                                            tags_of(&self.song_tags, song.id),
+                                        ),
+                                    });
+                                } else {
+                                    self.set_status("No song selected to edit.", StatusKind::Error);
+                                    return Ok(Mode::Searching(state));
                                 }
-                                _ => {}
                             }
                         } else {
                             state.query.push(ch);
@@ -1036,11 +1613,29 @@ impl App {
             (area, area)
         };
 
+        // Keep results clear of the inline search bar so the first match is
+        // never hidden underneath it.
+        let content_area = if matches!(self.mode, Mode::Searching(_)) {
+            let bar_height = SEARCH_BAR_HEIGHT.min(content_area.height);
+            Rect {
+                y: content_area.y + bar_height,
+                height: content_area.height - bar_height,
+                ..content_area
+            }
+        } else {
+            content_area
+        };
+
         match &self.screen {
             Screen::Binders => self.draw_binder_grid(frame, content_area),
             Screen::Songs(songs) => self.draw_song_view(frame, content_area, songs),
             Screen::SongManager(manager) => self.draw_song_manager(frame, content_area, manager),
             Screen::ToPrint(report) => self.draw_to_print(frame, content_area, report),
+            Screen::Backups(list) => self.draw_backups(frame, content_area, list),
+            Screen::Concerts(list) => self.draw_concerts(frame, content_area, list),
+            Screen::Choristers(list) => self.draw_choristers(frame, content_area, list),
+            Screen::Overdue(report) => self.draw_overdue(frame, content_area, report),
+            Screen::Setlist(setlist) => self.draw_setlist(frame, content_area, setlist),
         }
 
         if area.height >= footer_height {
@@ -1064,6 +1659,47 @@ impl App {
                 self.draw_confirm_to_print_exit(frame, area, confirm)
             }
             Mode::Searching(state) => self.draw_search_bar(frame, area, state),
+            Mode::ImportingCsv(form) => self.draw_csv_import_form(frame, area, form),
+            Mode::ExportingCsv(form) => self.draw_export_form(frame, area, form),
+            Mode::ConfirmCsvImport(preview) => self.draw_csv_import_preview(frame, area, preview),
+            Mode::ConfirmBackupRestore(backup) => {
+                self.draw_confirm_backup_restore(frame, area, backup)
+            }
+            Mode::ConfirmSendToPrinter(job) => self.draw_confirm_send_to_printer(frame, area, job),
+            Mode::ConfirmCopyOrder => self.draw_confirm_copy_order(frame, area),
+            Mode::AddingConcert(form) => self.draw_concert_form(frame, area, "Add Concert", form),
+            Mode::EditingConcert { form, .. } => {
+                self.draw_concert_form(frame, area, "Edit Concert", form)
+            }
+            Mode::ConfirmConcertDelete(concert) => {
+                self.draw_confirm_concert_delete(frame, area, concert)
+            }
+            Mode::PickingSetlistSongs(picker) => self.draw_setlist_picker(frame, area, picker),
+            Mode::SongHistory(history) => self.draw_song_history(frame, area, history),
+            Mode::AddingPerformance { history, form } => {
+                self.draw_song_history(frame, area, history);
+                self.draw_performance_form(frame, area, form);
+            }
+            Mode::AddingChorister(form) => {
+                self.draw_chorister_form(frame, area, "Add Chorister", form)
+            }
+            Mode::EditingChorister { form, .. } => {
+                self.draw_chorister_form(frame, area, "Edit Chorister", form)
+            }
+            Mode::ConfirmChoristerDelete(chorister) => {
+                self.draw_confirm_chorister_delete(frame, area, chorister)
+            }
+            Mode::AssigningBinder(picker) => self.draw_holder_picker(frame, area, picker),
+            Mode::BinderLog(log) => self.draw_binder_log(frame, area, log),
+            Mode::PickingLink(picker) => self.draw_link_picker(frame, area, picker),
+            Mode::AddingLink { picker, form } => {
+                self.draw_link_picker(frame, area, picker);
+                self.draw_link_form(frame, area, form);
+            }
+            Mode::AttachingFile { picker, form } => {
+                self.draw_link_picker(frame, area, picker);
+                self.draw_attach_form(frame, area, form);
+            }
             Mode::Normal => {}
         }
     }
@@ -1083,7 +1719,7 @@ impl App {
                 if let Some(song) = manager.current_song().cloned() {
                     self.mode = Mode::EditingSong {
                         song_id: song.id,
-                        form: SongForm::from_song(&song),
+                        form: SongForm::from_song(&song, tags_of(&self.song_tags, song.id)),
                     };
                 } else {
                     self.set_status("No song selected to edit.", StatusKind::Error);
@@ -1093,7 +1729,7 @@ impl App {
                 if let Some(song) = songs.current_song().cloned() {
                     self.mode = Mode::EditingSong {
                         song_id: song.id,
-                        form: SongForm::from_song(&song),
+                        form: SongForm::from_song(&song, tags_of(&self.song_tags, song.id)),
                     };
                 } else {
                     self.set_status("No song selected to edit.", StatusKind::Error);
@@ -1127,6 +1763,7 @@ impl App {
             return;
         }
 
+        let now = Local::now().naive_local();
         let rows = self.split_rows(area);
         for (row_idx, row_chunk) in rows.into_iter().enumerate() {
             let columns = self.split_columns(row_chunk);
@@ -1135,15 +1772,23 @@ impl App {
                 if let Some(binder) = self.binders.get(binder_index) {
                     let mut block = Block::default()
                         .borders(Borders::ALL)
-                        .title(format!("Binder {:02}", binder.number));
+                        .title(binder_card_title(binder))
+                        .title_bottom(self.binder_status_line(binder, now).right_aligned());
                     if binder_index == self.selected {
                         block = block.style(Style::default().fg(Color::Yellow));
                     }
                     let pattern = BINDER_ART[binder_index % BINDER_ART.len()];
                     let inner_width = column_chunk.width.saturating_sub(2);
                     let inner_height = column_chunk.height.saturating_sub(2);
+                    let holder = binder.holder_id.and_then(|id| {
+                        self.choristers
+                            .iter()
+                            .find(|chorister| chorister.id == id)
+                            .map(|chorister| chorister.name.as_str())
+                    });
                     let lines = build_binder_cover_lines(
                         binder,
+                        holder,
                         pattern,
                         inner_width,
                         inner_height,
@@ -1158,6 +1803,27 @@ impl App {
         }
     }
 
+    /// "On shelf", or how long the binder has been out; red once overdue.
+    fn binder_status_line(&self, binder: &Binder, now: NaiveDateTime) -> Line<'static> {
+        let Some(checkout) = self.checkouts.get(&binder.id) else {
+            return Line::from(Span::styled(
+                " On shelf ",
+                Style::default().fg(Color::Green),
+            ));
+        };
+        match days_out(&checkout.checked_out_at, now) {
+            Some(days) if days > i64::from(self.overdue_days) => Line::from(Span::styled(
+                format!(" Overdue {days}d "),
+                Style::default().fg(Color::Red),
+            )),
+            Some(days) => Line::from(Span::styled(
+                format!(" Out {days}d "),
+                Style::default().fg(Color::Yellow),
+            )),
+            None => Line::from(Span::styled(" Out ", Style::default().fg(Color::Yellow))),
+        }
+    }
+
     fn draw_song_view(&self, frame: &mut Frame, area: Rect, songs: &SongScreen) {
         let chunks = Layout::default()
             .direction(Direction::Vertical)
@@ -1171,6 +1837,11 @@ impl App {
                     Style::default().add_modifier(Modifier::BOLD),
                 ),
                 Span::raw(format!("  •  {}", songs.binder.label)),
+                Span::raw(if songs.binder.voice_part.is_empty() {
+                    String::new()
+                } else {
+                    format!("  •  Part {}", songs.binder.voice_part)
+                }),
             ]),
             Line::from(Span::raw(format!("{} songs linked", songs.songs.len()))),
         ])
@@ -1192,35 +1863,54 @@ impl App {
     fn draw_song_manager(&self, frame: &mut Frame, area: Rect, manager: &SongManagerScreen) {
         let mut list_area = area;
 
+        let filter_style = Style::default()
+            .fg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+        let key_style = Style::default()
+            .fg(Color::Cyan)
+            .add_modifier(Modifier::BOLD);
+        let mut indicator_lines = vec![Line::from(Span::styled(
+            "Song Manager",
+            Style::default().add_modifier(Modifier::BOLD),
+        ))];
         if manager.show_only_no_link {
+            indicator_lines.push(Line::from(vec![
+                Span::styled("No-link filter active", filter_style),
+                Span::raw(" - showing only songs without links (press "),
+                Span::styled("[l]", key_style),
+                Span::raw(" to show all)"),
+            ]));
+        }
+        if let Some(years) = manager.unsung_years {
+            let plural = if years == 1 { "" } else { "s" };
+            indicator_lines.push(Line::from(vec![
+                Span::styled("Unsung filter active", filter_style),
+                Span::raw(format!(
+                    " - songs not performed in {years} year{plural}, longest ago first (press "
+                )),
+                Span::styled("[n]", key_style),
+                Span::raw(" for longer or to show all)"),
+            ]));
+        }
+        if let Some(tag) = &manager.tag_filter {
+            indicator_lines.push(Line::from(vec![
+                Span::styled("Tag filter active", filter_style),
+                Span::raw(format!(" - songs tagged {tag} (press ")),
+                Span::styled("[t]", key_style),
+                Span::raw(" for the next tag or to show all)"),
+            ]));
+        }
+        if indicator_lines.len() > 1 {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Length(4), Constraint::Min(1)])
+                .constraints([
+                    Constraint::Length(indicator_lines.len() as u16 + 2),
+                    Constraint::Min(1),
+                ])
                 .split(area);
-            let indicator = Paragraph::new(vec![
-                Line::from(Span::styled(
-                    "Song Manager",
-                    Style::default().add_modifier(Modifier::BOLD),
-                )),
-                Line::from(vec![
-                    Span::styled(
-                        "No-link filter active",
-                        Style::default()
-                            .fg(Color::Yellow)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::raw(" - showing only songs without links (press "),
-                    Span::styled(
-                        "[l]",
-                        Style::default()
-                            .fg(Color::Cyan)
-                            .add_modifier(Modifier::BOLD),
-                    ),
-                    Span::raw(" to show all)"),
-                ]),
-            ])
-            .block(Block::default().borders(Borders::ALL))
-            .alignment(Alignment::Left);
+            let indicator = Paragraph::new(indicator_lines)
+                .block(Block::default().borders(Borders::ALL))
+                .alignment(Alignment::Left);
             frame.render_widget(indicator, chunks[0]);
             list_area = chunks[1];
         }
@@ -1243,7 +1933,11 @@ impl App {
                 .as_ref()
                 .map(|q| !q.trim().is_empty())
                 .unwrap_or(false);
-            let message_text = if manager.show_only_no_link && has_search {
+            let message_text = if manager.tag_filter.is_some() {
+                "No matching songs carry this tag."
+            } else if manager.unsung_years.is_some() {
+                "Every matching song has been performed recently."
+            } else if manager.show_only_no_link && has_search {
                 "No songs match the current search without links."
             } else if manager.show_only_no_link {
                 "No songs without links yet."
@@ -1263,9 +1957,13 @@ impl App {
     }
 
     fn draw_to_print(&self, frame: &mut Frame, area: Rect, report: &ToPrintScreen) {
-        let title = match report.mode {
-            ToPrintMode::ByBinder => "To Print • By Binder",
-            ToPrintMode::BySong => "To Print • By Song",
+        let view = match report.mode {
+            ToPrintMode::ByBinder => "By Binder",
+            ToPrintMode::BySong => "By Song",
+        };
+        let title = match &report.concert {
+            Some(concert) => format!("To Print • {} • {view}", concert.name),
+            None => format!("To Print • {view}"),
         };
         let block = Block::default().title(title).borders(Borders::ALL);
 
@@ -1302,17 +2000,15 @@ impl App {
                             };
                             let text = format!("{pointer}{}", row.text);
                             let link_style = if idx == report.selected_index {
-                                row
-                                    .song
-                                    .as_ref()
-                                    .and_then(|song| {
-                                        let trimmed = song.link.trim();
-                                        if trimmed.is_empty() {
-                                            None
-                                        } else {
-                                            Some(Style::default().fg(Color::Cyan))
-                                        }
-                                    })
+                                row.song.as_ref().and_then(|song| {
+                                    if !self.song_links.contains_key(&song.id)
+                                        && !self.song_attachments.contains_key(&song.id)
+                                    {
+                                        None
+                                    } else {
+                                        Some(Style::default().fg(Color::Cyan))
+                                    }
+                                })
                             } else {
                                 None
                             };
@@ -1333,6 +2029,193 @@ impl App {
         frame.render_widget(paragraph, area);
     }
 
+    fn draw_backups(&self, frame: &mut Frame, area: Rect, list: &BackupScreen) {
+        let title = match &self.backups {
+            Some(store) => format!("Restore from Backup • {}", store.dir().display()),
+            None => "Restore from Backup".to_string(),
+        };
+        let block = Block::default().title(title).borders(Borders::ALL);
+
+        let lines: Vec<Line> = if list.backups.is_empty() {
+            vec![Line::from("No backups yet.")]
+        } else {
+            list.backups
+                .iter()
+                .enumerate()
+                .map(|(idx, backup)| {
+                    let pointer = if idx == list.selected { "▶ " } else { "  " };
+                    let text = format!(
+                        "{pointer}{}  {:<28}  {} binders, {} songs",
+                        backup.taken_at.format("%Y-%m-%d %H:%M:%S"),
+                        backup.describe_reason(),
+                        backup.binders,
+                        backup.songs
+                    );
+                    if idx == list.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
+        };
+
+        // Keep the selection on screen once the list outgrows the panel.
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (list.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_concerts(&self, frame: &mut Frame, area: Rect, list: &ConcertScreen) {
+        let block = Block::default().title("Concerts").borders(Borders::ALL);
+
+        let lines: Vec<Line> = if list.concerts.is_empty() {
+            vec![Line::from("No concerts yet. Press + to add one.")]
+        } else {
+            list.concerts
+                .iter()
+                .enumerate()
+                .map(|(idx, concert)| {
+                    let pointer = if idx == list.selected { "▶ " } else { "  " };
+                    let date = if concert.date.is_empty() {
+                        "(no date)"
+                    } else {
+                        concert.date.as_str()
+                    };
+                    let mut text = format!("{pointer}{date:<10}  {}", concert.name);
+                    if !concert.venue.trim().is_empty() {
+                        text.push_str(&format!("  @ {}", concert.venue));
+                    }
+                    if idx == list.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
+        };
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (list.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_overdue(&self, frame: &mut Frame, area: Rect, report: &OverdueScreen) {
+        let plural = if report.days == 1 { "" } else { "s" };
+        let title = format!("Overdue • out more than {} day{plural}", report.days);
+        let block = Block::default().title(title).borders(Borders::ALL);
+
+        let lines: Vec<Line> = if report.entries.is_empty() {
+            vec![Line::from("No binders are overdue.")]
+        } else {
+            report
+                .entries
+                .iter()
+                .enumerate()
+                .map(|(idx, entry)| {
+                    let pointer = if idx == report.selected { "▶ " } else { "  " };
+                    let holder = entry
+                        .chorister
+                        .as_ref()
+                        .map_or("(nobody recorded)".to_string(), Chorister::display_name);
+                    let text = format!(
+                        "{pointer}Binder {:02}  {:>4} days  since {}  {holder}",
+                        entry.binder.number, entry.days_out, entry.checkout.checked_out_at
+                    );
+                    if idx == report.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
+        };
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (report.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_choristers(&self, frame: &mut Frame, area: Rect, list: &ChoristerScreen) {
+        let block = Block::default().title("Choristers").borders(Borders::ALL);
+
+        let lines: Vec<Line> = if list.choristers.is_empty() {
+            vec![Line::from("No choristers yet. Press + to add one.")]
+        } else {
+            list.choristers
+                .iter()
+                .enumerate()
+                .map(|(idx, chorister)| {
+                    let pointer = if idx == list.selected { "▶ " } else { "  " };
+                    let mut text = format!("{pointer}{:<24}", chorister.name);
+                    if !chorister.voice_part.is_empty() {
+                        text.push_str(&format!("  {:<10}", chorister.voice_part));
+                    }
+                    if !chorister.contact.is_empty() {
+                        text.push_str(&format!("  {}", chorister.contact));
+                    }
+                    let held: Vec<String> = self
+                        .binders
+                        .iter()
+                        .filter(|binder| binder.holder_id == Some(chorister.id))
+                        .map(|binder| format!("Binder {:02}", binder.number))
+                        .collect();
+                    if !held.is_empty() {
+                        text.push_str(&format!("  • holds {}", held.join(", ")));
+                    }
+                    if idx == list.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
+        };
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (list.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
+    fn draw_setlist(&self, frame: &mut Frame, area: Rect, setlist: &SetlistScreen) {
+        let title = format!("Setlist • {}", setlist.concert.display_title());
+        let block = Block::default().title(title).borders(Borders::ALL);
+
+        let lines: Vec<Line> = if setlist.songs.is_empty() {
+            vec![Line::from(
+                "No songs on this setlist yet. Press + to add some.",
+            )]
+        } else {
+            setlist
+                .songs
+                .iter()
+                .enumerate()
+                .map(|(idx, song)| {
+                    let pointer = if idx == setlist.selected {
+                        "▶ "
+                    } else {
+                        "  "
+                    };
+                    let text = format!("{pointer}{:>2}. {}", idx + 1, song.display_title());
+                    if idx == setlist.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
+        };
+
+        let visible = area.height.saturating_sub(2) as usize;
+        let scroll = (setlist.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, area);
+    }
+
     fn draw_footer(&self, frame: &mut Frame, area: Rect) {
         let block = Block::default().borders(Borders::TOP);
         frame.render_widget(block.clone(), area);
@@ -1351,7 +2234,7 @@ impl App {
     }
 
     fn draw_search_bar(&self, frame: &mut Frame, area: Rect, state: &SearchState) {
-        let height = 3u16.min(area.height);
+        let height = SEARCH_BAR_HEIGHT.min(area.height);
         let popup_area = Rect {
             x: area.x,
             y: area.y,
@@ -1377,7 +2260,7 @@ impl App {
             .fg(Color::Cyan)
             .add_modifier(Modifier::BOLD);
         match (&self.screen, &self.mode) {
-            (_, Mode::SelectingSong(_)) => Line::from(vec![
+            (_, Mode::PickingSetlistSongs(_)) | (_, Mode::SelectingSong(_)) => Line::from(vec![
                 Span::styled("[↑↓]", key_style),
                 Span::raw(" Navigate   "),
                 Span::styled("[Space]", key_style),
@@ -1387,17 +2270,39 @@ impl App {
                 Span::styled("[Esc]", key_style),
                 Span::raw(" Cancel"),
             ]),
+            (_, Mode::PickingLink(_))
+            | (_, Mode::AddingLink { .. })
+            | (_, Mode::AttachingFile { .. }) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Select   "),
+                Span::styled("[Enter]", key_style),
+                Span::raw(" Open   "),
+                Span::styled("[+]", key_style),
+                Span::raw(" Add Link   "),
+                Span::styled("[a]", key_style),
+                Span::raw(" Attach File   "),
+                Span::styled("[-]", key_style),
+                Span::raw(" Delete   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Close"),
+            ]),
             (Screen::ToPrint(report), _) => {
                 if report.director_exists {
                     Line::from(vec![
                         Span::styled("[Space]", key_style),
-                        Span::raw(" Toggle   "),
+                        Span::raw(" Next Stage   "),
                         Span::styled("[Tab]", key_style),
                         Span::raw(" Toggle View   "),
                         Span::styled("[↑↓]", key_style),
                         Span::raw(" Navigate   "),
                         Span::styled("[PgUp/PgDn]", key_style),
                         Span::raw(" Page   "),
+                        Span::styled("[x]", key_style),
+                        Span::raw(" Checklist   "),
+                        Span::styled("[b]", key_style),
+                        Span::raw(" Print Job   "),
+                        Span::styled("[s]", key_style),
+                        Span::raw(" Send to Printer   "),
                         Span::styled("[p]", key_style),
                         Span::raw(" Back   "),
                         Span::styled("[q]", key_style),
@@ -1412,15 +2317,11 @@ impl App {
                     ])
                 }
             }
-            (Screen::SongManager(_), _) => Line::from(vec![
+            (Screen::Concerts(_), _) => Line::from(vec![
                 Span::styled("[↑↓]", key_style),
                 Span::raw(" Select   "),
                 Span::styled("[Enter]", key_style),
-                Span::raw(" Open Link   "),
-                Span::styled("[f]", key_style),
-                Span::raw(" Search   "),
-                Span::styled("[l]", key_style),
-                Span::raw(" Toggle No-Link   "),
+                Span::raw(" Setlist   "),
                 Span::styled("[+]", key_style),
                 Span::raw(" Add   "),
                 Span::styled("[-]", key_style),
@@ -1429,66 +2330,188 @@ impl App {
                 Span::raw(" Edit   "),
                 Span::styled("[p]", key_style),
                 Span::raw(" To Print   "),
-                Span::styled("[s]", key_style),
-                Span::raw(" Binders   "),
+                Span::styled("[m]", key_style),
+                Span::raw(" Mark Performed   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Back   "),
                 Span::styled("[q]", key_style),
                 Span::raw(" Quit"),
             ]),
-            (Screen::Songs(_), _) => Line::from(vec![
+            (_, Mode::BinderLog(_)) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Scroll   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Close"),
+            ]),
+            (Screen::Overdue(_), _) => Line::from(vec![
                 Span::styled("[↑↓]", key_style),
                 Span::raw(" Select   "),
+                Span::styled("[i]", key_style),
+                Span::raw(" Check in   "),
+                Span::styled("[+/-]", key_style),
+                Span::raw(" Period ±7 days   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Back   "),
+                Span::styled("[q]", key_style),
+                Span::raw(" Quit"),
+            ]),
+            (_, Mode::AssigningBinder(_)) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Navigate   "),
                 Span::styled("[Enter]", key_style),
-                Span::raw(" Open Link   "),
-                Span::styled("[f]", key_style),
-                Span::raw(" Search   "),
+                Span::raw(" Assign   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Cancel"),
+            ]),
+            (Screen::Choristers(_), _) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Select   "),
                 Span::styled("[+]", key_style),
                 Span::raw(" Add   "),
                 Span::styled("[-]", key_style),
-                Span::raw(" Remove   "),
+                Span::raw(" Delete   "),
                 Span::styled("[e]", key_style),
                 Span::raw(" Edit   "),
-                Span::styled("[s]", key_style),
-                Span::raw(" Song Manager   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Back   "),
+                Span::styled("[q]", key_style),
+                Span::raw(" Quit"),
+            ]),
+            (Screen::Setlist(_), _) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Select   "),
+                Span::styled("[+]", key_style),
+                Span::raw(" Add   "),
+                Span::styled("[-]", key_style),
+                Span::raw(" Remove   "),
+                Span::styled("[[/]]", key_style),
+                Span::raw(" Move   "),
                 Span::styled("[p]", key_style),
                 Span::raw(" To Print   "),
                 Span::styled("[Esc]", key_style),
+                Span::raw(" Concerts   "),
+                Span::styled("[q]", key_style),
+                Span::raw(" Quit"),
+            ]),
+            (Screen::Backups(_), _) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Select   "),
+                Span::styled("[Enter]", key_style),
+                Span::raw(" Restore   "),
+                Span::styled("[b]", key_style),
                 Span::raw(" Back   "),
                 Span::styled("[q]", key_style),
                 Span::raw(" Quit"),
             ]),
-            _ => Line::from(vec![
-                Span::styled("[←↑↓→]", key_style),
-                Span::raw(" Move   "),
+            (_, Mode::SongHistory(_)) | (_, Mode::AddingPerformance { .. }) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Select   "),
+                Span::styled("[+]", key_style),
+                Span::raw(" Record Performance   "),
+                Span::styled("[-]", key_style),
+                Span::raw(" Delete   "),
+                Span::styled("[Esc]", key_style),
+                Span::raw(" Close"),
+            ]),
+            (Screen::SongManager(_), _) => Line::from(vec![
+                Span::styled("[↑↓]", key_style),
+                Span::raw(" Select   "),
                 Span::styled("[Enter]", key_style),
-                Span::raw(" Open   "),
+                Span::raw(" Links   "),
+                Span::styled("[f]", key_style),
+                Span::raw(" Search   "),
+                Span::styled("[l/n/t]", key_style),
+                Span::raw(" Filters   "),
+                Span::styled("[h]", key_style),
+                Span::raw(" History   "),
+                Span::styled("[i/x]", key_style),
+                Span::raw(" CSV   "),
                 Span::styled("[+]", key_style),
                 Span::raw(" Add   "),
                 Span::styled("[-]", key_style),
-                Span::raw(" Remove   "),
+                Span::raw(" Delete   "),
                 Span::styled("[e]", key_style),
                 Span::raw(" Edit   "),
-                Span::styled("[s]", key_style),
-                Span::raw(" Song Manager   "),
                 Span::styled("[p]", key_style),
                 Span::raw(" To Print   "),
+                Span::styled("[s]", key_style),
+                Span::raw(" Binders   "),
                 Span::styled("[q]", key_style),
                 Span::raw(" Quit"),
             ]),
-        }
-    }
-
-    fn draw_binder_form(&self, frame: &mut Frame, area: Rect, title: &str, form: &BinderForm) {
-        let popup_area = centered_rect(60, 40, area);
-        frame.render_widget(Clear, popup_area);
-
-        let block = Block::default().title(title).borders(Borders::ALL);
+            (Screen::Songs(songs), _) => {
+                let mut spans = vec![
+                    Span::styled("[↑↓]", key_style),
+                    Span::raw(" Select   "),
+                    Span::styled("[Enter]", key_style),
+                    Span::raw(" Links   "),
+                    Span::styled("[f]", key_style),
+                    Span::raw(" Search   "),
+                    Span::styled("[+]", key_style),
+                    Span::raw(" Add   "),
+                    Span::styled("[-]", key_style),
+                    Span::raw(" Remove   "),
+                    Span::styled("[e]", key_style),
+                    Span::raw(" Edit   "),
+                    Span::styled("[[/]]", key_style),
+                    Span::raw(" Move   "),
+                ];
+                if songs.binder.number == 0 {
+                    spans.push(Span::styled("[o]", key_style));
+                    spans.push(Span::raw(" Order to All   "));
+                }
+                spans.extend([
+                    Span::styled("[s]", key_style),
+                    Span::raw(" Songs   "),
+                    Span::styled("[p]", key_style),
+                    Span::raw(" To Print   "),
+                    Span::styled("[Esc]", key_style),
+                    Span::raw(" Back   "),
+                    Span::styled("[q]", key_style),
+                    Span::raw(" Quit"),
+                ]);
+                Line::from(spans)
+            }
+            _ => Line::from(vec![
+                Span::styled("[Enter]", key_style),
+                Span::raw(" Open  "),
+                Span::styled("[+/-/e]", key_style),
+                Span::raw(" Add/Del/Edit  "),
+                Span::styled("[t/T]", key_style),
+                Span::raw(" Index  "),
+                Span::styled("[s]", key_style),
+                Span::raw(" Songs  "),
+                Span::styled("[p]", key_style),
+                Span::raw(" To Print  "),
+                Span::styled("[c]", key_style),
+                Span::raw(" Concerts  "),
+                Span::styled("[m]", key_style),
+                Span::raw(" Members  "),
+                Span::styled("[a]", key_style),
+                Span::raw(" Assign  "),
+                Span::styled("[o/i/h/d]", key_style),
+                Span::raw(" Checkouts  "),
+                Span::styled("[b]", key_style),
+                Span::raw(" Backups  "),
+                Span::styled("[q]", key_style),
+                Span::raw(" Quit"),
+            ]),
+        }
+    }
+
+    fn draw_binder_form(&self, frame: &mut Frame, area: Rect, title: &str, form: &BinderForm) {
+        let popup_area = centered_rect(60, 40, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default().title(title).borders(Borders::ALL);
         frame.render_widget(block.clone(), popup_area);
         let inner = block.inner(popup_area);
 
         let number_line = form.build_line("Number", BinderField::Number);
         let label_line = form.build_line("Label", BinderField::Label);
+        let part_line = form.build_line("Part", BinderField::VoicePart);
 
-        let mut lines = vec![number_line, label_line, Line::from("")];
+        let mut lines = vec![number_line, label_line, part_line, Line::from("")];
 
         if let Some(error) = &form.error {
             lines.push(Line::from(Span::styled(
@@ -1520,23 +2543,30 @@ impl App {
                     inner.y + 1,
                 )
             }
+            BinderField::VoicePart => {
+                let prefix = "Part: ".len() as u16;
+                (
+                    inner.x + prefix + form.value_len(BinderField::VoicePart) as u16,
+                    inner.y + 2,
+                )
+            }
         };
         frame.set_cursor_position((cursor_x, cursor_y));
     }
 
     fn draw_song_form(&self, frame: &mut Frame, area: Rect, title: &str, form: &SongForm) {
-        let popup_area = centered_rect(70, 50, area);
+        let popup_area = centered_rect(70, 70, area);
         frame.render_widget(Clear, popup_area);
 
         let block = Block::default().title(title).borders(Borders::ALL);
         frame.render_widget(block.clone(), popup_area);
         let inner = block.inner(popup_area);
 
-        let title_line = form.build_line("Title", SongField::Title);
-        let composer_line = form.build_line("Composer", SongField::Composer);
-        let link_line = form.build_line("Link", SongField::Link);
-
-        let mut lines = vec![title_line, composer_line, link_line, Line::from("")];
+        let mut lines: Vec<Line> = SongField::ALL
+            .iter()
+            .map(|&field| form.build_line(field))
+            .collect();
+        lines.push(Line::from(""));
 
         if let Some(error) = &form.error {
             lines.push(Line::from(Span::styled(
@@ -1553,30 +2583,161 @@ impl App {
         let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
         frame.render_widget(paragraph, inner);
 
-        let (cursor_x, cursor_y) = match form.active {
-            SongField::Title => {
-                let prefix = "Title: ".len() as u16;
-                (
-                    inner.x + prefix + form.value_len(SongField::Title) as u16,
-                    inner.y,
-                )
-            }
-            SongField::Composer => {
-                let prefix = "Composer: ".len() as u16;
-                (
-                    inner.x + prefix + form.value_len(SongField::Composer) as u16,
-                    inner.y + 1,
-                )
-            }
-            SongField::Link => {
-                let prefix = "Link: ".len() as u16;
-                (
-                    inner.x + prefix + form.value_len(SongField::Link) as u16,
-                    inner.y + 2,
-                )
+        if let Some(row) = SongField::ALL
+            .iter()
+            .position(|&field| field == form.active)
+        {
+            let prefix = format!("{}: ", form.active.label()).chars().count() as u16;
+            frame.set_cursor_position((
+                inner.x + prefix + form.value_len(form.active) as u16,
+                inner.y + row as u16,
+            ));
+        }
+    }
+
+    fn draw_csv_import_form(&self, frame: &mut Frame, area: Rect, form: &CsvImportForm) {
+        let popup_area = centered_rect(70, 50, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Import Songs from CSV")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let mut lines: Vec<Line> = CsvImportField::ALL
+            .iter()
+            .map(|field| form.build_line(*field))
+            .collect();
+        lines.push(Line::from(""));
+
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Columns take a header name or number • Enter to preview • Tab to switch • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+
+        let row = CsvImportField::ALL
+            .iter()
+            .position(|field| *field == form.active)
+            .unwrap_or(0) as u16;
+        let prefix = format!("{}: ", form.active.label()).chars().count() as u16;
+        frame.set_cursor_position((
+            inner.x + prefix + form.value_len(form.active) as u16,
+            inner.y + row,
+        ));
+    }
+
+    fn draw_export_form(&self, frame: &mut Frame, area: Rect, form: &ExportForm) {
+        let popup_area = centered_rect(70, 30, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Export Library to CSV")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let mut lines = vec![
+            Line::from(vec![
+                Span::raw("Folder: "),
+                Span::styled(form.dir.clone(), Style::default().fg(Color::Yellow)),
+            ]),
+            Line::from("Writes songs, binders and memberships as dated CSV files."),
+            Line::from(""),
+        ];
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Enter to export • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+        frame.set_cursor_position((
+            inner.x + "Folder: ".len() as u16 + form.dir.chars().count() as u16,
+            inner.y,
+        ));
+    }
+
+    fn draw_csv_import_preview(&self, frame: &mut Frame, area: Rect, preview: &CsvImportPreview) {
+        let popup_area = centered_rect(70, 60, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Import Preview (dry run)")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let summary = preview.plan.summary();
+        let mut lines = vec![
+            Line::from(format!("File: {}", preview.form.path.trim())),
+            Line::from(""),
+            Line::from(format!("New songs: {}", summary.new_songs)),
+            Line::from(format!("Already in library: {}", summary.existing_songs)),
+            Line::from(format!("Duplicate rows merged: {}", summary.duplicate_rows)),
+            Line::from(format!(
+                "Binder assignments: {}",
+                summary.binder_assignments
+            )),
+            Line::from(format!("Rows skipped: {}", summary.skipped_rows)),
+        ];
+
+        let issue_style = Style::default().fg(Color::Red);
+        let issues = preview
+            .plan
+            .skipped
+            .iter()
+            .map(|issue| format!("Line {}: {} Skipped.", issue.line, issue.message))
+            .chain(
+                preview
+                    .plan
+                    .warnings
+                    .iter()
+                    .map(|issue| format!("Line {}: {}", issue.line, issue.message)),
+            );
+        let mut issues_shown = false;
+        for issue in issues {
+            if !issues_shown {
+                lines.push(Line::from(""));
+                issues_shown = true;
             }
-        };
-        frame.set_cursor_position((cursor_x, cursor_y));
+            lines.push(Line::from(Span::styled(issue, issue_style)));
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Enter to import in one step • Esc to change the mapping",
+            Style::default().fg(Color::Gray),
+        )));
+
+        // Keep the instructions visible when the issue list is long.
+        let height = inner.height as usize;
+        if lines.len() > height && height > 2 {
+            let hint = lines.pop();
+            lines.truncate(height - 2);
+            lines.push(Line::from("…"));
+            lines.extend(hint);
+        }
+
+        let paragraph = Paragraph::new(lines);
+        frame.render_widget(paragraph, inner);
     }
 
     fn draw_confirm_binder(&self, frame: &mut Frame, area: Rect, confirm: &ConfirmBinderDelete) {
@@ -1608,22 +2769,26 @@ impl App {
         frame.render_widget(paragraph, inner);
     }
 
-    fn draw_confirm_song(&self, frame: &mut Frame, area: Rect, confirm: &ConfirmSongRemove) {
+    fn draw_confirm_backup_restore(&self, frame: &mut Frame, area: Rect, backup: &BackupInfo) {
         let popup_area = centered_rect(60, 30, area);
         frame.render_widget(Clear, popup_area);
 
         let block = Block::default()
-            .title("Remove Song from Binder")
+            .title("Restore Backup")
             .borders(Borders::ALL);
         frame.render_widget(block.clone(), popup_area);
         let inner = block.inner(popup_area);
 
         let lines = vec![
             Line::from(format!(
-                "Remove '{}' from this binder?",
-                confirm.song.display_title()
+                "Replace the library with the backup from {}?",
+                backup.taken_at.format("%Y-%m-%d %H:%M:%S")
             )),
-            Line::from("This will not delete the song from other binders."),
+            Line::from(format!(
+                "It holds {} binders and {} songs.",
+                backup.binders, backup.songs
+            )),
+            Line::from("The current library is backed up first."),
             Line::from(""),
             Line::from(Span::styled(
                 "Press Y to confirm or N / Esc to cancel.",
@@ -1637,20 +2802,113 @@ impl App {
         frame.render_widget(paragraph, inner);
     }
 
-    fn draw_confirm_song_delete(&self, frame: &mut Frame, area: Rect, confirm: &ConfirmSongDelete) {
+    fn draw_confirm_send_to_printer(&self, frame: &mut Frame, area: Rect, job: &PrintJob) {
+        let popup_area = centered_rect(60, 50, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Send to Printer")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let printer = self
+            .printer
+            .as_ref()
+            .map(Printer::describe)
+            .unwrap_or_default();
+        let mut lines = vec![Line::from(format!("Send these scores to {printer}?"))];
+        lines.extend(job.entries.iter().map(|entry| {
+            Line::from(format!(
+                "  {} × {}  ({})",
+                entry.copies,
+                entry.song.display_title(),
+                entry.file_name
+            ))
+        }));
+        if !job.missing.is_empty() {
+            let titles: Vec<&str> = job
+                .missing
+                .iter()
+                .map(|demand| demand.song.title.as_str())
+                .collect();
+            lines.push(Line::from(format!(
+                "No PDF score for {}.",
+                titles.join(", ")
+            )));
+        }
+        lines.push(Line::from(""));
+        lines.push(Line::from(Span::styled(
+            "Press Y to send or N / Esc to cancel.",
+            Style::default().fg(Color::Gray),
+        )));
+
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: false });
+        frame.render_widget(paragraph, inner);
+    }
+
+    fn draw_concert_form(&self, frame: &mut Frame, area: Rect, title: &str, form: &ConcertForm) {
+        let popup_area = centered_rect(60, 40, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default().title(title).borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let fields = [
+            ("Name", ConcertField::Name),
+            ("Date", ConcertField::Date),
+            ("Venue", ConcertField::Venue),
+        ];
+        let mut lines: Vec<Line> = fields
+            .iter()
+            .map(|&(name, field)| form.build_line(name, field))
+            .collect();
+        lines.push(Line::from(""));
+
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Enter to save • Tab to switch • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+
+        if let Some(row) = fields.iter().position(|&(_, field)| field == form.active) {
+            let (name, field) = fields[row];
+            let prefix = format!("{name}: ").len() as u16;
+            frame.set_cursor_position((
+                inner.x + prefix + form.value_len(field) as u16,
+                inner.y + row as u16,
+            ));
+        }
+    }
+
+    fn draw_confirm_concert_delete(&self, frame: &mut Frame, area: Rect, concert: &Concert) {
         let popup_area = centered_rect(60, 30, area);
         frame.render_widget(Clear, popup_area);
 
-        let block = Block::default().title("Delete Song").borders(Borders::ALL);
+        let block = Block::default()
+            .title("Delete Concert")
+            .borders(Borders::ALL);
         frame.render_widget(block.clone(), popup_area);
         let inner = block.inner(popup_area);
 
         let lines = vec![
             Line::from(format!(
-                "Delete '{}' permanently?",
-                confirm.song.display_title()
+                "Delete {} and its setlist?",
+                concert.display_title()
             )),
-            Line::from("This will remove the song from all binders."),
+            Line::from("The songs stay in the library and in their binders."),
             Line::from(""),
             Line::from(Span::styled(
                 "Press Y to confirm or N / Esc to cancel.",
@@ -1664,305 +2922,869 @@ impl App {
         frame.render_widget(paragraph, inner);
     }
 
-    fn draw_confirm_to_print_exit(
+    fn draw_chorister_form(
         &self,
         frame: &mut Frame,
         area: Rect,
-        confirm: &ConfirmToPrintExit,
+        title: &str,
+        form: &ChoristerForm,
     ) {
-        let popup_area = centered_rect(70, 40, area);
+        let popup_area = centered_rect(60, 40, area);
         frame.render_widget(Clear, popup_area);
 
-        let title = if confirm.exit_app {
-            "Exit Application"
-        } else {
-            "Leave To Print"
-        };
         let block = Block::default().title(title).borders(Borders::ALL);
         frame.render_widget(block.clone(), popup_area);
         let inner = block.inner(popup_area);
 
-        let message = if confirm.exit_app {
-            "You have marked songs as added. Apply the changes before quitting?"
+        let fields = [
+            ("Name", ChoristerField::Name),
+            ("Voice part", ChoristerField::VoicePart),
+            ("Contact", ChoristerField::Contact),
+        ];
+        let mut lines: Vec<Line> = fields
+            .iter()
+            .map(|&(name, field)| form.build_line(name, field))
+            .collect();
+        lines.push(Line::from(""));
+
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
         } else {
-            "You have marked songs as added. Apply the changes before leaving?"
-        };
+            lines.push(Line::from(Span::styled(
+                "Enter to save • Tab to switch • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
+        }
 
-        let mut option_spans = Vec::new();
-        for (idx, label) in confirm.labels().iter().enumerate() {
-            if idx > 0 {
-                option_spans.push(Span::raw("   "));
-            }
-            let style = if confirm.selected_index() == idx {
-                Style::default()
-                    .fg(Color::Yellow)
-                    .add_modifier(Modifier::BOLD)
-            } else {
-                Style::default()
-            };
-            option_spans.push(Span::styled(*label, style));
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+
+        if let Some(row) = fields.iter().position(|&(_, field)| field == form.active) {
+            let (name, field) = fields[row];
+            let prefix = format!("{name}: ").len() as u16;
+            frame.set_cursor_position((
+                inner.x + prefix + form.value_len(field) as u16,
+                inner.y + row as u16,
+            ));
         }
+    }
+
+    fn draw_confirm_chorister_delete(&self, frame: &mut Frame, area: Rect, chorister: &Chorister) {
+        let popup_area = centered_rect(60, 30, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Delete Chorister")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
 
         let lines = vec![
-            Line::from(message),
-            Line::from(""),
-            Line::from(option_spans),
+            Line::from(format!("Delete {}?", chorister.display_name())),
+            Line::from("Any binders they hold become unassigned."),
             Line::from(""),
             Line::from(Span::styled(
-                "Use ←/→ to choose • Enter to confirm • Esc to cancel",
+                "Press Y to confirm or N / Esc to cancel.",
                 Style::default().fg(Color::Gray),
             )),
         ];
 
-        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true });
         frame.render_widget(paragraph, inner);
     }
 
-    fn render_song_cards(&self, frame: &mut Frame, area: Rect, songs: &[Song], selected: usize) {
-        if songs.is_empty() || area.height == 0 {
-            return;
-        }
+    fn draw_binder_log(&self, frame: &mut Frame, area: Rect, log: &BinderLog) {
+        let popup_area = centered_rect(60, 50, area);
+        frame.render_widget(Clear, popup_area);
 
-        let card_height = SONG_CARD_HEIGHT as usize;
-        let capacity = ((area.height as usize) / card_height).max(1);
-        let len = songs.len();
-        let mut start = if selected >= capacity {
-            selected + 1 - capacity
+        let block = Block::default()
+            .title(format!("Checkouts • Binder {:02}", log.binder.number))
+            .borders(Borders::ALL);
+
+        let lines: Vec<Line> = if log.checkouts.is_empty() {
+            vec![Line::from(Span::styled(
+                "Never checked out. Press 'o' on the grid to check it out.",
+                Style::default().fg(Color::Gray),
+            ))]
         } else {
-            0
+            log.checkouts
+                .iter()
+                .enumerate()
+                .map(|(idx, checkout)| {
+                    let pointer = if idx == log.selected { "▶ " } else { "  " };
+                    let back = checkout.checked_in_at.as_deref().unwrap_or("still out");
+                    let mut text = format!("{pointer}{} → {back:<16}", checkout.checked_out_at);
+                    if let Some(chorister) = checkout
+                        .chorister_id
+                        .and_then(|id| self.choristers.iter().find(|chorister| chorister.id == id))
+                    {
+                        text.push_str(&format!("  {}", chorister.name));
+                    }
+                    if idx == log.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
         };
-        if start + capacity > len {
-            start = len.saturating_sub(capacity);
-        }
-        let end = min(start + capacity, len);
-        let visible_len = end.saturating_sub(start);
-        if visible_len == 0 {
-            return;
-        }
-
-        let constraints: Vec<Constraint> = (0..visible_len)
-            .map(|_| Constraint::Length(SONG_CARD_HEIGHT))
-            .collect();
-        let rows = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(constraints)
-            .split(area);
-
-        for (idx, chunk) in rows.iter().enumerate() {
-            if chunk.height == 0 {
-                continue;
-            }
-
-            let song_index = start + idx;
-            if song_index >= len {
-                break;
-            }
-
-            let song = &songs[song_index];
-            let mut block = Block::default().borders(Borders::ALL);
-            let mut paragraph_style = Style::default();
-            if song_index == selected {
-                block = block.style(Style::default().fg(Color::Yellow));
-                paragraph_style = Style::default().fg(Color::Yellow);
-            }
 
-            let mut lines = Vec::new();
-            let title = if song_index == selected {
-                format!("▶ {}", song.title)
-            } else {
-                song.title.clone()
-            };
-            lines.push(Line::from(Span::styled(
-                title,
-                Style::default().add_modifier(Modifier::BOLD),
-            )));
+        let visible = popup_area.height.saturating_sub(2) as usize;
+        let scroll = (log.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, popup_area);
+    }
 
-            let composer_text = if song.composer.trim().is_empty() {
-                "Unknown composer".to_string()
-            } else {
-                song.composer.trim().to_string()
-            };
-            lines.push(Line::from(Span::styled(
-                composer_text,
-                Style::default().fg(Color::Gray),
-            )));
+    fn draw_holder_picker(&self, frame: &mut Frame, area: Rect, picker: &HolderPicker) {
+        let popup_area = centered_rect(50, 60, area);
+        frame.render_widget(Clear, popup_area);
 
-            if !song.link.trim().is_empty() {
-                lines.push(Line::from(Span::styled(
-                    song.link.trim().to_string(),
-                    Style::default().fg(Color::Cyan),
-                )));
-            }
+        let title = format!("Assign Binder {:02}", picker.binder.number);
+        let block = Block::default().title(title).borders(Borders::ALL);
 
-            let paragraph = Paragraph::new(lines)
-                .block(block)
-                .wrap(Wrap { trim: true })
-                .alignment(Alignment::Left)
-                .style(paragraph_style);
+        let names = std::iter::once("Nobody".to_string()).chain(picker.choristers.iter().map(
+            |chorister| {
+                let held = picker.binder.holder_id == Some(chorister.id);
+                let marker = if held { "  (current)" } else { "" };
+                format!("{}{marker}", chorister.display_name())
+            },
+        ));
+        let lines: Vec<Line> = names
+            .enumerate()
+            .map(|(idx, name)| {
+                let pointer = if idx == picker.selected { "▶ " } else { "  " };
+                let text = format!("{pointer}{name}");
+                if idx == picker.selected {
+                    Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                } else {
+                    Line::from(text)
+                }
+            })
+            .collect();
 
-            frame.render_widget(paragraph, *chunk);
-        }
+        let visible = popup_area.height.saturating_sub(2) as usize;
+        let scroll = (picker.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, popup_area);
     }
 
-    fn draw_add_song(&self, frame: &mut Frame, area: Rect, state: &AddSongState) {
-        let popup_area = centered_rect(70, 50, area);
+    fn draw_setlist_picker(&self, frame: &mut Frame, area: Rect, picker: &SetlistPicker) {
+        let popup_area = centered_rect(70, 60, area);
         frame.render_widget(Clear, popup_area);
 
         let block = Block::default()
-            .title("Add Song to Binder")
+            .title("Add Songs to Setlist")
             .borders(Borders::ALL);
-        frame.render_widget(block.clone(), popup_area);
-        let inner = block.inner(popup_area);
 
-        let items: Vec<ListItem> = state
-            .items
+        let lines: Vec<Line> = picker
+            .songs
             .iter()
             .enumerate()
-            .map(|(index, item)| match item {
-                AddSongItem::CreateNew => ListItem::new("Create a new song"),
-                AddSongItem::Existing(song) => {
-                    let checkbox = if state.is_checked(index) {
-                        "[x]"
-                    } else {
-                        "[ ]"
-                    };
-                    let mut spans = vec![Span::raw(format!("{checkbox} "))];
-                    if state.director_song_ids.contains(&song.id) {
-                        spans.push(Span::styled(
-                            "*",
-                            Style::default().fg(Color::Rgb(255, 165, 0)),
-                        ));
-                        spans.push(Span::raw(" "));
-                    }
-                    spans.push(Span::raw(song.display_title()));
-                    ListItem::new(Line::from(spans))
+            .map(|(idx, song)| {
+                let pointer = if idx == picker.selected { "▶ " } else { "  " };
+                let checkbox = if picker.checked.contains(&song.id) {
+                    "[x]"
+                } else {
+                    "[ ]"
+                };
+                let text = format!("{pointer}{checkbox} {}", song.display_title());
+                if idx == picker.selected {
+                    Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                } else {
+                    Line::from(text)
                 }
             })
             .collect();
 
-        let list = List::new(items)
-            .block(Block::default().borders(Borders::NONE))
-            .highlight_style(Style::default().fg(Color::Yellow))
-            .highlight_symbol("▶ ");
-
-        let mut list_state = ListState::default();
-        list_state.select(Some(state.selected));
-        frame.render_stateful_widget(list, inner, &mut list_state);
+        let visible = popup_area.height.saturating_sub(2) as usize;
+        let scroll = (picker.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, popup_area);
     }
 
-    fn split_rows(&self, area: Rect) -> Vec<Rect> {
-        let row_count = self.row_count().max(1) as u16;
-        let percent = (100 / row_count).max(1);
-        let chunks = Layout::default()
-            .direction(Direction::Vertical)
-            .constraints(vec![Constraint::Percentage(percent); row_count as usize])
-            .split(area);
-        chunks.iter().cloned().collect()
-    }
+    fn draw_song_history(&self, frame: &mut Frame, area: Rect, history: &SongHistory) {
+        let popup_area = centered_rect(60, 50, area);
+        frame.render_widget(Clear, popup_area);
 
-    fn split_columns(&self, area: Rect) -> Vec<Rect> {
-        let columns = GRID_COLUMNS.max(1) as u16;
-        let percent = (100 / columns).max(1);
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints(vec![Constraint::Percentage(percent); columns as usize])
-            .split(area);
-        chunks.iter().cloned().collect()
-    }
+        let block = Block::default()
+            .title(format!("Performances • {}", history.song.title))
+            .borders(Borders::ALL);
 
-    fn set_status<S: Into<String>>(&mut self, text: S, kind: StatusKind) {
-        self.status = Some(StatusMessage {
-            text: text.into(),
-            kind,
-        });
-    }
+        let lines: Vec<Line> = if history.performances.is_empty() {
+            vec![Line::from(Span::styled(
+                "Not performed yet. Press '+' to record a performance.",
+                Style::default().fg(Color::Gray),
+            ))]
+        } else {
+            history
+                .performances
+                .iter()
+                .enumerate()
+                .map(|(idx, performance)| {
+                    let pointer = if idx == history.selected {
+                        "▶ "
+                    } else {
+                        "  "
+                    };
+                    let text = format!("{pointer}{}  {}", performance.date, performance.event);
+                    if idx == history.selected {
+                        Line::from(Span::styled(text, Style::default().fg(Color::Cyan)))
+                    } else {
+                        Line::from(text)
+                    }
+                })
+                .collect()
+        };
 
-    fn clear_status(&mut self) {
-        self.status = None;
+        let visible = popup_area.height.saturating_sub(2) as usize;
+        let scroll = (history.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, popup_area);
     }
 
-    fn save_new_binder(&mut self, form: &BinderForm) -> Result<()> {
-        let (number, label) = form.parse_inputs()?;
-        let binder = create_binder(&self.conn, number, &label)?;
-        self.reload_binders(Some(binder.id))?;
-        self.set_status(
-            format!("Added Binder {:02}.", binder.number),
-            StatusKind::Info,
-        );
-        Ok(())
-    }
+    fn draw_link_picker(&self, frame: &mut Frame, area: Rect, picker: &LinkPicker) {
+        let popup_area = centered_rect(70, 50, area);
+        frame.render_widget(Clear, popup_area);
 
-    fn save_existing_binder(&mut self, id: i64, form: &BinderForm) -> Result<()> {
-        let (number, label) = form.parse_inputs()?;
-        update_binder(&self.conn, id, number, &label)?;
-        self.reload_binders(Some(id))?;
-        self.set_status(format!("Updated Binder {:02}.", number), StatusKind::Info);
-        if let Screen::Songs(ref mut songs) = self.screen {
-            if songs.binder.id == id {
-                songs.binder.label = label;
-                songs.binder.number = number;
-            }
-        }
-        Ok(())
-    }
+        let block = Block::default()
+            .title(format!("Links • {}", picker.song.title))
+            .borders(Borders::ALL);
 
-    fn perform_delete(&mut self, confirm: &ConfirmBinderDelete) -> Result<()> {
-        delete_binder(&self.conn, confirm.id)?;
-        self.reload_binders(None)?;
-        self.screen = Screen::Binders;
-        self.set_status(
-            format!("Deleted Binder {:02}.", confirm.number),
-            StatusKind::Info,
-        );
-        Ok(())
+        let lines: Vec<Line> = if picker.choices.is_empty() {
+            vec![Line::from(Span::styled(
+                "No links yet. Press '+' to add one or 'a' to attach a file.",
+                Style::default().fg(Color::Gray),
+            ))]
+        } else {
+            picker
+                .choices
+                .iter()
+                .enumerate()
+                .map(|(idx, choice)| {
+                    let pointer = if idx == picker.selected { "▶ " } else { "  " };
+                    let label_style = if idx == picker.selected {
+                        Style::default().fg(Color::Cyan)
+                    } else {
+                        Style::default()
+                    };
+                    Line::from(vec![
+                        Span::styled(format!("{pointer}{}  ", choice.label), label_style),
+                        Span::styled(choice.url.clone(), Style::default().fg(Color::DarkGray)),
+                    ])
+                })
+                .collect()
+        };
+
+        let visible = popup_area.height.saturating_sub(2) as usize;
+        let scroll = (picker.selected + 1).saturating_sub(visible) as u16;
+        let paragraph = Paragraph::new(lines).block(block).scroll((scroll, 0));
+        frame.render_widget(paragraph, popup_area);
     }
 
-    fn reload_binders(&mut self, focus_id: Option<i64>) -> Result<()> {
-        self.binders = fetch_binders(&self.conn)?;
-        if self.binders.is_empty() {
-            self.selected = 0;
-            return Ok(());
-        }
+    fn draw_attach_form(&self, frame: &mut Frame, area: Rect, form: &AttachForm) {
+        let popup_area = centered_rect(70, 30, area);
+        frame.render_widget(Clear, popup_area);
 
-        if let Some(id) = focus_id {
-            if let Some((idx, _)) = self.binders.iter().enumerate().find(|(_, b)| b.id == id) {
-                self.selected = idx;
-                return Ok(());
-            }
-        }
+        let block = Block::default().title("Attach File").borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
 
-        if self.selected >= self.binders.len() {
-            self.selected = self.binders.len().saturating_sub(1);
+        let mut lines = vec![
+            Line::from(vec![
+                Span::raw("File: "),
+                Span::styled(form.path.clone(), Style::default().fg(Color::Yellow)),
+            ]),
+            Line::from("The file is copied into the attachments folder next to the library."),
+            Line::from(""),
+        ];
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Enter to attach • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
         }
 
-        Ok(())
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+        frame.set_cursor_position((
+            inner.x + "File: ".len() as u16 + form.path.chars().count() as u16,
+            inner.y,
+        ));
     }
 
-    fn open_binder_view(&mut self, binder: Binder) -> Result<()> {
-        let songs = fetch_songs_for_binder(&self.conn, binder.id)?;
-        self.screen = Screen::Songs(SongScreen::new(binder, songs));
-        Ok(())
-    }
+    fn draw_link_form(&self, frame: &mut Frame, area: Rect, form: &LinkForm) {
+        let popup_area = centered_rect(60, 35, area);
+        frame.render_widget(Clear, popup_area);
 
-    fn open_relative_binder(&mut self, offset: isize) -> Result<()> {
-        if self.binders.is_empty() {
-            return Ok(());
+        let block = Block::default().title("Add Link").borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let mut lines: Vec<Line> = LinkField::ALL
+            .iter()
+            .map(|&field| form.build_line(field))
+            .collect();
+        lines.push(Line::from(""));
+
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Enter to save • Tab to switch • ←/→ change type • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
         }
 
-        let current_id = match &self.screen {
-            Screen::Songs(songs) => songs.binder.id,
-            _ => return Ok(()),
-        };
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
 
-        let (target_id, binder_clone) = {
-            let mut ordered: Vec<&Binder> = self.binders.iter().collect();
-            ordered.sort_by_key(|binder| binder.number);
-            if ordered.is_empty() {
-                return Ok(());
+        if form.active != LinkField::Kind {
+            if let Some(row) = LinkField::ALL
+                .iter()
+                .position(|&field| field == form.active)
+            {
+                let prefix = format!("{}: ", form.active.label()).len() as u16;
+                frame.set_cursor_position((
+                    inner.x + prefix + form.value_len(form.active) as u16,
+                    inner.y + row as u16,
+                ));
             }
+        }
+    }
 
-            let len = ordered.len() as isize;
-            let current_pos = ordered
+    fn draw_performance_form(&self, frame: &mut Frame, area: Rect, form: &PerformanceForm) {
+        let popup_area = centered_rect(50, 30, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Record Performance")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let fields = [
+            ("Date", PerformanceField::Date),
+            ("Event", PerformanceField::Event),
+        ];
+        let mut lines: Vec<Line> = fields
+            .iter()
+            .map(|&(name, field)| form.build_line(name, field))
+            .collect();
+        lines.push(Line::from(""));
+
+        if let Some(error) = &form.error {
+            lines.push(Line::from(Span::styled(
+                error.clone(),
+                Style::default().fg(Color::Red),
+            )));
+        } else {
+            lines.push(Line::from(Span::styled(
+                "Enter to save • Tab to switch • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )));
+        }
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+
+        if let Some(row) = fields.iter().position(|&(_, field)| field == form.active) {
+            let (name, field) = fields[row];
+            let prefix = format!("{name}: ").len() as u16;
+            frame.set_cursor_position((
+                inner.x + prefix + form.value_len(field) as u16,
+                inner.y + row as u16,
+            ));
+        }
+    }
+
+    fn draw_confirm_copy_order(&self, frame: &mut Frame, area: Rect) {
+        let popup_area = centered_rect(60, 30, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Copy Director's Order")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let lines = vec![
+            Line::from("Put every binder's songs in the director's running order?"),
+            Line::from("Songs the director does not have stay at the end."),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press Y to confirm or N / Esc to cancel.",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+    }
+
+    fn draw_confirm_song(&self, frame: &mut Frame, area: Rect, confirm: &ConfirmSongRemove) {
+        let popup_area = centered_rect(60, 30, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Remove Song from Binder")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let lines = vec![
+            Line::from(format!(
+                "Remove '{}' from this binder?",
+                confirm.song.display_title()
+            )),
+            Line::from("This will not delete the song from other binders."),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press Y to confirm or N / Esc to cancel.",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+    }
+
+    fn draw_confirm_song_delete(&self, frame: &mut Frame, area: Rect, confirm: &ConfirmSongDelete) {
+        let popup_area = centered_rect(60, 30, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default().title("Delete Song").borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let lines = vec![
+            Line::from(format!(
+                "Delete '{}' permanently?",
+                confirm.song.display_title()
+            )),
+            Line::from("This will remove the song from all binders."),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Press Y to confirm or N / Esc to cancel.",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(lines)
+            .alignment(Alignment::Left)
+            .wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+    }
+
+    fn draw_confirm_to_print_exit(
+        &self,
+        frame: &mut Frame,
+        area: Rect,
+        confirm: &ConfirmToPrintExit,
+    ) {
+        let popup_area = centered_rect(70, 40, area);
+        frame.render_widget(Clear, popup_area);
+
+        let title = if confirm.exit_app {
+            "Exit Application"
+        } else {
+            "Leave To Print"
+        };
+        let block = Block::default().title(title).borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let message = if confirm.exit_app {
+            "Some songs are marked as inserted. Add them to their binders before quitting?"
+        } else {
+            "Some songs are marked as inserted. Add them to their binders before leaving?"
+        };
+
+        let mut option_spans = Vec::new();
+        for (idx, label) in confirm.labels().iter().enumerate() {
+            if idx > 0 {
+                option_spans.push(Span::raw("   "));
+            }
+            let style = if confirm.selected_index() == idx {
+                Style::default()
+                    .fg(Color::Yellow)
+                    .add_modifier(Modifier::BOLD)
+            } else {
+                Style::default()
+            };
+            option_spans.push(Span::styled(*label, style));
+        }
+
+        let lines = vec![
+            Line::from(message),
+            Line::from(""),
+            Line::from(option_spans),
+            Line::from(""),
+            Line::from(Span::styled(
+                "Use ←/→ to choose • Enter to confirm • Esc to cancel",
+                Style::default().fg(Color::Gray),
+            )),
+        ];
+
+        let paragraph = Paragraph::new(lines).wrap(Wrap { trim: true });
+        frame.render_widget(paragraph, inner);
+    }
+
+    fn render_song_cards(&self, frame: &mut Frame, area: Rect, songs: &[Song], selected: usize) {
+        if songs.is_empty() || area.height == 0 {
+            return;
+        }
+
+        let card_height = SONG_CARD_HEIGHT as usize;
+        let capacity = ((area.height as usize) / card_height).max(1);
+        let len = songs.len();
+        let mut start = if selected >= capacity {
+            selected + 1 - capacity
+        } else {
+            0
+        };
+        if start + capacity > len {
+            start = len.saturating_sub(capacity);
+        }
+        let end = min(start + capacity, len);
+        let visible_len = end.saturating_sub(start);
+        if visible_len == 0 {
+            return;
+        }
+
+        let constraints: Vec<Constraint> = (0..visible_len)
+            .map(|_| Constraint::Length(SONG_CARD_HEIGHT))
+            .collect();
+        let rows = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(constraints)
+            .split(area);
+
+        for (idx, chunk) in rows.iter().enumerate() {
+            if chunk.height == 0 {
+                continue;
+            }
+
+            let song_index = start + idx;
+            if song_index >= len {
+                break;
+            }
+
+            let song = &songs[song_index];
+            let mut block = Block::default().borders(Borders::ALL);
+            if !song.parts.is_empty() {
+                block =
+                    block.title(Line::from(format!(" Needed by {} ", song.parts)).right_aligned());
+            }
+            let mut paragraph_style = Style::default();
+            if song_index == selected {
+                block = block.style(Style::default().fg(Color::Yellow));
+                paragraph_style = Style::default().fg(Color::Yellow);
+            }
+
+            let mut lines = Vec::new();
+            let title = if song_index == selected {
+                format!("▶ {}", song.title)
+            } else {
+                song.title.clone()
+            };
+            let mut title_spans = vec![Span::styled(
+                title,
+                Style::default().add_modifier(Modifier::BOLD),
+            )];
+            title_spans.extend(tag_chips(tags_of(&self.song_tags, song.id)));
+            lines.push(Line::from(title_spans));
+
+            let composer_text = if song.composer.trim().is_empty() {
+                "Unknown composer".to_string()
+            } else {
+                song.composer.trim().to_string()
+            };
+            let history_text = match self.performance_stats.get(&song.id) {
+                Some(stats) => {
+                    let plural = if stats.count == 1 { "" } else { "s" };
+                    format!(
+                        "Last performed {} • {} performance{plural}",
+                        stats.last_date, stats.count
+                    )
+                }
+                None => "Never performed".to_string(),
+            };
+            lines.push(Line::from(vec![
+                Span::styled(composer_text, Style::default().fg(Color::Gray)),
+                Span::raw("  •  "),
+                Span::styled(history_text, Style::default().fg(Color::DarkGray)),
+            ]));
+
+            let details = song.details.summary();
+            if !details.is_empty() {
+                lines.push(Line::from(Span::styled(
+                    details,
+                    Style::default().fg(Color::Magenta),
+                )));
+            }
+
+            let mut link_spans = Vec::new();
+            if let Some(links) = self.song_links.get(&song.id) {
+                link_spans.push(Span::styled(
+                    link_summary(links),
+                    Style::default().fg(Color::Blue),
+                ));
+            }
+            if let Some(attachments) = self.song_attachments.get(&song.id) {
+                if !link_spans.is_empty() {
+                    link_spans.push(Span::raw("  +  "));
+                }
+                link_spans.push(Span::styled(
+                    attachment_summary(attachments),
+                    Style::default().fg(Color::Green),
+                ));
+            }
+            if !link_spans.is_empty() {
+                lines.push(Line::from(link_spans));
+            }
+
+            let paragraph = Paragraph::new(lines)
+                .block(block)
+                .wrap(Wrap { trim: true })
+                .alignment(Alignment::Left)
+                .style(paragraph_style);
+
+            frame.render_widget(paragraph, *chunk);
+        }
+    }
+
+    fn draw_add_song(&self, frame: &mut Frame, area: Rect, state: &AddSongState) {
+        let popup_area = centered_rect(70, 50, area);
+        frame.render_widget(Clear, popup_area);
+
+        let block = Block::default()
+            .title("Add Song to Binder")
+            .borders(Borders::ALL);
+        frame.render_widget(block.clone(), popup_area);
+        let inner = block.inner(popup_area);
+
+        let items: Vec<ListItem> = state
+            .items
+            .iter()
+            .enumerate()
+            .map(|(index, item)| match item {
+                AddSongItem::CreateNew => ListItem::new("Create a new song"),
+                AddSongItem::Existing(song) => {
+                    let checkbox = if state.is_checked(index) {
+                        "[x]"
+                    } else {
+                        "[ ]"
+                    };
+                    let mut spans = vec![Span::raw(format!("{checkbox} "))];
+                    if state.director_song_ids.contains(&song.id) {
+                        spans.push(Span::styled(
+                            "*",
+                            Style::default().fg(Color::Rgb(255, 165, 0)),
+                        ));
+                        spans.push(Span::raw(" "));
+                    }
+                    spans.push(Span::raw(song.display_title()));
+                    ListItem::new(Line::from(spans))
+                }
+            })
+            .collect();
+
+        let list = List::new(items)
+            .block(Block::default().borders(Borders::NONE))
+            .highlight_style(Style::default().fg(Color::Yellow))
+            .highlight_symbol("▶ ");
+
+        let mut list_state = ListState::default();
+        list_state.select(Some(state.selected));
+        frame.render_stateful_widget(list, inner, &mut list_state);
+    }
+
+    fn split_rows(&self, area: Rect) -> Vec<Rect> {
+        let row_count = self.row_count().max(1) as u16;
+        let percent = (100 / row_count).max(1);
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(vec![Constraint::Percentage(percent); row_count as usize])
+            .split(area);
+        chunks.iter().cloned().collect()
+    }
+
+    fn split_columns(&self, area: Rect) -> Vec<Rect> {
+        let columns = GRID_COLUMNS.max(1) as u16;
+        let percent = (100 / columns).max(1);
+        let chunks = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints(vec![Constraint::Percentage(percent); columns as usize])
+            .split(area);
+        chunks.iter().cloned().collect()
+    }
+
+    fn set_status<S: Into<String>>(&mut self, text: S, kind: StatusKind) {
+        self.status = Some(StatusMessage {
+            text: text.into(),
+            kind,
+        });
+    }
+
+    fn clear_status(&mut self) {
+        self.status = None;
+    }
+
+    fn save_new_binder(&mut self, number: i64, label: &str, part: &str) -> Result<(), DbError> {
+        let mut created = None;
+        self.library.transaction(&mut |library| {
+            let binder = library.create_binder(number, label)?;
+            if !part.is_empty() {
+                library.set_binder_part(binder.id, part)?;
+            }
+            created = Some(binder);
+            Ok(())
+        })?;
+        let binder = created.expect("transaction created the binder");
+        self.reload_binders(Some(binder.id))?;
+        self.set_status(
+            format!("Added Binder {:02}.", binder.number),
+            StatusKind::Info,
+        );
+        Ok(())
+    }
+
+    fn save_existing_binder(
+        &mut self,
+        id: i64,
+        number: i64,
+        label: String,
+        part: String,
+    ) -> Result<(), DbError> {
+        self.library.transaction(&mut |library| {
+            library.update_binder(id, number, &label)?;
+            library.set_binder_part(id, &part)
+        })?;
+        self.reload_binders(Some(id))?;
+        self.set_status(format!("Updated Binder {:02}.", number), StatusKind::Info);
+        if let Screen::Songs(ref mut songs) = self.screen {
+            if songs.binder.id == id {
+                songs.binder.label = label;
+                songs.binder.number = number;
+                songs.binder.voice_part = part;
+            }
+        }
+        Ok(())
+    }
+
+    /// React to a failed binder save. Returns whether the form should stay open:
+    /// a duplicate number sends focus back to the number field, while a binder
+    /// that vanished underneath an edit closes the form and reloads the grid.
+    fn handle_binder_save_error(&mut self, form: &mut BinderForm, err: DbError) -> Result<bool> {
+        match err {
+            DbError::DuplicateBinderNumber(_) => {
+                form.focus(BinderField::Number);
+                let message = err.to_string();
+                form.error = Some(message.clone());
+                self.set_status(message, StatusKind::Error);
+                Ok(true)
+            }
+            DbError::NotFound(Entity::Binder) => {
+                self.reload_binders(None)?;
+                self.set_status("That binder no longer exists.", StatusKind::Error);
+                Ok(false)
+            }
+            other => {
+                let message = other.to_string();
+                form.error = Some(message.clone());
+                self.set_status(message, StatusKind::Error);
+                Ok(true)
+            }
+        }
+    }
+
+    fn perform_delete(&mut self, confirm: &ConfirmBinderDelete) -> Result<(), DbError> {
+        self.backup_before(BackupReason::DeleteBinder)?;
+        self.library.delete_binder(confirm.id)?;
+        self.reload_binders(None)?;
+        self.screen = Screen::Binders;
+        self.set_status(
+            format!("Deleted Binder {:02}.", confirm.number),
+            StatusKind::Info,
+        );
+        Ok(())
+    }
+
+    /// Take a backup ahead of a destructive change. Callers abort the change if
+    /// this fails, so nothing is lost without a copy.
+    fn backup_before(&mut self, reason: BackupReason) -> Result<(), DbError> {
+        if let Some(store) = &self.backups {
+            store.create(self.library.as_ref(), reason)?;
+        }
+        Ok(())
+    }
+
+    fn reload_binders(&mut self, focus_id: Option<i64>) -> Result<(), DbError> {
+        self.binders = self.library.fetch_binders()?;
+        self.choristers = self.library.fetch_choristers()?;
+        self.checkouts = open_checkouts(self.library.as_ref())?;
+        if self.binders.is_empty() {
+            self.selected = 0;
+            return Ok(());
+        }
+
+        if let Some(id) = focus_id {
+            if let Some((idx, _)) = self.binders.iter().enumerate().find(|(_, b)| b.id == id) {
+                self.selected = idx;
+                return Ok(());
+            }
+        }
+
+        if self.selected >= self.binders.len() {
+            self.selected = self.binders.len().saturating_sub(1);
+        }
+
+        Ok(())
+    }
+
+    fn open_binder_view(&mut self, binder: Binder) -> Result<()> {
+        let songs = self.library.fetch_songs_for_binder(binder.id)?;
+        self.reload_song_summaries()?;
+        self.screen = Screen::Songs(SongScreen::new(binder, songs));
+        Ok(())
+    }
+
+    fn open_relative_binder(&mut self, offset: isize) -> Result<()> {
+        if self.binders.is_empty() {
+            return Ok(());
+        }
+
+        let current_id = match &self.screen {
+            Screen::Songs(songs) => songs.binder.id,
+            _ => return Ok(()),
+        };
+
+        let (target_id, binder_clone) = {
+            let mut ordered: Vec<&Binder> = self.binders.iter().collect();
+            ordered.sort_by_key(|binder| binder.number);
+            if ordered.is_empty() {
+                return Ok(());
+            }
+
+            let len = ordered.len() as isize;
+            let current_pos = ordered
                 .iter()
                 .position(|binder| binder.id == current_id)
                 .unwrap_or(0);
@@ -1971,221 +3793,1528 @@ impl App {
             (binder_ref.id, binder_ref.clone())
         };
 
-        if let Some(idx) = self
-            .binders
-            .iter()
-            .position(|binder| binder.id == target_id)
-        {
-            self.selected = idx;
+        if let Some(idx) = self
+            .binders
+            .iter()
+            .position(|binder| binder.id == target_id)
+        {
+            self.selected = idx;
+        }
+
+        self.open_binder_view(binder_clone)
+    }
+
+    fn open_backups(&mut self) -> Result<()> {
+        let Some(store) = &self.backups else {
+            self.set_status("Backups are turned off.", StatusKind::Error);
+            return Ok(());
+        };
+        self.screen = Screen::Backups(BackupScreen::new(store.list()?));
+        Ok(())
+    }
+
+    /// Write the index sheet of `binder`, or of every binder, into the exports
+    /// folder as HTML and plain text, then open the HTML copy for printing.
+    fn print_binder_index(&mut self, binder: Option<&Binder>) -> Result<()> {
+        let Some(dir) = self.data_dir.as_ref().map(|dir| dir.join(EXPORT_DIR_NAME)) else {
+            self.set_status("No data folder to write the index into.", StatusKind::Error);
+            return Ok(());
+        };
+        let (contents, name, what) = match binder {
+            Some(binder) => (
+                vec![binder_contents(self.library.as_ref(), binder)?],
+                format!("binder-{:02}", binder.number),
+                format!("Binder {:02}", binder.number),
+            ),
+            None => {
+                let contents = all_binder_contents(self.library.as_ref())?;
+                let what = format!("all {} binders", contents.len());
+                (contents, "all-binders".to_string(), what)
+            }
+        };
+        let stamp = Local::now().format("%Y-%m-%d");
+        let html = dir.join(format!("index-{name}-{stamp}.html"));
+        let text = dir.join(format!("index-{name}-{stamp}.txt"));
+        let written = fs::create_dir_all(&dir)
+            .and_then(|()| fs::write(&html, contents_html(&contents)))
+            .and_then(|()| fs::write(&text, contents_text(&contents)));
+        if let Err(err) = written {
+            self.set_status(
+                format!("Could not write the index: {err}"),
+                StatusKind::Error,
+            );
+            return Ok(());
+        }
+        match (self.opener)(&html.to_string_lossy()) {
+            Ok(()) => self.set_status(
+                format!(
+                    "Opened the index of {what} for printing ({}, plain text alongside).",
+                    html.display()
+                ),
+                StatusKind::Info,
+            ),
+            Err(err) => self.set_status(
+                format!(
+                    "Wrote the index of {what} to {}, but could not open it: {err}",
+                    html.display()
+                ),
+                StatusKind::Error,
+            ),
+        }
+        Ok(())
+    }
+
+    /// Write the To Print report as a Markdown and HTML checklist plus songs
+    /// and binders CSV files into the exports folder, then open the HTML copy
+    /// for printing. Copies ticked off as printed or inserted are already on
+    /// the print queue, so the counts match the screen.
+    fn export_to_print_checklist(&mut self, concert: Option<&Concert>) -> Result<()> {
+        let Some(dir) = self.data_dir.as_ref().map(|dir| dir.join(EXPORT_DIR_NAME)) else {
+            self.set_status(
+                "No data folder to write the checklist into.",
+                StatusKind::Error,
+            );
+            return Ok(());
+        };
+        let target = concert.map_or(ToPrintTarget::Director, |concert| {
+            ToPrintTarget::Concert(concert.id)
+        });
+        let Some(report) = to_print_report_for(self.library.as_ref(), target)? else {
+            return Ok(());
+        };
+        let title = checklist_title(concert);
+        let stamp = Local::now().format("%Y-%m-%d").to_string();
+        let written = fs::create_dir_all(&dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| {
+                ChecklistFormat::ALL
+                    .into_iter()
+                    .map(|format| {
+                        let path = dir.join(format.file_name(&stamp));
+                        fs::write(&path, render_checklist(&report, &title, format)?)?;
+                        Ok(path)
+                    })
+                    .collect::<Result<Vec<_>>>()
+            });
+        let html = match written {
+            Ok(paths) => paths[1].clone(),
+            Err(err) => {
+                self.set_status(
+                    format!("Could not write the checklist: {err}"),
+                    StatusKind::Error,
+                );
+                return Ok(());
+            }
+        };
+        match (self.opener)(&html.to_string_lossy()) {
+            Ok(()) => self.set_status(
+                format!(
+                    "Opened the checklist for printing; Markdown and CSV copies are in {}.",
+                    dir.display()
+                ),
+                StatusKind::Info,
+            ),
+            Err(err) => self.set_status(
+                format!(
+                    "Wrote the checklist to {}, but could not open it: {err}",
+                    dir.display()
+                ),
+                StatusKind::Error,
+            ),
+        }
+        Ok(())
+    }
+
+    /// Combine the PDF scores of `songs` into one print job in the exports
+    /// folder, each repeated for the copies still needed, and open it. Songs
+    /// ticked off on the report no longer count.
+    fn build_print_job(&mut self, concert: Option<&Concert>, songs: &[SongDemand]) -> Result<()> {
+        if songs.iter().all(|demand| demand.needed == 0) {
+            self.set_status(
+                "Every binder is complete; there is nothing to print.",
+                StatusKind::Info,
+            );
+            return Ok(());
+        }
+        let Some(dir) = self.data_dir.as_ref().map(|dir| dir.join(EXPORT_DIR_NAME)) else {
+            self.set_status(
+                "No data folder to write the print job into.",
+                StatusKind::Error,
+            );
+            return Ok(());
+        };
+        let job = plan_print_job(self.library.as_ref(), self.attachments.as_ref(), songs)?;
+        let path = dir.join(format!("print-job-{}.pdf", Local::now().format("%Y-%m-%d")));
+        let written = fs::create_dir_all(&dir)
+            .map_err(PrintJobError::Write)
+            .and_then(|()| write_print_job(&job, &checklist_title(concert), &path));
+        let summary = match written {
+            Ok(summary) => summary,
+            Err(err) => {
+                self.set_status(err.to_string(), StatusKind::Error);
+                return Ok(());
+            }
+        };
+        let mut message = format!(
+            "{} {} of {} {}, {} pages plus the cover.",
+            summary.copies,
+            if summary.copies == 1 {
+                "copy"
+            } else {
+                "copies"
+            },
+            job.entries.len(),
+            if job.entries.len() == 1 {
+                "song"
+            } else {
+                "songs"
+            },
+            summary.pages
+        );
+        if !job.missing.is_empty() {
+            let titles: Vec<&str> = job
+                .missing
+                .iter()
+                .map(|demand| demand.song.title.as_str())
+                .collect();
+            message.push_str(&format!(" No PDF for {}.", titles.join(", ")));
+        }
+        match (self.opener)(&path.to_string_lossy()) {
+            Ok(()) => self.set_status(format!("Opened the print job: {message}"), StatusKind::Info),
+            Err(err) => self.set_status(
+                format!(
+                    "Wrote the print job to {}, but could not open it: {err}",
+                    path.display()
+                ),
+                StatusKind::Error,
+            ),
+        }
+        Ok(())
+    }
+
+    fn open_song_manager(&mut self) -> Result<()> {
+        let songs = self.library.fetch_all_songs()?;
+        self.reload_composers()?;
+        self.reload_song_summaries()?;
+        self.screen = Screen::SongManager(SongManagerScreen::new(
+            songs,
+            self.performance_stats.clone(),
+            self.song_tags.clone(),
+            self.linked_song_ids(),
+        ));
+        Ok(())
+    }
+
+    fn open_to_print_view(&mut self) -> Result<()> {
+        self.open_to_print(None)
+    }
+
+    /// Show the To Print report against `concert`'s setlist, or against the
+    /// director's binder when `concert` is `None`.
+    fn open_to_print(&mut self, concert: Option<Concert>) -> Result<()> {
+        let target = concert.as_ref().map_or(ToPrintTarget::Director, |concert| {
+            ToPrintTarget::Concert(concert.id)
+        });
+        let report = match to_print_report_for(self.library.as_ref(), target) {
+            Ok(report) => report,
+            Err(DbError::NotFound(Entity::Concert)) => {
+                self.open_concerts(None)?;
+                self.set_status("That concert no longer exists.", StatusKind::Error);
+                return Ok(());
+            }
+            Err(err) => return Err(err.into()),
+        };
+        self.screen = match report {
+            Some(report) => {
+                let binder_reports = report
+                    .binders
+                    .into_iter()
+                    .map(|shortfall| BinderReport {
+                        binder_id: shortfall.binder.id,
+                        binder_number: shortfall.binder.number,
+                        binder_label: shortfall.binder.label,
+                        voice_part: shortfall.binder.voice_part,
+                        songs: shortfall.missing,
+                    })
+                    .collect();
+                let song_totals = report
+                    .songs
+                    .into_iter()
+                    .map(|demand| SongNeeded {
+                        song: demand.song,
+                        needed: demand.needed,
+                    })
+                    .collect();
+                Screen::ToPrint(ToPrintScreen::with_data(
+                    concert,
+                    binder_reports,
+                    song_totals,
+                ))
+            }
+            None => Screen::ToPrint(ToPrintScreen::missing_director()),
+        };
+
+        Ok(())
+    }
+
+    /// Close the To Print report, returning to the concerts screen if it was
+    /// run against a concert and to the binder grid otherwise.
+    fn leave_to_print(&mut self) -> Result<()> {
+        let concert_id = match &self.screen {
+            Screen::ToPrint(report) => report.concert.as_ref().map(|concert| concert.id),
+            _ => None,
+        };
+        match concert_id {
+            Some(id) => self.open_concerts(Some(id)),
+            None => {
+                self.screen = Screen::Binders;
+                Ok(())
+            }
+        }
+    }
+
+    /// Show the concerts screen, selecting `select` if given.
+    fn open_concerts(&mut self, select: Option<i64>) -> Result<()> {
+        let mut list = ConcertScreen::new(self.library.fetch_concerts()?);
+        if let Some(id) = select {
+            list.select_concert(id);
+        }
+        self.screen = Screen::Concerts(list);
+        Ok(())
+    }
+
+    fn open_overdue(&mut self) -> Result<()> {
+        let now = Local::now().naive_local();
+        let report = OverdueScreen::load(self.library.as_ref(), now, self.overdue_days)?;
+        self.screen = Screen::Overdue(report);
+        Ok(())
+    }
+
+    fn refresh_overdue(&mut self) -> Result<()> {
+        if let Screen::Overdue(ref mut report) = self.screen {
+            let now = Local::now().naive_local();
+            report.reload(self.library.as_ref(), now, self.overdue_days)?;
+        }
+        Ok(())
+    }
+
+    /// Check `binder` out to its holder as of now.
+    fn check_out(&mut self, binder: &Binder) -> Result<()> {
+        let stamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+        match check_out_binder(self.library.as_mut(), binder.id, &stamp) {
+            Ok(checkout) => {
+                self.reload_binders(Some(binder.id))?;
+                let holder = checkout
+                    .chorister_id
+                    .and_then(|id| self.choristers.iter().find(|chorister| chorister.id == id));
+                let message = match holder {
+                    Some(chorister) => format!(
+                        "Binder {:02} checked out to {}.",
+                        binder.number, chorister.name
+                    ),
+                    None => format!("Binder {:02} checked out.", binder.number),
+                };
+                self.set_status(message, StatusKind::Info);
+            }
+            Err(DbError::AlreadyCheckedOut { .. }) => self.set_status(
+                format!("Binder {:02} is already checked out.", binder.number),
+                StatusKind::Error,
+            ),
+            Err(DbError::NotFound(Entity::Binder)) => {
+                self.reload_binders(None)?;
+                self.set_status("That binder no longer exists.", StatusKind::Error);
+            }
+            Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+        }
+        Ok(())
+    }
+
+    /// Put `binder` back on the shelf as of now.
+    fn check_in(&mut self, binder: &Binder) -> Result<()> {
+        let stamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+        match check_in_binder(self.library.as_mut(), binder.id, &stamp) {
+            Ok(_) => {
+                self.reload_binders(Some(binder.id))?;
+                self.set_status(
+                    format!("Binder {:02} is back on the shelf.", binder.number),
+                    StatusKind::Info,
+                );
+            }
+            Err(DbError::NotCheckedOut { .. }) => self.set_status(
+                format!("Binder {:02} is already on the shelf.", binder.number),
+                StatusKind::Error,
+            ),
+            Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+        }
+        Ok(())
+    }
+
+    fn open_choristers(&mut self, select: Option<i64>) -> Result<()> {
+        self.choristers = self.library.fetch_choristers()?;
+        let mut list = ChoristerScreen::new(self.choristers.clone());
+        if let Some(id) = select {
+            list.select_chorister(id);
+        }
+        self.screen = Screen::Choristers(list);
+        Ok(())
+    }
+
+    /// Reload members and binders after a chorister changes, so binder covers
+    /// pick up renamed or removed holders.
+    fn refresh_choristers(&mut self, select: Option<i64>) -> Result<()> {
+        let focus = self.current_binder().map(|binder| binder.id);
+        self.reload_binders(focus)?;
+        if let Screen::Choristers(ref mut list) = self.screen {
+            list.set_choristers(self.choristers.clone());
+            if let Some(id) = select {
+                list.select_chorister(id);
+            }
+        }
+        Ok(())
+    }
+
+    fn open_setlist(&mut self, concert: Concert) -> Result<()> {
+        let songs = self.library.fetch_setlist(concert.id)?;
+        self.screen = Screen::Setlist(SetlistScreen::new(concert, songs));
+        Ok(())
+    }
+
+    fn refresh_setlist(&mut self) -> Result<()> {
+        if let Screen::Setlist(ref mut setlist) = self.screen {
+            let updated = self.library.fetch_setlist(setlist.concert.id)?;
+            setlist.set_songs(updated);
+        }
+        Ok(())
+    }
+
+    fn refresh_concerts(&mut self, select: Option<i64>) -> Result<()> {
+        if let Screen::Concerts(ref mut list) = self.screen {
+            list.set_concerts(self.library.fetch_concerts()?);
+            if let Some(id) = select {
+                list.select_concert(id);
+            }
+        }
+        Ok(())
+    }
+
+    fn apply_to_print_changes(&mut self, assignments: &[(i64, i64)]) -> Result<usize> {
+        // All or nothing, so a failure leaves every item pending as shown.
+        self.library.transaction(&mut |library| {
+            for &(binder_id, song_id) in assignments {
+                library.add_song_to_binder(binder_id, song_id)?;
+            }
+            Ok(())
+        })?;
+        let applied = assignments.len();
+
+        if applied > 0 {
+            self.refresh_song_manager()?;
+            self.refresh_song_screen()?;
+        }
+
+        Ok(applied)
+    }
+
+    /// Surface a failed binder-song link. A foreign key failure means the binder
+    /// or song was deleted in the meantime, so the visible lists are reloaded.
+    fn report_link_error(&mut self, err: DbError) -> Result<()> {
+        if matches!(err, DbError::ForeignKey) {
+            self.reload_binders(None)?;
+            self.refresh_song_screen()?;
+            self.set_status("That binder or song no longer exists.", StatusKind::Error);
+        } else {
+            self.refresh_song_screen()?;
+            self.set_status(err.to_string(), StatusKind::Error);
+        }
+        Ok(())
+    }
+
+    fn refresh_song_screen(&mut self) -> Result<()> {
+        if let Screen::Songs(ref mut songs) = self.screen {
+            let updated = self.library.fetch_songs_for_binder(songs.binder.id)?;
+            songs.set_songs(updated);
+            self.reload_song_summaries()?;
+        }
+        Ok(())
+    }
+
+    fn refresh_song_manager(&mut self) -> Result<()> {
+        if let Screen::SongManager(ref mut manager) = self.screen {
+            let updated = self.library.fetch_all_songs()?;
+            manager.set_songs(updated);
+        }
+        self.reload_composers()?;
+        self.reload_song_summaries()?;
+        Ok(())
+    }
+
+    /// Reload the per-song performance summary, tags, typed links and
+    /// attachments shown on song cards and used by the song manager's filters.
+    fn reload_song_summaries(&mut self) -> Result<()> {
+        self.performance_stats = self.library.fetch_performance_stats()?;
+        self.song_tags = song_tags(self.library.as_ref())?;
+        self.song_links = song_links(self.library.as_ref())?;
+        self.song_attachments = song_attachments(self.library.as_ref())?;
+        let linked = self.linked_song_ids();
+        if let Screen::SongManager(ref mut manager) = self.screen {
+            manager.set_stats(self.performance_stats.clone());
+            manager.set_tags(self.song_tags.clone());
+            manager.set_linked(linked);
+        }
+        Ok(())
+    }
+
+    /// Songs with a link or an attached file.
+    fn linked_song_ids(&self) -> HashSet<i64> {
+        self.song_links
+            .keys()
+            .chain(self.song_attachments.keys())
+            .copied()
+            .collect()
+    }
+
+    /// Record every song on `concert`'s setlist as performed on the concert's
+    /// date. Concerts without a date or songs are left alone.
+    fn mark_concert_performed(&mut self, concert: Concert) -> Result<()> {
+        if concert.date.is_empty() {
+            self.set_status(
+                format!("Give {} a date before marking it performed.", concert.name),
+                StatusKind::Error,
+            );
+            return Ok(());
+        }
+        if self.library.fetch_setlist(concert.id)?.is_empty() {
+            self.set_status(
+                format!("The {} setlist is empty.", concert.name),
+                StatusKind::Error,
+            );
+            return Ok(());
+        }
+        match record_setlist_performances(self.library.as_mut(), concert.id) {
+            Ok(0) => self.set_status(
+                format!("{} is already in the performance history.", concert.name),
+                StatusKind::Info,
+            ),
+            Ok(added) => {
+                let plural = if added == 1 { "" } else { "s" };
+                self.set_status(
+                    format!(
+                        "Recorded {added} performance{plural} from {}.",
+                        concert.name
+                    ),
+                    StatusKind::Info,
+                );
+            }
+            Err(DbError::NotFound(Entity::Concert)) => {
+                self.refresh_concerts(None)?;
+                self.set_status("That concert no longer exists.", StatusKind::Error);
+            }
+            Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+        }
+        Ok(())
+    }
+
+    fn reload_composers(&mut self) -> Result<()> {
+        self.composers = self.library.fetch_composers()?;
+        Ok(())
+    }
+
+    fn next_binder_number(&self) -> i64 {
+        self.binders
+            .iter()
+            .map(|binder| binder.number)
+            .max()
+            .unwrap_or(0)
+            + 1
+    }
+
+    fn current_binder(&self) -> Option<&Binder> {
+        self.binders.get(self.selected)
+    }
+
+    fn binder_count(&self) -> usize {
+        self.binders.len()
+    }
+
+    fn row_count(&self) -> usize {
+        let cols = GRID_COLUMNS.max(1);
+        self.binder_count().div_ceil(cols)
+    }
+
+    fn move_horizontal(&mut self, offset: isize) {
+        if matches!(self.screen, Screen::Binders) && !self.binders.is_empty() {
+            let new_index = self.selected as isize + offset;
+            if (0..self.binder_count() as isize).contains(&new_index) {
+                self.selected = new_index as usize;
+            }
+        }
+    }
+
+    fn move_vertical(&mut self, offset: isize) {
+        if matches!(self.screen, Screen::Binders) && !self.binders.is_empty() {
+            let cols = GRID_COLUMNS as isize;
+            let new_index = self.selected as isize + offset * cols;
+            if (0..self.binder_count() as isize).contains(&new_index) {
+                self.selected = new_index as usize;
+            }
+        }
+    }
+
+    fn handle_confirm_to_print_exit(
+        &mut self,
+        code: KeyCode,
+        mut confirm: ConfirmToPrintExit,
+        exit: &mut bool,
+    ) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => Ok(Mode::Normal),
+            KeyCode::Left | KeyCode::Up => {
+                confirm.previous();
+                Ok(Mode::ConfirmToPrintExit(confirm))
+            }
+            KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
+                confirm.next();
+                Ok(Mode::ConfirmToPrintExit(confirm))
+            }
+            KeyCode::Enter => match confirm.selection {
+                ConfirmPrintChoice::Apply => {
+                    let assignments = if let Screen::ToPrint(report) = &self.screen {
+                        report.pending_assignments()
+                    } else {
+                        Vec::new()
+                    };
+
+                    match self.apply_to_print_changes(&assignments) {
+                        Ok(applied) => {
+                            let message = if applied == 0 {
+                                "No changes to apply.".to_string()
+                            } else {
+                                let plural = if applied == 1 { "" } else { "s" };
+                                format!("Applied {applied} song{plural}.")
+                            };
+                            self.set_status(message, StatusKind::Info);
+                        }
+                        Err(err) => {
+                            let message = surface_error(&err);
+                            self.set_status(message, StatusKind::Error);
+                            return Ok(Mode::ConfirmToPrintExit(confirm));
+                        }
+                    }
+
+                    if confirm.exit_app {
+                        *exit = true;
+                    } else {
+                        self.leave_to_print()?;
+                    }
+                    Ok(Mode::Normal)
+                }
+                ConfirmPrintChoice::Keep => {
+                    if confirm.exit_app {
+                        *exit = true;
+                    } else {
+                        self.set_status("Kept the print queue for next time.", StatusKind::Info);
+                        self.leave_to_print()?;
+                    }
+                    Ok(Mode::Normal)
+                }
+                ConfirmPrintChoice::Cancel => Ok(Mode::Normal),
+            },
+            _ => Ok(Mode::ConfirmToPrintExit(confirm)),
         }
+    }
 
-        self.open_binder_view(binder_clone)
+    fn handle_confirm_backup_restore(&mut self, code: KeyCode, backup: BackupInfo) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.set_status("Restore cancelled.", StatusKind::Info);
+                Ok(Mode::Normal)
+            }
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let Some(store) = self.backups.clone() else {
+                    return Ok(Mode::Normal);
+                };
+                match store.restore(self.library.as_mut(), &backup) {
+                    Ok(()) => {
+                        self.reload_binders(None)?;
+                        self.reload_composers()?;
+                        self.screen = Screen::Binders;
+                        self.set_status(
+                            format!(
+                                "Restored the backup from {} ({} binders, {} songs).",
+                                backup.taken_at.format("%Y-%m-%d %H:%M:%S"),
+                                backup.binders,
+                                backup.songs
+                            ),
+                            StatusKind::Info,
+                        );
+                    }
+                    Err(err) => {
+                        self.set_status(
+                            format!("Restore failed, the library is unchanged: {err}"),
+                            StatusKind::Error,
+                        );
+                    }
+                }
+                Ok(Mode::Normal)
+            }
+            _ => Ok(Mode::ConfirmBackupRestore(backup)),
+        }
     }
 
-    fn open_song_manager(&mut self) -> Result<()> {
-        let songs = fetch_all_songs(&self.conn)?;
-        self.reload_composers()?;
-        self.screen = Screen::SongManager(SongManagerScreen::new(songs));
-        Ok(())
+    /// Look up the scores of `songs` and ask before sending them to the
+    /// printer.
+    fn prepare_send_to_printer(&mut self, songs: &[SongDemand]) -> Result<Mode> {
+        if self.printer.is_none() {
+            self.set_status("Printing is not set up here.", StatusKind::Error);
+            return Ok(Mode::Normal);
+        }
+        if songs.is_empty() {
+            self.set_status(
+                "Every binder is complete; there is nothing to print.",
+                StatusKind::Info,
+            );
+            return Ok(Mode::Normal);
+        }
+        let job = plan_print_job(self.library.as_ref(), self.attachments.as_ref(), songs)?;
+        if job.entries.is_empty() {
+            self.set_status(PrintJobError::NoScores.to_string(), StatusKind::Error);
+            return Ok(Mode::Normal);
+        }
+        self.clear_status();
+        Ok(Mode::ConfirmSendToPrinter(job))
     }
 
-    fn open_to_print_view(&mut self) -> Result<()> {
-        if let Some(director) = self
-            .binders
-            .iter()
-            .find(|binder| binder.number == 0)
-            .cloned()
-        {
-            let director_songs = fetch_songs_for_binder(&self.conn, director.id)?;
-            let mut binder_reports = Vec::new();
-            let mut song_totals: Vec<SongNeeded> = Vec::new();
+    fn handle_confirm_send_to_printer(&mut self, code: KeyCode, job: PrintJob) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.set_status("Nothing was sent to the printer.", StatusKind::Info);
+                Ok(Mode::Normal)
+            }
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let Some(printer) = self.printer.clone() else {
+                    return Ok(Mode::Normal);
+                };
+                let submitted_at = Local::now().format(TIMESTAMP_FORMAT).to_string();
+                // Errors from here on are shown, not returned: the printer may
+                // already have taken some of the jobs.
+                let outcome = send_print_jobs(self.library.as_mut(), &printer, &job, &submitted_at);
+                let (sent, refused): (Vec<_>, Vec<_>) =
+                    outcome.entries.iter().partition(|entry| entry.accepted());
+                // What the printer accepted no longer needs printing, even if
+                // the queue cannot be saved.
+                let mut queue_error = None;
+                if let Screen::ToPrint(ref mut report) = self.screen {
+                    for song_id in sent.iter().filter_map(|entry| entry.song_id) {
+                        for (binder_id, song_id) in report.mark_printed(song_id) {
+                            if let Err(err) = self.library.set_print_stage(
+                                binder_id,
+                                song_id,
+                                PrintStage::Printed,
+                                &submitted_at,
+                            ) {
+                                queue_error.get_or_insert(err);
+                            }
+                        }
+                    }
+                }
+                let copies: i64 = sent.iter().map(|entry| entry.copies).sum();
+                let mut message = format!(
+                    "Sent {} {} ({copies} {}) to {}.",
+                    sent.len(),
+                    if sent.len() == 1 { "score" } else { "scores" },
+                    if copies == 1 { "copy" } else { "copies" },
+                    printer.describe()
+                );
+                if let Some(first) = refused.first() {
+                    message.push_str(&format!(" {} refused: {}", refused.len(), first.error));
+                }
+                if let Some(err) = &outcome.log_error {
+                    message.push_str(&format!(
+                        " Could not record the print log, so nothing more was sent: {err}"
+                    ));
+                }
+                if let Some(err) = &queue_error {
+                    message.push_str(&format!(" Could not save the print queue: {err}"));
+                }
+                let kind =
+                    if refused.is_empty() && outcome.log_error.is_none() && queue_error.is_none() {
+                        StatusKind::Info
+                    } else {
+                        StatusKind::Error
+                    };
+                self.set_status(message, kind);
+                Ok(Mode::Normal)
+            }
+            _ => Ok(Mode::ConfirmSendToPrinter(job)),
+        }
+    }
 
-            for binder in self
-                .binders
-                .iter()
-                .filter(|binder| binder.id != director.id)
-            {
-                let songs = fetch_songs_for_binder(&self.conn, binder.id)?;
-                let song_ids: HashSet<i64> = songs.iter().map(|song| song.id).collect();
-
-                let mut missing = Vec::new();
-                for song in &director_songs {
-                    if !song_ids.contains(&song.id) {
-                        missing.push(MissingSong {
-                            song: song.clone(),
-                            checked: false,
-                        });
-
-                        if let Some(entry) = song_totals
-                            .iter_mut()
-                            .find(|entry| entry.song.id == song.id)
-                        {
-                            entry.needed += 1;
-                        } else {
-                            song_totals.push(SongNeeded {
-                                song: song.clone(),
-                                needed: 1,
-                            });
+    fn handle_confirm_copy_order(&mut self, code: KeyCode) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.set_status("Order unchanged.", StatusKind::Info);
+                Ok(Mode::Normal)
+            }
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                match copy_director_order(self.library.as_mut()) {
+                    Ok(Some(count)) => {
+                        let plural = if count == 1 { "" } else { "s" };
+                        self.set_status(
+                            format!("Copied the director's order to {count} binder{plural}."),
+                            StatusKind::Info,
+                        )
+                    }
+                    Ok(None) => self.set_status("No director binder found.", StatusKind::Error),
+                    Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+                }
+                Ok(Mode::Normal)
+            }
+            _ => Ok(Mode::ConfirmCopyOrder),
+        }
+    }
+
+    fn handle_concert_form(
+        &mut self,
+        code: KeyCode,
+        id: Option<i64>,
+        mut form: ConcertForm,
+    ) -> Result<Mode> {
+        let mut keep_open = true;
+        match code {
+            KeyCode::Esc => {
+                let message = if id.is_some() {
+                    "Edit cancelled."
+                } else {
+                    "Add concert cancelled."
+                };
+                self.set_status(message, StatusKind::Info);
+                keep_open = false;
+            }
+            KeyCode::Tab => form.toggle_field(true),
+            KeyCode::BackTab => form.toggle_field(false),
+            KeyCode::Backspace => form.backspace(),
+            KeyCode::Enter => match form.parse_inputs() {
+                Ok((name, date, venue)) => {
+                    let saved = match id {
+                        Some(id) => self
+                            .library
+                            .update_concert(id, &name, &date, &venue)
+                            .map(|()| (id, "Concert updated.")),
+                        None => self
+                            .library
+                            .create_concert(&name, &date, &venue)
+                            .map(|concert| (concert.id, "Concert added.")),
+                    };
+                    match saved {
+                        Ok((id, message)) => {
+                            self.refresh_concerts(Some(id))?;
+                            self.set_status(message, StatusKind::Info);
+                            keep_open = false;
+                        }
+                        Err(DbError::NotFound(Entity::Concert)) => {
+                            self.refresh_concerts(None)?;
+                            self.set_status("That concert no longer exists.", StatusKind::Error);
+                            keep_open = false;
+                        }
+                        Err(err) => {
+                            let message = err.to_string();
+                            form.error = Some(message.clone());
+                            self.set_status(message, StatusKind::Error);
+                        }
+                    }
+                }
+                Err(err) => {
+                    let message = surface_error(&err);
+                    form.error = Some(message.clone());
+                    self.set_status(message, StatusKind::Error);
+                }
+            },
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
+        }
+
+        match (keep_open, id) {
+            (false, _) => Ok(Mode::Normal),
+            (true, Some(id)) => Ok(Mode::EditingConcert { id, form }),
+            (true, None) => Ok(Mode::AddingConcert(form)),
+        }
+    }
+
+    fn handle_confirm_concert_delete(&mut self, code: KeyCode, concert: Concert) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.set_status("Deletion cancelled.", StatusKind::Info);
+                Ok(Mode::Normal)
+            }
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let deleted = self
+                    .backup_before(BackupReason::DeleteConcert)
+                    .and_then(|()| self.library.delete_concert(concert.id));
+                match deleted {
+                    Ok(()) => {
+                        self.refresh_concerts(None)?;
+                        self.set_status("Concert deleted.", StatusKind::Info);
+                        Ok(Mode::Normal)
+                    }
+                    Err(DbError::NotFound(Entity::Concert)) => {
+                        self.refresh_concerts(None)?;
+                        self.set_status("That concert was already deleted.", StatusKind::Info);
+                        Ok(Mode::Normal)
+                    }
+                    Err(err) => {
+                        self.set_status(err.to_string(), StatusKind::Error);
+                        Ok(Mode::ConfirmConcertDelete(concert))
+                    }
+                }
+            }
+            _ => Ok(Mode::ConfirmConcertDelete(concert)),
+        }
+    }
+
+    fn handle_chorister_form(
+        &mut self,
+        code: KeyCode,
+        id: Option<i64>,
+        mut form: ChoristerForm,
+    ) -> Result<Mode> {
+        let mut keep_open = true;
+        match code {
+            KeyCode::Esc => {
+                let message = if id.is_some() {
+                    "Edit cancelled."
+                } else {
+                    "Add chorister cancelled."
+                };
+                self.set_status(message, StatusKind::Info);
+                keep_open = false;
+            }
+            KeyCode::Tab => form.toggle_field(true),
+            KeyCode::BackTab => form.toggle_field(false),
+            KeyCode::Backspace => form.backspace(),
+            KeyCode::Enter => match form.parse_inputs() {
+                Ok((name, voice_part, contact)) => {
+                    let saved = match id {
+                        Some(id) => self
+                            .library
+                            .update_chorister(id, &name, &voice_part, &contact)
+                            .map(|()| (id, "Chorister updated.")),
+                        None => self
+                            .library
+                            .create_chorister(&name, &voice_part, &contact)
+                            .map(|chorister| (chorister.id, "Chorister added.")),
+                    };
+                    match saved {
+                        Ok((id, message)) => {
+                            self.refresh_choristers(Some(id))?;
+                            self.set_status(message, StatusKind::Info);
+                            keep_open = false;
+                        }
+                        Err(DbError::NotFound(Entity::Chorister)) => {
+                            self.refresh_choristers(None)?;
+                            self.set_status("That chorister no longer exists.", StatusKind::Error);
+                            keep_open = false;
+                        }
+                        Err(err) => {
+                            let message = err.to_string();
+                            form.error = Some(message.clone());
+                            self.set_status(message, StatusKind::Error);
                         }
                     }
                 }
-
-                if !missing.is_empty() {
-                    binder_reports.push(BinderReport {
-                        binder_id: binder.id,
-                        binder_number: binder.number,
-                        binder_label: binder.label.clone(),
-                        songs: missing,
-                    });
+                Err(err) => {
+                    let message = surface_error(&err);
+                    form.error = Some(message.clone());
+                    self.set_status(message, StatusKind::Error);
+                }
+            },
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
+        }
+
+        match (keep_open, id) {
+            (false, _) => Ok(Mode::Normal),
+            (true, Some(id)) => Ok(Mode::EditingChorister { id, form }),
+            (true, None) => Ok(Mode::AddingChorister(form)),
+        }
+    }
+
+    fn handle_confirm_chorister_delete(
+        &mut self,
+        code: KeyCode,
+        chorister: Chorister,
+    ) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                self.set_status("Deletion cancelled.", StatusKind::Info);
+                Ok(Mode::Normal)
+            }
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                let deleted = self
+                    .backup_before(BackupReason::DeleteChorister)
+                    .and_then(|()| self.library.delete_chorister(chorister.id));
+                match deleted {
+                    Ok(()) => {
+                        self.refresh_choristers(None)?;
+                        self.set_status("Chorister deleted.", StatusKind::Info);
+                        Ok(Mode::Normal)
+                    }
+                    Err(DbError::NotFound(Entity::Chorister)) => {
+                        self.refresh_choristers(None)?;
+                        self.set_status("That chorister was already deleted.", StatusKind::Info);
+                        Ok(Mode::Normal)
+                    }
+                    Err(err) => {
+                        self.set_status(err.to_string(), StatusKind::Error);
+                        Ok(Mode::ConfirmChoristerDelete(chorister))
+                    }
+                }
+            }
+            _ => Ok(Mode::ConfirmChoristerDelete(chorister)),
+        }
+    }
+
+    fn handle_binder_log(&mut self, code: KeyCode, mut log: BinderLog) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('H') => return Ok(Mode::Normal),
+            KeyCode::Up => log.move_selection(-1),
+            KeyCode::Down => log.move_selection(1),
+            KeyCode::PageUp => log.move_selection(-5),
+            KeyCode::PageDown => log.move_selection(5),
+            _ => {}
+        }
+        Ok(Mode::BinderLog(log))
+    }
+
+    fn handle_assign_binder(&mut self, code: KeyCode, mut picker: HolderPicker) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => {
+                self.set_status("Assignment cancelled.", StatusKind::Info);
+                return Ok(Mode::Normal);
+            }
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            KeyCode::PageUp => picker.move_selection(-5),
+            KeyCode::PageDown => picker.move_selection(5),
+            KeyCode::Home => picker.select_first(),
+            KeyCode::End => picker.select_last(),
+            KeyCode::Enter => {
+                let binder = &picker.binder;
+                let holder = picker.chosen();
+                match self
+                    .library
+                    .assign_binder(binder.id, holder.map(|chorister| chorister.id))
+                {
+                    Ok(()) => {
+                        let message = match holder {
+                            Some(chorister) => format!(
+                                "Binder {:02} assigned to {}.",
+                                binder.number, chorister.name
+                            ),
+                            None => format!("Binder {:02} is unassigned.", binder.number),
+                        };
+                        self.reload_binders(Some(binder.id))?;
+                        self.set_status(message, StatusKind::Info);
+                    }
+                    Err(err @ (DbError::NotFound(_) | DbError::ForeignKey)) => {
+                        self.reload_binders(Some(binder.id))?;
+                        self.set_status(err.to_string(), StatusKind::Error);
+                    }
+                    Err(err) => {
+                        self.set_status(err.to_string(), StatusKind::Error);
+                        return Ok(Mode::AssigningBinder(picker));
+                    }
+                }
+                return Ok(Mode::Normal);
+            }
+            _ => {}
+        }
+        Ok(Mode::AssigningBinder(picker))
+    }
+
+    fn handle_pick_setlist_songs(
+        &mut self,
+        code: KeyCode,
+        mut picker: SetlistPicker,
+    ) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => return Ok(Mode::Normal),
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            KeyCode::PageUp => picker.move_selection(-5),
+            KeyCode::PageDown => picker.move_selection(5),
+            KeyCode::Home => picker.select_first(),
+            KeyCode::End => picker.select_last(),
+            KeyCode::Char(' ') => picker.toggle_current(),
+            KeyCode::Enter => {
+                let chosen = picker.chosen_song_ids();
+                let mut added = 0usize;
+                for song_id in chosen {
+                    if let Err(err) = self.library.add_song_to_setlist(picker.concert_id, song_id) {
+                        self.refresh_setlist()?;
+                        self.set_status(err.to_string(), StatusKind::Error);
+                        return Ok(Mode::Normal);
+                    }
+                    added += 1;
+                }
+                self.refresh_setlist()?;
+                if let (Screen::Setlist(setlist), true) = (&mut self.screen, added > 0) {
+                    setlist.select_last();
                 }
+                let message = if added == 1 {
+                    "Song added to the setlist.".to_string()
+                } else {
+                    format!("Added {added} songs to the setlist.")
+                };
+                self.set_status(message, StatusKind::Info);
+                return Ok(Mode::Normal);
             }
-
-            self.screen = Screen::ToPrint(ToPrintScreen::with_data(binder_reports, song_totals));
-        } else {
-            self.screen = Screen::ToPrint(ToPrintScreen::missing_director());
+            _ => {}
         }
-
-        Ok(())
+        Ok(Mode::PickingSetlistSongs(picker))
     }
 
-    fn apply_to_print_changes(&mut self, assignments: &[(i64, i64)]) -> Result<usize> {
-        let mut applied = 0;
-        for &(binder_id, song_id) in assignments {
-            add_song_to_binder(&self.conn, binder_id, song_id)?;
-            applied += 1;
-        }
-
-        if applied > 0 {
-            self.refresh_song_manager()?;
-            self.refresh_song_screen()?;
-        }
-
-        Ok(applied)
+    /// Open the link picker for `song`.
+    fn open_link_picker(&mut self, song: Song) -> Result<Mode> {
+        let picker = LinkPicker::load(self.library.as_ref(), song)?;
+        self.clear_status();
+        Ok(Mode::PickingLink(picker))
     }
 
-    fn refresh_song_screen(&mut self) -> Result<()> {
-        if let Screen::Songs(ref mut songs) = self.screen {
-            let updated = fetch_songs_for_binder(&self.conn, songs.binder.id)?;
-            songs.set_songs(updated);
+    /// Leave the link picker, back to the search it was opened from if any.
+    fn close_link_picker(&mut self) -> Mode {
+        match self.saved_search.take() {
+            Some(state) => Mode::Searching(state),
+            None => Mode::Normal,
         }
-        Ok(())
     }
 
-    fn refresh_song_manager(&mut self) -> Result<()> {
-        if let Screen::SongManager(ref mut manager) = self.screen {
-            let updated = fetch_all_songs(&self.conn)?;
-            manager.set_songs(updated);
+    fn handle_link_picker(&mut self, code: KeyCode, mut picker: LinkPicker) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => return Ok(self.close_link_picker()),
+            KeyCode::Up => picker.move_selection(-1),
+            KeyCode::Down => picker.move_selection(1),
+            KeyCode::Enter => {
+                if let Some(choice) = picker.current_choice() {
+                    let target = match &choice.target {
+                        LinkTarget::Attachment(attachment) => self.attached_file_path(attachment),
+                        LinkTarget::Typed(_) => Ok(choice.url.clone()),
+                    };
+                    match target.and_then(|target| Ok((self.opener)(&target)?)) {
+                        Ok(()) => {
+                            self.set_status(
+                                format!("Opened {} for {}.", choice.label, picker.song.title),
+                                StatusKind::Info,
+                            );
+                            return Ok(self.close_link_picker());
+                        }
+                        Err(err) => self.set_status(
+                            format!("Failed to open link: {}", surface_error(&err)),
+                            StatusKind::Error,
+                        ),
+                    }
+                }
+            }
+            KeyCode::Char('a') | KeyCode::Char('A') => {
+                if self.attachments.is_none() {
+                    self.set_status(
+                        "Attachments need a library file to live next to.",
+                        StatusKind::Error,
+                    );
+                } else {
+                    self.clear_status();
+                    return Ok(Mode::AttachingFile {
+                        picker,
+                        form: AttachForm::default(),
+                    });
+                }
+            }
+            KeyCode::Char('+') => {
+                self.clear_status();
+                return Ok(Mode::AddingLink {
+                    picker,
+                    form: LinkForm::default(),
+                });
+            }
+            KeyCode::Char('-') => match picker.current_choice().map(|choice| &choice.target) {
+                Some(&LinkTarget::Typed(id)) => match self.library.delete_song_link(id) {
+                    Ok(()) | Err(DbError::NotFound(Entity::Link)) => {
+                        picker.reload(self.library.as_ref())?;
+                        self.reload_song_summaries()?;
+                        self.set_status("Link removed.", StatusKind::Info);
+                    }
+                    Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+                },
+                Some(LinkTarget::Attachment(attachment)) => {
+                    match self.library.delete_attachment(attachment.id) {
+                        Ok(()) | Err(DbError::NotFound(Entity::Attachment)) => {
+                            picker.reload(self.library.as_ref())?;
+                            self.reload_song_summaries()?;
+                            self.set_status("Attachment removed.", StatusKind::Info);
+                        }
+                        Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+                    }
+                }
+                None => {}
+            },
+            _ => {}
         }
-        self.reload_composers()?;
-        Ok(())
-    }
-
-    fn reload_composers(&mut self) -> Result<()> {
-        self.composers = fetch_composers(&self.conn)?;
-        Ok(())
-    }
-
-    fn next_binder_number(&self) -> i64 {
-        self.binders
-            .iter()
-            .map(|binder| binder.number)
-            .max()
-            .unwrap_or(0)
-            + 1
-    }
-
-    fn current_binder(&self) -> Option<&Binder> {
-        self.binders.get(self.selected)
+        Ok(Mode::PickingLink(picker))
     }
 
-    fn binder_count(&self) -> usize {
-        self.binders.len()
+    fn handle_link_form(
+        &mut self,
+        code: KeyCode,
+        mut picker: LinkPicker,
+        mut form: LinkForm,
+    ) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => {
+                self.set_status("No link added.", StatusKind::Info);
+                return Ok(Mode::PickingLink(picker));
+            }
+            KeyCode::Tab | KeyCode::BackTab => form.toggle_field(),
+            KeyCode::Left if form.active == LinkField::Kind => form.cycle_kind(false),
+            KeyCode::Right if form.active == LinkField::Kind => form.cycle_kind(true),
+            KeyCode::Backspace => form.backspace(),
+            KeyCode::Enter => match form.parse_inputs() {
+                Ok(input) => {
+                    match self.library.add_song_link(
+                        picker.song.id,
+                        input.kind,
+                        &input.part,
+                        &input.label,
+                        &input.url,
+                    ) {
+                        Ok(link) => {
+                            picker.reload(self.library.as_ref())?;
+                            picker.select_link(link.id);
+                            self.reload_song_summaries()?;
+                            self.set_status(
+                                format!("Added {} to {}.", link.describe(), picker.song.title),
+                                StatusKind::Info,
+                            );
+                            return Ok(Mode::PickingLink(picker));
+                        }
+                        Err(DbError::ForeignKey) => {
+                            self.refresh_song_manager()?;
+                            self.saved_search = None;
+                            self.set_status("That song no longer exists.", StatusKind::Error);
+                            return Ok(Mode::Normal);
+                        }
+                        Err(err) => {
+                            let message = err.to_string();
+                            form.error = Some(message.clone());
+                            self.set_status(message, StatusKind::Error);
+                        }
+                    }
+                }
+                Err(err) => {
+                    let message = surface_error(&err);
+                    form.error = Some(message.clone());
+                    self.set_status(message, StatusKind::Error);
+                }
+            },
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
+        }
+        Ok(Mode::AddingLink { picker, form })
     }
 
-    fn row_count(&self) -> usize {
-        let cols = GRID_COLUMNS.max(1);
-        (self.binder_count() + cols - 1) / cols
+    /// The stored copy of `attachment`, checked against its checksum.
+    fn attached_file_path(&self, attachment: &Attachment) -> Result<String> {
+        let store = self
+            .attachments
+            .as_ref()
+            .ok_or(DbError::AttachmentMissing(attachment.file_name.clone()))?;
+        Ok(store
+            .verified_path(attachment)?
+            .to_string_lossy()
+            .into_owned())
     }
 
-    fn move_horizontal(&mut self, offset: isize) {
-        if matches!(self.screen, Screen::Binders) && !self.binders.is_empty() {
-            let new_index = self.selected as isize + offset;
-            if (0..self.binder_count() as isize).contains(&new_index) {
-                self.selected = new_index as usize;
+    fn handle_attach_form(
+        &mut self,
+        code: KeyCode,
+        mut picker: LinkPicker,
+        mut form: AttachForm,
+    ) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => {
+                self.set_status("No file attached.", StatusKind::Info);
+                return Ok(Mode::PickingLink(picker));
+            }
+            KeyCode::Backspace => {
+                form.backspace();
+                form.error = None;
+            }
+            KeyCode::Enter => {
+                let Some(store) = self.attachments.clone() else {
+                    return Ok(Mode::PickingLink(picker));
+                };
+                let stamp = Local::now().format(TIMESTAMP_FORMAT).to_string();
+                let attached = form.parse_path().and_then(|path| {
+                    Ok(attach_file(
+                        self.library.as_mut(),
+                        &store,
+                        picker.song.id,
+                        &path,
+                        &stamp,
+                    )?)
+                });
+                match attached {
+                    Ok(attachment) => {
+                        picker.reload(self.library.as_ref())?;
+                        picker.select_attachment(attachment.id);
+                        self.reload_song_summaries()?;
+                        self.set_status(
+                            format!(
+                                "Attached {} ({}) to {}.",
+                                attachment.file_name,
+                                format_size(attachment.size_bytes),
+                                picker.song.title
+                            ),
+                            StatusKind::Info,
+                        );
+                        return Ok(Mode::PickingLink(picker));
+                    }
+                    Err(err) => {
+                        let message = surface_error(&err);
+                        form.error = Some(message.clone());
+                        self.set_status(message, StatusKind::Error);
+                    }
+                }
             }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
         }
+        Ok(Mode::AttachingFile { picker, form })
     }
 
-    fn move_vertical(&mut self, offset: isize) {
-        if matches!(self.screen, Screen::Binders) && !self.binders.is_empty() {
-            let cols = GRID_COLUMNS as isize;
-            let new_index = self.selected as isize + offset * cols;
-            if (0..self.binder_count() as isize).contains(&new_index) {
-                self.selected = new_index as usize;
+    fn handle_song_history(&mut self, code: KeyCode, mut history: SongHistory) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('h') | KeyCode::Char('H') => return Ok(Mode::Normal),
+            KeyCode::Up => history.move_selection(-1),
+            KeyCode::Down => history.move_selection(1),
+            KeyCode::PageUp => history.move_selection(-5),
+            KeyCode::PageDown => history.move_selection(5),
+            KeyCode::Char('+') => {
+                self.clear_status();
+                let today = Local::now().format("%Y-%m-%d").to_string();
+                return Ok(Mode::AddingPerformance {
+                    history,
+                    form: PerformanceForm::dated(today),
+                });
+            }
+            KeyCode::Char('-') => {
+                if let Some(performance) = history.current_performance().cloned() {
+                    match self.library.delete_performance(performance.id) {
+                        Ok(()) | Err(DbError::NotFound(Entity::Performance)) => {
+                            history.reload(self.library.as_ref())?;
+                            self.reload_song_summaries()?;
+                            self.set_status(
+                                format!("Removed the {} performance.", performance.date),
+                                StatusKind::Info,
+                            );
+                        }
+                        Err(err) => self.set_status(err.to_string(), StatusKind::Error),
+                    }
+                }
             }
+            _ => {}
         }
+        Ok(Mode::SongHistory(history))
     }
 
-    fn handle_confirm_to_print_exit(
+    fn handle_performance_form(
         &mut self,
         code: KeyCode,
-        mut confirm: ConfirmToPrintExit,
-        exit: &mut bool,
+        mut history: SongHistory,
+        mut form: PerformanceForm,
     ) -> Result<Mode> {
         match code {
-            KeyCode::Esc => Ok(Mode::Normal),
-            KeyCode::Left | KeyCode::Up => {
-                confirm.previous();
-                Ok(Mode::ConfirmToPrintExit(confirm))
-            }
-            KeyCode::Right | KeyCode::Down | KeyCode::Tab => {
-                confirm.next();
-                Ok(Mode::ConfirmToPrintExit(confirm))
+            KeyCode::Esc => {
+                self.set_status("Nothing recorded.", StatusKind::Info);
+                return Ok(Mode::SongHistory(history));
             }
-            KeyCode::Enter => match confirm.selection {
-                ConfirmPrintChoice::Apply => {
-                    let assignments = if let Screen::ToPrint(report) = &self.screen {
-                        report.pending_assignments()
-                    } else {
-                        Vec::new()
-                    };
-
-                    match self.apply_to_print_changes(&assignments) {
-                        Ok(applied) => {
-                            let message = if applied == 0 {
-                                "No changes to apply.".to_string()
-                            } else {
-                                let plural = if applied == 1 { "" } else { "s" };
-                                format!("Applied {applied} song{plural}.")
-                            };
-                            self.set_status(message, StatusKind::Info);
+            KeyCode::Tab | KeyCode::BackTab => form.toggle_field(),
+            KeyCode::Backspace => form.backspace(),
+            KeyCode::Enter => match form.parse_inputs() {
+                Ok((date, event)) => {
+                    match self
+                        .library
+                        .record_performance(history.song.id, &date, &event)
+                    {
+                        Ok(performance) => {
+                            history.reload(self.library.as_ref())?;
+                            if let Some(index) = history
+                                .performances
+                                .iter()
+                                .position(|entry| entry.id == performance.id)
+                            {
+                                history.selected = index;
+                            }
+                            self.reload_song_summaries()?;
+                            self.set_status(
+                                format!(
+                                    "Recorded a performance of {} on {date}.",
+                                    history.song.title
+                                ),
+                                StatusKind::Info,
+                            );
+                            return Ok(Mode::SongHistory(history));
+                        }
+                        Err(DbError::ForeignKey) => {
+                            self.refresh_song_manager()?;
+                            self.set_status("That song no longer exists.", StatusKind::Error);
+                            return Ok(Mode::Normal);
                         }
                         Err(err) => {
-                            let message = surface_error(&err);
+                            let message = err.to_string();
+                            form.error = Some(message.clone());
                             self.set_status(message, StatusKind::Error);
-                            return Ok(Mode::ConfirmToPrintExit(confirm));
                         }
                     }
+                }
+                Err(err) => {
+                    let message = surface_error(&err);
+                    form.error = Some(message.clone());
+                    self.set_status(message, StatusKind::Error);
+                }
+            },
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
+        }
+        Ok(Mode::AddingPerformance { history, form })
+    }
 
-                    if confirm.exit_app {
-                        *exit = true;
-                    } else {
-                        self.screen = Screen::Binders;
+    fn handle_csv_import(&mut self, code: KeyCode, mut form: CsvImportForm) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => {
+                self.set_status("Import cancelled.", StatusKind::Info);
+                return Ok(Mode::Normal);
+            }
+            KeyCode::Tab | KeyCode::Down => form.move_field(1),
+            KeyCode::BackTab | KeyCode::Up => form.move_field(-1),
+            KeyCode::Backspace => {
+                form.backspace();
+                form.error = None;
+            }
+            KeyCode::Enter => {
+                let planned = form.parse_path().and_then(|path| {
+                    Ok(plan_import_file(
+                        self.library.as_ref(),
+                        &path,
+                        &form.mapping(),
+                    )?)
+                });
+                match planned {
+                    Ok(plan) => {
+                        self.clear_status();
+                        form.error = None;
+                        return Ok(Mode::ConfirmCsvImport(CsvImportPreview { form, plan }));
+                    }
+                    Err(err) => {
+                        let message = surface_error(&err);
+                        form.error = Some(message.clone());
+                        self.set_status(message, StatusKind::Error);
                     }
-                    Ok(Mode::Normal)
                 }
-                ConfirmPrintChoice::Discard => {
-                    if confirm.exit_app {
-                        *exit = true;
-                    } else {
-                        self.set_status("Discarded pending changes.", StatusKind::Info);
-                        self.screen = Screen::Binders;
+            }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
+        }
+        Ok(Mode::ImportingCsv(form))
+    }
+
+    fn handle_csv_export(&mut self, code: KeyCode, mut form: ExportForm) -> Result<Mode> {
+        match code {
+            KeyCode::Esc => {
+                self.set_status("Export cancelled.", StatusKind::Info);
+                return Ok(Mode::Normal);
+            }
+            KeyCode::Backspace => {
+                form.backspace();
+                form.error = None;
+            }
+            KeyCode::Enter => {
+                let stamp = Local::now().format("%Y-%m-%d").to_string();
+                let exported = form.parse_dir().and_then(|dir| {
+                    Ok(export_csv_files(self.library.as_ref(), &dir, &stamp)?)
+                        .map(|files| (dir, files))
+                });
+                match exported {
+                    Ok((dir, files)) => {
+                        let counts: Vec<String> = files
+                            .iter()
+                            .map(|file| format!("{} {}", file.rows, file.kind.name()))
+                            .collect();
+                        self.set_status(
+                            format!("Exported {} to {}.", counts.join(", "), dir.display()),
+                            StatusKind::Info,
+                        );
+                        return Ok(Mode::Normal);
+                    }
+                    Err(err) => {
+                        let message = surface_error(&err);
+                        form.error = Some(message.clone());
+                        self.set_status(message, StatusKind::Error);
                     }
-                    Ok(Mode::Normal)
                 }
-                ConfirmPrintChoice::Cancel => Ok(Mode::Normal),
-            },
-            _ => Ok(Mode::ConfirmToPrintExit(confirm)),
+            }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+            }
+            _ => {}
+        }
+        Ok(Mode::ExportingCsv(form))
+    }
+
+    fn handle_confirm_csv_import(
+        &mut self,
+        code: KeyCode,
+        preview: CsvImportPreview,
+    ) -> Result<Mode> {
+        match code {
+            KeyCode::Esc | KeyCode::Char('n') | KeyCode::Char('N') => {
+                Ok(Mode::ImportingCsv(preview.form))
+            }
+            KeyCode::Enter | KeyCode::Char('y') | KeyCode::Char('Y') => {
+                if preview.plan.is_empty() {
+                    self.set_status("Nothing to import.", StatusKind::Info);
+                    return Ok(Mode::Normal);
+                }
+                match apply_import(self.library.as_mut(), &preview.plan) {
+                    Ok(summary) => {
+                        self.refresh_song_manager()?;
+                        self.refresh_song_screen()?;
+                        self.set_status(
+                            format!("Imported: {}.", summary.describe()),
+                            StatusKind::Info,
+                        );
+                        Ok(Mode::Normal)
+                    }
+                    Err(err) => {
+                        self.set_status(
+                            format!("Import failed, nothing was saved: {err}"),
+                            StatusKind::Error,
+                        );
+                        Ok(Mode::ConfirmCsvImport(preview))
+                    }
+                }
+            }
+            _ => Ok(Mode::ConfirmCsvImport(preview)),
         }
     }
 
@@ -2197,11 +5326,9 @@ impl App {
     ) -> Result<Mode> {
         let mut keep_open = true;
         match code {
-            KeyCode::Esc => {
-                if !form.cancel_autocomplete() {
-                    self.set_status("Song creation cancelled.", StatusKind::Info);
-                    keep_open = false;
-                }
+            KeyCode::Esc if !form.cancel_autocomplete() => {
+                self.set_status("Song creation cancelled.", StatusKind::Info);
+                keep_open = false;
             }
             KeyCode::Tab => {
                 let consumed = form.has_active_suggestion() && form.accept_suggestion();
@@ -2219,11 +5346,20 @@ impl App {
                 form.update_suggestion(&self.composers);
             }
             KeyCode::Enter => match form.parse_inputs() {
-                Ok((title, composer, link)) => {
-                    match create_song(&self.conn, &title, &composer, &link) {
+                Ok(input) => {
+                    let mut created = None;
+                    let saved = self.library.transaction(&mut |library| {
+                        let song = library.create_song(&input.title, &input.composer)?;
+                        library.set_song_parts(song.id, &input.parts)?;
+                        library.set_song_details(song.id, &input.details)?;
+                        set_song_tags(library, song.id, &input.tags)?;
+                        created = Some(song);
+                        Ok(())
+                    });
+                    match saved.map(|()| created.take().expect("transaction created the song")) {
                         Ok(song) => {
                             if let Some(binder_id) = binder_id {
-                                add_song_to_binder(&self.conn, binder_id, song.id)?;
+                                self.library.add_song_to_binder(binder_id, song.id)?;
                                 self.refresh_song_screen()?;
                                 self.set_status("Song created and added.", StatusKind::Info);
                             } else {
@@ -2233,7 +5369,7 @@ impl App {
                             keep_open = false;
                         }
                         Err(err) => {
-                            let message = surface_error(&err);
+                            let message = err.to_string();
                             form.error = Some(message.clone());
                             self.set_status(message, StatusKind::Error);
                         }
@@ -2245,11 +5381,9 @@ impl App {
                     self.set_status(message, StatusKind::Error);
                 }
             },
-            KeyCode::Char(ch) => {
-                if form.push_char(ch) {
-                    form.error = None;
-                    form.update_suggestion(&self.composers);
-                }
+            KeyCode::Char(ch) if form.push_char(ch) => {
+                form.error = None;
+                form.update_suggestion(&self.composers);
             }
             _ => {}
         }
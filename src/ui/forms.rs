@@ -1,29 +1,35 @@
+use std::path::{Path, PathBuf};
+
 use anyhow::{anyhow, Context, Result};
+use directories::BaseDirs;
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 
-use crate::models::{Binder, Song};
+use chrono::NaiveDate;
+
+use crate::db::parse_tags;
+use crate::models::{
+    format_duration, parse_duration, Binder, Chorister, Concert, LinkKind, Song, SongDetails,
+};
+use crate::transfer::{ColumnMapping, ImportPlan};
 
 /// Internal representation of the "binder" form fields.
 #[derive(Default, Clone)]
 pub(crate) struct BinderForm {
     pub(crate) number: String,
     pub(crate) label: String,
+    pub(crate) voice_part: String,
     pub(crate) active: BinderField,
     pub(crate) error: Option<String>,
 }
 
 /// Fields available within the binder form.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
 pub(crate) enum BinderField {
+    #[default]
     Number,
     Label,
-}
-
-impl Default for BinderField {
-    fn default() -> Self {
-        BinderField::Number
-    }
+    VoicePart,
 }
 
 impl BinderForm {
@@ -41,6 +47,7 @@ impl BinderForm {
         Self {
             number: binder.number.to_string(),
             label: binder.label.clone(),
+            voice_part: binder.voice_part.clone(),
             active: BinderField::Number,
             error: None,
         }
@@ -51,11 +58,12 @@ impl BinderForm {
         self.active = field;
     }
 
-    /// Swap focus between the number and label fields.
+    /// Move focus to the next field, wrapping around.
     pub(crate) fn toggle_field(&mut self) {
         self.active = match self.active {
             BinderField::Number => BinderField::Label,
-            BinderField::Label => BinderField::Number,
+            BinderField::Label => BinderField::VoicePart,
+            BinderField::VoicePart => BinderField::Number,
         };
     }
 
@@ -70,9 +78,13 @@ impl BinderForm {
                     false
                 }
             }
-            BinderField::Label => {
+            BinderField::Label | BinderField::VoicePart => {
                 if !ch.is_control() {
-                    self.label.push(ch);
+                    if self.active == BinderField::Label {
+                        self.label.push(ch);
+                    } else {
+                        self.voice_part.push(ch);
+                    }
                     true
                 } else {
                     false
@@ -90,11 +102,15 @@ impl BinderForm {
             BinderField::Label => {
                 self.label.pop();
             }
+            BinderField::VoicePart => {
+                self.voice_part.pop();
+            }
         }
     }
 
-    /// Validate the inputs and return typed values ready for persistence.
-    pub(crate) fn parse_inputs(&self) -> Result<(i64, String)> {
+    /// Validate the inputs and return `(number, label, voice_part)` ready for
+    /// persistence.
+    pub(crate) fn parse_inputs(&self) -> Result<(i64, String, String)> {
         let number_raw = self.number.trim();
         if number_raw.is_empty() {
             return Err(anyhow!("Binder number is required."));
@@ -106,7 +122,11 @@ impl BinderForm {
         if label.is_empty() {
             return Err(anyhow!("Binder label is required."));
         }
-        Ok((number, label.to_string()))
+        Ok((
+            number,
+            label.to_string(),
+            self.voice_part.trim().to_string(),
+        ))
     }
 
     /// Render a single line for the form widget.
@@ -114,10 +134,15 @@ impl BinderForm {
         let (value, is_active) = match field {
             BinderField::Number => (&self.number, self.active == BinderField::Number),
             BinderField::Label => (&self.label, self.active == BinderField::Label),
+            BinderField::VoicePart => (&self.voice_part, self.active == BinderField::VoicePart),
         };
 
         let display = if value.is_empty() {
-            "<required>".to_string()
+            match field {
+                BinderField::VoicePart => "<S, A, T, B or custom; blank needs every song>",
+                _ => "<required>",
+            }
+            .to_string()
         } else {
             value.clone()
         };
@@ -141,6 +166,7 @@ impl BinderForm {
         match field {
             BinderField::Number => self.number.chars().count(),
             BinderField::Label => self.label.chars().count(),
+            BinderField::VoicePart => self.voice_part.chars().count(),
         }
     }
 }
@@ -168,7 +194,18 @@ impl ConfirmBinderDelete {
 pub(crate) struct SongForm {
     pub(crate) title: String,
     pub(crate) composer: String,
-    pub(crate) link: String,
+    pub(crate) parts: String,
+    /// Comma-separated tag names.
+    pub(crate) tags: String,
+    pub(crate) arranger: String,
+    pub(crate) voicing: String,
+    pub(crate) accompaniment: String,
+    pub(crate) language: String,
+    pub(crate) publisher: String,
+    pub(crate) catalog_number: String,
+    /// Typed as `m:ss` or whole minutes.
+    pub(crate) duration: String,
+    pub(crate) page_count: String,
     pub(crate) active: SongField,
     pub(crate) error: Option<String>,
     pub(crate) suggestion: Option<String>,
@@ -176,26 +213,105 @@ pub(crate) struct SongForm {
 }
 
 /// Enumerates the fields within the song form to drive focus management.
-#[derive(Copy, Clone, PartialEq, Eq)]
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
 pub(crate) enum SongField {
+    #[default]
     Title,
     Composer,
-    Link,
+    Parts,
+    Tags,
+    Arranger,
+    Voicing,
+    Accompaniment,
+    Language,
+    Publisher,
+    CatalogNumber,
+    Duration,
+    PageCount,
 }
 
-impl Default for SongField {
-    fn default() -> Self {
-        SongField::Title
+impl SongField {
+    /// Every field, in the order the form shows and cycles through them.
+    pub(crate) const ALL: [SongField; 12] = [
+        SongField::Title,
+        SongField::Composer,
+        SongField::Parts,
+        SongField::Tags,
+        SongField::Arranger,
+        SongField::Voicing,
+        SongField::Accompaniment,
+        SongField::Language,
+        SongField::Publisher,
+        SongField::CatalogNumber,
+        SongField::Duration,
+        SongField::PageCount,
+    ];
+
+    /// Label shown before the field's value.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            SongField::Title => "Title",
+            SongField::Composer => "Composer",
+            SongField::Parts => "Needed by",
+            SongField::Tags => "Tags",
+            SongField::Arranger => "Arranger",
+            SongField::Voicing => "Voicing",
+            SongField::Accompaniment => "Accompaniment",
+            SongField::Language => "Language",
+            SongField::Publisher => "Publisher",
+            SongField::CatalogNumber => "Catalog no.",
+            SongField::Duration => "Duration",
+            SongField::PageCount => "Pages",
+        }
     }
+
+    fn placeholder(self) -> &'static str {
+        match self {
+            SongField::Title => "<required>",
+            SongField::Parts => "<all parts, or e.g. T, B>",
+            SongField::Tags => "<optional, e.g. Advent, Gospel>",
+            SongField::Voicing => "<optional, e.g. SATB, SSA, TTBB>",
+            SongField::Accompaniment => "<optional, e.g. a cappella, piano>",
+            SongField::Duration => "<optional, m:ss>",
+            _ => "<optional>",
+        }
+    }
+}
+
+/// Validated song form contents, ready to be saved.
+pub(crate) struct SongInput {
+    pub(crate) title: String,
+    pub(crate) composer: String,
+    /// Tidied into a `S, A` style list.
+    pub(crate) parts: String,
+    pub(crate) tags: Vec<String>,
+    pub(crate) details: SongDetails,
 }
 
 impl SongForm {
-    /// Populate the form from an existing song when entering edit mode.
-    pub(crate) fn from_song(song: &Song) -> Self {
+    /// Populate the form from an existing song and its tags when entering
+    /// edit mode.
+    pub(crate) fn from_song(song: &Song, tags: &[String]) -> Self {
+        let details = &song.details;
         Self {
             title: song.title.clone(),
             composer: song.composer.clone(),
-            link: song.link.clone(),
+            parts: song.parts.clone(),
+            tags: tags.join(", "),
+            arranger: details.arranger.clone(),
+            voicing: details.voicing.clone(),
+            accompaniment: details.accompaniment.clone(),
+            language: details.language.clone(),
+            publisher: details.publisher.clone(),
+            catalog_number: details.catalog_number.clone(),
+            duration: details
+                .duration_seconds
+                .map(format_duration)
+                .unwrap_or_default(),
+            page_count: details
+                .page_count
+                .map(|pages| pages.to_string())
+                .unwrap_or_default(),
             active: SongField::Title,
             error: None,
             suggestion: None,
@@ -203,13 +319,47 @@ impl SongForm {
         }
     }
 
-    /// Cycle focus across the three song fields.
+    fn value(&self, field: SongField) -> &String {
+        match field {
+            SongField::Title => &self.title,
+            SongField::Composer => &self.composer,
+            SongField::Parts => &self.parts,
+            SongField::Tags => &self.tags,
+            SongField::Arranger => &self.arranger,
+            SongField::Voicing => &self.voicing,
+            SongField::Accompaniment => &self.accompaniment,
+            SongField::Language => &self.language,
+            SongField::Publisher => &self.publisher,
+            SongField::CatalogNumber => &self.catalog_number,
+            SongField::Duration => &self.duration,
+            SongField::PageCount => &self.page_count,
+        }
+    }
+
+    fn value_mut(&mut self, field: SongField) -> &mut String {
+        match field {
+            SongField::Title => &mut self.title,
+            SongField::Composer => &mut self.composer,
+            SongField::Parts => &mut self.parts,
+            SongField::Tags => &mut self.tags,
+            SongField::Arranger => &mut self.arranger,
+            SongField::Voicing => &mut self.voicing,
+            SongField::Accompaniment => &mut self.accompaniment,
+            SongField::Language => &mut self.language,
+            SongField::Publisher => &mut self.publisher,
+            SongField::CatalogNumber => &mut self.catalog_number,
+            SongField::Duration => &mut self.duration,
+            SongField::PageCount => &mut self.page_count,
+        }
+    }
+
+    /// Cycle focus across the song fields.
     pub(crate) fn toggle_field(&mut self) {
-        self.active = match self.active {
-            SongField::Title => SongField::Composer,
-            SongField::Composer => SongField::Link,
-            SongField::Link => SongField::Title,
-        };
+        let index = SongField::ALL
+            .iter()
+            .position(|&field| field == self.active)
+            .unwrap_or(0);
+        self.active = SongField::ALL[(index + 1) % SongField::ALL.len()];
         if self.active != SongField::Composer {
             self.clear_suggestion();
         }
@@ -220,45 +370,64 @@ impl SongForm {
         if ch.is_control() {
             return false;
         }
-        match self.active {
-            SongField::Title => self.title.push(ch),
-            SongField::Composer => {
-                self.autocomplete_disabled = false;
-                self.composer.push(ch);
-            }
-            SongField::Link => self.link.push(ch),
+        if self.active == SongField::Composer {
+            self.autocomplete_disabled = false;
         }
+        self.value_mut(self.active).push(ch);
         true
     }
 
     /// Remove a character from the active field.
     pub(crate) fn backspace(&mut self) {
-        match self.active {
-            SongField::Title => {
-                self.title.pop();
-            }
-            SongField::Composer => {
-                self.composer.pop();
-                self.autocomplete_disabled = false;
-            }
-            SongField::Link => {
-                self.link.pop();
-            }
+        self.value_mut(self.active).pop();
+        if self.active == SongField::Composer {
+            self.autocomplete_disabled = false;
         }
     }
 
     /// Validate and normalize form inputs before they are written to the
     /// database.
-    pub(crate) fn parse_inputs(&self) -> Result<(String, String, String)> {
+    pub(crate) fn parse_inputs(&self) -> Result<SongInput> {
         let title = self.title.trim();
         if title.is_empty() {
             return Err(anyhow!("Song title is required."));
         }
-        Ok((
-            title.to_string(),
-            self.composer.trim().to_string(),
-            self.link.trim().to_string(),
-        ))
+        let parts: Vec<&str> = self
+            .parts
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .collect();
+        let duration_seconds = match self.duration.trim() {
+            "" => None,
+            text => Some(
+                parse_duration(text)
+                    .ok_or_else(|| anyhow!("Duration must be m:ss or whole minutes."))?,
+            ),
+        };
+        let page_count = match self.page_count.trim() {
+            "" => None,
+            text => Some(
+                text.parse()
+                    .map_err(|_| anyhow!("Pages must be a whole number."))?,
+            ),
+        };
+        Ok(SongInput {
+            title: title.to_string(),
+            composer: self.composer.trim().to_string(),
+            parts: parts.join(", "),
+            tags: parse_tags(&self.tags),
+            details: SongDetails {
+                arranger: self.arranger.trim().to_string(),
+                voicing: self.voicing.trim().to_string(),
+                accompaniment: self.accompaniment.trim().to_string(),
+                language: self.language.trim().to_string(),
+                publisher: self.publisher.trim().to_string(),
+                catalog_number: self.catalog_number.trim().to_string(),
+                duration_seconds,
+                page_count,
+            },
+        })
     }
 
     /// Update the composer autocomplete suggestion based on current input.
@@ -343,21 +512,12 @@ impl SongForm {
 
     /// Render a styled line for the modal form, optionally appending the
     /// autocomplete suffix.
-    pub(crate) fn build_line(&self, field_name: &str, field: SongField) -> Line<'static> {
-        let (value, is_active) = match field {
-            SongField::Title => (&self.title, self.active == SongField::Title),
-            SongField::Composer => (&self.composer, self.active == SongField::Composer),
-            SongField::Link => (&self.link, self.active == SongField::Link),
-        };
-
-        let placeholder = match field {
-            SongField::Title => "<required>",
-            SongField::Composer => "<optional>",
-            SongField::Link => "<optional>",
-        };
+    pub(crate) fn build_line(&self, field: SongField) -> Line<'static> {
+        let value = self.value(field);
+        let is_active = self.active == field;
 
         let display = if value.is_empty() {
-            placeholder.to_string()
+            field.placeholder().to_string()
         } else {
             value.clone()
         };
@@ -370,7 +530,7 @@ impl SongForm {
             Style::default()
         };
 
-        let mut spans = vec![Span::raw(format!("{field_name}: "))];
+        let mut spans = vec![Span::raw(format!("{}: ", field.label()))];
 
         if field == SongField::Composer && is_active && !value.is_empty() {
             spans.push(Span::styled(value.clone(), style));
@@ -391,12 +551,552 @@ impl SongForm {
 
     /// Character length of the requested field.
     pub(crate) fn value_len(&self, field: SongField) -> usize {
+        self.value(field).chars().count()
+    }
+}
+
+/// Form state for creating or editing a concert.
+#[derive(Default, Clone)]
+pub(crate) struct ConcertForm {
+    pub(crate) name: String,
+    pub(crate) date: String,
+    pub(crate) venue: String,
+    pub(crate) active: ConcertField,
+    pub(crate) error: Option<String>,
+}
+
+/// Fields available within the concert form.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum ConcertField {
+    #[default]
+    Name,
+    Date,
+    Venue,
+}
+
+impl ConcertForm {
+    /// Populate the form from an existing concert when editing.
+    pub(crate) fn from_concert(concert: &Concert) -> Self {
+        Self {
+            name: concert.name.clone(),
+            date: concert.date.clone(),
+            venue: concert.venue.clone(),
+            active: ConcertField::Name,
+            error: None,
+        }
+    }
+
+    /// Cycle focus forwards (or backwards) across the three fields.
+    pub(crate) fn toggle_field(&mut self, forward: bool) {
+        self.active = match (self.active, forward) {
+            (ConcertField::Name, true) | (ConcertField::Venue, false) => ConcertField::Date,
+            (ConcertField::Date, true) | (ConcertField::Name, false) => ConcertField::Venue,
+            (ConcertField::Venue, true) | (ConcertField::Date, false) => ConcertField::Name,
+        };
+    }
+
+    fn field_mut(&mut self, field: ConcertField) -> &mut String {
+        match field {
+            ConcertField::Name => &mut self.name,
+            ConcertField::Date => &mut self.date,
+            ConcertField::Venue => &mut self.venue,
+        }
+    }
+
+    fn field(&self, field: ConcertField) -> &String {
+        match field {
+            ConcertField::Name => &self.name,
+            ConcertField::Date => &self.date,
+            ConcertField::Venue => &self.venue,
+        }
+    }
+
+    /// Insert a character into the active field. The date only takes digits
+    /// and dashes.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        let allowed = match self.active {
+            ConcertField::Date => ch.is_ascii_digit() || ch == '-',
+            ConcertField::Name | ConcertField::Venue => !ch.is_control(),
+        };
+        if allowed {
+            self.field_mut(self.active).push(ch);
+        }
+        allowed
+    }
+
+    /// Remove the last character from the active field.
+    pub(crate) fn backspace(&mut self) {
+        self.field_mut(self.active).pop();
+    }
+
+    /// Validate the inputs, returning `(name, date, venue)`. The date may be
+    /// left blank but otherwise has to be a real `YYYY-MM-DD` date.
+    pub(crate) fn parse_inputs(&self) -> Result<(String, String, String)> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Concert name is required."));
+        }
+        let date = self.date.trim();
+        if !date.is_empty() {
+            NaiveDate::parse_from_str(date, "%Y-%m-%d")
+                .map_err(|_| anyhow!("Concert date must look like 2024-12-24."))?;
+        }
+        Ok((
+            name.to_string(),
+            date.to_string(),
+            self.venue.trim().to_string(),
+        ))
+    }
+
+    /// Render a single line for the form widget.
+    pub(crate) fn build_line(&self, field_name: &str, field: ConcertField) -> Line<'static> {
+        let value = self.field(field);
+        let is_active = self.active == field;
+        let placeholder = match field {
+            ConcertField::Name => "<required>",
+            ConcertField::Date => "<YYYY-MM-DD, optional>",
+            ConcertField::Venue => "<optional>",
+        };
+        let display = if value.is_empty() {
+            placeholder.to_string()
+        } else {
+            value.clone()
+        };
+
+        let style = if is_active {
+            Style::default().fg(Color::Yellow)
+        } else if value.is_empty() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::raw(format!("{field_name}: ")),
+            Span::styled(display, style),
+        ])
+    }
+
+    /// Character length of the requested field.
+    pub(crate) fn value_len(&self, field: ConcertField) -> usize {
+        self.field(field).chars().count()
+    }
+}
+
+/// Form state for adding or editing a chorister.
+#[derive(Default, Clone)]
+pub(crate) struct ChoristerForm {
+    pub(crate) name: String,
+    pub(crate) voice_part: String,
+    pub(crate) contact: String,
+    pub(crate) active: ChoristerField,
+    pub(crate) error: Option<String>,
+}
+
+/// Fields available within the chorister form.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum ChoristerField {
+    #[default]
+    Name,
+    VoicePart,
+    Contact,
+}
+
+impl ChoristerForm {
+    /// Populate the form from an existing chorister when editing.
+    pub(crate) fn from_chorister(chorister: &Chorister) -> Self {
+        Self {
+            name: chorister.name.clone(),
+            voice_part: chorister.voice_part.clone(),
+            contact: chorister.contact.clone(),
+            active: ChoristerField::Name,
+            error: None,
+        }
+    }
+
+    /// Cycle focus forwards (or backwards) across the three fields.
+    pub(crate) fn toggle_field(&mut self, forward: bool) {
+        self.active = match (self.active, forward) {
+            (ChoristerField::Name, true) | (ChoristerField::Contact, false) => {
+                ChoristerField::VoicePart
+            }
+            (ChoristerField::VoicePart, true) | (ChoristerField::Name, false) => {
+                ChoristerField::Contact
+            }
+            (ChoristerField::Contact, true) | (ChoristerField::VoicePart, false) => {
+                ChoristerField::Name
+            }
+        };
+    }
+
+    fn field_mut(&mut self, field: ChoristerField) -> &mut String {
+        match field {
+            ChoristerField::Name => &mut self.name,
+            ChoristerField::VoicePart => &mut self.voice_part,
+            ChoristerField::Contact => &mut self.contact,
+        }
+    }
+
+    fn field(&self, field: ChoristerField) -> &String {
+        match field {
+            ChoristerField::Name => &self.name,
+            ChoristerField::VoicePart => &self.voice_part,
+            ChoristerField::Contact => &self.contact,
+        }
+    }
+
+    /// Insert a character into the active field.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        if ch.is_control() {
+            return false;
+        }
+        self.field_mut(self.active).push(ch);
+        true
+    }
+
+    /// Remove the last character from the active field.
+    pub(crate) fn backspace(&mut self) {
+        self.field_mut(self.active).pop();
+    }
+
+    /// Validate the inputs, returning `(name, voice_part, contact)`.
+    pub(crate) fn parse_inputs(&self) -> Result<(String, String, String)> {
+        let name = self.name.trim();
+        if name.is_empty() {
+            return Err(anyhow!("Chorister name is required."));
+        }
+        Ok((
+            name.to_string(),
+            self.voice_part.trim().to_string(),
+            self.contact.trim().to_string(),
+        ))
+    }
+
+    /// Render a single line for the form widget.
+    pub(crate) fn build_line(&self, field_name: &str, field: ChoristerField) -> Line<'static> {
+        let value = self.field(field);
+        let is_active = self.active == field;
+        let placeholder = match field {
+            ChoristerField::Name => "<required>",
+            ChoristerField::VoicePart => "<e.g. Soprano, optional>",
+            ChoristerField::Contact => "<phone or email, optional>",
+        };
+        let display = if value.is_empty() {
+            placeholder.to_string()
+        } else {
+            value.clone()
+        };
+
+        let style = if is_active {
+            Style::default().fg(Color::Yellow)
+        } else if value.is_empty() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::raw(format!("{field_name}: ")),
+            Span::styled(display, style),
+        ])
+    }
+
+    /// Character length of the requested field.
+    pub(crate) fn value_len(&self, field: ChoristerField) -> usize {
+        self.field(field).chars().count()
+    }
+}
+
+/// Form state for recording a performance of one song.
+#[derive(Default, Clone)]
+pub(crate) struct PerformanceForm {
+    pub(crate) date: String,
+    pub(crate) event: String,
+    pub(crate) active: PerformanceField,
+    pub(crate) error: Option<String>,
+}
+
+/// Fields available within the performance form.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum PerformanceField {
+    #[default]
+    Date,
+    Event,
+}
+
+impl PerformanceForm {
+    /// Start a form dated `date`, usually today.
+    pub(crate) fn dated(date: String) -> Self {
+        Self {
+            date,
+            ..Self::default()
+        }
+    }
+
+    /// Switch focus between the date and the event.
+    pub(crate) fn toggle_field(&mut self) {
+        self.active = match self.active {
+            PerformanceField::Date => PerformanceField::Event,
+            PerformanceField::Event => PerformanceField::Date,
+        };
+    }
+
+    fn field_mut(&mut self, field: PerformanceField) -> &mut String {
+        match field {
+            PerformanceField::Date => &mut self.date,
+            PerformanceField::Event => &mut self.event,
+        }
+    }
+
+    fn field(&self, field: PerformanceField) -> &String {
+        match field {
+            PerformanceField::Date => &self.date,
+            PerformanceField::Event => &self.event,
+        }
+    }
+
+    /// Insert a character into the active field. The date only takes digits
+    /// and dashes.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        let allowed = match self.active {
+            PerformanceField::Date => ch.is_ascii_digit() || ch == '-',
+            PerformanceField::Event => !ch.is_control(),
+        };
+        if allowed {
+            self.field_mut(self.active).push(ch);
+        }
+        allowed
+    }
+
+    /// Remove the last character from the active field.
+    pub(crate) fn backspace(&mut self) {
+        self.field_mut(self.active).pop();
+    }
+
+    /// Validate the inputs, returning `(date, event)`. Unlike a concert, a
+    /// performance always needs a real date.
+    pub(crate) fn parse_inputs(&self) -> Result<(String, String)> {
+        let date = self.date.trim();
+        if date.is_empty() {
+            return Err(anyhow!("Performance date is required."));
+        }
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| anyhow!("Performance date must look like 2024-12-24."))?;
+        Ok((date.to_string(), self.event.trim().to_string()))
+    }
+
+    /// Render a single line for the form widget.
+    pub(crate) fn build_line(&self, field_name: &str, field: PerformanceField) -> Line<'static> {
+        let value = self.field(field);
+        let is_active = self.active == field;
+        let placeholder = match field {
+            PerformanceField::Date => "<YYYY-MM-DD>",
+            PerformanceField::Event => "<optional>",
+        };
+        let display = if value.is_empty() {
+            placeholder.to_string()
+        } else {
+            value.clone()
+        };
+
+        let style = if is_active {
+            Style::default().fg(Color::Yellow)
+        } else if value.is_empty() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::raw(format!("{field_name}: ")),
+            Span::styled(display, style),
+        ])
+    }
+
+    /// Character length of the requested field.
+    pub(crate) fn value_len(&self, field: PerformanceField) -> usize {
+        self.field(field).chars().count()
+    }
+}
+
+/// Form state for adding a typed link to a song.
+#[derive(Clone)]
+pub(crate) struct LinkForm {
+    pub(crate) kind: LinkKind,
+    pub(crate) part: String,
+    pub(crate) label: String,
+    pub(crate) url: String,
+    pub(crate) active: LinkField,
+    pub(crate) error: Option<String>,
+}
+
+impl Default for LinkForm {
+    fn default() -> Self {
+        Self {
+            kind: LinkKind::Score,
+            part: String::new(),
+            label: String::new(),
+            url: String::new(),
+            active: LinkField::default(),
+            error: None,
+        }
+    }
+}
+
+/// Fields available within the link form.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum LinkField {
+    #[default]
+    Kind,
+    Part,
+    Label,
+    Url,
+}
+
+impl LinkField {
+    /// Every field, top to bottom.
+    pub(crate) const ALL: [LinkField; 4] = [
+        LinkField::Kind,
+        LinkField::Part,
+        LinkField::Label,
+        LinkField::Url,
+    ];
+
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            LinkField::Kind => "Type",
+            LinkField::Part => "Part",
+            LinkField::Label => "Label",
+            LinkField::Url => "URL",
+        }
+    }
+}
+
+/// Validated values from the link form.
+pub(crate) struct LinkInput {
+    pub(crate) kind: LinkKind,
+    pub(crate) part: String,
+    pub(crate) label: String,
+    pub(crate) url: String,
+}
+
+impl LinkForm {
+    /// Move focus to the next field, wrapping around.
+    pub(crate) fn toggle_field(&mut self) {
+        let index = LinkField::ALL
+            .iter()
+            .position(|&field| field == self.active)
+            .unwrap_or(0);
+        self.active = LinkField::ALL[(index + 1) % LinkField::ALL.len()];
+    }
+
+    /// Step the link type forwards or backwards through [`LinkKind::ALL`].
+    pub(crate) fn cycle_kind(&mut self, forward: bool) {
+        let count = LinkKind::ALL.len();
+        let index = LinkKind::ALL
+            .iter()
+            .position(|&kind| kind == self.kind)
+            .unwrap_or(0);
+        let next = if forward {
+            (index + 1) % count
+        } else {
+            (index + count - 1) % count
+        };
+        self.kind = LinkKind::ALL[next];
+    }
+
+    fn field_mut(&mut self, field: LinkField) -> Option<&mut String> {
+        match field {
+            LinkField::Kind => None,
+            LinkField::Part => Some(&mut self.part),
+            LinkField::Label => Some(&mut self.label),
+            LinkField::Url => Some(&mut self.url),
+        }
+    }
+
+    fn display_value(&self, field: LinkField) -> String {
         match field {
-            SongField::Title => self.title.chars().count(),
-            SongField::Composer => self.composer.chars().count(),
-            SongField::Link => self.link.chars().count(),
+            LinkField::Kind => format!("◀ {} ▶", self.kind.label()),
+            LinkField::Part => self.part.clone(),
+            LinkField::Label => self.label.clone(),
+            LinkField::Url => self.url.clone(),
         }
     }
+
+    /// Insert a character into the active field. On the type field a space
+    /// cycles the type instead.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        if ch.is_control() {
+            return false;
+        }
+        match self.field_mut(self.active) {
+            Some(value) => value.push(ch),
+            None if ch == ' ' => self.cycle_kind(true),
+            None => return false,
+        }
+        true
+    }
+
+    /// Remove the last character from the active field.
+    pub(crate) fn backspace(&mut self) {
+        if let Some(value) = self.field_mut(self.active) {
+            value.pop();
+        }
+    }
+
+    /// Validate the inputs. The part is only kept for rehearsal tracks.
+    pub(crate) fn parse_inputs(&self) -> Result<LinkInput> {
+        let url = self.url.trim();
+        if url.is_empty() {
+            return Err(anyhow!("Link URL is required."));
+        }
+        let part = if self.kind == LinkKind::Rehearsal {
+            self.part.trim().to_string()
+        } else {
+            String::new()
+        };
+        Ok(LinkInput {
+            kind: self.kind,
+            part,
+            label: self.label.trim().to_string(),
+            url: url.to_string(),
+        })
+    }
+
+    /// Render a single line for the form widget.
+    pub(crate) fn build_line(&self, field: LinkField) -> Line<'static> {
+        let value = self.display_value(field);
+        let is_active = self.active == field;
+        let placeholder = match field {
+            LinkField::Kind => "",
+            LinkField::Part => "<rehearsal tracks only, e.g. T>",
+            LinkField::Label => "<optional, e.g. King's College 1998>",
+            LinkField::Url => "<https://… or a file path>",
+        };
+        let display = if value.is_empty() {
+            placeholder.to_string()
+        } else {
+            value
+        };
+
+        let style = if is_active {
+            Style::default().fg(Color::Yellow)
+        } else if display == placeholder {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::raw(format!("{}: ", field.label())),
+            Span::styled(display, style),
+        ])
+    }
+
+    /// Character length of the requested field as displayed.
+    pub(crate) fn value_len(&self, field: LinkField) -> usize {
+        self.display_value(field).chars().count()
+    }
 }
 
 /// State for confirming the removal of a song from a specific binder.
@@ -410,8 +1110,8 @@ pub(crate) struct ConfirmSongDelete {
     pub(crate) song: Song,
 }
 
-/// Tracks the user's choice when leaving the "To Print" flow with unsaved
-/// changes.
+/// Tracks the user's choice when leaving the "To Print" flow while songs are
+/// marked as inserted but not yet linked to their binders.
 pub(crate) struct ConfirmToPrintExit {
     pub(crate) exit_app: bool,
     pub(crate) selection: ConfirmPrintChoice,
@@ -426,30 +1126,30 @@ impl ConfirmToPrintExit {
         }
     }
 
-    /// Move the selection forward (Apply → Discard → Cancel).
+    /// Move the selection forward (Apply → Keep → Cancel).
     pub(crate) fn next(&mut self) {
         self.selection = match self.selection {
-            ConfirmPrintChoice::Apply => ConfirmPrintChoice::Discard,
-            ConfirmPrintChoice::Discard => ConfirmPrintChoice::Cancel,
+            ConfirmPrintChoice::Apply => ConfirmPrintChoice::Keep,
+            ConfirmPrintChoice::Keep => ConfirmPrintChoice::Cancel,
             ConfirmPrintChoice::Cancel => ConfirmPrintChoice::Apply,
         };
     }
 
-    /// Move the selection backward (Apply ← Discard ← Cancel).
+    /// Move the selection backward (Apply ← Keep ← Cancel).
     pub(crate) fn previous(&mut self) {
         self.selection = match self.selection {
             ConfirmPrintChoice::Apply => ConfirmPrintChoice::Cancel,
-            ConfirmPrintChoice::Discard => ConfirmPrintChoice::Apply,
-            ConfirmPrintChoice::Cancel => ConfirmPrintChoice::Discard,
+            ConfirmPrintChoice::Keep => ConfirmPrintChoice::Apply,
+            ConfirmPrintChoice::Cancel => ConfirmPrintChoice::Keep,
         };
     }
 
     /// Labels rendered on the dialog buttons.
     pub(crate) fn labels(&self) -> [&'static str; 3] {
         if self.exit_app {
-            ["Apply & Quit", "Discard & Quit", "Cancel"]
+            ["Apply & Quit", "Keep & Quit", "Cancel"]
         } else {
-            ["Apply & Leave", "Discard & Leave", "Cancel"]
+            ["Apply & Leave", "Keep & Leave", "Cancel"]
         }
     }
 
@@ -457,7 +1157,7 @@ impl ConfirmToPrintExit {
     pub(crate) fn selected_index(&self) -> usize {
         match self.selection {
             ConfirmPrintChoice::Apply => 0,
-            ConfirmPrintChoice::Discard => 1,
+            ConfirmPrintChoice::Keep => 1,
             ConfirmPrintChoice::Cancel => 2,
         }
     }
@@ -466,7 +1166,242 @@ impl ConfirmToPrintExit {
 /// Options presented in the print confirmation dialog.
 #[derive(Copy, Clone)]
 pub(crate) enum ConfirmPrintChoice {
+    /// Link the inserted songs to their binders.
     Apply,
-    Discard,
+    /// Leave the print queue as it is for next time.
+    Keep,
+    /// Stay on the report.
     Cancel,
 }
+
+/// Inputs for importing songs from a CSV file. Column fields left blank fall
+/// back to the usual header names.
+#[derive(Default, Clone)]
+pub(crate) struct CsvImportForm {
+    pub(crate) path: String,
+    pub(crate) title_column: String,
+    pub(crate) composer_column: String,
+    pub(crate) link_column: String,
+    pub(crate) binders_column: String,
+    pub(crate) active: CsvImportField,
+    pub(crate) error: Option<String>,
+}
+
+/// Fields of the CSV import form, in display order.
+#[derive(Copy, Clone, PartialEq, Eq, Default)]
+pub(crate) enum CsvImportField {
+    #[default]
+    Path,
+    Title,
+    Composer,
+    Link,
+    Binders,
+}
+
+impl CsvImportField {
+    pub(crate) const ALL: [CsvImportField; 5] = [
+        CsvImportField::Path,
+        CsvImportField::Title,
+        CsvImportField::Composer,
+        CsvImportField::Link,
+        CsvImportField::Binders,
+    ];
+
+    /// Label shown in front of the field value.
+    pub(crate) fn label(self) -> &'static str {
+        match self {
+            CsvImportField::Path => "CSV file",
+            CsvImportField::Title => "Title column",
+            CsvImportField::Composer => "Composer column",
+            CsvImportField::Link => "Link column",
+            CsvImportField::Binders => "Binders column",
+        }
+    }
+
+    fn index(self) -> usize {
+        Self::ALL
+            .iter()
+            .position(|field| *field == self)
+            .unwrap_or(0)
+    }
+}
+
+impl CsvImportForm {
+    /// Move focus down (`step` 1) or up (`step` -1), wrapping around.
+    pub(crate) fn move_field(&mut self, step: isize) {
+        let len = CsvImportField::ALL.len() as isize;
+        let index = (self.active.index() as isize + step).rem_euclid(len);
+        self.active = CsvImportField::ALL[index as usize];
+    }
+
+    fn value(&self, field: CsvImportField) -> &String {
+        match field {
+            CsvImportField::Path => &self.path,
+            CsvImportField::Title => &self.title_column,
+            CsvImportField::Composer => &self.composer_column,
+            CsvImportField::Link => &self.link_column,
+            CsvImportField::Binders => &self.binders_column,
+        }
+    }
+
+    fn value_mut(&mut self, field: CsvImportField) -> &mut String {
+        match field {
+            CsvImportField::Path => &mut self.path,
+            CsvImportField::Title => &mut self.title_column,
+            CsvImportField::Composer => &mut self.composer_column,
+            CsvImportField::Link => &mut self.link_column,
+            CsvImportField::Binders => &mut self.binders_column,
+        }
+    }
+
+    /// Insert a character into the active field.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        if ch.is_control() {
+            return false;
+        }
+        self.value_mut(self.active).push(ch);
+        true
+    }
+
+    /// Remove the last character from the active field.
+    pub(crate) fn backspace(&mut self) {
+        self.value_mut(self.active).pop();
+    }
+
+    /// The file to read, with a leading `~/` expanded to the home directory.
+    pub(crate) fn parse_path(&self) -> Result<PathBuf> {
+        parse_path_input(&self.path, "CSV file is required.")
+    }
+
+    /// Column mapping from the optional column fields.
+    pub(crate) fn mapping(&self) -> ColumnMapping {
+        let column = |value: &String| {
+            let value = value.trim();
+            (!value.is_empty()).then(|| value.to_string())
+        };
+        ColumnMapping {
+            title: column(&self.title_column),
+            composer: column(&self.composer_column),
+            link: column(&self.link_column),
+            binders: column(&self.binders_column),
+        }
+    }
+
+    /// Render a single line for the form widget.
+    pub(crate) fn build_line(&self, field: CsvImportField) -> Line<'static> {
+        let value = self.value(field);
+        let display = match (value.is_empty(), field) {
+            (false, _) => value.clone(),
+            (true, CsvImportField::Path) => "<required>".to_string(),
+            (true, _) => "<auto>".to_string(),
+        };
+
+        let style = if self.active == field {
+            Style::default().fg(Color::Yellow)
+        } else if value.is_empty() {
+            Style::default().fg(Color::DarkGray)
+        } else {
+            Style::default()
+        };
+
+        Line::from(vec![
+            Span::raw(format!("{}: ", field.label())),
+            Span::styled(display, style),
+        ])
+    }
+
+    /// Character length of the requested field.
+    pub(crate) fn value_len(&self, field: CsvImportField) -> usize {
+        self.value(field).chars().count()
+    }
+}
+
+/// A planned CSV import waiting for confirmation. The form is kept so the
+/// user can go back and adjust the mapping.
+pub(crate) struct CsvImportPreview {
+    pub(crate) form: CsvImportForm,
+    pub(crate) plan: ImportPlan,
+}
+
+/// Turn a typed path into a `PathBuf`, expanding a leading `~/` to the home
+/// directory. `missing` is the error shown when the input is blank.
+fn parse_path_input(raw: &str, missing: &str) -> Result<PathBuf> {
+    let raw = raw.trim();
+    if raw.is_empty() {
+        return Err(anyhow!("{missing}"));
+    }
+    if let Some(rest) = raw.strip_prefix("~/") {
+        let dirs = BaseDirs::new().context("Could not determine the home directory.")?;
+        return Ok(dirs.home_dir().join(rest));
+    }
+    Ok(PathBuf::from(raw))
+}
+
+/// Single-field form asking where CSV exports should be written.
+#[derive(Default, Clone)]
+pub(crate) struct ExportForm {
+    pub(crate) dir: String,
+    pub(crate) error: Option<String>,
+}
+
+impl ExportForm {
+    /// Start with a suggested directory, if the app knows one.
+    pub(crate) fn with_dir(dir: Option<&Path>) -> Self {
+        Self {
+            dir: dir.map(|dir| dir.display().to_string()).unwrap_or_default(),
+            error: None,
+        }
+    }
+
+    /// Insert a character into the directory field.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        if ch.is_control() {
+            return false;
+        }
+        self.dir.push(ch);
+        true
+    }
+
+    /// Remove the last character from the directory field.
+    pub(crate) fn backspace(&mut self) {
+        self.dir.pop();
+    }
+
+    /// The target directory, with a leading `~/` expanded.
+    pub(crate) fn parse_dir(&self) -> Result<PathBuf> {
+        parse_path_input(&self.dir, "Export folder is required.")
+    }
+}
+
+/// Single-field form asking for a local file to attach to a song.
+#[derive(Default, Clone)]
+pub(crate) struct AttachForm {
+    pub(crate) path: String,
+    pub(crate) error: Option<String>,
+}
+
+impl AttachForm {
+    /// Insert a character into the path field.
+    pub(crate) fn push_char(&mut self, ch: char) -> bool {
+        if ch.is_control() {
+            return false;
+        }
+        self.path.push(ch);
+        true
+    }
+
+    /// Remove the last character from the path field.
+    pub(crate) fn backspace(&mut self) {
+        self.path.pop();
+    }
+
+    /// The file to attach, with a leading `~/` expanded. Fails unless it
+    /// names an existing file.
+    pub(crate) fn parse_path(&self) -> Result<PathBuf> {
+        let path = parse_path_input(&self.path, "File path is required.")?;
+        if !path.is_file() {
+            return Err(anyhow!("{} is not a file.", path.display()));
+        }
+        Ok(path)
+    }
+}
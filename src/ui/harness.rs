@@ -0,0 +1,185 @@
+//! Headless driver for [`App`] used by the UI regression tests. It feeds key
+//! scripts through the same dispatch path as the real event loop and renders
+//! into ratatui's `TestBackend`, so tests can assert on both the screen and the
+//! library underneath.
+
+use std::path::PathBuf;
+
+use crossterm::event::{KeyCode, KeyEvent, KeyModifiers};
+use ratatui::backend::TestBackend;
+use ratatui::Terminal;
+
+use crate::db::{AttachmentStore, BackupStore, Library, MemoryLibrary};
+use crate::printing::Printer;
+
+use super::app::App;
+use super::terminal::dispatch_key;
+
+/// Terminal size used unless a test asks for something else. Wide enough for
+/// every footer to fit on one line.
+const DEFAULT_SIZE: (u16, u16) = (160, 48);
+
+pub(crate) struct Harness {
+    app: App,
+    terminal: Terminal<TestBackend>,
+    exited: bool,
+}
+
+impl Harness {
+    /// Build an app over `library`, loading binders and composers the same way
+    /// `main` does.
+    pub(crate) fn new(library: MemoryLibrary) -> Self {
+        let binders = library.fetch_binders().expect("load binders");
+        let composers = library.fetch_composers().expect("load composers");
+        let mut app = App::new(Box::new(library), binders, composers);
+        app.set_opener(|_| Ok(()));
+        let (width, height) = DEFAULT_SIZE;
+        let terminal = Terminal::new(TestBackend::new(width, height)).expect("test terminal");
+        Self {
+            app,
+            terminal,
+            exited: false,
+        }
+    }
+
+    /// Turn on rotating backups the way `main` does, including the startup
+    /// backup.
+    pub(crate) fn enable_backups(&mut self, store: BackupStore) -> &mut Self {
+        self.app.enable_backups(store);
+        self
+    }
+
+    /// Point the app at a data folder, as `main` does with the library's
+    /// folder.
+    pub(crate) fn set_data_dir(&mut self, dir: PathBuf) -> &mut Self {
+        self.app.set_data_dir(dir);
+        self
+    }
+
+    /// Keep attached files in `store`, as `main` does next to the library.
+    pub(crate) fn enable_attachments(&mut self, store: AttachmentStore) -> &mut Self {
+        self.app.set_attachments(store);
+        self
+    }
+
+    /// Send print jobs through `printer`, as `main` does with the configured
+    /// one.
+    pub(crate) fn enable_printer(&mut self, printer: Printer) -> &mut Self {
+        self.app.set_printer(printer);
+        self
+    }
+
+    /// Press a single key without modifiers.
+    pub(crate) fn press(&mut self, code: KeyCode) -> &mut Self {
+        self.send(KeyEvent::new(code, KeyModifiers::NONE))
+    }
+
+    /// Press `Ctrl` plus a character, as the terminal would report it.
+    pub(crate) fn ctrl(&mut self, ch: char) -> &mut Self {
+        self.send(KeyEvent::new(KeyCode::Char(ch), KeyModifiers::CONTROL))
+    }
+
+    /// Play a key script. Plain characters are typed as-is; named keys go in
+    /// angle brackets (`<Enter>`, `<Esc>`, `<Tab>`, `<BackTab>`, `<Space>`,
+    /// `<Backspace>`, `<Up>`, `<Down>`, `<Left>`, `<Right>`, `<PgUp>`,
+    /// `<PgDn>`, `<Home>`, `<End>`, `<lt>` for a literal `<`) and control
+    /// chords as `<C-x>`.
+    pub(crate) fn keys(&mut self, script: &str) -> &mut Self {
+        let mut chars = script.chars();
+        while let Some(ch) = chars.next() {
+            if ch != '<' {
+                self.press(KeyCode::Char(ch));
+                continue;
+            }
+            let name: String = chars.by_ref().take_while(|&c| c != '>').collect();
+            if let Some(chord) = name.strip_prefix("C-") {
+                let mut letters = chord.chars();
+                let letter = letters.next().expect("control chord needs a key");
+                assert!(letters.next().is_none(), "bad control chord <{name}>");
+                self.ctrl(letter);
+                continue;
+            }
+            let code = match name.as_str() {
+                "Enter" => KeyCode::Enter,
+                "Esc" => KeyCode::Esc,
+                "Tab" => KeyCode::Tab,
+                "BackTab" => KeyCode::BackTab,
+                "Space" => KeyCode::Char(' '),
+                "Backspace" => KeyCode::Backspace,
+                "Up" => KeyCode::Up,
+                "Down" => KeyCode::Down,
+                "Left" => KeyCode::Left,
+                "Right" => KeyCode::Right,
+                "PgUp" => KeyCode::PageUp,
+                "PgDn" => KeyCode::PageDown,
+                "Home" => KeyCode::Home,
+                "End" => KeyCode::End,
+                "lt" => KeyCode::Char('<'),
+                other => panic!("unknown key <{other}> in script"),
+            };
+            self.press(code);
+        }
+        self
+    }
+
+    /// Whether the app asked to quit.
+    pub(crate) fn exited(&self) -> bool {
+        self.exited
+    }
+
+    /// Borrow the library to assert on persisted state.
+    pub(crate) fn library(&self) -> &dyn Library {
+        self.app.library()
+    }
+
+    /// Change the library without going through the UI.
+    pub(crate) fn library_mut(&mut self) -> &mut dyn Library {
+        self.app.library_mut()
+    }
+
+    /// Draw a frame and return the screen as text, one line per terminal row
+    /// with trailing spaces trimmed.
+    pub(crate) fn render(&mut self) -> String {
+        self.terminal
+            .draw(|frame| self.app.draw(frame))
+            .expect("draw frame");
+        let buffer = self.terminal.backend().buffer();
+        let width = buffer.area.width as usize;
+        buffer
+            .content
+            .chunks(width)
+            .map(|row| {
+                let line: String = row.iter().map(|cell| cell.symbol()).collect();
+                line.trim_end().to_string()
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+
+    /// Render and assert that `needle` appears somewhere on screen.
+    pub(crate) fn assert_screen_contains(&mut self, needle: &str) {
+        let screen = self.render();
+        assert!(
+            screen.contains(needle),
+            "expected screen to contain {needle:?}\n{screen}"
+        );
+    }
+
+    /// Render and assert that `needle` does not appear on screen.
+    pub(crate) fn assert_screen_lacks(&mut self, needle: &str) {
+        let screen = self.render();
+        assert!(
+            !screen.contains(needle),
+            "expected screen not to contain {needle:?}\n{screen}"
+        );
+    }
+
+    fn send(&mut self, event: KeyEvent) -> &mut Self {
+        assert!(!self.exited, "key sent after the app exited");
+        // Draw before every key, like the event loop, so state that only
+        // settles during rendering is exercised too.
+        self.render();
+        self.exited = dispatch_key(&mut self.app, event).expect("key handler failed");
+        self
+    }
+}
@@ -1,9 +1,11 @@
+use std::collections::HashMap;
+
 use anyhow::Error;
 use ratatui::layout::{Constraint, Direction, Layout, Rect};
 use ratatui::style::{Color, Modifier, Style};
 use ratatui::text::{Line, Span};
 
-use crate::models::Binder;
+use crate::models::{Attachment, Binder, SongLink};
 
 /// Repeat a short ASCII motif until it fills the requested width.
 pub(crate) fn repeat_pattern_row(row: &str, width: usize) -> String {
@@ -19,6 +21,15 @@ pub(crate) fn repeat_pattern_row(row: &str, width: usize) -> String {
     repeated
 }
 
+/// Card title for a binder, with its voice part when it has one.
+pub(crate) fn binder_card_title(binder: &Binder) -> String {
+    if binder.voice_part.is_empty() {
+        format!("Binder {:02}", binder.number)
+    } else {
+        format!("Binder {:02} · {}", binder.number, binder.voice_part)
+    }
+}
+
 /// Render the binder label centered inside square brackets.
 pub(crate) fn binder_label_line(label: &str, width: usize) -> String {
     if width == 0 {
@@ -28,29 +39,32 @@ pub(crate) fn binder_label_line(label: &str, width: usize) -> String {
     if trimmed.is_empty() {
         return " ".repeat(width);
     }
-    let mut decorated = format!("[ {} ]", trimmed);
-    if decorated.len() > width {
-        decorated.truncate(width);
+    centered(&format!("[ {} ]", trimmed), width)
+}
+
+/// Render the name of the chorister holding a binder, centered.
+pub(crate) fn binder_holder_line(name: &str, width: usize) -> String {
+    if width == 0 {
+        return String::new();
     }
-    let padding = width.saturating_sub(decorated.len());
+    centered(&format!("♪ {}", name.trim()), width)
+}
+
+/// Pad `text` with spaces on both sides to exactly `width` characters,
+/// cutting it short if it does not fit.
+fn centered(text: &str, width: usize) -> String {
+    let text: String = text.chars().take(width).collect();
+    let padding = width.saturating_sub(text.chars().count());
     let left = padding / 2;
-    let right = padding - left;
-    let mut line = String::with_capacity(width);
-    line.push_str(&" ".repeat(left));
-    line.push_str(&decorated);
-    line.push_str(&" ".repeat(right));
-    if line.len() < width {
-        line.push_str(&" ".repeat(width - line.len()));
-    } else if line.len() > width {
-        line.truncate(width);
-    }
-    line
+    format!("{}{text}{}", " ".repeat(left), " ".repeat(padding - left))
 }
 
 /// Build the textual payload for a binder card, mixing the repeating pattern
-/// with an optional bold highlight when the card is selected.
+/// with an optional bold highlight when the card is selected. `holder` is the
+/// name of the chorister the binder is assigned to.
 pub(crate) fn build_binder_cover_lines(
     binder: &Binder,
+    holder: Option<&str>,
     pattern: &[&str],
     inner_width: u16,
     inner_height: u16,
@@ -64,7 +78,13 @@ pub(crate) fn build_binder_cover_lines(
 
     let mut lines = Vec::with_capacity(height);
     let pattern_rows = pattern.len();
-    let label_lines = if height >= 2 { 2 } else { 1 };
+    // The holder's name goes under the label when there is room for it.
+    let holder = holder.filter(|_| height >= 3);
+    let label_lines = match (height >= 2, holder.is_some()) {
+        (true, true) => 3,
+        (true, false) => 2,
+        (false, _) => 1,
+    };
     let pattern_height = height.saturating_sub(label_lines);
     let pattern_style = if selected {
         Style::default().fg(Color::Gray)
@@ -104,6 +124,13 @@ pub(crate) fn build_binder_cover_lines(
         lines.push(Line::from(label_content));
     }
 
+    if let Some(name) = holder {
+        lines.push(Line::from(vec![Span::styled(
+            binder_holder_line(name, width),
+            Style::default().fg(Color::Cyan),
+        )]));
+    }
+
     while lines.len() < height {
         lines.push(Line::from(vec![Span::styled(
             " ".repeat(width),
@@ -114,6 +141,44 @@ pub(crate) fn build_binder_cover_lines(
     lines
 }
 
+/// A song's tag names from the per-song tag map; empty when it has none.
+pub(crate) fn tags_of(tags: &HashMap<i64, Vec<String>>, song_id: i64) -> &[String] {
+    tags.get(&song_id).map_or(&[], Vec::as_slice)
+}
+
+/// Render tags as chips, each preceded by a space so they can follow a title.
+pub(crate) fn tag_chips(tags: &[String]) -> Vec<Span<'static>> {
+    let chip_style = Style::default().fg(Color::Black).bg(Color::Cyan);
+    tags.iter()
+        .flat_map(|tag| [Span::raw(" "), Span::styled(format!(" {tag} "), chip_style)])
+        .collect()
+}
+
+/// List typed links by kind and part for a song card, e.g.
+/// `Recording • Rehearsal track (T)`.
+pub(crate) fn link_summary(links: &[SongLink]) -> String {
+    links
+        .iter()
+        .map(|link| {
+            if link.part.trim().is_empty() {
+                link.kind.label().to_string()
+            } else {
+                format!("{} ({})", link.kind.label(), link.part.trim())
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(" • ")
+}
+
+/// Names of a song's attached files for its card, e.g. "Files: score.pdf".
+pub(crate) fn attachment_summary(attachments: &[Attachment]) -> String {
+    let names: Vec<&str> = attachments
+        .iter()
+        .map(|attachment| attachment.file_name.as_str())
+        .collect();
+    format!("Files: {}", names.join(" • "))
+}
+
 /// Produce a rectangle centered within `area` that spans the requested percent
 /// of the width and height. Used for modal dialogs.
 pub(crate) fn centered_rect(percent_x: u16, percent_y: u16, area: Rect) -> Rect {
@@ -1,8 +1,12 @@
 mod app;
 mod forms;
+#[cfg(test)]
+mod harness;
 mod helpers;
 mod screens;
 mod terminal;
+#[cfg(test)]
+mod tests;
 
 pub use app::App;
 pub use terminal::run_app;
@@ -1,11 +1,17 @@
 use std::cmp::{min, Ordering};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
-use anyhow::{Context, Result};
-use rusqlite::Connection;
+use chrono::{Local, Months, NaiveDateTime};
 
-use crate::db::fetch_available_songs;
-use crate::models::{Binder, Song};
+use crate::db::{BackupInfo, DbResult, Library};
+use crate::models::{
+    format_size, Attachment, Binder, BinderCheckout, Chorister, Concert, Performance,
+    PerformanceStats, PrintStage, Song, SongLink,
+};
+use crate::reports::{overdue_report, MissingSong, OverdueBinder, SongDemand};
+
+/// Steps the "not performed in N years" filter cycles through.
+const UNSUNG_YEARS: &[u32] = &[1, 2, 3, 5];
 
 /// Wrapper around the global song list used by the manager screen.
 pub(crate) struct SongManagerScreen {
@@ -13,16 +19,46 @@ pub(crate) struct SongManagerScreen {
     pub(crate) filtered_songs: Vec<Song>,
     pub(crate) filter: Option<String>,
     pub(crate) show_only_no_link: bool,
+    /// When set, only songs not performed within this many years are listed,
+    /// longest-unsung first.
+    pub(crate) unsung_years: Option<u32>,
+    pub(crate) stats: HashMap<i64, PerformanceStats>,
+    /// When set, only songs carrying this tag (ignoring case) are listed.
+    pub(crate) tag_filter: Option<String>,
+    pub(crate) tags: HashMap<i64, Vec<String>>,
+    /// Songs with at least one link, which the no-link filter leaves out.
+    pub(crate) linked: HashSet<i64>,
     pub(crate) selected: usize,
 }
 
+/// The date `years` years before today as `YYYY-MM-DD`, comparable with
+/// performance dates.
+fn years_ago(years: u32) -> String {
+    let today = Local::now().date_naive();
+    today
+        .checked_sub_months(Months::new(years * 12))
+        .unwrap_or(today)
+        .format("%Y-%m-%d")
+        .to_string()
+}
+
 impl SongManagerScreen {
-    pub(crate) fn new(songs: Vec<Song>) -> Self {
+    pub(crate) fn new(
+        songs: Vec<Song>,
+        stats: HashMap<i64, PerformanceStats>,
+        tags: HashMap<i64, Vec<String>>,
+        linked: HashSet<i64>,
+    ) -> Self {
         let mut screen = Self {
             filtered_songs: Vec::new(),
             songs,
             filter: None,
             show_only_no_link: false,
+            unsung_years: None,
+            stats,
+            tag_filter: None,
+            tags,
+            linked,
             selected: 0,
         };
         screen.apply_filter();
@@ -38,10 +74,7 @@ impl SongManagerScreen {
             } else {
                 self.songs
                     .iter()
-                    .filter(|s| {
-                        s.title.to_lowercase().contains(&ql)
-                            || s.composer.to_lowercase().contains(&ql)
-                    })
+                    .filter(|s| s.matches_query(&ql))
                     .cloned()
                     .collect()
             }
@@ -52,12 +85,34 @@ impl SongManagerScreen {
         if self.show_only_no_link {
             self.filtered_songs = base
                 .into_iter()
-                .filter(|s| s.link.trim().is_empty())
+                .filter(|s| !self.linked.contains(&s.id))
                 .collect();
         } else {
             self.filtered_songs = base;
         }
 
+        if let Some(tag) = &self.tag_filter {
+            let tags = &self.tags;
+            self.filtered_songs.retain(|song| {
+                tags.get(&song.id)
+                    .is_some_and(|names| names.iter().any(|name| name.eq_ignore_ascii_case(tag)))
+            });
+        }
+
+        if let Some(years) = self.unsung_years {
+            let cutoff = years_ago(years);
+            let stats = &self.stats;
+            let last_date = |song: &Song| {
+                stats
+                    .get(&song.id)
+                    .map_or("", |stat| stat.last_date.as_str())
+            };
+            self.filtered_songs
+                .retain(|song| last_date(song) < cutoff.as_str());
+            self.filtered_songs
+                .sort_by(|a, b| last_date(a).cmp(last_date(b)));
+        }
+
         if self.filtered_songs.is_empty() {
             self.selected = 0;
         } else if self.selected >= self.filtered_songs.len() {
@@ -76,6 +131,71 @@ impl SongManagerScreen {
         self.show_only_no_link
     }
 
+    /// Step the "not performed in N years" filter to the next length, turning
+    /// it off after the longest. Returns the new setting.
+    pub(crate) fn cycle_unsung_filter(&mut self) -> Option<u32> {
+        self.unsung_years = match self.unsung_years {
+            None => UNSUNG_YEARS.first().copied(),
+            Some(years) => UNSUNG_YEARS.iter().copied().find(|&step| step > years),
+        };
+        self.apply_filter();
+        self.unsung_years
+    }
+
+    pub(crate) fn set_stats(&mut self, stats: HashMap<i64, PerformanceStats>) {
+        self.stats = stats;
+        self.apply_filter();
+    }
+
+    pub(crate) fn set_linked(&mut self, linked: HashSet<i64>) {
+        self.linked = linked;
+        self.apply_filter();
+    }
+
+    /// Every tag in use, sorted ignoring case.
+    fn tags_in_use(&self) -> Vec<String> {
+        let mut names: Vec<String> = Vec::new();
+        for name in self.tags.values().flatten() {
+            if !names.iter().any(|seen| seen.eq_ignore_ascii_case(name)) {
+                names.push(name.clone());
+            }
+        }
+        names.sort_by_key(|name| name.to_lowercase());
+        names
+    }
+
+    /// Step the tag filter to the next tag in use, turning it off after the
+    /// last. Returns the new setting.
+    pub(crate) fn cycle_tag_filter(&mut self) -> Option<String> {
+        let names = self.tags_in_use();
+        self.tag_filter = match &self.tag_filter {
+            None => names.first().cloned(),
+            Some(current) => names
+                .iter()
+                .position(|name| name.eq_ignore_ascii_case(current))
+                .and_then(|index| names.get(index + 1))
+                .cloned(),
+        };
+        self.apply_filter();
+        self.tag_filter.clone()
+    }
+
+    /// Replace the per-song tags, dropping the tag filter if no song carries
+    /// that tag any more.
+    pub(crate) fn set_tags(&mut self, tags: HashMap<i64, Vec<String>>) {
+        self.tags = tags;
+        if let Some(current) = &self.tag_filter {
+            if !self
+                .tags_in_use()
+                .iter()
+                .any(|name| name.eq_ignore_ascii_case(current))
+            {
+                self.tag_filter = None;
+            }
+        }
+        self.apply_filter();
+    }
+
     pub(crate) fn current_song(&self) -> Option<&Song> {
         self.filtered_songs.get(self.selected)
     }
@@ -130,6 +250,9 @@ pub(crate) enum ToPrintMode {
 /// All state required to render and interact with the "To Print" report.
 pub(crate) struct ToPrintScreen {
     pub(crate) director_exists: bool,
+    /// The concert whose setlist the binders are compared against; `None`
+    /// when comparing against the director's binder.
+    pub(crate) concert: Option<Concert>,
     pub(crate) mode: ToPrintMode,
     pub(crate) binder_reports: Vec<BinderReport>,
     pub(crate) binder_rows: Vec<BinderRow>,
@@ -137,16 +260,20 @@ pub(crate) struct ToPrintScreen {
     pub(crate) song_rows: Vec<SongRow>,
     pub(crate) scroll: u16,
     pub(crate) selected_index: usize,
-    pub(crate) pending_changes: usize,
 }
 
 impl ToPrintScreen {
+    /// Build the report from what each binder is missing and the copies each
+    /// song needs. Songs already printed or inserted, as `binder_reports`
+    /// records them from the print queue, are taken off the copies needed.
     pub(crate) fn with_data(
+        concert: Option<Concert>,
         binder_reports: Vec<BinderReport>,
         song_totals: Vec<SongNeeded>,
     ) -> Self {
         let mut screen = Self {
             director_exists: true,
+            concert,
             mode: ToPrintMode::ByBinder,
             binder_reports,
             binder_rows: Vec::new(),
@@ -154,7 +281,6 @@ impl ToPrintScreen {
             song_rows: Vec::new(),
             scroll: 0,
             selected_index: 0,
-            pending_changes: 0,
         };
         screen.refresh_binder_rows();
         screen.refresh_song_rows();
@@ -164,6 +290,7 @@ impl ToPrintScreen {
     pub(crate) fn missing_director() -> Self {
         Self {
             director_exists: false,
+            concert: None,
             mode: ToPrintMode::ByBinder,
             binder_reports: Vec::new(),
             binder_rows: Vec::new(),
@@ -171,7 +298,6 @@ impl ToPrintScreen {
             song_rows: Vec::new(),
             scroll: 0,
             selected_index: 0,
-            pending_changes: 0,
         }
     }
 
@@ -214,11 +340,7 @@ impl ToPrintScreen {
         if !self.director_exists {
             return;
         }
-        if self.current_len() == 0 {
-            self.selected_index = 0;
-        } else {
-            self.selected_index = 0;
-        }
+        self.selected_index = 0;
         self.update_scroll();
     }
 
@@ -283,7 +405,8 @@ impl ToPrintScreen {
         }
     }
 
-    pub(crate) fn toggle_current(&mut self) -> Option<bool> {
+    /// Binder and song indexes of the highlighted song row, if any.
+    fn current_song_index(&self) -> Option<(usize, usize)> {
         if !self.director_exists || self.mode != ToPrintMode::ByBinder {
             return None;
         }
@@ -292,35 +415,86 @@ impl ToPrintScreen {
         if row.kind != BinderRowKind::Song {
             return None;
         }
+        Some((row.binder_index?, row.song_index?))
+    }
 
-        let binder_idx = row.binder_index?;
-        let song_idx = row.song_index?;
-        let (song_id, now_checked) = {
-            let entry = &mut self.binder_reports[binder_idx].songs[song_idx];
-            entry.checked = !entry.checked;
-            (entry.song.id, entry.checked)
-        };
+    /// The binder id, song id and stage the highlighted song would move on to
+    /// (needed → printed → inserted → needed), without changing anything, so
+    /// the caller can save it to the print queue first.
+    pub(crate) fn next_stage_for_current(&self) -> Option<(i64, i64, PrintStage)> {
+        let (binder_idx, song_idx) = self.current_song_index()?;
+        let report = &self.binder_reports[binder_idx];
+        let entry = &report.songs[song_idx];
+        Some((report.binder_id, entry.song.id, entry.stage.next()))
+    }
 
-        if now_checked {
-            self.pending_changes += 1;
-            self.adjust_song_needed(song_id, -1);
-        } else {
-            self.pending_changes = self.pending_changes.saturating_sub(1);
-            self.adjust_song_needed(song_id, 1);
+    /// Move the highlighted song on to its next stage, as reported by
+    /// [`ToPrintScreen::next_stage_for_current`].
+    pub(crate) fn advance_current(&mut self) {
+        let Some((binder_idx, song_idx)) = self.current_song_index() else {
+            return;
+        };
+        let entry = &mut self.binder_reports[binder_idx].songs[song_idx];
+        let previous = entry.stage;
+        entry.stage = previous.next();
+        let (song_id, stage) = (entry.song.id, entry.stage);
+
+        match (previous, stage) {
+            (PrintStage::Needed, _) => self.adjust_song_needed(song_id, -1),
+            (_, PrintStage::Needed) => self.adjust_song_needed(song_id, 1),
+            _ => {}
         }
         self.refresh_binder_rows();
-        Some(now_checked)
     }
 
+    /// Mark every binder's copy of `song_id` that was still needed as
+    /// printed, e.g. once the printer accepted the job. Returns the
+    /// `(binder_id, song_id)` pairs that moved.
+    pub(crate) fn mark_printed(&mut self, song_id: i64) -> Vec<(i64, i64)> {
+        let mut moved = Vec::new();
+        for report in &mut self.binder_reports {
+            for missing in &mut report.songs {
+                if missing.song.id == song_id && missing.stage == PrintStage::Needed {
+                    missing.stage = PrintStage::Printed;
+                    moved.push((report.binder_id, song_id));
+                }
+            }
+        }
+        if !moved.is_empty() {
+            self.adjust_song_needed(song_id, -(moved.len() as isize));
+            self.refresh_binder_rows();
+        }
+        moved
+    }
+
+    /// Copies still needed per song, leaving out what has been ticked off.
+    pub(crate) fn song_demands(&self) -> Vec<SongDemand> {
+        self.song_totals
+            .iter()
+            .filter(|entry| entry.needed > 0)
+            .map(|entry| SongDemand {
+                song: entry.song.clone(),
+                needed: entry.needed,
+            })
+            .collect()
+    }
+
+    /// Whether any song is marked as inserted but not yet linked to its
+    /// binder.
     pub(crate) fn has_pending_changes(&self) -> bool {
-        self.pending_changes > 0
+        self.binder_reports
+            .iter()
+            .flat_map(|report| &report.songs)
+            .any(|missing| missing.stage == PrintStage::Inserted)
     }
 
+    /// `(binder_id, song_id)` pairs confirmed inserted, to be linked when the
+    /// changes are applied.
     pub(crate) fn pending_assignments(&self) -> Vec<(i64, i64)> {
         let mut assignments = Vec::new();
         for report in &self.binder_reports {
             for missing in &report.songs {
-                if missing.checked {
+                if missing.stage == PrintStage::Inserted {
                     assignments.push((report.binder_id, missing.song.id));
                 }
             }
@@ -359,6 +533,9 @@ impl ToPrintScreen {
         self.scroll = min(desired, max_scroll);
     }
 
+    /// Change the copies still needed of `song_id` by `delta`. A song whose
+    /// copies were all printed before the screen opened has no total yet and
+    /// gets one here.
     fn adjust_song_needed(&mut self, song_id: i64, delta: isize) {
         if let Some(entry) = self
             .song_totals
@@ -367,6 +544,19 @@ impl ToPrintScreen {
         {
             let updated = (entry.needed as isize + delta).max(0) as usize;
             entry.needed = updated;
+        } else if delta > 0 {
+            let song = self
+                .binder_reports
+                .iter()
+                .flat_map(|report| &report.songs)
+                .find(|missing| missing.song.id == song_id)
+                .map(|missing| missing.song.clone());
+            if let Some(song) = song {
+                self.song_totals.push(SongNeeded {
+                    song,
+                    needed: delta as usize,
+                });
+            }
         }
         self.refresh_song_rows();
     }
@@ -428,18 +618,26 @@ impl ToPrintScreen {
 
         let mut rows = Vec::new();
         for (binder_idx, report) in self.binder_reports.iter().enumerate() {
+            let mut text = format!(
+                "Binder {:02} • {}",
+                report.binder_number, report.binder_label
+            );
+            if !report.voice_part.is_empty() {
+                text.push_str(&format!(" • {}", report.voice_part));
+            }
             rows.push(BinderRow {
                 kind: BinderRowKind::Header,
-                text: format!(
-                    "Binder {:02} • {}",
-                    report.binder_number, report.binder_label
-                ),
+                text,
                 binder_index: Some(binder_idx),
                 song_index: None,
             });
 
             for (song_idx, song) in report.songs.iter().enumerate() {
-                let checkbox = if song.checked { "[x]" } else { "[ ]" };
+                let checkbox = match song.stage {
+                    PrintStage::Needed => "[ ]",
+                    PrintStage::Printed => "[p]",
+                    PrintStage::Inserted => "[x]",
+                };
                 rows.push(BinderRow {
                     kind: BinderRowKind::Song,
                     text: format!("{} {}", checkbox, song.song.display_title()),
@@ -467,15 +665,10 @@ pub(crate) struct BinderReport {
     pub(crate) binder_id: i64,
     pub(crate) binder_number: i64,
     pub(crate) binder_label: String,
+    pub(crate) voice_part: String,
     pub(crate) songs: Vec<MissingSong>,
 }
 
-/// Song that needs to be added to a binder, with a checkbox state.
-pub(crate) struct MissingSong {
-    pub(crate) song: Song,
-    pub(crate) checked: bool,
-}
-
 /// Row rendered in the binder list (either a header or an individual song).
 pub(crate) struct BinderRow {
     pub(crate) kind: BinderRowKind,
@@ -556,10 +749,7 @@ impl SongScreen {
                 self.filtered_songs = self
                     .songs
                     .iter()
-                    .filter(|s| {
-                        s.title.to_lowercase().contains(&ql)
-                            || s.composer.to_lowercase().contains(&ql)
-                    })
+                    .filter(|s| s.matches_query(&ql))
                     .cloned()
                     .collect();
             }
@@ -619,6 +809,13 @@ impl SongScreen {
         self.apply_filter();
     }
 
+    /// Move the selection onto the song with `id`, if it is listed.
+    pub(crate) fn select_song(&mut self, id: i64) {
+        if let Some(index) = self.filtered_songs.iter().position(|song| song.id == id) {
+            self.selected = index;
+        }
+    }
+
     fn ensure_in_bounds(&mut self) {
         if self.filtered_songs.is_empty() {
             self.selected = 0;
@@ -641,15 +838,19 @@ pub(crate) struct AddSongState {
 #[derive(Clone)]
 pub(crate) enum AddSongItem {
     CreateNew,
-    Existing(Song),
+    Existing(Box<Song>),
 }
 
 impl AddSongState {
-    pub(crate) fn load(conn: &Connection, binder_id: i64) -> Result<Self> {
+    pub(crate) fn load(library: &dyn Library, binder_id: i64) -> DbResult<Self> {
         let mut items = vec![AddSongItem::CreateNew];
-        let available = fetch_available_songs(conn, binder_id)?;
-        let director_song_ids = Self::load_director_song_ids(conn)?;
-        items.extend(available.into_iter().map(AddSongItem::Existing));
+        let available = library.fetch_available_songs(binder_id)?;
+        let director_song_ids = library.fetch_director_song_ids()?;
+        items.extend(
+            available
+                .into_iter()
+                .map(|song| AddSongItem::Existing(Box::new(song))),
+        );
         Ok(Self {
             binder_id,
             items,
@@ -714,27 +915,522 @@ impl AddSongState {
             .iter()
             .filter_map(|item| match item {
                 AddSongItem::Existing(song) if self.checked.contains(&song.id) => {
-                    Some(song.clone())
+                    Some(song.as_ref().clone())
                 }
                 _ => None,
             })
             .collect()
     }
+}
+
+/// Backups offered on the restore screen, newest first.
+pub(crate) struct BackupScreen {
+    pub(crate) backups: Vec<BackupInfo>,
+    pub(crate) selected: usize,
+}
+
+impl BackupScreen {
+    pub(crate) fn new(backups: Vec<BackupInfo>) -> Self {
+        Self {
+            backups,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn current_backup(&self) -> Option<&BackupInfo> {
+        self.backups.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.backups.is_empty() {
+            return;
+        }
+        let last = self.backups.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    pub(crate) fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.selected = self.backups.len().saturating_sub(1);
+    }
+}
+
+/// Concerts listed soonest first on the concerts screen.
+pub(crate) struct ConcertScreen {
+    pub(crate) concerts: Vec<Concert>,
+    pub(crate) selected: usize,
+}
+
+impl ConcertScreen {
+    pub(crate) fn new(concerts: Vec<Concert>) -> Self {
+        Self {
+            concerts,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn current_concert(&self) -> Option<&Concert> {
+        self.concerts.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.concerts.is_empty() {
+            return;
+        }
+        let last = self.concerts.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    pub(crate) fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.selected = self.concerts.len().saturating_sub(1);
+    }
+
+    /// Move the selection onto the concert with `id`, if it is listed.
+    pub(crate) fn select_concert(&mut self, id: i64) {
+        if let Some(index) = self.concerts.iter().position(|concert| concert.id == id) {
+            self.selected = index;
+        }
+    }
+
+    pub(crate) fn set_concerts(&mut self, concerts: Vec<Concert>) {
+        self.concerts = concerts;
+        self.selected = self.selected.min(self.concerts.len().saturating_sub(1));
+    }
+}
+
+/// One concert's setlist in running order.
+pub(crate) struct SetlistScreen {
+    pub(crate) concert: Concert,
+    pub(crate) songs: Vec<Song>,
+    pub(crate) selected: usize,
+}
+
+impl SetlistScreen {
+    pub(crate) fn new(concert: Concert, songs: Vec<Song>) -> Self {
+        Self {
+            concert,
+            songs,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn current_song(&self) -> Option<&Song> {
+        self.songs.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.songs.is_empty() {
+            return;
+        }
+        let last = self.songs.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    pub(crate) fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.selected = self.songs.len().saturating_sub(1);
+    }
+
+    /// Move the selection onto the song with `id`, if it is listed.
+    pub(crate) fn select_song(&mut self, id: i64) {
+        if let Some(index) = self.songs.iter().position(|song| song.id == id) {
+            self.selected = index;
+        }
+    }
+
+    pub(crate) fn set_songs(&mut self, songs: Vec<Song>) {
+        self.songs = songs;
+        self.selected = self.selected.min(self.songs.len().saturating_sub(1));
+    }
+}
+
+/// Picker listing the songs not yet on a setlist. Space checks several songs;
+/// Enter adds the checked ones, or the highlighted one if none are checked.
+pub(crate) struct SetlistPicker {
+    pub(crate) concert_id: i64,
+    pub(crate) songs: Vec<Song>,
+    pub(crate) selected: usize,
+    pub(crate) checked: HashSet<i64>,
+}
+
+impl SetlistPicker {
+    pub(crate) fn load(library: &dyn Library, concert_id: i64) -> DbResult<Self> {
+        let on_setlist: HashSet<i64> = library
+            .fetch_setlist(concert_id)?
+            .iter()
+            .map(|song| song.id)
+            .collect();
+        let songs = library
+            .fetch_all_songs()?
+            .into_iter()
+            .filter(|song| !on_setlist.contains(&song.id))
+            .collect();
+        Ok(Self {
+            concert_id,
+            songs,
+            selected: 0,
+            checked: HashSet::new(),
+        })
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.songs.is_empty() {
+            return;
+        }
+        let last = self.songs.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    pub(crate) fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.selected = self.songs.len().saturating_sub(1);
+    }
+
+    pub(crate) fn toggle_current(&mut self) {
+        if let Some(song) = self.songs.get(self.selected) {
+            if !self.checked.remove(&song.id) {
+                self.checked.insert(song.id);
+            }
+        }
+    }
+
+    /// Ids to add, in list order.
+    pub(crate) fn chosen_song_ids(&self) -> Vec<i64> {
+        if self.checked.is_empty() {
+            return self
+                .songs
+                .get(self.selected)
+                .map(|song| vec![song.id])
+                .unwrap_or_default();
+        }
+        self.songs
+            .iter()
+            .filter(|song| self.checked.contains(&song.id))
+            .map(|song| song.id)
+            .collect()
+    }
+}
+
+/// Performance history of one song, most recent first, shown in a popup from
+/// the song manager.
+pub(crate) struct SongHistory {
+    pub(crate) song: Song,
+    pub(crate) performances: Vec<Performance>,
+    pub(crate) selected: usize,
+}
+
+impl SongHistory {
+    pub(crate) fn load(library: &dyn Library, song: Song) -> DbResult<Self> {
+        let performances = library.fetch_song_performances(song.id)?;
+        Ok(Self {
+            song,
+            performances,
+            selected: 0,
+        })
+    }
+
+    pub(crate) fn current_performance(&self) -> Option<&Performance> {
+        self.performances.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.performances.is_empty() {
+            return;
+        }
+        let last = self.performances.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    /// Reload the list after a change, keeping the selection in range.
+    pub(crate) fn reload(&mut self, library: &dyn Library) -> DbResult<()> {
+        self.performances = library.fetch_song_performances(self.song.id)?;
+        self.selected = self.selected.min(self.performances.len().saturating_sub(1));
+        Ok(())
+    }
+}
+
+/// What a link picker row points at.
+pub(crate) enum LinkTarget {
+    /// A typed link, by id.
+    Typed(i64),
+    /// A file in the attachments folder.
+    Attachment(Attachment),
+}
+
+/// One row of the link picker: one of the song's typed links or an attached
+/// file.
+pub(crate) struct LinkChoice {
+    pub(crate) label: String,
+    /// The URL, or the size and date of an attached file.
+    pub(crate) url: String,
+    pub(crate) target: LinkTarget,
+}
+
+/// Every link of one song, shown in a popup when Enter is pressed on the song.
+pub(crate) struct LinkPicker {
+    pub(crate) song: Song,
+    pub(crate) choices: Vec<LinkChoice>,
+    pub(crate) selected: usize,
+}
+
+impl LinkPicker {
+    pub(crate) fn load(library: &dyn Library, song: Song) -> DbResult<Self> {
+        let mut picker = Self {
+            song,
+            choices: Vec::new(),
+            selected: 0,
+        };
+        picker.reload(library)?;
+        Ok(picker)
+    }
+
+    pub(crate) fn current_choice(&self) -> Option<&LinkChoice> {
+        self.choices.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.choices.is_empty() {
+            return;
+        }
+        let last = self.choices.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    /// Reload the typed links and attachments after a change, keeping the
+    /// selection in range.
+    pub(crate) fn reload(&mut self, library: &dyn Library) -> DbResult<()> {
+        let links: Vec<SongLink> = library
+            .fetch_song_links()?
+            .into_iter()
+            .filter(|link| link.song_id == self.song.id)
+            .collect();
+        let attachments: Vec<Attachment> = library
+            .fetch_attachments()?
+            .into_iter()
+            .filter(|attachment| attachment.song_id == self.song.id)
+            .collect();
+        self.choices.clear();
+        self.choices
+            .extend(links.into_iter().map(|link| LinkChoice {
+                label: link.describe(),
+                url: link.url,
+                target: LinkTarget::Typed(link.id),
+            }));
+        self.choices
+            .extend(attachments.into_iter().map(|attachment| LinkChoice {
+                label: format!("Attachment – {}", attachment.file_name),
+                url: format!(
+                    "{}, added {}",
+                    format_size(attachment.size_bytes),
+                    attachment.added_at
+                ),
+                target: LinkTarget::Attachment(attachment),
+            }));
+        self.selected = self.selected.min(self.choices.len().saturating_sub(1));
+        Ok(())
+    }
+
+    /// Select the typed link with `id`, if it is listed.
+    pub(crate) fn select_link(&mut self, id: i64) {
+        self.select(|target| matches!(target, LinkTarget::Typed(link_id) if *link_id == id));
+    }
+
+    /// Select the attachment with `id`, if it is listed.
+    pub(crate) fn select_attachment(&mut self, id: i64) {
+        self.select(
+            |target| matches!(target, LinkTarget::Attachment(attachment) if attachment.id == id),
+        );
+    }
+
+    fn select(&mut self, wanted: impl Fn(&LinkTarget) -> bool) {
+        if let Some(index) = self
+            .choices
+            .iter()
+            .position(|choice| wanted(&choice.target))
+        {
+            self.selected = index;
+        }
+    }
+}
+
+/// Checkout log of one binder, most recent first, shown in a popup from the
+/// binder grid.
+pub(crate) struct BinderLog {
+    pub(crate) binder: Binder,
+    pub(crate) checkouts: Vec<BinderCheckout>,
+    pub(crate) selected: usize,
+}
+
+impl BinderLog {
+    pub(crate) fn load(library: &dyn Library, binder: Binder) -> DbResult<Self> {
+        let mut checkouts: Vec<BinderCheckout> = library
+            .fetch_checkouts()?
+            .into_iter()
+            .filter(|checkout| checkout.binder_id == binder.id)
+            .collect();
+        checkouts.reverse();
+        Ok(Self {
+            binder,
+            checkouts,
+            selected: 0,
+        })
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.checkouts.is_empty() {
+            return;
+        }
+        let last = self.checkouts.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+}
+
+/// Binders out longer than `days` days, longest out first.
+pub(crate) struct OverdueScreen {
+    pub(crate) days: u32,
+    pub(crate) entries: Vec<OverdueBinder>,
+    pub(crate) selected: usize,
+}
+
+impl OverdueScreen {
+    pub(crate) fn load(library: &dyn Library, now: NaiveDateTime, days: u32) -> DbResult<Self> {
+        Ok(Self {
+            days,
+            entries: overdue_report(library, now, days)?,
+            selected: 0,
+        })
+    }
+
+    pub(crate) fn current_entry(&self) -> Option<&OverdueBinder> {
+        self.entries.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.entries.is_empty() {
+            return;
+        }
+        let last = self.entries.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    /// Recompute the report, e.g. after a check-in or a new period, keeping
+    /// the selection in range.
+    pub(crate) fn reload(
+        &mut self,
+        library: &dyn Library,
+        now: NaiveDateTime,
+        days: u32,
+    ) -> DbResult<()> {
+        self.days = days;
+        self.entries = overdue_report(library, now, days)?;
+        self.selected = self.selected.min(self.entries.len().saturating_sub(1));
+        Ok(())
+    }
+}
+
+/// The choir members who can be handed binders.
+pub(crate) struct ChoristerScreen {
+    pub(crate) choristers: Vec<Chorister>,
+    pub(crate) selected: usize,
+}
+
+impl ChoristerScreen {
+    pub(crate) fn new(choristers: Vec<Chorister>) -> Self {
+        Self {
+            choristers,
+            selected: 0,
+        }
+    }
+
+    pub(crate) fn current_chorister(&self) -> Option<&Chorister> {
+        self.choristers.get(self.selected)
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        if self.choristers.is_empty() {
+            return;
+        }
+        let last = self.choristers.len() as isize - 1;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    pub(crate) fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.selected = self.choristers.len().saturating_sub(1);
+    }
+
+    /// Move the selection onto the chorister with `id`, if it is listed.
+    pub(crate) fn select_chorister(&mut self, id: i64) {
+        if let Some(index) = self
+            .choristers
+            .iter()
+            .position(|chorister| chorister.id == id)
+        {
+            self.selected = index;
+        }
+    }
+
+    pub(crate) fn set_choristers(&mut self, choristers: Vec<Chorister>) {
+        self.choristers = choristers;
+        self.selected = self.selected.min(self.choristers.len().saturating_sub(1));
+    }
+}
+
+/// Picker for the chorister holding a binder. The first row stands for
+/// "nobody", so a binder can also be taken back.
+pub(crate) struct HolderPicker {
+    pub(crate) binder: Binder,
+    pub(crate) choristers: Vec<Chorister>,
+    pub(crate) selected: usize,
+}
+
+impl HolderPicker {
+    /// List every chorister, starting on the binder's current holder.
+    pub(crate) fn new(binder: Binder, choristers: Vec<Chorister>) -> Self {
+        let selected = binder
+            .holder_id
+            .and_then(|id| choristers.iter().position(|chorister| chorister.id == id))
+            .map_or(0, |index| index + 1);
+        Self {
+            binder,
+            choristers,
+            selected,
+        }
+    }
+
+    pub(crate) fn move_selection(&mut self, offset: isize) {
+        let last = self.choristers.len() as isize;
+        self.selected = (self.selected as isize + offset).clamp(0, last) as usize;
+    }
+
+    pub(crate) fn select_first(&mut self) {
+        self.selected = 0;
+    }
+
+    pub(crate) fn select_last(&mut self) {
+        self.selected = self.choristers.len();
+    }
 
-    fn load_director_song_ids(conn: &Connection) -> Result<HashSet<i64>> {
-        let mut stmt = conn
-            .prepare(
-                "SELECT bs.song_id
-                 FROM binder_songs bs
-                 INNER JOIN binders b ON b.id = bs.binder_id
-                 WHERE b.number = 0",
-            )
-            .context("failed to prepare director song lookup")?;
-        let ids = stmt
-            .query_map([], |row| row.get(0))
-            .context("failed to iterate director songs")?
-            .collect::<rusqlite::Result<Vec<i64>>>()
-            .context("failed to collect director song ids")?;
-        Ok(ids.into_iter().collect())
+    /// The highlighted chorister, or `None` on the "nobody" row.
+    pub(crate) fn chosen(&self) -> Option<&Chorister> {
+        self.selected
+            .checked_sub(1)
+            .and_then(|index| self.choristers.get(index))
     }
 }
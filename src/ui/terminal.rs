@@ -2,7 +2,7 @@ use std::io::{self, Stdout};
 use std::time::Duration;
 
 use anyhow::{Context, Result};
-use crossterm::event::{self, Event, KeyCode, KeyEventKind, KeyModifiers};
+use crossterm::event::{self, Event, KeyCode, KeyEvent, KeyEventKind, KeyModifiers};
 use crossterm::execute;
 use crossterm::terminal::{
     disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen,
@@ -28,24 +28,8 @@ pub fn run_app(app: &mut App) -> Result<()> {
 
         if event::poll(Duration::from_millis(250)).context("event polling failed")? {
             if let Event::Key(key_event) = event::read().context("failed to read event")? {
-                if key_event.kind == KeyEventKind::Press {
-                    if key_event.modifiers.contains(KeyModifiers::CONTROL) {
-                        match key_event.code {
-                            KeyCode::Char('e') => {
-                                app.handle_ctrl_e()?;
-                                continue;
-                            }
-                            KeyCode::Char('l') => {
-                                app.handle_ctrl_l()?;
-                                continue;
-                            }
-                            _ => {}
-                        }
-                    }
-
-                    if app.handle_key(key_event.code)? {
-                        break Ok(());
-                    }
+                if dispatch_key(app, key_event)? {
+                    break Ok(());
                 }
             }
         }
@@ -55,6 +39,32 @@ pub fn run_app(app: &mut App) -> Result<()> {
     result
 }
 
+/// Route a single key event to the app, giving the control chords the event
+/// loop reserves (Ctrl+E to edit from search, Ctrl+L to toggle the no-link
+/// filter) precedence over the regular key handler. Returns `true` once the
+/// app asks to exit.
+pub(crate) fn dispatch_key(app: &mut App, key_event: KeyEvent) -> Result<bool> {
+    if key_event.kind != KeyEventKind::Press {
+        return Ok(false);
+    }
+
+    if key_event.modifiers.contains(KeyModifiers::CONTROL) {
+        match key_event.code {
+            KeyCode::Char('e') => {
+                app.handle_ctrl_e()?;
+                return Ok(false);
+            }
+            KeyCode::Char('l') => {
+                app.handle_ctrl_l()?;
+                return Ok(false);
+            }
+            _ => {}
+        }
+    }
+
+    app.handle_key(key_event.code)
+}
+
 fn cleanup_terminal(terminal: &mut Terminal<CrosstermBackend<Stdout>>) -> Result<()> {
     disable_raw_mode().context("failed to disable raw mode")?;
     execute!(terminal.backend_mut(), LeaveAlternateScreen)
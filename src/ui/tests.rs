@@ -0,0 +1,873 @@
+//! Scripted regression tests for the TUI flows, driven through [`Harness`].
+
+use chrono::Local;
+use crossterm::event::KeyCode;
+
+use crate::db::{attach_file, AttachmentStore, BackupStore, Library, MemoryLibrary};
+use crate::models::{LinkKind, PrintStage};
+use crate::reports::days_out;
+use crate::reports::tests::write_blank_pdf;
+
+use super::harness::Harness;
+
+/// Director binder (number 0) holding two songs, plus Binder 01 that only has
+/// the first one. Returns the library and the ids of (binder 01, missing song).
+fn library_with_director() -> (MemoryLibrary, i64, i64) {
+    let mut library = MemoryLibrary::new();
+    let director = library.create_binder(0, "Director").unwrap();
+    let sopranos = library.create_binder(1, "Sopranos").unwrap();
+    let ave = library.create_song("Ave Verum", "Mozart").unwrap();
+    library
+        .add_song_link(
+            ave.id,
+            LinkKind::Score,
+            "",
+            "",
+            "https://example.org/ave.pdf",
+        )
+        .unwrap();
+    let cantique = library.create_song("Cantique", "Fauré").unwrap();
+    library.add_song_to_binder(director.id, ave.id).unwrap();
+    library
+        .add_song_to_binder(director.id, cantique.id)
+        .unwrap();
+    library.add_song_to_binder(sopranos.id, ave.id).unwrap();
+    (library, sopranos.id, cantique.id)
+}
+
+fn song_titles(library: &dyn Library, binder_id: i64) -> Vec<String> {
+    library
+        .fetch_songs_for_binder(binder_id)
+        .unwrap()
+        .into_iter()
+        .map(|song| song.title)
+        .collect()
+}
+
+#[test]
+fn add_binder_persists_and_selects_it() {
+    let mut h = Harness::new(MemoryLibrary::new());
+    h.assert_screen_contains("No binders yet.");
+
+    h.keys("+<Tab>Altos<Enter>");
+
+    let binders = h.library().fetch_binders().unwrap();
+    assert_eq!(binders.len(), 1);
+    assert_eq!((binders[0].number, binders[0].label.as_str()), (1, "Altos"));
+    h.assert_screen_contains("Binder 01");
+    h.assert_screen_contains("[ Altos ]");
+    h.assert_screen_contains("Added Binder 01.");
+}
+
+#[test]
+fn duplicate_binder_number_keeps_form_open() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("+<Backspace>1<Tab>Tenors<Enter>");
+
+    h.assert_screen_contains("Add Binder");
+    h.assert_screen_contains("Binder number 1 already exists.");
+    assert_eq!(h.library().fetch_binders().unwrap().len(), 2);
+
+    h.keys("<Esc>");
+    h.assert_screen_lacks("Add Binder");
+}
+
+#[test]
+fn to_print_apply_links_inserted_songs() {
+    let (library, sopranos_id, cantique_id) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("p");
+    h.assert_screen_contains("To Print • By Binder");
+    h.assert_screen_contains("[ ] Cantique - Fauré");
+
+    // Row 0 is the binder header, row 1 the missing song.
+    h.keys("<Down><Space>");
+    h.assert_screen_contains("[p] Cantique - Fauré");
+    h.assert_screen_contains("Marked song as printed.");
+    h.keys("<Space>");
+    h.assert_screen_contains("[x] Cantique - Fauré");
+
+    h.keys("<Esc>");
+    h.assert_screen_contains("Apply & Leave");
+    h.keys("<Enter>");
+
+    h.assert_screen_contains("Applied 1 song.");
+    let linked = h
+        .library()
+        .fetch_songs_for_binder(sopranos_id)
+        .unwrap()
+        .into_iter()
+        .any(|song| song.id == cantique_id);
+    assert!(linked, "inserted song should be linked to the binder");
+    assert!(h.library().fetch_print_queue().unwrap().is_empty());
+}
+
+#[test]
+fn to_print_queue_errors_show_in_the_status_bar() {
+    let (library, sopranos_id, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("p");
+    h.library_mut().delete_binder(sopranos_id).unwrap();
+    h.keys("<Down><Space>");
+    h.assert_screen_contains("Could not save the print queue:");
+    assert!(!h.exited());
+    assert!(h.library().fetch_print_queue().unwrap().is_empty());
+
+    // The row keeps the stage that is actually saved.
+    h.assert_screen_contains("[ ] Cantique - Fauré");
+    h.keys("<Space><Space>");
+    h.assert_screen_contains("[ ] Cantique - Fauré");
+    h.keys("<Esc>");
+    h.assert_screen_lacks("Apply & Leave");
+}
+
+#[test]
+fn to_print_apply_links_all_or_nothing() {
+    let (mut library, sopranos_id, _) = library_with_director();
+    let altos = library.create_binder(2, "Altos").unwrap();
+    let mut h = Harness::new(library);
+
+    h.keys("p<Down><Space><Space><Down><Down><Space><Space>");
+    h.library_mut().delete_binder(altos.id).unwrap();
+    h.keys("<Esc><Enter>");
+
+    // Altos is gone, so nothing is linked and both songs stay pending.
+    h.assert_screen_contains("Apply & Leave");
+    assert_eq!(song_titles(h.library(), sopranos_id), vec!["Ave Verum"]);
+}
+
+#[test]
+fn to_print_queue_is_kept_between_visits() {
+    let (library, sopranos_id, cantique_id) = library_with_director();
+    let mut h = Harness::new(library);
+
+    // Printed copies are saved straight away and leave without asking.
+    h.keys("p<Down><Space><Esc>");
+    h.assert_screen_lacks("Apply & Leave");
+    let queue = h.library().fetch_print_queue().unwrap();
+    assert_eq!(
+        (queue[0].binder_id, queue[0].song_id, queue[0].stage),
+        (sopranos_id, cantique_id, PrintStage::Printed)
+    );
+    h.keys("p<Tab>");
+    h.assert_screen_contains("No songs need printing.");
+
+    h.keys("<Tab><Down><Space><Esc><Right><Enter>");
+    h.assert_screen_contains("Kept the print queue for next time.");
+    assert_eq!(song_titles(h.library(), sopranos_id), vec!["Ave Verum"]);
+    h.keys("p");
+    h.assert_screen_contains("[x] Cantique - Fauré");
+
+    // A third press puts the song back on the list to print.
+    h.keys("<Down><Space>");
+    h.assert_screen_contains("[ ] Cantique - Fauré");
+    h.assert_screen_contains("Song needs printing again.");
+    assert!(h.library().fetch_print_queue().unwrap().is_empty());
+    h.keys("<Tab>");
+    h.assert_screen_contains("Cantique - Fauré  (1 copy)");
+}
+
+#[test]
+fn to_print_by_song_counts_copies() {
+    let (mut library, _, _) = library_with_director();
+    library.create_binder(2, "Altos").unwrap();
+    let mut h = Harness::new(library);
+
+    h.keys("p<Tab>");
+
+    h.assert_screen_contains("To Print • By Song");
+    h.assert_screen_contains("Cantique - Fauré  (2 copies)");
+    h.assert_screen_contains("Ave Verum - Mozart  (1 copy)");
+}
+
+#[test]
+fn to_print_skips_songs_a_binder_part_does_not_sing() {
+    let (mut library, sopranos_id, cantique_id) = library_with_director();
+    library.set_song_parts(cantique_id, "T, B").unwrap();
+    library.create_binder(2, "Tenors").unwrap();
+    let mut h = Harness::new(library);
+
+    // Give the Sopranos binder its part through the edit form.
+    h.keys("<Right>e<Tab><Tab>S<Enter>");
+    h.assert_screen_contains("Binder 01 · S");
+    let sopranos = h.library().fetch_binders().unwrap();
+    let sopranos = sopranos.iter().find(|binder| binder.id == sopranos_id);
+    assert_eq!(sopranos.map(|binder| binder.voice_part.as_str()), Some("S"));
+
+    h.keys("p");
+    h.assert_screen_lacks("Binder 01 • Sopranos");
+    h.assert_screen_contains("Binder 02 • Tenors");
+    h.keys("<Tab>");
+    h.assert_screen_contains("Cantique - Fauré  (1 copy)");
+}
+
+#[test]
+fn search_filters_song_manager_and_ctrl_e_edits() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("sfcant");
+    h.assert_screen_contains("Search: cant");
+    h.assert_screen_contains("▶ Cantique");
+    h.assert_screen_lacks("Ave Verum");
+
+    h.keys("<C-e>");
+    h.assert_screen_contains("Edit Song");
+    h.keys("<Backspace><Backspace><Backspace><Backspace>ical<Enter>");
+
+    // Saving returns to the search that was active before editing.
+    h.assert_screen_contains("Search: cant");
+    h.assert_screen_contains("Song updated.");
+    let titles: Vec<String> = h
+        .library()
+        .fetch_all_songs()
+        .unwrap()
+        .into_iter()
+        .map(|song| song.title)
+        .collect();
+    assert_eq!(titles, vec!["Ave Verum", "Cantical"]);
+
+    h.keys("<Esc>");
+    h.assert_screen_contains("Mozart");
+}
+
+#[test]
+fn song_details_are_edited_shown_and_searchable() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("sfave<C-e><Tab><Tab><Tab><Tab>Süssmayr<Tab>SATB<Tab><Tab><Tab>");
+    h.keys("Carus<Tab>40.123<Tab>3:75<Enter>");
+    h.assert_screen_contains("Duration must be m:ss or whole minutes.");
+    // Durations too long to store are refused rather than wrapping around.
+    for too_long in ["99999999", "71582788:59"] {
+        h.keys("<Backspace>".repeat(12).as_str());
+        h.keys(too_long);
+        h.keys("<Enter>");
+        h.assert_screen_contains("Duration must be m:ss or whole minutes.");
+    }
+
+    h.keys("<Backspace>".repeat(12).as_str());
+    h.keys("3:05<Tab>4<Enter>");
+    h.assert_screen_contains("Song updated.");
+    let details = h.library().fetch_all_songs().unwrap()[0].details.clone();
+    assert_eq!(details.duration_seconds, Some(185));
+    assert_eq!(details.page_count, Some(4));
+
+    h.keys("<Esc>");
+    h.assert_screen_contains("arr. Süssmayr • SATB • Carus 40.123 • 3:05 • 4 pp.");
+
+    h.keys("f40.12");
+    h.assert_screen_contains("▶ Ave Verum");
+    h.assert_screen_lacks("Cantique");
+}
+
+#[test]
+fn song_tags_show_as_chips_and_filter_the_song_manager() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("se<Tab><Tab><Tab>Latin, Communion, latin<Enter>");
+    h.assert_screen_contains("Song updated.");
+    h.assert_screen_contains("Ave Verum  Communion   Latin");
+
+    h.keys("<Down>e<Tab><Tab><Tab>LATIN<Enter>");
+    h.assert_screen_contains("Cantique  Latin");
+    assert_eq!(h.library().fetch_tags().unwrap().len(), 2);
+
+    h.keys("t");
+    h.assert_screen_contains("Showing songs tagged Communion.");
+    h.assert_screen_contains("Tag filter active - songs tagged Communion");
+    h.assert_screen_lacks("Cantique");
+    h.keys("t");
+    h.assert_screen_contains("Showing songs tagged Latin.");
+    h.assert_screen_contains("Cantique");
+    h.keys("t");
+    h.assert_screen_contains("Showing songs with any tag or none.");
+    h.assert_screen_lacks("Tag filter active");
+
+    // Reopening the form shows the saved tags.
+    h.keys("e");
+    h.assert_screen_contains("Communion, Latin");
+}
+
+#[test]
+fn enter_opens_a_link_picker_that_adds_typed_links() {
+    let (library, _, cantique_id) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("s<Down><Enter>");
+    h.assert_screen_contains("Links • Cantique");
+    h.assert_screen_contains("No links yet. Press '+' to add one or 'a' to attach a file.");
+
+    h.keys("+<Right><Right><Enter>");
+    h.assert_screen_contains("Type: ◀ Rehearsal track ▶");
+    h.assert_screen_contains("Link URL is required.");
+    h.keys("<Tab>T<Tab><Tab>https://example.org/t.mp3<Enter>");
+    h.assert_screen_contains("Added Rehearsal track (T) to Cantique.");
+    h.assert_screen_contains("▶ Rehearsal track (T)  https://example.org/t.mp3");
+    let links = h.library().fetch_song_links().unwrap();
+    assert_eq!(links.len(), 2);
+    assert_eq!(links[1].song_id, cantique_id);
+    assert_eq!(links[1].kind, LinkKind::Rehearsal);
+
+    // The card lists typed links, and they count for the no-link filter.
+    h.keys("<Esc>");
+    h.assert_screen_contains("Rehearsal track (T)");
+    h.keys("l");
+    h.assert_screen_contains("No songs without links yet.");
+
+    h.keys("l<Up><Enter>");
+    h.assert_screen_contains("▶ Score  https://example.org/ave.pdf");
+
+    // Closing the picker returns to the search it was opened from.
+    h.keys("<Esc>fcan<Enter>");
+    h.assert_screen_contains("Links • Cantique");
+    h.keys("-");
+    h.assert_screen_contains("Link removed.");
+    h.keys("<Esc>");
+    h.assert_screen_contains("Search: can");
+    assert_eq!(h.library().fetch_song_links().unwrap().len(), 1);
+}
+
+#[test]
+fn link_picker_attaches_local_files() {
+    let (library, _, cantique_id) = library_with_director();
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("cantique.pdf");
+    std::fs::write(&source, b"%PDF-1.4 cantique").unwrap();
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+    let mut h = Harness::new(library);
+    h.enable_attachments(store.clone());
+
+    h.keys("s<Down><Enter>a<Enter>");
+    h.assert_screen_contains("Attach File");
+    h.assert_screen_contains("File path is required.");
+    h.keys(&format!("{}<Enter>", source.display()));
+    h.assert_screen_contains("Attached cantique.pdf (17 B) to Cantique.");
+    h.assert_screen_contains("▶ Attachment – cantique.pdf  17 B, added");
+    let attachments = h.library().fetch_attachments().unwrap();
+    assert_eq!(attachments.len(), 1);
+    assert_eq!(attachments[0].song_id, cantique_id);
+
+    // A damaged copy is reported instead of opened.
+    std::fs::write(store.path_of(&attachments[0]), b"damaged").unwrap();
+    h.keys("<Enter>");
+    h.assert_screen_contains("Attached file 'cantique.pdf' does not match its checksum.");
+
+    // The card lists the file, and it counts for the no-link filter.
+    h.keys("<Esc>");
+    h.assert_screen_contains("Files: cantique.pdf");
+    h.keys("l");
+    h.assert_screen_contains("No songs without links yet.");
+
+    h.keys("l<Down><Enter>-");
+    h.assert_screen_contains("Attachment removed.");
+    assert!(h.library().fetch_attachments().unwrap().is_empty());
+}
+
+#[test]
+fn t_writes_binder_index_sheets() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+    h.keys("t");
+    h.assert_screen_contains("No data folder to write the index into.");
+
+    let dir = tempfile::tempdir().unwrap();
+    h.set_data_dir(dir.path().to_path_buf());
+    h.keys("<Right>t");
+    h.assert_screen_contains("Opened the index of Binder 01 for printing");
+    let stamp = Local::now().format("%Y-%m-%d");
+    let exports = dir.path().join("exports");
+    let text =
+        std::fs::read_to_string(exports.join(format!("index-binder-01-{stamp}.txt"))).unwrap();
+    assert!(text.contains("  1  Ave Verum  Mozart"));
+    assert!(exports
+        .join(format!("index-binder-01-{stamp}.html"))
+        .exists());
+
+    h.keys("T");
+    h.assert_screen_contains("Opened the index of all 2 binders for printing");
+    let html =
+        std::fs::read_to_string(exports.join(format!("index-all-binders-{stamp}.html"))).unwrap();
+    assert!(html.contains("<td>Cantique</td><td>Fauré</td>"));
+}
+
+#[test]
+fn x_exports_the_to_print_checklist() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+    h.keys("px");
+    h.assert_screen_contains("No data folder to write the checklist into.");
+
+    let dir = tempfile::tempdir().unwrap();
+    h.set_data_dir(dir.path().to_path_buf());
+    h.keys("x");
+    h.assert_screen_contains("Opened the checklist for printing");
+    let stamp = Local::now().format("%Y-%m-%d");
+    let exports = dir.path().join("exports");
+    let markdown = std::fs::read_to_string(exports.join(format!("to-print-{stamp}.md"))).unwrap();
+    assert!(markdown.contains("| Cantique | Fauré | 1 |"));
+    assert!(markdown.contains("### Binder 01 – Sopranos – 1 song\n\n- [ ] Cantique – Fauré"));
+    let songs =
+        std::fs::read_to_string(exports.join(format!("to-print-songs-{stamp}.csv"))).unwrap();
+    assert!(songs.ends_with(",Cantique,Fauré,1\n"));
+    assert!(exports.join(format!("to-print-{stamp}.html")).exists());
+    assert!(exports
+        .join(format!("to-print-binders-{stamp}.csv"))
+        .exists());
+}
+
+#[test]
+fn b_builds_a_print_job_from_the_songs_still_needed() {
+    let (mut library, _, cantique_id) = library_with_director();
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("cantique.pdf");
+    write_blank_pdf(&source, 3);
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+    attach_file(
+        &mut library,
+        &store,
+        cantique_id,
+        &source,
+        "2024-05-01 10:00",
+    )
+    .unwrap();
+    let mut h = Harness::new(library);
+    h.enable_attachments(store);
+    h.keys("pb");
+    h.assert_screen_contains("No data folder to write the print job into.");
+
+    h.set_data_dir(dir.path().to_path_buf());
+    h.keys("b");
+    h.assert_screen_contains("Opened the print job: 1 copy of 1 song, 3 pages plus the cover.");
+    let stamp = Local::now().format("%Y-%m-%d");
+    let job = dir.path().join(format!("exports/print-job-{stamp}.pdf"));
+    assert_eq!(lopdf::Document::load(job).unwrap().get_pages().len(), 4);
+
+    // Songs ticked off as added are not printed again.
+    h.keys("<Down><Space>b");
+    h.assert_screen_contains("Every binder is complete; there is nothing to print.");
+}
+
+#[cfg(unix)]
+#[test]
+fn s_sends_the_songs_still_needed_to_the_printer() {
+    use crate::printing::tests::{lp_calls, stub_lp};
+    use crate::printing::Printer;
+
+    let (mut library, _, cantique_id) = library_with_director();
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("cantique.pdf");
+    write_blank_pdf(&source, 2);
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+    attach_file(
+        &mut library,
+        &store,
+        cantique_id,
+        &source,
+        "2024-05-01 10:00",
+    )
+    .unwrap();
+    let mut h = Harness::new(library);
+    h.enable_attachments(store);
+    h.keys("ps");
+    h.assert_screen_contains("Printing is not set up here.");
+
+    let lp = stub_lp(dir.path());
+    h.enable_printer(Printer::new(Some("Office".to_string())).with_command(lp));
+    h.keys("s");
+    h.assert_screen_contains("Send to Printer");
+    h.assert_screen_contains("1 × Cantique - Fauré  (cantique.pdf)");
+    h.keys("n");
+    h.assert_screen_contains("Nothing was sent to the printer.");
+    assert!(lp_calls(dir.path()).is_empty());
+
+    h.keys("sy");
+    h.assert_screen_contains("Sent 1 score (1 copy) to Office.");
+    h.assert_screen_contains("[p] Cantique - Fauré");
+    assert_eq!(lp_calls(dir.path()).len(), 1);
+    assert_eq!(
+        h.library().fetch_print_queue().unwrap()[0].stage,
+        PrintStage::Printed
+    );
+    let log = h.library().fetch_print_log().unwrap();
+    assert_eq!(log.len(), 1);
+    assert_eq!(
+        (log[0].song_id, log[0].copies, log[0].request_id.as_str()),
+        (Some(cantique_id), 1, "Office-1")
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn printer_sends_survive_a_failed_queue_save() {
+    use crate::printing::tests::{lp_calls, stub_lp};
+    use crate::printing::Printer;
+
+    let (mut library, sopranos_id, cantique_id) = library_with_director();
+    let dir = tempfile::tempdir().unwrap();
+    let source = dir.path().join("cantique.pdf");
+    write_blank_pdf(&source, 2);
+    let store = AttachmentStore::new(dir.path().join("attachments"));
+    attach_file(
+        &mut library,
+        &store,
+        cantique_id,
+        &source,
+        "2024-05-01 10:00",
+    )
+    .unwrap();
+    let mut h = Harness::new(library);
+    h.enable_attachments(store);
+    h.enable_printer(Printer::new(Some("Office".to_string())).with_command(stub_lp(dir.path())));
+
+    // The binder goes away while the popup is open, so the queue cannot be
+    // saved; the job the printer took is still reported and kept on screen.
+    h.keys("ps");
+    h.library_mut().delete_binder(sopranos_id).unwrap();
+    h.keys("y");
+    assert!(!h.exited());
+    assert_eq!(lp_calls(dir.path()).len(), 1);
+    h.assert_screen_contains("Sent 1 score (1 copy) to Office. Could not save the print queue:");
+    h.assert_screen_contains("[p] Cantique - Fauré");
+    assert_eq!(h.library().fetch_print_log().unwrap().len(), 1);
+    assert!(h.library().fetch_print_queue().unwrap().is_empty());
+}
+
+#[test]
+fn ctrl_l_toggles_no_link_filter_during_search() {
+    let (library, _, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("sf<C-l>");
+    h.assert_screen_contains("Showing songs without links.");
+    h.assert_screen_contains("No-link filter active");
+    h.assert_screen_lacks("Ave Verum");
+
+    h.keys("<C-l><Esc>");
+    h.assert_screen_contains("Showing all songs.");
+    h.assert_screen_contains("Ave Verum");
+}
+
+#[test]
+fn binder_view_adds_existing_song() {
+    let (library, sopranos_id, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    // Binder 01 is the second card; open it and pick the only available song.
+    h.keys("<Right><Enter>");
+    h.assert_screen_contains("Binder 01  •  Sopranos");
+    h.keys("+");
+    h.assert_screen_contains("Add Song to Binder");
+    h.keys("<Down><Enter>");
+
+    h.assert_screen_contains("Song added to binder.");
+    assert_eq!(
+        song_titles(h.library(), sopranos_id),
+        vec!["Ave Verum", "Cantique"]
+    );
+}
+
+#[test]
+fn quitting_from_binder_grid_exits() {
+    let mut h = Harness::new(MemoryLibrary::new());
+    h.press(KeyCode::Char('q'));
+    assert!(h.exited());
+}
+
+#[test]
+fn song_manager_imports_csv_after_preview() {
+    let (library, sopranos_id, _) = library_with_director();
+    let mut file = tempfile::NamedTempFile::new().unwrap();
+    std::io::Write::write_all(
+        &mut file,
+        b"Piece,Composer,Binder\nGloria,Vivaldi,1\nave verum,MOZART,1\n",
+    )
+    .unwrap();
+    let path = file.path().to_str().unwrap().to_string();
+    let mut h = Harness::new(library);
+
+    h.keys("si");
+    h.assert_screen_contains("Import Songs from CSV");
+    h.keys(&format!("{path}<Enter>"));
+    h.assert_screen_contains("Could not find a title column; map one explicitly.");
+
+    h.keys("<Tab>Piece<Enter>");
+    h.assert_screen_contains("Import Preview (dry run)");
+    h.assert_screen_contains("New songs: 1");
+    h.assert_screen_contains("Already in library: 1");
+    h.assert_screen_contains("Binder assignments: 1");
+    assert_eq!(h.library().fetch_all_songs().unwrap().len(), 2);
+
+    h.keys("<Enter>");
+    h.assert_screen_contains(
+        "Imported: 1 new song, 1 already in the library, 1 binder assignment.",
+    );
+    h.assert_screen_contains("Gloria");
+    assert_eq!(
+        song_titles(h.library(), sopranos_id),
+        vec!["Ave Verum", "Gloria"]
+    );
+}
+
+#[test]
+fn song_manager_exports_csv_files() {
+    let (library, _, _) = library_with_director();
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("board");
+    let mut h = Harness::new(library);
+
+    h.keys("sx");
+    h.assert_screen_contains("Export Library to CSV");
+    h.keys(&format!("{}<Enter>", target.display()));
+
+    h.assert_screen_contains("Exported 2 songs, 2 binders, 3 memberships to");
+    let mut names: Vec<String> = std::fs::read_dir(&target)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .collect();
+    names.sort();
+    assert_eq!(names.len(), 3);
+    assert!(names[0].starts_with("binders-") && names[0].ends_with(".csv"));
+    let songs = std::fs::read_to_string(target.join(&names[2])).unwrap();
+    assert_eq!(
+        songs,
+        "id,title,composer,link,binders\n\
+         1,Ave Verum,Mozart,https://example.org/ave.pdf,0; 1\n\
+         2,Cantique,Fauré,,0\n"
+    );
+}
+
+#[test]
+fn deleted_binder_comes_back_from_a_backup() {
+    let (library, sopranos_id, _) = library_with_director();
+    let dir = tempfile::tempdir().unwrap();
+    let mut h = Harness::new(library);
+    h.enable_backups(BackupStore::new(dir.path().to_path_buf(), 5));
+
+    h.keys("<Right>-y");
+    h.assert_screen_contains("Deleted Binder 01.");
+    assert_eq!(h.library().fetch_binders().unwrap().len(), 1);
+
+    h.keys("b");
+    h.assert_screen_contains("Restore from Backup");
+    h.assert_screen_contains("before deleting a binder");
+    h.assert_screen_contains("on startup");
+    h.assert_screen_contains("2 binders, 2 songs");
+
+    h.keys("<Enter>");
+    h.assert_screen_contains("Replace the library with the backup from");
+    h.keys("y");
+
+    h.assert_screen_contains("Restored the backup from");
+    h.assert_screen_contains("[ Sopranos ]");
+    assert_eq!(song_titles(h.library(), sopranos_id), vec!["Ave Verum"]);
+}
+
+#[test]
+fn backups_screen_needs_backups_enabled() {
+    let mut h = Harness::new(MemoryLibrary::new());
+    h.keys("b");
+    h.assert_screen_contains("Backups are turned off.");
+}
+
+#[test]
+fn song_view_reorders_and_copies_director_order() {
+    let (library, sopranos_id, cantique_id) = library_with_director();
+    let mut library = library;
+    library
+        .add_song_to_binder(sopranos_id, cantique_id)
+        .unwrap();
+    let mut h = Harness::new(library);
+
+    // Director binder: move Cantique to the top, then share the order.
+    h.keys("<Enter><Down>[");
+    let director_id = h.library().fetch_binders().unwrap()[0].id;
+    assert_eq!(
+        song_titles(h.library(), director_id),
+        vec!["Cantique", "Ave Verum"]
+    );
+    h.keys("o");
+    h.assert_screen_contains("Copy Director's Order");
+    h.keys("y");
+    h.assert_screen_contains("Copied the director's order to 1 binder.");
+    assert_eq!(
+        song_titles(h.library(), sopranos_id),
+        vec!["Cantique", "Ave Verum"]
+    );
+
+    // The selection follows the moved song, and `o` is director-only.
+    h.keys("]]<Esc><Right><Enter>o");
+    assert_eq!(
+        song_titles(h.library(), director_id),
+        vec!["Ave Verum", "Cantique"]
+    );
+    h.assert_screen_contains("Open the director's binder (00) to copy its order.");
+}
+
+#[test]
+fn concert_setlist_drives_to_print() {
+    let (library, sopranos_id, cantique_id) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("c");
+    h.assert_screen_contains("No concerts yet.");
+    h.keys("+Spring<Tab>2025-13-01<Enter>");
+    h.assert_screen_contains("Concert date must look like 2024-12-24.");
+    h.keys("<Backspace><Backspace><Backspace><Backspace><Backspace>04-12<Tab>Hall<Enter>");
+    h.assert_screen_contains("Concert added.");
+    h.assert_screen_contains("2025-04-12  Spring  @ Hall");
+
+    // Add Cantique (the second song in the picker) to the setlist.
+    h.keys("<Enter>+<Down><Enter>");
+    h.assert_screen_contains("Setlist • Spring • 2025-04-12 • Hall");
+    h.assert_screen_contains(" 1. Cantique - Fauré");
+
+    h.keys("p");
+    h.assert_screen_contains("To Print • Spring • By Binder");
+    // Binder 00 has Cantique already; Binder 01 does not.
+    h.assert_screen_lacks("Binder 00");
+    h.keys("<Down><Space><Space><Esc>");
+    h.assert_screen_contains("Apply");
+    h.keys("<Enter>");
+    h.assert_screen_contains("Applied 1 song.");
+    h.assert_screen_contains("▶ 2025-04-12  Spring");
+    assert_eq!(
+        song_titles(h.library(), sopranos_id),
+        vec!["Ave Verum", "Cantique"]
+    );
+    assert_eq!(h.library().fetch_setlist(1).unwrap()[0].id, cantique_id);
+}
+
+#[test]
+fn performance_history_feeds_cards_and_the_unsung_filter() {
+    let (mut library, _, cantique_id) = library_with_director();
+    let ave_id = library.fetch_all_songs().unwrap()[0].id;
+    library
+        .record_performance(ave_id, "2001-05-01", "Festival")
+        .unwrap();
+    let spring = library.create_concert("Spring", "2020-04-12", "").unwrap();
+    library.add_song_to_setlist(spring.id, cantique_id).unwrap();
+    let mut h = Harness::new(library);
+
+    h.keys("s");
+    h.assert_screen_contains("[s] Binders   [q] Quit");
+    h.assert_screen_contains("Last performed 2001-05-01 • 1 performance");
+    h.assert_screen_contains("Never performed");
+
+    // Both songs are unsung for a year; the never-performed one comes first.
+    h.keys("n");
+    h.assert_screen_contains("Showing songs not performed in 1 year.");
+    h.assert_screen_contains("▶ Cantique");
+    h.assert_screen_contains("Ave Verum");
+
+    h.keys("<Down>h");
+    h.assert_screen_contains("Performances • Ave Verum");
+    h.assert_screen_contains("2001-05-01  Festival");
+    h.keys("+<Tab>Evensong<Enter>");
+    h.assert_screen_contains("Recorded a performance of Ave Verum on");
+    assert_eq!(
+        h.library().fetch_song_performances(ave_id).unwrap().len(),
+        2
+    );
+    h.keys("<Esc>");
+    h.assert_screen_lacks("Mozart");
+    h.assert_screen_contains("Cantique");
+    h.keys("nnnn");
+    h.assert_screen_contains("Showing songs regardless of when they were performed.");
+    h.assert_screen_contains("Ave Verum");
+
+    h.keys("<Esc>cm");
+    h.assert_screen_contains("Recorded 1 performance from Spring.");
+    h.keys("m");
+    h.assert_screen_contains("Spring is already in the performance history.");
+    let stats = h.library().fetch_performance_stats().unwrap();
+    assert_eq!(stats[&cantique_id].last_date, "2020-04-12");
+}
+
+#[test]
+fn choristers_can_be_added_and_handed_a_binder() {
+    let (library, sopranos_id, _) = library_with_director();
+    let mut h = Harness::new(library);
+
+    h.keys("a");
+    h.assert_screen_contains("No choristers yet. Press m to add them.");
+
+    h.keys("m");
+    h.assert_screen_contains("No choristers yet. Press + to add one.");
+    h.keys("+<Enter>");
+    h.assert_screen_contains("Chorister name is required.");
+    h.keys("Anna<Tab>Soprano<Enter>");
+    h.assert_screen_contains("Chorister added.");
+    h.assert_screen_contains("Anna");
+
+    h.keys("<Esc><Right>a");
+    h.assert_screen_contains("Assign Binder 01");
+    h.keys("<Down><Enter>");
+    h.assert_screen_contains("Binder 01 assigned to Anna.");
+    h.assert_screen_contains("♪ Anna");
+    let binder = h
+        .library()
+        .fetch_binders()
+        .unwrap()
+        .into_iter()
+        .find(|binder| binder.id == sopranos_id)
+        .unwrap();
+    assert!(binder.holder_id.is_some());
+
+    h.keys("m");
+    h.assert_screen_contains("holds Binder 01");
+    h.keys("-y");
+    h.assert_screen_contains("Chorister deleted.");
+    h.keys("<Esc>");
+    h.assert_screen_lacks("♪ Anna");
+}
+
+#[test]
+fn binders_check_out_and_show_up_as_overdue() {
+    let (mut library, sopranos_id, _) = library_with_director();
+    let anna = library.create_chorister("Anna", "Soprano", "").unwrap();
+    library.assign_binder(sopranos_id, Some(anna.id)).unwrap();
+    library
+        .record_checkout(sopranos_id, Some(anna.id), "2020-01-01 10:00", None)
+        .unwrap();
+    let days = days_out("2020-01-01 10:00", Local::now().naive_local()).unwrap();
+    let mut h = Harness::new(library);
+
+    h.assert_screen_contains("On shelf");
+    h.assert_screen_contains(&format!("Overdue {days}d"));
+
+    h.keys("o");
+    h.assert_screen_contains("Binder 00 checked out.");
+    h.assert_screen_contains("Out 0d");
+    h.keys("o");
+    h.assert_screen_contains("Binder 00 is already checked out.");
+    h.keys("i");
+    h.assert_screen_contains("Binder 00 is back on the shelf.");
+    h.keys("i");
+    h.assert_screen_contains("Binder 00 is already on the shelf.");
+
+    h.keys("h");
+    h.assert_screen_contains("Checkouts • Binder 00");
+    h.assert_screen_lacks("still out");
+    h.keys("<Esc><Right>h");
+    h.assert_screen_contains("2020-01-01 10:00 → still out");
+    h.assert_screen_contains("Anna");
+
+    h.keys("<Esc>d");
+    h.assert_screen_contains("Overdue • out more than 28 days");
+    h.assert_screen_contains("Anna (Soprano)");
+    h.keys("-");
+    h.assert_screen_contains("out more than 21 days");
+    h.keys("i");
+    h.assert_screen_contains("Binder 01 is back on the shelf.");
+    h.assert_screen_contains("No binders are overdue.");
+    h.keys("<Esc>");
+    assert_eq!(h.render().matches("On shelf").count(), 2);
+    assert_eq!(h.library().fetch_checkouts().unwrap().len(), 2);
+}